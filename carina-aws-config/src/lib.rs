@@ -0,0 +1,87 @@
+//! AWS SDK configuration shared by the Carina AWS providers
+//!
+//! Both the `aws` and `awscc` providers accept `profile` and `assume_role_arn`
+//! in their provider blocks; this crate turns them into an [`SdkConfig`] the
+//! same way for both. It also holds the helpers both providers use to turn SDK
+//! errors into provider errors.
+
+use aws_config::profile::ProfileFileCredentialsProvider;
+use aws_config::sts::AssumeRoleProvider;
+use aws_config::{Region, SdkConfig};
+use aws_smithy_runtime_api::client::result::SdkError;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use aws_smithy_types::error::metadata::ProvideErrorMetadata;
+use carina_core::provider::{ProviderError, ProviderErrorKind};
+
+/// Load the SDK configuration for a region. Credentials come from `profile` when
+/// given (instead of the default chain), and are then used to assume
+/// `assume_role_arn` when given.
+pub async fn load_sdk_config(
+    region: &str,
+    profile: Option<&str>,
+    assume_role_arn: Option<&str>,
+) -> SdkConfig {
+    let region = Region::new(region.to_string());
+    let mut loader =
+        aws_config::defaults(aws_config::BehaviorVersion::latest()).region(region.clone());
+    if let Some(profile) = profile {
+        loader = loader.credentials_provider(
+            ProfileFileCredentialsProvider::builder()
+                .profile_name(profile)
+                .build(),
+        );
+    }
+    let config = loader.load().await;
+
+    let Some(role_arn) = assume_role_arn else {
+        return config;
+    };
+    let role = AssumeRoleProvider::builder(role_arn)
+        .session_name("carina")
+        .configure(&config)
+        .build()
+        .await;
+    aws_config::defaults(aws_config::BehaviorVersion::latest())
+        .region(region)
+        .credentials_provider(role)
+        .load()
+        .await
+}
+
+/// Attaching an AWS SDK error as the cause of a provider error
+pub trait WithSdkCause {
+    /// Set the error as the cause and classify the error kind from its code
+    fn with_sdk_cause<E, R>(self, err: SdkError<E, R>) -> Self
+    where
+        E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+        R: std::fmt::Debug + Send + Sync + 'static;
+}
+
+impl WithSdkCause for ProviderError {
+    fn with_sdk_cause<E, R>(self, err: SdkError<E, R>) -> Self
+    where
+        E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+        R: std::fmt::Debug + Send + Sync + 'static,
+    {
+        let kind = err
+            .code()
+            .map(ProviderErrorKind::from_error_code)
+            .unwrap_or_default();
+        self.with_kind(kind).with_cause(err)
+    }
+}
+
+/// Format an AWS SDK error as "Code: message", without the SDK's debug structure.
+/// The full error is kept as the ProviderError cause for verbose output.
+pub fn format_sdk_error<E, R>(err: &SdkError<E, R>) -> String
+where
+    E: ProvideErrorMetadata + std::error::Error + 'static,
+    R: std::fmt::Debug,
+{
+    match (err.code(), err.message()) {
+        (Some(code), Some(message)) => format!("{}: {}", code, message),
+        (Some(code), None) => code.to_string(),
+        (None, Some(message)) => message.to_string(),
+        (None, None) => DisplayErrorContext(err).to_string(),
+    }
+}
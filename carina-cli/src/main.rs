@@ -1,27 +1,39 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::builder::BoolishValueParser;
+use clap::{ArgAction, CommandFactory, Parser, Subcommand};
 use clap_complete::{Shell, generate};
 use colored::Colorize;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
 use similar::{ChangeTag, TextDiff};
 
-use carina_core::differ::create_plan;
+use carina_core::differ::{attribute_values_equal, create_plan_with_capabilities};
 use carina_core::effect::Effect;
 use carina_core::formatter::{self, FormatConfig};
 use carina_core::module_resolver;
-use carina_core::parser::{self, BackendConfig, ParsedFile, TypeExpr};
+use carina_core::parser::{
+    self, BackendConfig, OutputParameter, ParsedFile, ProviderConfig, TypeExpr,
+};
 use carina_core::plan::Plan;
-use carina_core::provider::{BoxFuture, Provider, ProviderError, ProviderResult, ResourceType};
+use carina_core::provider::{
+    BoxFuture, Capabilities, Provider, ProviderError, ProviderErrorKind, ProviderResult,
+    ResourceType,
+};
 use carina_core::resource::{Resource, ResourceId, State, Value};
-use carina_core::schema::ResourceSchema;
-use carina_core::schema::validate_cidr;
+use carina_core::schema::{ResourceSchema, TypeError};
+use carina_core::schema::{cidr_contains, cidrs_overlap, validate_cidr};
 use carina_provider_aws::schemas;
 use carina_provider_awscc::AwsccProvider;
 use carina_state::{
-    BackendConfig as StateBackendConfig, BackendError, LockInfo, ResourceState, StateBackend,
-    StateFile, create_backend, create_local_backend,
+    BackendConfig as StateBackendConfig, BackendError, LockGuard, LockInfo, ResourceState,
+    StateBackend, StateFile, create_backend, create_local_backend,
 };
 use std::collections::HashSet;
 
@@ -33,31 +45,146 @@ use carina_provider_aws::AwsProvider;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Show full AWS SDK error details instead of just the error code and message
+    #[arg(long, global = true)]
+    verbose: bool,
+
+    /// Disable colored output (also disabled by NO_COLOR, or when stdout is not a terminal)
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Ask for input interactively (set to false to fail instead of prompting)
+    #[arg(
+        long,
+        global = true,
+        env = "CARINA_INPUT",
+        default_value_t = true,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        action = ArgAction::Set,
+        value_parser = BoolishValueParser::new()
+    )]
+    input: bool,
+
+    /// Maximum number of concurrent provider operations (state reads, and independent
+    /// changes during apply); 1 applies changes one at a time
+    #[arg(long, global = true, env = "CARINA_PARALLELISM", default_value_t = 10)]
+    parallelism: usize,
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Create a starter configuration with a provider and optional backend
+    Init {
+        /// Path of the .crn file to create
+        #[arg(default_value = "main.crn")]
+        path: PathBuf,
+
+        /// AWS region for the provider (e.g., ap-northeast-1)
+        #[arg(long)]
+        region: Option<String>,
+
+        /// S3 bucket for remote state (omit to skip the backend block)
+        #[arg(long)]
+        backend_bucket: Option<String>,
+
+        /// Object key for the state file in the backend bucket
+        #[arg(long)]
+        backend_key: Option<String>,
+    },
     /// Validate the configuration file
     Validate {
         /// Path to .crn file or directory
         #[arg(default_value = ".")]
         path: PathBuf,
+
+        /// Set an input value (e.g., --var env=prod); may be repeated, and overrides --var-file
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+
+        /// Read input values from a file (a JSON object or `key = value` lines)
+        #[arg(long, value_name = "PATH")]
+        var_file: Option<PathBuf>,
     },
     /// Show execution plan without applying changes
     Plan {
         /// Path to .crn file or directory
         #[arg(default_value = ".")]
         path: PathBuf,
+
+        /// Save the computed plan to this file so `carina apply <file>` runs exactly it
+        #[arg(long)]
+        out: Option<PathBuf>,
+
+        /// Only operate on this resource (resource_type.name or binding name) and its
+        /// dependencies; may be repeated
+        #[arg(long = "target", value_name = "ADDRESS")]
+        targets: Vec<String>,
+
+        /// Print the plan as JSON on stdout instead of the human-readable tree
+        #[arg(long)]
+        json: bool,
+
+        /// Exit with 2 when the plan has changes (0 when it has none, 1 on error)
+        #[arg(long)]
+        detailed_exitcode: bool,
+
+        /// Read every resource from the provider before planning; with
+        /// --refresh=false, plan against the attributes recorded in state instead
+        #[arg(
+            long,
+            default_value_t = true,
+            num_args = 0..=1,
+            default_missing_value = "true",
+            action = ArgAction::Set,
+            value_parser = BoolishValueParser::new()
+        )]
+        refresh: bool,
+
+        /// Set an input value (e.g., --var env=prod); may be repeated, and overrides --var-file
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+
+        /// Read input values from a file (a JSON object or `key = value` lines)
+        #[arg(long, value_name = "PATH")]
+        var_file: Option<PathBuf>,
     },
     /// Apply changes to reach the desired state
     Apply {
-        /// Path to .crn file or directory
+        /// Path to .crn file or directory, or a plan file saved with `plan --out`
         #[arg(default_value = ".")]
         path: PathBuf,
 
         /// Skip confirmation prompt (auto-approve)
         #[arg(long)]
         auto_approve: bool,
+
+        /// Read state and print the plan as apply would, without making any changes
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Set an input value (e.g., --var env=prod); may be repeated, and overrides --var-file
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+
+        /// Read input values from a file (a JSON object or `key = value` lines)
+        #[arg(long, value_name = "PATH")]
+        var_file: Option<PathBuf>,
+
+        /// Only operate on this resource (resource_type.name or binding name) and its
+        /// dependencies; may be repeated
+        #[arg(long = "target", value_name = "ADDRESS")]
+        targets: Vec<String>,
+
+        /// How long to wait for a held state lock (e.g., 30s, 2m); 0 fails immediately
+        #[arg(long, default_value = "0s", value_parser = parse_duration)]
+        lock_timeout: Duration,
+
+        /// Give up on a provider operation that takes longer than this (e.g., 300s, 10m);
+        /// no limit when omitted
+        #[arg(long, value_parser = parse_duration)]
+        operation_timeout: Option<Duration>,
     },
     /// Destroy all resources defined in the configuration file
     Destroy {
@@ -68,6 +195,67 @@ enum Commands {
         /// Skip confirmation prompt (auto-approve)
         #[arg(long)]
         auto_approve: bool,
+
+        /// Only destroy this resource (resource_type.name or binding name) and the
+        /// resources that depend on it; may be repeated
+        #[arg(long = "target", value_name = "ADDRESS")]
+        targets: Vec<String>,
+
+        /// Destroy every resource recorded in state, including ones no longer in
+        /// the configuration
+        #[arg(long, conflicts_with = "targets")]
+        from_state: bool,
+
+        /// Set an input value (e.g., --var env=prod); may be repeated, and overrides --var-file
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+
+        /// Read input values from a file (a JSON object or `key = value` lines)
+        #[arg(long, value_name = "PATH")]
+        var_file: Option<PathBuf>,
+
+        /// How long to wait for a held state lock (e.g., 30s, 2m); 0 fails immediately
+        #[arg(long, default_value = "0s", value_parser = parse_duration)]
+        lock_timeout: Duration,
+
+        /// Give up on a provider operation that takes longer than this (e.g., 300s, 10m);
+        /// no limit when omitted
+        #[arg(long, value_parser = parse_duration)]
+        operation_timeout: Option<Duration>,
+    },
+    /// Update state with the attributes of the real infrastructure
+    Refresh {
+        /// Path to .crn file or directory
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// How long to wait for a held state lock (e.g., 30s, 2m); 0 fails immediately
+        #[arg(long, default_value = "0s", value_parser = parse_duration)]
+        lock_timeout: Duration,
+    },
+    /// Print the resource dependency graph in Graphviz DOT format
+    Graph {
+        /// Path to .crn file or directory
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+    /// Print the values of the configuration's outputs, resolved against the stored state
+    Output {
+        /// Path to .crn file or directory
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Print only this output's value (strings unquoted, for shell capture)
+        name: Option<String>,
+    },
+    /// Evaluate an expression (e.g., 'vpc.id') against the configuration and stored state
+    Eval {
+        /// Expression to evaluate
+        expr: String,
+
+        /// Path to .crn file or directory
+        #[arg(default_value = ".")]
+        path: PathBuf,
     },
     /// Format .crn files
     Fmt {
@@ -106,6 +294,21 @@ enum Commands {
         #[command(subcommand)]
         command: StateCommands,
     },
+    /// List available providers and their resource types
+    Providers {
+        /// Path to .crn file or directory whose provider settings are used
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Only show resource types for this provider (e.g., aws, awscc)
+        #[arg(long)]
+        provider: Option<String>,
+    },
+    /// Show documentation for a resource type's attributes
+    Explain {
+        /// Resource type to describe (e.g., aws.s3.bucket); lists all types when omitted
+        resource_type: Option<String>,
+    },
     /// Generate shell completions
     Completions {
         /// Shell to generate completions for
@@ -121,10 +324,59 @@ enum ModuleCommands {
         /// Path to module .crn file
         file: PathBuf,
     },
+    /// Create a module directory with a skeleton main.crn
+    Init {
+        /// Name of the module directory to create
+        name: String,
+
+        /// Directory to create the module in
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
 }
 
 #[derive(Subcommand)]
 enum StateCommands {
+    /// Print the state stored in the configured backend as JSON
+    Pull {
+        /// Path to .crn file or directory containing backend configuration
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+    /// Write a state file (e.g., from `state pull`) to the configured backend
+    Push {
+        /// JSON state file to push
+        file: PathBuf,
+
+        /// Path to .crn file or directory containing backend configuration
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Push even if the backend's state has a higher serial
+        #[arg(long)]
+        force: bool,
+
+        /// How long to wait for a held state lock (e.g., 30s, 2m); 0 fails immediately
+        #[arg(long, default_value = "0s", value_parser = parse_duration)]
+        lock_timeout: Duration,
+    },
+    /// Rename a resource in state (e.g., after renaming it in the configuration)
+    /// so that it is not destroyed and recreated
+    Mv {
+        /// Current address of the resource (resource_type.name)
+        from_address: String,
+
+        /// New address of the resource, of the same type (resource_type.new_name)
+        to_address: String,
+
+        /// Path to .crn file or directory containing backend configuration
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// How long to wait for a held state lock (e.g., 30s, 2m); 0 fails immediately
+        #[arg(long, default_value = "0s", value_parser = parse_duration)]
+        lock_timeout: Duration,
+    },
     /// Delete state bucket (requires --force flag)
     BucketDelete {
         /// Name of the bucket to delete
@@ -143,12 +395,114 @@ enum StateCommands {
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    VERBOSE.store(cli.verbose, Ordering::Relaxed);
+    if !color_enabled(cli.no_color, std::env::var_os("NO_COLOR").as_deref()) {
+        colored::control::set_override(false);
+    }
 
     let result = match cli.command {
-        Commands::Validate { path } => run_validate(&path),
-        Commands::Plan { path } => run_plan(&path).await,
-        Commands::Apply { path, auto_approve } => run_apply(&path, auto_approve).await,
-        Commands::Destroy { path, auto_approve } => run_destroy(&path, auto_approve).await,
+        Commands::Init {
+            path,
+            region,
+            backend_bucket,
+            backend_key,
+        } => run_init(&path, region, backend_bucket, backend_key, cli.input),
+        Commands::Validate {
+            path,
+            vars,
+            var_file,
+        } => load_input_vars(&vars, var_file.as_deref())
+            .and_then(|input_vars| run_validate(&path, &input_vars, cli.input)),
+        Commands::Plan {
+            path,
+            out,
+            targets,
+            json,
+            detailed_exitcode,
+            refresh,
+            vars,
+            var_file,
+        } => {
+            JSON_OUTPUT.store(json, Ordering::Relaxed);
+            let result = async {
+                let input_vars = load_input_vars(&vars, var_file.as_deref())?;
+                run_plan(
+                    &path,
+                    out.as_deref(),
+                    &targets,
+                    &input_vars,
+                    cli.input,
+                    refresh,
+                    cli.parallelism,
+                )
+                .await
+            }
+            .await;
+            match result {
+                Ok(true) if detailed_exitcode => std::process::exit(2),
+                result => result.map(|_| ()),
+            }
+        }
+        Commands::Apply {
+            path,
+            auto_approve,
+            dry_run,
+            vars,
+            var_file,
+            targets,
+            lock_timeout,
+            operation_timeout,
+        } => {
+            async {
+                let input_vars = load_input_vars(&vars, var_file.as_deref())?;
+                run_apply(
+                    &path,
+                    auto_approve,
+                    dry_run,
+                    &targets,
+                    &input_vars,
+                    cli.input,
+                    cli.parallelism,
+                    lock_timeout,
+                    operation_timeout,
+                )
+                .await
+            }
+            .await
+        }
+        Commands::Destroy {
+            path,
+            auto_approve,
+            targets,
+            from_state,
+            vars,
+            var_file,
+            lock_timeout,
+            operation_timeout,
+        } => {
+            async {
+                let input_vars = load_input_vars(&vars, var_file.as_deref())?;
+                run_destroy(
+                    &path,
+                    auto_approve,
+                    &targets,
+                    from_state,
+                    &input_vars,
+                    cli.input,
+                    cli.parallelism,
+                    lock_timeout,
+                    operation_timeout,
+                )
+                .await
+            }
+            .await
+        }
+        Commands::Refresh { path, lock_timeout } => {
+            run_refresh(&path, cli.parallelism, lock_timeout).await
+        }
+        Commands::Graph { path } => run_graph(&path),
+        Commands::Output { path, name } => run_output(&path, name.as_deref()).await,
+        Commands::Eval { expr, path } => run_eval(&expr, &path).await,
         Commands::Fmt {
             path,
             check,
@@ -157,7 +511,9 @@ async fn main() {
         } => run_fmt(&path, check, diff, recursive),
         Commands::Module { command } => run_module_command(command),
         Commands::ForceUnlock { lock_id, path } => run_force_unlock(&lock_id, &path).await,
-        Commands::State { command } => run_state_command(command).await,
+        Commands::State { command } => run_state_command(command, cli.input).await,
+        Commands::Providers { path, provider } => run_providers(&path, provider.as_deref()).await,
+        Commands::Explain { resource_type } => run_explain(resource_type.as_deref()),
         Commands::Completions { shell } => {
             generate(shell, &mut Cli::command(), "carina", &mut std::io::stdout());
             Ok(())
@@ -170,6 +526,66 @@ async fn main() {
     }
 }
 
+/// Whether `--verbose` was given (read when formatting provider errors)
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Whether stdout is reserved for machine-readable output (`plan --json`)
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Set while apply or destroy stop on Ctrl-C themselves, saving state before exiting
+static INTERRUPTS_HANDLED: AtomicBool = AtomicBool::new(false);
+
+/// Print a status line to stdout, or to stderr when stdout carries `--json` output
+macro_rules! notice {
+    ($($arg:tt)*) => {
+        if JSON_OUTPUT.load(Ordering::Relaxed) {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// Whether output should be colored: not with `--no-color`, not when `NO_COLOR` is set to a
+/// non-empty value (https://no-color.org), and not when stdout is piped or redirected
+fn color_enabled(no_color_flag: bool, no_color_env: Option<&std::ffi::OsStr>) -> bool {
+    !no_color_flag
+        && no_color_env.is_none_or(|value| value.is_empty())
+        && std::io::stdout().is_terminal()
+}
+
+/// Format a provider error, with a hint for permission and throttling failures
+/// and the underlying SDK error appended in verbose mode
+fn format_provider_error(e: &ProviderError) -> String {
+    let message = match e.kind {
+        ProviderErrorKind::AccessDenied => format!(
+            "{} (access denied: check the permissions of the credentials, profile or assumed role in use)",
+            e
+        ),
+        ProviderErrorKind::Throttled => format!(
+            "{} (throttled by the provider: retry later or lower --parallelism)",
+            e
+        ),
+        _ => e.to_string(),
+    };
+    match &e.cause {
+        Some(cause) if VERBOSE.load(Ordering::Relaxed) => format!("{}\n{:?}", message, cause),
+        _ => message,
+    }
+}
+
+/// Fail with a descriptive error when interactive input is needed but disabled
+fn require_input(input: bool, what: &str, hint: &str) -> Result<(), String> {
+    if input {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} required but interactive input is disabled by --input=false ({})",
+            what, hint
+        ))
+    }
+}
+
 fn get_schemas() -> HashMap<String, ResourceSchema> {
     let mut all_schemas = HashMap::new();
     for schema in schemas::all_schemas() {
@@ -186,6 +602,17 @@ fn validate_resources(resources: &[Resource]) -> Result<(), String> {
     let schemas = get_schemas();
     let mut all_errors = Vec::new();
 
+    // A cycle leaves no valid order to create the resources in
+    if let Some(cycle) = find_dependency_cycle(resources) {
+        all_errors.push(format!("Circular dependency: {}", cycle.join(" -> ")));
+    }
+    all_errors.extend(validate_network_topology(resources));
+
+    // Validate in a stable order so error output is the same on every run
+    let mut resources: Vec<&Resource> = resources.iter().collect();
+    resources
+        .sort_by(|a, b| (&a.id.resource_type, &a.id.name).cmp(&(&b.id.resource_type, &b.id.name)));
+
     for resource in resources {
         // Construct schema key based on provider
         // For aws provider, use just the resource_type (e.g., "vpc")
@@ -197,15 +624,43 @@ fn validate_resources(resources: &[Resource]) -> Result<(), String> {
             _ => resource.id.resource_type.clone(),
         };
 
+        // The aws provider cannot unset attributes, so a null would plan an update
+        // that never takes effect
+        if schema_key == resource.id.resource_type {
+            let mut nulls: Vec<&String> = resource
+                .attributes
+                .iter()
+                .filter(|(key, value)| !key.starts_with('_') && **value == Value::Null)
+                .map(|(key, _)| key)
+                .collect();
+            nulls.sort();
+            for attribute in nulls {
+                let location = resource
+                    .location_of(attribute)
+                    .map(|l| format!("{}: ", l))
+                    .unwrap_or_default();
+                all_errors.push(format!(
+                    "{}{}.{}: {}: null is not supported by the aws provider",
+                    location, resource.id.resource_type, resource.id.name, attribute
+                ));
+            }
+        }
+
         match schemas.get(&schema_key) {
             Some(schema) => {
-                if let Err(errors) = schema.validate(&resource.attributes) {
-                    for error in errors {
-                        all_errors.push(format!(
-                            "{}.{}: {}",
-                            resource.id.resource_type, resource.id.name, error
-                        ));
-                    }
+                for (attribute, error) in schema.attribute_errors(&resource.attributes) {
+                    let location = resource
+                        .location_of(&attribute)
+                        .map(|l| format!("{}: ", l))
+                        .unwrap_or_default();
+                    let attribute = match error {
+                        TypeError::MissingRequired { .. } => String::new(),
+                        _ => format!("{}: ", attribute),
+                    };
+                    all_errors.push(format!(
+                        "{}{}.{}: {}{}",
+                        location, resource.id.resource_type, resource.id.name, attribute, error
+                    ));
                 }
             }
             None => {
@@ -221,10 +676,338 @@ fn validate_resources(resources: &[Resource]) -> Result<(), String> {
     }
 }
 
+/// Check subnet CIDR blocks across resources: subnets of the same VPC must not
+/// overlap, and each must lie within one of its VPC's CIDR blocks (primary or
+/// secondary) when the VPC is declared here. Invalid CIDRs are left to schema
+/// validation. Instances must also use security groups of their subnet's VPC, as
+/// far as both are declared here.
+fn validate_network_topology(resources: &[Resource]) -> Vec<String> {
+    let label = |resource: &Resource| format!("{}.{}", resource.id.resource_type, resource.id.name);
+    let cidr_of = |resource: &Resource| match resource.attributes.get("cidr_block") {
+        Some(Value::String(cidr)) if validate_cidr(cidr).is_ok() => Some(cidr.clone()),
+        _ => None,
+    };
+    // The binding (or literal id) a reference attribute points at
+    let target_of = |value: &Value| match value {
+        Value::ResourceRef(binding, _) => Some(binding.clone()),
+        Value::TypedResourceRef { binding_name, .. } => Some(binding_name.clone()),
+        Value::String(id) => Some(id.clone()),
+        _ => None,
+    };
+    let vpc_of = |resource: &Resource| resource.attributes.get("vpc_id").and_then(target_of);
+    let bindings = |resource_type: &str| -> HashMap<String, &Resource> {
+        resources
+            .iter()
+            .filter(|r| r.id.resource_type == resource_type)
+            .filter_map(|r| match r.attributes.get("_binding") {
+                Some(Value::String(binding)) => Some((binding.clone(), r)),
+                _ => None,
+            })
+            .collect()
+    };
+
+    // VPCs by binding name, so subnets can find them through `vpc_id = vpc.id`
+    let vpcs: HashMap<String, &Resource> = resources
+        .iter()
+        .filter(|r| matches!(r.id.resource_type.as_str(), "vpc" | "ec2_vpc"))
+        .filter_map(|r| match r.attributes.get("_binding") {
+            Some(Value::String(binding)) => Some((binding.clone(), r)),
+            _ => None,
+        })
+        .collect();
+
+    // Subnets grouped by the VPC binding (or literal VPC id) they belong to
+    let mut subnets_by_vpc: BTreeMap<String, Vec<(&Resource, String)>> = BTreeMap::new();
+    for resource in resources {
+        if !matches!(resource.id.resource_type.as_str(), "subnet" | "ec2_subnet") {
+            continue;
+        }
+        let Some(vpc) = vpc_of(resource) else {
+            continue;
+        };
+        if let Some(cidr) = cidr_of(resource) {
+            subnets_by_vpc
+                .entry(vpc)
+                .or_default()
+                .push((resource, cidr));
+        }
+    }
+
+    let mut errors = Vec::new();
+    for (vpc, mut subnets) in subnets_by_vpc {
+        subnets.sort_by_key(|(subnet, _)| label(subnet));
+
+        if let Some(vpc_resource) = vpcs.get(&vpc)
+            && let Some(vpc_cidr) = cidr_of(vpc_resource)
+        {
+            let mut vpc_cidrs = vec![vpc_cidr];
+            if let Some(Value::List(secondary)) =
+                vpc_resource.attributes.get("secondary_cidr_blocks")
+            {
+                vpc_cidrs.extend(secondary.iter().filter_map(|cidr| match cidr {
+                    Value::String(cidr) if validate_cidr(cidr).is_ok() => Some(cidr.clone()),
+                    _ => None,
+                }));
+            }
+            for (subnet, cidr) in &subnets {
+                if !vpc_cidrs
+                    .iter()
+                    .any(|vpc_cidr| cidr_contains(vpc_cidr, cidr).unwrap_or(true))
+                {
+                    errors.push(format!(
+                        "{}: CIDR {} is outside {} ({})",
+                        label(subnet),
+                        cidr,
+                        label(vpc_resource),
+                        vpc_cidrs.join(", ")
+                    ));
+                }
+            }
+        }
+
+        for (i, (subnet, cidr)) in subnets.iter().enumerate() {
+            for (other, other_cidr) in &subnets[i + 1..] {
+                if cidrs_overlap(cidr, other_cidr).unwrap_or(false) {
+                    errors.push(format!(
+                        "{} ({}) overlaps {} ({})",
+                        label(subnet),
+                        cidr,
+                        label(other),
+                        other_cidr
+                    ));
+                }
+            }
+        }
+    }
+
+    // AWS rejects security groups of another VPC than the instance's subnet
+    let subnets = bindings("subnet");
+    let security_groups = bindings("security_group");
+    let mut instances: Vec<&Resource> = resources
+        .iter()
+        .filter(|r| r.id.resource_type == "ec2.instance")
+        .collect();
+    instances.sort_by_key(|instance| label(instance));
+    for instance in instances {
+        let Some(subnet) = instance
+            .attributes
+            .get("subnet_id")
+            .and_then(target_of)
+            .and_then(|binding| subnets.get(&binding))
+        else {
+            continue;
+        };
+        let Some(vpc) = vpc_of(subnet) else {
+            continue;
+        };
+        let Some(Value::List(groups)) = instance.attributes.get("security_group_ids") else {
+            continue;
+        };
+        for group in groups
+            .iter()
+            .filter_map(target_of)
+            .filter_map(|binding| security_groups.get(&binding))
+        {
+            if let Some(group_vpc) = vpc_of(group)
+                && group_vpc != vpc
+            {
+                errors.push(format!(
+                    "{}: {} is in VPC {}, but {} is in VPC {}",
+                    label(instance),
+                    label(group),
+                    group_vpc,
+                    label(subnet),
+                    vpc
+                ));
+            }
+        }
+    }
+    errors
+}
+
+/// Run init command
+fn run_init(
+    path: &Path,
+    region: Option<String>,
+    backend_bucket: Option<String>,
+    backend_key: Option<String>,
+    input: bool,
+) -> Result<(), String> {
+    if path.exists() {
+        return Err(format!(
+            "{} already exists. Refusing to overwrite it.",
+            path.display()
+        ));
+    }
+
+    let region = match region {
+        Some(region) => region,
+        None if input => prompt_line("AWS region", Some("ap-northeast-1"))?,
+        None => "ap-northeast-1".to_string(),
+    };
+    let region_type = carina_provider_aws::schemas::types::aws_region();
+    region_type
+        .validate(&Value::String(region.clone()))
+        .map_err(|e| e.to_string())?;
+    let region_dsl = format!(
+        "aws.Region.{}",
+        region
+            .rsplit('.')
+            .next()
+            .unwrap_or(&region)
+            .replace('-', "_")
+    );
+
+    let backend_bucket = match backend_bucket {
+        Some(bucket) => Some(bucket),
+        None if input => {
+            let bucket = prompt_line("S3 bucket for remote state (empty to skip)", None)?;
+            (!bucket.is_empty()).then_some(bucket)
+        }
+        None => None,
+    };
+
+    let backend_key = match (&backend_bucket, backend_key) {
+        (Some(_), Some(key)) => Some(key),
+        (Some(_), None) if input => Some(prompt_line("State file key", Some("carina.crnstate"))?),
+        (Some(_), None) => Some("carina.crnstate".to_string()),
+        (None, Some(_)) => return Err("--backend-key requires --backend-bucket".to_string()),
+        (None, None) => None,
+    };
+
+    let content = init_config(
+        &region_dsl,
+        backend_bucket.as_deref().zip(backend_key.as_deref()),
+    );
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    println!("{} {}", "Created".green().bold(), path.display());
+    if backend_bucket.is_some() {
+        println!(
+            "  The state bucket will be created on the first `carina apply` if it does not exist."
+        );
+    }
+    Ok(())
+}
+
+/// Render the configuration written by `carina init`, with an optional S3 backend
+/// given as (bucket, key)
+fn init_config(region_dsl: &str, backend: Option<(&str, &str)>) -> String {
+    let mut content = String::new();
+    if let Some((bucket, key)) = backend {
+        content.push_str(&format!(
+            "backend s3 {{\n  bucket = {}\n  key = {}\n  region = {}\n}}\n\n",
+            string_literal(bucket),
+            string_literal(key),
+            region_dsl
+        ));
+    }
+    content.push_str(&format!("provider aws {{\n  region = {}\n}}\n", region_dsl));
+    formatter::format(&content, &FormatConfig::default()).unwrap_or(content)
+}
+
+/// Quote a value as a DSL string literal, escaping quotes, backslashes and `$`
+fn string_literal(value: &str) -> String {
+    let mut literal = String::from('"');
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | '$') {
+            literal.push('\\');
+        }
+        literal.push(c);
+    }
+    literal.push('"');
+    literal
+}
+
+/// Prompt for a single line of input, falling back to the default on empty input
+fn prompt_line(message: &str, default: Option<&str>) -> Result<String, String> {
+    match default {
+        Some(default) => print!("{} [{}]: ", message, default),
+        None => print!("{}: ", message),
+    }
+    std::io::Write::flush(&mut std::io::stdout()).map_err(|e| e.to_string())?;
+
+    let mut line = String::new();
+    std::io::stdin()
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+
+    let line = line.trim();
+    Ok(if line.is_empty() {
+        default.unwrap_or_default().to_string()
+    } else {
+        line.to_string()
+    })
+}
+
 fn run_module_command(command: ModuleCommands) -> Result<(), String> {
     match command {
         ModuleCommands::Info { file } => run_module_info(&file),
+        ModuleCommands::Init { name, path } => run_module_init(&name, &path),
+    }
+}
+
+fn run_module_init(name: &str, path: &Path) -> Result<(), String> {
+    // The name doubles as the alias callers import the module as
+    let mut chars = name.chars();
+    if !chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(format!(
+            "Invalid module name '{}': use a letter followed by letters, digits or underscores (e.g. {})",
+            name,
+            name.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+        ));
     }
+
+    let dir = path.join(name);
+    if dir.is_file() || fs::read_dir(&dir).is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(format!(
+            "{} already exists and is not empty. Refusing to overwrite it.",
+            dir.display()
+        ));
+    }
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let module_name = derive_module_name(&dir);
+    let main = dir.join("main.crn");
+    fs::write(&main, module_skeleton(&module_name))
+        .map_err(|e| format!("Failed to write {}: {}", main.display(), e))?;
+
+    println!("{} {}", "Created".green().bold(), main.display());
+    println!(
+        "  Use it with: import \"{}\" as {}",
+        dir.display(),
+        module_name
+    );
+    Ok(())
+}
+
+/// The main.crn of a new module: empty input and output blocks and a commented example
+fn module_skeleton(module_name: &str) -> String {
+    format!(
+        r#"# {name} module
+#
+# Declare the values callers pass in `input`, and the values they can
+# reference in `output`.
+
+input {{
+  # vpc: ref(aws.vpc)
+}}
+
+output {{
+  # security_group: ref(aws.security_group) = sg.id
+}}
+
+# Example resource:
+#
+# let sg = aws.security_group {{
+#   name   = "{name}-sg"
+#   vpc_id = input.vpc
+# }}
+"#,
+        name = module_name
+    )
 }
 
 fn run_module_info(path: &PathBuf) -> Result<(), String> {
@@ -264,16 +1047,21 @@ fn load_module_from_directory(dir: &PathBuf) -> Result<ParsedFile, String> {
         backend: None,
     };
 
+    let mut paths = Vec::new();
     for entry in entries {
         let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
+        paths.push(entry.path());
+    }
+    paths.sort();
 
+    for path in paths {
         if path.extension().is_some_and(|ext| ext == "crn") {
             let content = fs::read_to_string(&path)
                 .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
 
-            let parsed = parser::parse(&content)
+            let mut parsed = parser::parse(&content)
                 .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+            parsed.set_source_file(&path.display().to_string());
 
             // Merge parsed content
             merged.providers.extend(parsed.providers);
@@ -323,20 +1111,32 @@ fn derive_module_name(path: &Path) -> String {
     file_stem.to_string()
 }
 
-/// Validate provider region attribute
+/// Validate provider region attribute, and that credential attributes are strings
 fn validate_provider_region(parsed: &ParsedFile) -> Result<(), String> {
     // Use the same region type for both aws and awscc providers
     let region_type = carina_provider_aws::schemas::types::aws_region();
 
     for provider in &parsed.providers {
-        if (provider.name == "aws" || provider.name == "awscc")
-            && let Some(region_value) = provider.attributes.get("region")
+        if provider.name != "aws" && provider.name != "awscc" {
+            continue;
+        }
+        if let Some(region_value) = provider.attributes.get("region")
             && let Err(e) = region_type.validate(region_value)
         {
             return Err(format!("provider {}: {}", provider.name, e));
         }
-    }
-    Ok(())
+        for key in ["profile", "assume_role_arn", "endpoint_url"] {
+            if let Some(value) = provider.attributes.get(key)
+                && !matches!(value, Value::String(_))
+            {
+                return Err(format!(
+                    "provider {}: {} must be a string",
+                    provider.name, key
+                ));
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Validate module call arguments against module input types
@@ -405,8 +1205,10 @@ fn load_directory_module(dir_path: &Path) -> Option<ParsedFile> {
         backend: None,
     };
 
-    for entry in entries.flatten() {
-        let path = entry.path();
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+
+    for path in paths {
         if path.extension().is_some_and(|ext| ext == "crn")
             && let Ok(content) = fs::read_to_string(&path)
             && let Ok(parsed) = parser::parse(&content)
@@ -440,8 +1242,9 @@ fn load_configuration(path: &PathBuf) -> Result<LoadedConfig, String> {
         // Single file mode (existing behavior)
         let content = fs::read_to_string(path)
             .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
-        let parsed =
+        let mut parsed =
             parser::parse_and_resolve(&content).map_err(|e| format!("Parse error: {}", e))?;
+        parsed.set_source_file(&path.display().to_string());
         let backend_file = if parsed.backend.is_some() {
             Some(path.clone())
         } else {
@@ -475,7 +1278,8 @@ fn load_configuration(path: &PathBuf) -> Result<LoadedConfig, String> {
             let content = fs::read_to_string(file)
                 .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
             match parser::parse_and_resolve(&content) {
-                Ok(parsed) => {
+                Ok(mut parsed) => {
+                    parsed.set_source_file(&file.display().to_string());
                     merged.providers.extend(parsed.providers);
                     merged.resources.extend(parsed.resources);
                     merged.variables.extend(parsed.variables);
@@ -550,8 +1354,193 @@ fn validate_module_arg_type(type_expr: &TypeExpr, value: &Value) -> Option<Strin
     }
 }
 
-fn run_validate(path: &PathBuf) -> Result<(), String> {
+/// Read the input values given with `--var key=value` and `--var-file`, before they are
+/// coerced to the declared types. `--var` values override the file's.
+fn load_input_vars(
+    vars: &[String],
+    var_file: Option<&Path>,
+) -> Result<HashMap<String, Value>, String> {
+    let mut input_vars = match var_file {
+        Some(file) => {
+            let content = fs::read_to_string(file)
+                .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            parse_var_file(&content).map_err(|e| format!("{}: {}", file.display(), e))?
+        }
+        None => HashMap::new(),
+    };
+
+    for var in vars {
+        let (key, value) = var
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid --var '{}', expected KEY=VALUE", var))?;
+        input_vars.insert(key.trim().to_string(), Value::String(value.to_string()));
+    }
+    Ok(input_vars)
+}
+
+/// Parse a var file: either a JSON object, or `key = value` lines where `#` starts a
+/// comment and a double-quoted value is taken verbatim
+fn parse_var_file(content: &str) -> Result<HashMap<String, Value>, String> {
+    if content.trim_start().starts_with('{') {
+        let json: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(content).map_err(|e| format!("Invalid JSON: {}", e))?;
+        return Ok(json
+            .iter()
+            .map(|(k, v)| (k.clone(), json_to_value(v)))
+            .collect());
+    }
+
+    let mut input_vars = HashMap::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected key = value", i + 1))?;
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        input_vars.insert(key.trim().to_string(), Value::String(value.to_string()));
+    }
+    Ok(input_vars)
+}
+
+/// Give each declared `input {}` parameter its supplied value, or else its default, and
+/// substitute the `input.*` references. Returns the values used.
+///
+/// A required input without a value is prompted for on a terminal, unless `input` is false
+/// (`--input=false`), in which case it is an error.
+fn apply_input_vars(
+    parsed: &mut ParsedFile,
+    input_vars: &HashMap<String, Value>,
+    input: bool,
+) -> Result<HashMap<String, Value>, String> {
+    let mut errors = Vec::new();
+
+    let mut undeclared: Vec<&String> = input_vars
+        .keys()
+        .filter(|name| !parsed.inputs.iter().any(|input| &input.name == *name))
+        .collect();
+    undeclared.sort();
+    for name in undeclared {
+        errors.push(format!(
+            "Input '{}' is not declared in an input block",
+            name
+        ));
+    }
+
+    let mut values = HashMap::new();
+    for decl in &parsed.inputs {
+        let hint = format!("set it with --var {}=<value> or --var-file", decl.name);
+        let supplied = match (input_vars.get(&decl.name), &decl.default) {
+            (Some(value), _) => value.clone(),
+            (None, Some(default)) => {
+                values.insert(decl.name.clone(), default.clone());
+                continue;
+            }
+            (None, None) => {
+                if let Err(e) = require_input(input, &format!("Input '{}'", decl.name), &hint) {
+                    errors.push(e);
+                    continue;
+                }
+                if !std::io::stdin().is_terminal() {
+                    errors.push(format!("Input '{}' is required; {}", decl.name, hint));
+                    continue;
+                }
+                match prompt_line(
+                    &format!("Value for input '{}' ({})", decl.name, decl.type_expr),
+                    None,
+                ) {
+                    Ok(line) => Value::String(line),
+                    Err(e) => {
+                        errors.push(format!("Input '{}': {}", decl.name, e));
+                        continue;
+                    }
+                }
+            }
+        };
+        match coerce_input(&decl.type_expr, supplied) {
+            Ok(value) => {
+                values.insert(decl.name.clone(), value);
+            }
+            Err(e) => errors.push(format!("Input '{}': {}", decl.name, e)),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+    module_resolver::substitute_root_inputs(parsed, &values);
+    Ok(values)
+}
+
+/// Coerce a supplied input value to its declared type. Values given as text (from `--var`
+/// or a `key = value` file) are parsed; lists and maps are written as JSON.
+fn coerce_input(type_expr: &TypeExpr, value: Value) -> Result<Value, String> {
+    let coerced = match (type_expr, value) {
+        (TypeExpr::Int, Value::String(s)) => s
+            .trim()
+            .parse()
+            .map(Value::Int)
+            .map_err(|_| format!("expected int, got \"{}\"", s))?,
+        (TypeExpr::Bool, Value::String(s)) => match s.trim() {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => {
+                return Err(format!("expected bool, got \"{}\". Use true or false.", s));
+            }
+        },
+        (TypeExpr::List(_) | TypeExpr::Map(_), Value::String(s)) => {
+            let json: serde_json::Value = serde_json::from_str(&s)
+                .map_err(|_| format!("expected {} written as JSON, got \"{}\"", type_expr, s))?;
+            return coerce_input(type_expr, json_to_value(&json));
+        }
+        (TypeExpr::List(inner), Value::List(items)) => Value::List(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    coerce_input(inner, item).map_err(|e| format!("element {}: {}", i, e))
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        (TypeExpr::Map(inner), Value::Map(map)) => Value::Map(
+            map.into_iter()
+                .map(|(k, v)| match coerce_input(inner, v) {
+                    Ok(v) => Ok((k, v)),
+                    Err(e) => Err(format!("key '{}': {}", k, e)),
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        (TypeExpr::String | TypeExpr::Cidr | TypeExpr::Ref(_), value @ Value::String(_))
+        | (TypeExpr::Int, value @ Value::Int(_))
+        | (TypeExpr::Bool, value @ Value::Bool(_)) => value,
+        (_, value) => {
+            return Err(format!(
+                "expected {}, got {}",
+                type_expr,
+                format_value(&value)
+            ));
+        }
+    };
+
+    match validate_module_arg_type(type_expr, &coerced) {
+        Some(e) => Err(e),
+        None => Ok(coerced),
+    }
+}
+
+fn run_validate(
+    path: &PathBuf,
+    input_vars: &HashMap<String, Value>,
+    input: bool,
+) -> Result<(), String> {
     let mut parsed = load_configuration(path)?.parsed;
+    apply_input_vars(&mut parsed, input_vars, input)?;
 
     let base_dir = get_base_dir(path);
 
@@ -589,9 +1578,245 @@ fn run_validate(path: &PathBuf) -> Result<(), String> {
     Ok(())
 }
 
-async fn run_plan(path: &PathBuf) -> Result<(), String> {
+fn run_graph(path: &PathBuf) -> Result<(), String> {
+    let mut parsed = load_configuration(path)?.parsed;
+
+    // Resolve module imports and expand module calls
+    let base_dir = get_base_dir(path);
+    module_resolver::resolve_modules(&mut parsed, base_dir)
+        .map_err(|e| format!("Module resolution error: {}", e))?;
+
+    print!("{}", dependency_graph_dot(&parsed.resources));
+    Ok(())
+}
+
+/// Render resources as a Graphviz DOT digraph with an edge from each dependency
+/// to its dependent. Cycles are emitted as-is; DOT handles them.
+fn dependency_graph_dot(resources: &[Resource]) -> String {
+    let address =
+        |resource: &Resource| format!("{}.{}", resource.id.resource_type, resource.id.name);
+
+    let mut binding_to_address: HashMap<String, String> = HashMap::new();
+    for resource in resources {
+        if let Some(Value::String(binding_name)) = resource.attributes.get("_binding") {
+            binding_to_address.insert(binding_name.clone(), address(resource));
+        }
+    }
+
+    let nodes: BTreeSet<String> = resources.iter().map(address).collect();
+    let mut edges: BTreeSet<(String, String)> = BTreeSet::new();
+    for resource in resources {
+        for dep in get_resource_dependencies(resource) {
+            if let Some(dep_address) = binding_to_address.get(&dep) {
+                edges.insert((dep_address.clone(), address(resource)));
+            }
+        }
+    }
+
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut dot = String::from("digraph carina {\n    rankdir = LR;\n");
+    for node in &nodes {
+        dot.push_str(&format!("    {};\n", quote(node)));
+    }
+    for (from, to) in &edges {
+        dot.push_str(&format!("    {} -> {};\n", quote(from), quote(to)));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+async fn run_output(path: &PathBuf, name: Option<&str>) -> Result<(), String> {
+    let mut parsed = load_configuration(path)?.parsed;
+
+    // Resolve module imports and expand module calls
+    let base_dir = get_base_dir(path);
+    module_resolver::resolve_modules(&mut parsed, base_dir)
+        .map_err(|e| format!("Module resolution error: {}", e))?;
+
+    if parsed.outputs.is_empty() {
+        return Err(format!("No outputs are declared in {}", path.display()));
+    }
+
+    let state_file = read_stored_state(&parsed).await?;
+    let binding_map = output_binding_map(&parsed.resources, state_file.as_ref());
+
+    // Prefer the value recorded by the last apply; resolve outputs it didn't record
+    let lookup = |output: &OutputParameter| match state_file
+        .as_ref()
+        .and_then(|state| state.outputs.get(&output.name))
+    {
+        Some(json) => json_to_value(json),
+        None => resolve_output(output, &binding_map),
+    };
+
+    match name {
+        Some(name) => {
+            let output = parsed
+                .outputs
+                .iter()
+                .find(|output| output.name == name)
+                .ok_or_else(|| {
+                    let declared: Vec<&str> =
+                        parsed.outputs.iter().map(|o| o.name.as_str()).collect();
+                    format!(
+                        "Output '{}' is not declared (available: {})",
+                        name,
+                        declared.join(", ")
+                    )
+                })?;
+            // A bare string prints unquoted so `$(carina output . name)` captures the value itself
+            match lookup(output) {
+                Value::String(s) => println!("{}", s),
+                value => println!("{}", format_output(&value)),
+            }
+        }
+        None => {
+            for output in &parsed.outputs {
+                let value = lookup(output);
+                println!("{} = {}", output.name, format_output(&value));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Evaluate a DSL expression against the configuration and the stored state
+async fn run_eval(expr: &str, path: &PathBuf) -> Result<(), String> {
     let mut parsed = load_configuration(path)?.parsed;
 
+    // Resolve module imports and expand module calls
+    let base_dir = get_base_dir(path);
+    module_resolver::resolve_modules(&mut parsed, base_dir)
+        .map_err(|e| format!("Module resolution error: {}", e))?;
+
+    let value = parser::parse_expression_in(expr, &parsed)
+        .map_err(|e| format!("Invalid expression: {}", e))?;
+    let state_file = read_stored_state(&parsed).await?;
+    let binding_map = output_binding_map(&parsed.resources, state_file.as_ref());
+    println!("{}", format_eval(&resolve_ref_value(&value, &binding_map)));
+    Ok(())
+}
+
+/// Format an evaluated expression, explaining what is missing when it can't be resolved
+fn format_eval(value: &Value) -> String {
+    if value.is_known() {
+        format_value(value)
+    } else {
+        format!(
+            "(unresolved: {} is not recorded in state; run `carina apply`)",
+            format_value(value)
+        )
+    }
+}
+
+/// Read the state from the configured backend, or the local one when there is none
+async fn read_stored_state(parsed: &ParsedFile) -> Result<Option<StateFile>, String> {
+    let backend: Box<dyn StateBackend> = if let Some(config) = parsed.backend.as_ref() {
+        let state_config = convert_backend_config(config);
+        create_backend(&state_config)
+            .await
+            .map_err(|e| format!("Failed to create backend: {}", e))?
+    } else {
+        create_local_backend()
+    };
+    backend
+        .read_state()
+        .await
+        .map_err(|e| format!("Failed to read state: {}", e))
+}
+
+/// Build the binding map outputs are resolved against: each binding's DSL attributes,
+/// overlaid with what state recorded for it. The state identifier is exposed as `id`,
+/// so an output of `ref(...)` type prints the underlying resource id.
+fn output_binding_map(
+    resources: &[Resource],
+    state_file: Option<&StateFile>,
+) -> HashMap<String, HashMap<String, Value>> {
+    let mut binding_map: HashMap<String, HashMap<String, Value>> = HashMap::new();
+
+    for resource in resources {
+        let Some(Value::String(binding_name)) = resource.attributes.get("_binding") else {
+            continue;
+        };
+        let mut attrs = resource.attributes.clone();
+        if let Some(resource_state) = state_file
+            .and_then(|state| state.find_resource(&resource.id.resource_type, &resource.id.name))
+        {
+            for (k, v) in &resource_state.attributes {
+                attrs.insert(k.clone(), json_to_value(v));
+            }
+            if let Some(identifier) = &resource_state.identifier {
+                attrs
+                    .entry("id".to_string())
+                    .or_insert_with(|| Value::String(identifier.clone()));
+            }
+        }
+        binding_map.insert(binding_name.clone(), attrs);
+    }
+
+    binding_map
+}
+
+/// Resolve an output's value; an output declared without a value stays unknown
+fn resolve_output(
+    output: &OutputParameter,
+    binding_map: &HashMap<String, HashMap<String, Value>>,
+) -> Value {
+    match &output.value {
+        Some(value) => resolve_ref_value(value, binding_map),
+        None => Value::UnresolvedIdent(output.name.clone(), None),
+    }
+}
+
+/// Record the resolved value of each declared output in state. Outputs that can't be
+/// resolved yet keep their previous value. Returns whether the recorded outputs changed.
+fn record_outputs(
+    state: &mut StateFile,
+    outputs: &[OutputParameter],
+    resources: &[Resource],
+) -> bool {
+    let binding_map = output_binding_map(resources, Some(state));
+    let mut recorded = BTreeMap::new();
+    for output in outputs {
+        let value = resolve_output(output, &binding_map);
+        if value.is_known() {
+            recorded.insert(output.name.clone(), value_to_json(&value));
+        } else if let Some(previous) = state.outputs.get(&output.name) {
+            recorded.insert(output.name.clone(), previous.clone());
+        }
+    }
+
+    let changed = recorded != state.outputs;
+    state.outputs = recorded;
+    changed
+}
+
+/// Format a resolved output, with a placeholder for values that state can't supply yet
+fn format_output(value: &Value) -> String {
+    if value.is_known() {
+        format_value(value)
+    } else {
+        format!(
+            "(unknown: {} is not recorded in state; run `carina apply`)",
+            format_value(value)
+        )
+    }
+}
+
+/// Compute and print the plan. Returns whether it has changes.
+async fn run_plan(
+    path: &PathBuf,
+    out: Option<&Path>,
+    targets: &[String],
+    input_vars: &HashMap<String, Value>,
+    input: bool,
+    refresh: bool,
+    parallelism: usize,
+) -> Result<bool, String> {
+    let loaded = load_configuration(path)?;
+    let mut parsed = loaded.parsed;
+    apply_input_vars(&mut parsed, input_vars, input)?;
+
     // Resolve module imports and expand module calls
     let base_dir = get_base_dir(path);
     module_resolver::resolve_modules(&mut parsed, base_dir)
@@ -605,6 +1830,8 @@ async fn run_plan(path: &PathBuf) -> Result<(), String> {
 
     validate_resources(&parsed.resources)?;
 
+    parsed.resources = select_targets(&parsed.resources, targets, TargetScope::Dependencies);
+
     // Check for backend configuration and load state
     // Use local backend by default if no backend is configured
     let mut will_create_state_bucket = false;
@@ -680,33 +1907,114 @@ async fn run_plan(path: &PathBuf) -> Result<(), String> {
 
     // Show bootstrap plan if needed
     if will_create_state_bucket {
-        println!("{}", "Bootstrap Plan:".cyan().bold());
-        println!(
+        notice!("{}", "Bootstrap Plan:".cyan().bold());
+        notice!(
             "  {} {} (state bucket with versioning enabled)",
             "+".green(),
             format!("aws.s3.bucket.{}", state_bucket_name).green()
         );
-        println!(
+        notice!(
             "  {} Resource definition will be added to .crn file",
             "→".cyan()
         );
-        println!();
+        notice!();
     }
 
-    let plan = create_plan_from_parsed(&parsed, &state_file).await?;
-    print_plan(&plan);
-    Ok(())
+    if will_create_state_bucket && out.is_some() {
+        return Err(
+            "Cannot save a plan before the state bucket exists; run 'carina apply' first"
+                .to_string(),
+        );
+    }
+
+    warn_hardcoded_references(&parsed.resources, &state_file);
+    warn_possible_renames(&parsed.resources, &state_file);
+
+    let (plan, resources, current_states, drift) =
+        create_plan_from_parsed(&parsed, &state_file, refresh, parallelism).await?;
+    if JSON_OUTPUT.load(Ordering::Relaxed) {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&plan_to_json(&plan, &drift)).map_err(|e| e.to_string())?
+        );
+    } else {
+        print_drift(&drift);
+        print_plan(&plan);
+    }
+    check_prevent_destroy(&plan)?;
+    let has_changes = plan.mutation_count() > 0;
+
+    if let Some(out) = out {
+        let saved = SavedPlan {
+            providers: parsed.providers,
+            backend: parsed.backend,
+            backend_file: loaded
+                .backend_file
+                .map(|file| fs::canonicalize(&file).unwrap_or(file)),
+            outputs: parsed.outputs,
+            lineage: state_file.as_ref().map(|s| s.lineage.clone()),
+            serial: state_file.as_ref().map(|s| s.serial),
+            resources,
+            current_states: current_states.into_values().collect(),
+            plan,
+        };
+        saved.save(out)?;
+        notice!();
+        notice!(
+            "Saved plan to {}. Apply it with: carina apply {}",
+            out.display().to_string().cyan(),
+            out.display()
+        );
+    }
+    Ok(has_changes)
 }
 
-async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
-    let loaded = load_configuration(path)?;
-    let mut parsed = loaded.parsed;
-    let backend_file = loaded.backend_file;
+#[allow(clippy::too_many_arguments)]
+async fn run_apply(
+    path: &PathBuf,
+    auto_approve: bool,
+    dry_run: bool,
+    targets: &[String],
+    input_vars: &HashMap<String, Value>,
+    input: bool,
+    parallelism: usize,
+    lock_timeout: Duration,
+    operation_timeout: Option<Duration>,
+) -> Result<(), String> {
+    // A saved plan carries the path of the configuration it was computed from
+    let saved_plan = SavedPlan::load(path)?;
+    if saved_plan.is_some() && !targets.is_empty() {
+        return Err(
+            "--target cannot be used with a saved plan; pass it to 'carina plan' instead"
+                .to_string(),
+        );
+    }
+    if saved_plan.is_some() && !input_vars.is_empty() {
+        return Err(
+            "--var and --var-file cannot be used with a saved plan; pass them to 'carina plan' instead"
+                .to_string(),
+        );
+    }
 
-    // Resolve module imports and expand module calls
-    let base_dir = get_base_dir(path);
-    module_resolver::resolve_modules(&mut parsed, base_dir)
-        .map_err(|e| format!("Module resolution error: {}", e))?;
+    // A saved plan is applied as it was computed, even if the configuration has changed since
+    let (mut parsed, backend_file, input_vars) = match &saved_plan {
+        Some(saved) => (
+            saved.configuration(),
+            saved.backend_file.clone(),
+            HashMap::new(),
+        ),
+        None => {
+            let loaded = load_configuration(path)?;
+            let mut parsed = loaded.parsed;
+            let input_vars = apply_input_vars(&mut parsed, input_vars, input)?;
+
+            // Resolve module imports and expand module calls
+            let base_dir = get_base_dir(path);
+            module_resolver::resolve_modules(&mut parsed, base_dir)
+                .map_err(|e| format!("Module resolution error: {}", e))?;
+            (parsed, loaded.backend_file, input_vars)
+        }
+    };
 
     // Validate provider region
     validate_provider_region(&parsed)?;
@@ -718,22 +2026,36 @@ async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
 
     // Check for backend configuration - use local backend by default
     let backend_config = parsed.backend.as_ref();
-    let backend: Box<dyn StateBackend> = if let Some(config) = backend_config {
+    let backend: Arc<dyn StateBackend> = if let Some(config) = backend_config {
         let state_config = convert_backend_config(config);
         create_backend(&state_config)
             .await
             .map_err(|e| format!("Failed to create backend: {}", e))?
+            .into()
     } else {
-        create_local_backend()
+        create_local_backend().into()
     };
 
     // Handle bootstrap if S3 backend is configured
     #[allow(unused_assignments)]
-    let mut lock: Option<LockInfo> = None;
+    let mut lock: Option<StateLock> = None;
     #[allow(unused_assignments)]
     let mut state_file: Option<StateFile> = None;
 
-    if let Some(config) = backend_config {
+    // A dry run never bootstraps the backend, so a missing state bucket means empty state
+    let skip_backend = dry_run
+        && backend_config.is_some()
+        && !backend
+            .bucket_exists()
+            .await
+            .map_err(|e| format!("Failed to check bucket: {}", e))?;
+
+    if skip_backend {
+        println!(
+            "{} State bucket not found; apply would create it before other resources.",
+            "Note:".yellow().bold()
+        );
+    } else if let Some(config) = backend_config {
         // Check if bucket exists (bootstrap detection)
         let bucket_exists = backend
             .bucket_exists()
@@ -768,7 +2090,13 @@ async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
 
                 // Create the bucket resource first
                 let region = get_aws_region(&parsed);
-                let aws_provider = AwsProvider::new(&region).await;
+                let aws_provider = AwsProvider::with_options(
+                    &region,
+                    get_provider_string(&parsed, "aws", "profile"),
+                    get_provider_string(&parsed, "aws", "assume_role_arn"),
+                    get_provider_string(&parsed, "aws", "endpoint_url"),
+                )
+                .await;
 
                 match aws_provider.create(bucket_resource).await {
                     Ok(_) => {
@@ -853,11 +2181,14 @@ async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
                     );
 
                     // Re-parse the updated configuration to include the new resource
-                    parsed = load_configuration(path)?.parsed;
-                    if let Err(e) =
-                        module_resolver::resolve_modules(&mut parsed, get_base_dir(path))
-                    {
-                        return Err(format!("Module resolution error: {}", e));
+                    if saved_plan.is_none() {
+                        parsed = load_configuration(path)?.parsed;
+                        apply_input_vars(&mut parsed, &input_vars, false)?;
+                        if let Err(e) =
+                            module_resolver::resolve_modules(&mut parsed, get_base_dir(path))
+                        {
+                            return Err(format!("Module resolution error: {}", e));
+                        }
                     }
                 } else {
                     return Err(format!(
@@ -883,20 +2214,7 @@ async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
 
         // Acquire lock
         println!("{}", "Acquiring state lock...".cyan());
-        lock = Some(backend.acquire_lock("apply").await.map_err(|e| match e {
-            BackendError::Locked {
-                who,
-                lock_id,
-                operation,
-            } => {
-                format!(
-                    "State is locked by {} (lock ID: {}, operation: {})\n\
-                            If you believe this is stale, run: carina force-unlock {}",
-                    who, lock_id, operation, lock_id
-                )
-            }
-            _ => format!("Failed to acquire lock: {}", e),
-        })?);
+        lock = Some(acquire_lock(&backend, "apply", lock_timeout).await?);
         println!("  {} Lock acquired", "✓".green());
 
         // Read current state from backend
@@ -907,20 +2225,7 @@ async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
     } else {
         // Local backend: acquire lock and read state
         println!("{}", "Acquiring state lock...".cyan());
-        lock = Some(backend.acquire_lock("apply").await.map_err(|e| match e {
-            BackendError::Locked {
-                who,
-                lock_id,
-                operation,
-            } => {
-                format!(
-                    "State is locked by {} (lock ID: {}, operation: {})\n\
-                            If you believe this is stale, run: carina force-unlock {}",
-                    who, lock_id, operation, lock_id
-                )
-            }
-            _ => format!("Failed to acquire lock: {}", e),
-        })?);
+        lock = Some(acquire_lock(&backend, "apply", lock_timeout).await?);
         println!("  {} Lock acquired", "✓".green());
 
         // Read current state from local file
@@ -930,23 +2235,56 @@ async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
             .map_err(|e| format!("Failed to read state: {}", e))?;
     }
 
-    // Sort resources by dependencies
-    let sorted_resources = sort_resources_by_dependencies(&parsed.resources);
+    if let Some(saved) = &saved_plan
+        && let Err(e) = saved.check_current(&state_file)
+    {
+        // Release lock if we have one
+        if let Some(lock) = lock {
+            lock.release().await?;
+        }
+        return Err(e);
+    }
+
+    // Selected after bootstrap, which may re-parse the configuration
+    parsed.resources = select_targets(&parsed.resources, targets, TargetScope::Dependencies);
+
+    warn_hardcoded_references(&parsed.resources, &state_file);
 
     // Select appropriate Provider based on configuration
     let provider: Box<dyn Provider> = get_provider(&parsed).await;
 
-    // Read states for all resources using identifier from state
-    // In identifier-based approach, if there's no identifier in state, the resource doesn't exist
-    let mut current_states: HashMap<ResourceId, State> = HashMap::new();
-    for resource in &sorted_resources {
-        let identifier = get_identifier_from_state(&state_file, resource);
-        let state = provider
-            .read(&resource.id, identifier.as_deref())
-            .await
-            .map_err(|e| format!("Failed to read state: {}", e))?;
-        current_states.insert(resource.id.clone(), state);
-    }
+    let (sorted_resources, current_states, plan) = if let Some(saved) = saved_plan {
+        println!("{}", "Using saved plan.".cyan());
+        let current_states: HashMap<ResourceId, State> = saved
+            .current_states
+            .into_iter()
+            .map(|state| (state.id.clone(), state))
+            .collect();
+        (saved.resources, current_states, saved.plan)
+    } else {
+        // Sort resources by dependencies
+        let sorted_resources = sort_resources_by_dependencies(&parsed.resources);
+
+        // Read states for all resources using identifier from state
+        // In identifier-based approach, if there's no identifier in state, the resource doesn't exist
+        let current_states = read_current_states(
+            provider.as_ref(),
+            &sorted_resources,
+            &state_file,
+            parallelism,
+        )
+        .await?;
+
+        // Resolve references and create initial plan for display
+        let mut resources_for_plan = sorted_resources.clone();
+        resolve_refs_with_state(&mut resources_for_plan, &current_states);
+        let plan = create_plan_with_capabilities(
+            &resources_for_plan,
+            &current_states,
+            &provider_capabilities(provider.as_ref()),
+        );
+        (sorted_resources, current_states, plan)
+    };
 
     // Build initial binding map for reference resolution
     let mut binding_map: HashMap<String, HashMap<String, Value>> = HashMap::new();
@@ -967,20 +2305,31 @@ async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
         }
     }
 
-    // Resolve references and create initial plan for display
-    let mut resources_for_plan = sorted_resources.clone();
-    resolve_refs_with_state(&mut resources_for_plan, &current_states);
-    let plan = create_plan(&resources_for_plan, &current_states);
-
     if plan.is_empty() {
-        println!("{}", "No changes needed.".green());
+        println!(
+            "{}{}",
+            "No changes needed.".green(),
+            plan.summary().unchanged_suffix()
+        );
 
-        // Release lock if we have one
-        if let Some(lock_info) = &lock {
+        // Outputs can change without any resource changing (e.g., a new output block)
+        let mut state = state_file.clone().unwrap_or_default();
+        if !dry_run && record_outputs(&mut state, &parsed.outputs, &sorted_resources) {
+            state.increment_serial();
             backend
-                .release_lock(lock_info)
+                .write_state(&state)
                 .await
-                .map_err(|e| format!("Failed to release lock: {}", e))?;
+                .map_err(|e| format!("Failed to write state: {}", e))?;
+            println!(
+                "  {} Outputs updated (serial: {})",
+                "✓".green(),
+                state.serial
+            );
+        }
+
+        // Release lock if we have one
+        if let Some(lock) = lock {
+            lock.release().await?;
         }
 
         return Ok(());
@@ -988,8 +2337,41 @@ async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
 
     print_plan(&plan);
 
-    // Confirmation prompt
-    if !auto_approve {
+    if let Err(e) = check_prevent_destroy(&plan)
+        .and_then(|()| check_creatable(&plan, &provider_capabilities(provider.as_ref())))
+    {
+        // Release lock if we have one
+        if let Some(lock) = lock {
+            lock.release().await?;
+        }
+        return Err(e);
+    }
+
+    if dry_run {
+        println!();
+        println!(
+            "{}",
+            "Dry run complete. No changes were made.".green().bold()
+        );
+
+        // Release lock if we have one
+        if let Some(lock) = lock {
+            lock.release().await?;
+        }
+
+        return Ok(());
+    }
+
+    // Confirmation prompt
+    if !auto_approve {
+        if let Err(e) = require_input(input, "Confirmation", "use --auto-approve to skip it") {
+            // Release lock if we have one
+            if let Some(lock) = lock {
+                lock.release().await?;
+            }
+            return Err(e);
+        }
+
         println!(
             "{}",
             "Do you want to perform these actions?".yellow().bold()
@@ -1011,11 +2393,8 @@ async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
             println!("{}", "Apply cancelled.".yellow());
 
             // Release lock if we have one
-            if let Some(lock_info) = &lock {
-                backend
-                    .release_lock(lock_info)
-                    .await
-                    .map_err(|e| format!("Failed to release lock: {}", e))?;
+            if let Some(lock) = lock {
+                lock.release().await?;
             }
 
             return Ok(());
@@ -1028,98 +2407,122 @@ async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
 
     let mut success_count = 0;
     let mut failure_count = 0;
+    let mut skip_count = 0;
     let mut applied_states: HashMap<ResourceId, State> = HashMap::new();
-
-    // Apply each effect in order, resolving references dynamically
-    for effect in plan.effects() {
-        match effect {
-            Effect::Create(resource) => {
-                // Re-resolve references with current binding_map
-                let mut resolved_resource = resource.clone();
-                for (key, value) in &resource.attributes {
-                    resolved_resource
-                        .attributes
-                        .insert(key.clone(), resolve_ref_value(value, &binding_map));
+    // Resources deleted so far, including ones a failed replacement left deleted
+    let deleted_ids: std::sync::Mutex<HashSet<ResourceId>> = Default::default();
+    // Bindings whose effect failed or was skipped; effects referencing them are skipped
+    let mut failed_bindings: HashSet<String> = HashSet::new();
+
+    // On Ctrl-C, stop applying and fall through to saving state and releasing the lock
+    let interrupt = tokio::signal::ctrl_c();
+    tokio::pin!(interrupt);
+    let mut interrupted = false;
+    INTERRUPTS_HANDLED.store(true, Ordering::SeqCst);
+
+    // Apply effects level by level: the effects in a level don't depend on each other and
+    // run concurrently; the binding map is updated between levels so references to
+    // resources created in earlier levels resolve to their ids
+    let effects = plan.effects();
+    let levels: Vec<Vec<usize>> = if parallelism <= 1 {
+        (0..effects.len()).map(|i| vec![i]).collect()
+    } else {
+        effect_levels(effects)
+    };
+    for level in levels {
+        let mut runnable = Vec::new();
+        for i in level {
+            let resource = effect_resource(&effects[i]);
+            match resource.and_then(|r| failed_dependency(r, &failed_bindings)) {
+                Some(dep) => {
+                    println!(
+                        "  {} {} - skipped: depends on '{}', which was not applied",
+                        "-".yellow(),
+                        format_effect(&effects[i]),
+                        dep
+                    );
+                    skip_count += 1;
+                    if let Some(Value::String(binding_name)) =
+                        resource.and_then(|r| r.attributes.get("_binding"))
+                    {
+                        failed_bindings.insert(binding_name.clone());
+                    }
                 }
+                None => runnable.push(i),
+            }
+        }
 
-                match provider.create(&resolved_resource).await {
-                    Ok(state) => {
-                        println!("  {} {}", "✓".green(), format_effect(effect));
-                        success_count += 1;
-
-                        // Track the applied state
-                        applied_states.insert(resource.id.clone(), state.clone());
-
-                        // Update binding_map with the newly created resource's state (including id)
-                        if let Some(Value::String(binding_name)) =
-                            resource.attributes.get("_binding")
-                        {
-                            let mut attrs = resolved_resource.attributes.clone();
-                            for (k, v) in &state.attributes {
-                                attrs.insert(k.clone(), v.clone());
-                            }
-                            binding_map.insert(binding_name.clone(), attrs);
-                        }
-                    }
-                    Err(e) => {
-                        println!("  {} {} - {}", "✗".red(), format_effect(effect), e);
-                        failure_count += 1;
+        // Record each outcome as it arrives, so effects that finished before an
+        // interrupt still reach the state
+        let mut outcomes = Vec::new();
+        {
+            let mut level_outcomes = stream::iter(runnable.iter().map(|&i| {
+                let outcome = with_operation_timeout(
+                    operation_timeout,
+                    apply_effect(
+                        provider.as_ref(),
+                        &effects[i],
+                        &binding_map,
+                        &current_states,
+                        &deleted_ids,
+                    ),
+                );
+                async move { (i, outcome.await) }
+            }))
+            .buffer_unordered(parallelism.max(1));
+            loop {
+                tokio::select! {
+                    outcome = level_outcomes.next() => match outcome {
+                        Some(outcome) => outcomes.push(outcome),
+                        None => break,
+                    },
+                    _ = &mut interrupt => {
+                        interrupted = true;
+                        break;
                     }
                 }
             }
-            Effect::Update { id, from, to } => {
-                // Re-resolve references
-                let mut resolved_to = to.clone();
-                for (key, value) in &to.attributes {
-                    resolved_to
-                        .attributes
-                        .insert(key.clone(), resolve_ref_value(value, &binding_map));
-                }
-
-                // Get identifier from current state
-                let identifier = from.identifier.as_deref().unwrap_or("");
-                match provider.update(id, identifier, from, &resolved_to).await {
-                    Ok(state) => {
-                        println!("  {} {}", "✓".green(), format_effect(effect));
-                        success_count += 1;
+        }
 
-                        // Track the applied state
-                        applied_states.insert(id.clone(), state.clone());
+        for (i, outcome) in outcomes {
+            match outcome {
+                Ok(applied) => {
+                    println!("  {} {}", "✓".green(), format_effect(&effects[i]));
+                    success_count += 1;
 
-                        // Update binding_map
-                        if let Some(Value::String(binding_name)) = to.attributes.get("_binding") {
-                            let mut attrs = resolved_to.attributes.clone();
+                    // Track the applied state and update binding_map with it (including id)
+                    if let Some((resolved, state)) = applied {
+                        if let Some(Value::String(binding_name)) =
+                            resolved.attributes.get("_binding")
+                        {
+                            let mut attrs = resolved.attributes.clone();
                             for (k, v) in &state.attributes {
                                 attrs.insert(k.clone(), v.clone());
                             }
                             binding_map.insert(binding_name.clone(), attrs);
                         }
-                    }
-                    Err(e) => {
-                        println!("  {} {} - {}", "✗".red(), format_effect(effect), e);
-                        failure_count += 1;
+                        applied_states.insert(resolved.id, state);
                     }
                 }
-            }
-            Effect::Delete(id) => {
-                // Get identifier from current state
-                let identifier = current_states
-                    .get(id)
-                    .and_then(|s| s.identifier.as_deref())
-                    .unwrap_or("");
-                match provider.delete(id, identifier).await {
-                    Ok(()) => {
-                        println!("  {} {}", "✓".green(), format_effect(effect));
-                        success_count += 1;
-                    }
-                    Err(e) => {
-                        println!("  {} {} - {}", "✗".red(), format_effect(effect), e);
-                        failure_count += 1;
+                Err(e) => {
+                    println!("  {} {} - {}", "✗".red(), format_effect(&effects[i]), e);
+                    failure_count += 1;
+                    if let Some(Value::String(binding_name)) =
+                        effect_resource(&effects[i]).and_then(|r| r.attributes.get("_binding"))
+                    {
+                        failed_bindings.insert(binding_name.clone());
                     }
                 }
             }
-            Effect::Read { .. } => {}
         }
+        if interrupted {
+            break;
+        }
+    }
+
+    INTERRUPTS_HANDLED.store(false, Ordering::SeqCst);
+    if interrupted {
+        print_interrupted();
     }
 
     // Save state
@@ -1129,8 +2532,8 @@ async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
     // Get or create state file
     let mut state = state_file.unwrap_or_default();
 
-    // Update state with current resources
-    for resource in &sorted_resources {
+    // Update state with current resources (data sources are never stored)
+    for resource in sorted_resources.iter().filter(|r| !r.read_only) {
         let existing = state.find_resource(&resource.id.resource_type, &resource.id.name);
         if let Some(applied_state) = applied_states.get(&resource.id) {
             let resource_state = resource_to_state(resource, applied_state, existing);
@@ -1144,12 +2547,12 @@ async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
     }
 
     // Remove deleted resources from state
-    for effect in plan.effects() {
-        if let Effect::Delete(id) = effect {
-            state.remove_resource(&id.resource_type, &id.name);
-        }
+    for id in deleted_ids.into_inner().unwrap_or_default() {
+        state.remove_resource(&id.resource_type, &id.name);
     }
 
+    record_outputs(&mut state, &parsed.outputs, &sorted_resources);
+
     // Increment serial and save
     state.increment_serial();
     backend
@@ -1159,16 +2562,20 @@ async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
     println!("  {} State saved (serial: {})", "✓".green(), state.serial);
 
     // Release lock
-    if let Some(ref lock_info) = lock {
-        backend
-            .release_lock(lock_info)
-            .await
-            .map_err(|e| format!("Failed to release lock: {}", e))?;
+    if let Some(lock) = lock {
+        lock.release().await?;
         println!("  {} Lock released", "✓".green());
     }
 
+    if interrupted {
+        return Err(format!(
+            "Apply interrupted. {} succeeded, {} failed, {} skipped before the interrupt.",
+            success_count, failure_count, skip_count
+        ));
+    }
+
     println!();
-    if failure_count == 0 {
+    if failure_count == 0 && skip_count == 0 {
         println!(
             "{}",
             format!("Apply complete! {} changes applied.", success_count)
@@ -1179,8 +2586,8 @@ async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
         println!(
             "{}",
             format!(
-                "Apply failed. {} succeeded, {} failed.",
-                success_count, failure_count
+                "Apply failed. {} succeeded, {} failed, {} skipped.",
+                success_count, failure_count, skip_count
             )
             .red()
             .bold()
@@ -1190,8 +2597,198 @@ async fn run_apply(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
     Ok(())
 }
 
-async fn run_destroy(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
+/// Run a provider operation, failing it once `timeout` elapses. The abandoned
+/// operation may still complete in the provider.
+async fn with_operation_timeout<T>(
+    timeout: Option<Duration>,
+    operation: impl std::future::Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, operation)
+            .await
+            .unwrap_or_else(|_| {
+                Err(format!(
+                    "timed out after {:?}; the operation may still complete in the provider",
+                    timeout
+                ))
+            }),
+        None => operation.await,
+    }
+}
+
+/// Tell the user that in-flight operations were abandoned on Ctrl-C
+fn print_interrupted() {
+    println!();
+    println!(
+        "{}",
+        "Interrupted. Operations in progress were abandoned and may still complete in the provider."
+            .yellow()
+    );
+}
+
+/// Group effects (by index) into levels that can run concurrently. An effect runs in a
+/// later level than every effect on a resource it references. Deletes carry no references,
+/// so each runs alone, after everything before it in the plan and before everything after.
+fn effect_levels(effects: &[Effect]) -> Vec<Vec<usize>> {
+    let mut levels: Vec<Vec<usize>> = Vec::new();
+    let mut binding_levels: HashMap<String, usize> = HashMap::new();
+    let mut floor = 0;
+
+    for (i, effect) in effects.iter().enumerate() {
+        let resource = effect_resource(effect);
+
+        let level = match resource {
+            Some(resource) => get_resource_dependencies(resource)
+                .iter()
+                .filter_map(|dep| binding_levels.get(dep))
+                .map(|level| level + 1)
+                .fold(floor, usize::max),
+            None => levels.len().max(floor),
+        };
+        if level == levels.len() {
+            levels.push(Vec::new());
+        }
+        levels[level].push(i);
+
+        match resource {
+            Some(resource) => {
+                if let Some(Value::String(binding_name)) = resource.attributes.get("_binding") {
+                    binding_levels.insert(binding_name.clone(), level);
+                }
+            }
+            None => floor = level + 1,
+        }
+    }
+
+    levels
+}
+
+/// The desired resource of an effect, or None for deletes
+fn effect_resource(effect: &Effect) -> Option<&Resource> {
+    match effect {
+        Effect::Create(resource) | Effect::Read { resource } => Some(resource),
+        Effect::Update { to, .. } | Effect::Replace { to, .. } => Some(to),
+        Effect::Delete(_) => None,
+    }
+}
+
+/// The first (by name) binding the resource references whose effect was not applied
+fn failed_dependency(resource: &Resource, failed_bindings: &HashSet<String>) -> Option<String> {
+    get_resource_dependencies(resource)
+        .into_iter()
+        .filter(|dep| failed_bindings.contains(dep))
+        .min()
+}
+
+/// Apply a single effect with its references resolved against `binding_map`. Returns the
+/// resolved resource and its new state for creates, updates and replacements, or the
+/// message to report on failure. Resources that end up deleted are added to `deleted`,
+/// including one whose replacement failed to be created after it was deleted.
+async fn apply_effect(
+    provider: &dyn Provider,
+    effect: &Effect,
+    binding_map: &HashMap<String, HashMap<String, Value>>,
+    current_states: &HashMap<ResourceId, State>,
+    deleted: &std::sync::Mutex<HashSet<ResourceId>>,
+) -> Result<Option<(Resource, State)>, String> {
+    let resolve = |resource: &Resource| {
+        let mut resolved = resource.clone();
+        for (key, value) in &resource.attributes {
+            resolved
+                .attributes
+                .insert(key.clone(), resolve_ref_value(value, binding_map));
+        }
+        resolved
+    };
+
+    let result = match effect {
+        Effect::Create(resource) => {
+            let resolved = resolve(resource);
+            provider
+                .create(&resolved)
+                .await
+                .map(|state| Some((resolved, state)))
+        }
+        Effect::Update { id, from, to } => {
+            let resolved = resolve(to);
+            let identifier = from.identifier.as_deref().unwrap_or("");
+            provider
+                .update(id, identifier, from, &resolved)
+                .await
+                .map(|state| Some((resolved, state)))
+        }
+        Effect::Replace { id, from, to } => {
+            // Delete the existing resource, then create its replacement
+            let resolved = resolve(to);
+            let identifier = from.identifier.as_deref().unwrap_or("");
+            match delete_resource(provider, id, identifier).await {
+                Ok(()) => {
+                    deleted.lock().unwrap().insert(id.clone());
+                    let created = provider.create(&resolved).await;
+                    if created.is_ok() {
+                        deleted.lock().unwrap().remove(id);
+                    }
+                    created.map(|state| Some((resolved, state)))
+                }
+                Err(e) => Err(e),
+            }
+        }
+        Effect::Delete(id) => {
+            let identifier = current_states
+                .get(id)
+                .and_then(|s| s.identifier.as_deref())
+                .unwrap_or("");
+            delete_resource(provider, id, identifier).await.map(|()| {
+                deleted.lock().unwrap().insert(id.clone());
+                None
+            })
+        }
+        Effect::Read { resource } => {
+            // Data sources were already read before planning; their attributes are
+            // in binding_map. Only verify that the referenced resource exists.
+            return if current_states.get(&resource.id).is_some_and(|s| s.exists) {
+                Ok(None)
+            } else {
+                Err("data source not found".to_string())
+            };
+        }
+    };
+    result.map_err(|e| format_provider_error(&e))
+}
+
+/// Delete a resource, unless its type has no delete of its own (a route, for one,
+/// goes away with its route table)
+async fn delete_resource(
+    provider: &dyn Provider,
+    id: &ResourceId,
+    identifier: &str,
+) -> ProviderResult<()> {
+    let deletable = provider
+        .resource_types()
+        .iter()
+        .find(|t| t.name() == id.resource_type)
+        .is_none_or(|t| t.capabilities().delete);
+    if deletable {
+        provider.delete(id, identifier).await
+    } else {
+        Ok(())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_destroy(
+    path: &PathBuf,
+    auto_approve: bool,
+    targets: &[String],
+    from_state: bool,
+    input_vars: &HashMap<String, Value>,
+    input: bool,
+    parallelism: usize,
+    lock_timeout: Duration,
+    operation_timeout: Option<Duration>,
+) -> Result<(), String> {
     let mut parsed = load_configuration(path)?.parsed;
+    apply_input_vars(&mut parsed, input_vars, input)?;
 
     // Resolve module imports and expand module calls
     let base_dir = get_base_dir(path);
@@ -1204,25 +2801,28 @@ async fn run_destroy(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
     // Apply default region from provider
     apply_default_region(&mut parsed);
 
-    if parsed.resources.is_empty() {
+    if parsed.resources.is_empty() && !from_state {
         println!("{}", "No resources defined in configuration.".yellow());
         return Ok(());
     }
 
+    parsed.resources = select_targets(&parsed.resources, targets, TargetScope::Dependents);
+
     // Check for backend configuration - use local backend by default
     let backend_config = parsed.backend.as_ref();
-    let backend: Box<dyn StateBackend> = if let Some(config) = backend_config {
+    let backend: Arc<dyn StateBackend> = if let Some(config) = backend_config {
         let state_config = convert_backend_config(config);
         create_backend(&state_config)
             .await
             .map_err(|e| format!("Failed to create backend: {}", e))?
+            .into()
     } else {
-        create_local_backend()
+        create_local_backend().into()
     };
 
     // Handle state locking
     #[allow(unused_assignments)]
-    let mut lock: Option<LockInfo> = None;
+    let mut lock: Option<StateLock> = None;
     #[allow(unused_assignments)]
     let mut state_file: Option<StateFile> = None;
     let mut protected_bucket: Option<String> = None;
@@ -1237,20 +2837,7 @@ async fn run_destroy(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
 
     // Acquire lock
     println!("{}", "Acquiring state lock...".cyan());
-    lock = Some(backend.acquire_lock("destroy").await.map_err(|e| match e {
-        BackendError::Locked {
-            who,
-            lock_id,
-            operation,
-        } => {
-            format!(
-                "State is locked by {} (lock ID: {}, operation: {})\n\
-                        If you believe this is stale, run: carina force-unlock {}",
-                who, lock_id, operation, lock_id
-            )
-        }
-        _ => format!("Failed to acquire lock: {}", e),
-    })?);
+    lock = Some(acquire_lock(&backend, "destroy", lock_timeout).await?);
     println!("  {} Lock acquired", "✓".green());
 
     // Read current state from backend
@@ -1259,6 +2846,23 @@ async fn run_destroy(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
         .await
         .map_err(|e| format!("Failed to read state: {}", e))?;
 
+    // Resources the configuration protects with `lifecycle { prevent_destroy = true }`,
+    // looked up before --from-state replaces the configured resources
+    let prevented: HashSet<ResourceId> = parsed
+        .resources
+        .iter()
+        .filter(|r| prevents_destroy(r))
+        .map(|r| r.id.clone())
+        .collect();
+
+    // With --from-state, destroy what state records rather than what is configured
+    if from_state {
+        parsed.resources = state_file
+            .as_ref()
+            .map(resources_from_state)
+            .unwrap_or_default();
+    }
+
     // Sort resources by dependencies (for creation order)
     let sorted_resources = sort_resources_by_dependencies(&parsed.resources);
 
@@ -1269,15 +2873,8 @@ async fn run_destroy(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
     let provider: Box<dyn Provider> = get_provider(&parsed).await;
 
     // Read states for all resources using identifier from state
-    let mut current_states: HashMap<ResourceId, State> = HashMap::new();
-    for resource in &destroy_order {
-        let identifier = get_identifier_from_state(&state_file, resource);
-        let state = provider
-            .read(&resource.id, identifier.as_deref())
-            .await
-            .map_err(|e| format!("Failed to read state: {}", e))?;
-        current_states.insert(resource.id.clone(), state);
-    }
+    let current_states =
+        read_current_states(provider.as_ref(), &destroy_order, &state_file, parallelism).await?;
 
     // Collect resources that exist and will be destroyed
     // Skip the state bucket if it matches the backend bucket
@@ -1285,16 +2882,26 @@ async fn run_destroy(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
     let resources_to_destroy: Vec<&Resource> = destroy_order
         .iter()
         .filter(|r| {
+            // Data sources are not managed by Carina and must never be destroyed
+            if r.read_only {
+                return false;
+            }
+
             if !current_states.get(&r.id).map(|s| s.exists).unwrap_or(false) {
                 return false;
             }
 
             // Check if this is the protected state bucket
-            if r.id.resource_type == "s3.bucket"
-                && let Some(ref bucket_name) = protected_bucket
-                && let Some(Value::String(name)) = r.attributes.get("name")
-                && name == bucket_name
-            {
+            let is_state_bucket = r.id.resource_type == "s3.bucket"
+                && matches!(
+                    (&protected_bucket, r.attributes.get("name")),
+                    (Some(bucket_name), Some(Value::String(name))) if name == bucket_name
+                );
+            let marked_protected = state_file
+                .as_ref()
+                .and_then(|s| s.find_resource(&r.id.resource_type, &r.id.name))
+                .is_some_and(|s| s.protected);
+            if is_state_bucket || marked_protected {
                 protected_resources.push(r);
                 return false;
             }
@@ -1303,15 +2910,25 @@ async fn run_destroy(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
         })
         .collect();
 
+    let prevented: Vec<String> = resources_to_destroy
+        .iter()
+        .filter(|r| prevented.contains(&r.id))
+        .map(|r| format!("{}.{}", r.id.resource_type, r.id.name))
+        .collect();
+    if !prevented.is_empty() {
+        // Release lock if we have one
+        if let Some(lock) = lock {
+            lock.release().await?;
+        }
+        return Err(prevent_destroy_error("destroy", &prevented));
+    }
+
     if resources_to_destroy.is_empty() && protected_resources.is_empty() {
         println!("{}", "No resources to destroy.".green());
 
         // Release lock if we have one
-        if let Some(lock_info) = &lock {
-            backend
-                .release_lock(lock_info)
-                .await
-                .map_err(|e| format!("Failed to release lock: {}", e))?;
+        if let Some(lock) = lock {
+            lock.release().await?;
         }
 
         return Ok(());
@@ -1361,11 +2978,8 @@ async fn run_destroy(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
         );
 
         // Release lock if we have one
-        if let Some(lock_info) = &lock {
-            backend
-                .release_lock(lock_info)
-                .await
-                .map_err(|e| format!("Failed to release lock: {}", e))?;
+        if let Some(lock) = lock {
+            lock.release().await?;
         }
 
         return Ok(());
@@ -1373,12 +2987,23 @@ async fn run_destroy(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
 
     // Confirmation prompt
     if !auto_approve {
-        println!(
-            "{}",
-            "Do you really want to destroy all resources?"
-                .yellow()
-                .bold()
-        );
+        if let Err(e) = require_input(input, "Confirmation", "use --auto-approve to skip it") {
+            // Release lock if we have one
+            if let Some(lock) = lock {
+                lock.release().await?;
+            }
+            return Err(e);
+        }
+
+        let question = if targets.is_empty() {
+            "Do you really want to destroy all resources?".to_string()
+        } else {
+            format!(
+                "Do you really want to destroy the {} targeted resource(s)?",
+                resources_to_destroy.len()
+            )
+        };
+        println!("{}", question.yellow().bold());
         println!(
             "  {}",
             "This action cannot be undone. Type 'yes' to confirm.".yellow()
@@ -1396,11 +3021,8 @@ async fn run_destroy(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
             println!("{}", "Destroy cancelled.".yellow());
 
             // Release lock if we have one
-            if let Some(lock_info) = &lock {
-                backend
-                    .release_lock(lock_info)
-                    .await
-                    .map_err(|e| format!("Failed to release lock: {}", e))?;
+            if let Some(lock) = lock {
+                lock.release().await?;
             }
 
             return Ok(());
@@ -1415,6 +3037,12 @@ async fn run_destroy(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
     let mut failure_count = 0;
     let mut destroyed_ids: Vec<ResourceId> = Vec::new();
 
+    // On Ctrl-C, stop destroying and fall through to saving state and releasing the lock
+    let interrupt = tokio::signal::ctrl_c();
+    tokio::pin!(interrupt);
+    let mut interrupted = false;
+    INTERRUPTS_HANDLED.store(true, Ordering::SeqCst);
+
     for resource in &resources_to_destroy {
         let effect = Effect::Delete(resource.id.clone());
 
@@ -1424,7 +3052,18 @@ async fn run_destroy(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
             .and_then(|s| s.identifier.as_deref())
             .unwrap_or("");
 
-        let delete_result = provider.delete(&resource.id, identifier).await;
+        let delete = with_operation_timeout(operation_timeout, async {
+            delete_resource(provider.as_ref(), &resource.id, identifier)
+                .await
+                .map_err(|e| format_provider_error(&e))
+        });
+        let delete_result = tokio::select! {
+            result = delete => result,
+            _ = &mut interrupt => {
+                interrupted = true;
+                break;
+            }
+        };
 
         match delete_result {
             Ok(()) => {
@@ -1439,6 +3078,11 @@ async fn run_destroy(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
         }
     }
 
+    INTERRUPTS_HANDLED.store(false, Ordering::SeqCst);
+    if interrupted {
+        print_interrupted();
+    }
+
     // Save state
     println!();
     println!("{}", "Saving state...".cyan());
@@ -1460,14 +3104,18 @@ async fn run_destroy(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
     println!("  {} State saved (serial: {})", "✓".green(), state.serial);
 
     // Release lock
-    if let Some(ref lock_info) = lock {
-        backend
-            .release_lock(lock_info)
-            .await
-            .map_err(|e| format!("Failed to release lock: {}", e))?;
+    if let Some(lock) = lock {
+        lock.release().await?;
         println!("  {} Lock released", "✓".green());
     }
 
+    if interrupted {
+        return Err(format!(
+            "Destroy interrupted. {} succeeded, {} failed before the interrupt.",
+            success_count, failure_count
+        ));
+    }
+
     println!();
     if failure_count == 0 {
         println!(
@@ -1491,66 +3139,580 @@ async fn run_destroy(path: &PathBuf, auto_approve: bool) -> Result<(), String> {
     Ok(())
 }
 
-/// Get identifier from state file for a resource
-fn get_identifier_from_state(
-    state_file: &Option<StateFile>,
-    resource: &Resource,
-) -> Option<String> {
-    if let Some(state) = state_file
-        && let Some(resource_state) =
-            state.find_resource(&resource.id.resource_type, &resource.id.name)
-    {
-        return resource_state.identifier.clone();
-    }
-    None
-}
+async fn run_refresh(
+    path: &PathBuf,
+    parallelism: usize,
+    lock_timeout: Duration,
+) -> Result<(), String> {
+    let mut parsed = load_configuration(path)?.parsed;
 
-fn get_aws_region_dsl(parsed: &ParsedFile) -> Option<String> {
-    for provider in &parsed.providers {
-        if provider.name == "aws"
-            && let Some(Value::String(region)) = provider.attributes.get("region")
-        {
-            return Some(region.clone());
-        }
-    }
-    None
-}
+    // Resolve module imports and expand module calls
+    let base_dir = get_base_dir(path);
+    module_resolver::resolve_modules(&mut parsed, base_dir)
+        .map_err(|e| format!("Module resolution error: {}", e))?;
 
-/// Get region from provider configuration (AWS format: ap-northeast-1)
-fn get_aws_region(parsed: &ParsedFile) -> String {
-    if let Some(region) = get_aws_region_dsl(parsed) {
-        // Convert from aws.Region.ap_northeast_1 format to ap-northeast-1 format
-        if region.starts_with("aws.Region.") {
-            return region
-                .strip_prefix("aws.Region.")
-                .unwrap_or(&region)
-                .replace('_', "-");
-        }
-        return region;
-    }
-    // Default region
-    "ap-northeast-1".to_string()
-}
+    // Validate provider region
+    validate_provider_region(&parsed)?;
 
-/// Get region from awscc provider configuration (AWS format: ap-northeast-1)
-fn get_awscc_region(parsed: &ParsedFile) -> String {
-    for provider in &parsed.providers {
-        if provider.name == "awscc"
-            && let Some(Value::String(region)) = provider.attributes.get("region")
-        {
-            // Convert from aws.Region.ap_northeast_1 format to ap-northeast-1 format
-            if region.starts_with("aws.Region.") {
-                return region
-                    .strip_prefix("aws.Region.")
-                    .unwrap_or(region)
-                    .replace('_', "-");
-            }
-            return region.clone();
-        }
-    }
-    // Default region
-    "ap-northeast-1".to_string()
-}
+    // Apply default region from provider
+    apply_default_region(&mut parsed);
+
+    // Check for backend configuration - use local backend by default
+    let backend: Arc<dyn StateBackend> = if let Some(config) = parsed.backend.as_ref() {
+        let state_config = convert_backend_config(config);
+        create_backend(&state_config)
+            .await
+            .map_err(|e| format!("Failed to create backend: {}", e))?
+            .into()
+    } else {
+        create_local_backend().into()
+    };
+
+    // Acquire lock
+    println!("{}", "Acquiring state lock...".cyan());
+    let lock = acquire_lock(&backend, "refresh", lock_timeout).await?;
+    println!("  {} Lock acquired", "✓".green());
+
+    let result = refresh_state(backend.as_ref(), &parsed, parallelism).await;
+
+    // Release lock whether or not the refresh succeeded
+    lock.release().await?;
+    println!("  {} Lock released", "✓".green());
+
+    result
+}
+
+/// Re-read every resource recorded in state and write the live attributes back,
+/// dropping entries for resources that no longer exist
+async fn refresh_state(
+    backend: &dyn StateBackend,
+    parsed: &ParsedFile,
+    parallelism: usize,
+) -> Result<(), String> {
+    let state_file = backend
+        .read_state()
+        .await
+        .map_err(|e| format!("Failed to read state: {}", e))?;
+    let Some(mut state) = state_file.clone() else {
+        println!("{}", "No state found. Nothing to refresh.".yellow());
+        return Ok(());
+    };
+
+    // Only resources already tracked in state are refreshed; data sources are never stored
+    let resources: Vec<Resource> = sort_resources_by_dependencies(&parsed.resources)
+        .into_iter()
+        .filter(|r| {
+            !r.read_only
+                && state
+                    .find_resource(&r.id.resource_type, &r.id.name)
+                    .is_some()
+        })
+        .collect();
+
+    // Select appropriate Provider based on configuration
+    let provider: Box<dyn Provider> = get_provider(parsed).await;
+
+    // Read states for all resources using identifier from state
+    let current_states =
+        read_current_states(provider.as_ref(), &resources, &state_file, parallelism).await?;
+
+    println!();
+    println!("{}", "Refreshing state...".cyan().bold());
+    println!();
+
+    let mut updated_count = 0;
+    let mut removed_count = 0;
+    for resource in &resources {
+        let address = format!("{}.{}", resource.id.resource_type, resource.id.name);
+        let existing = state.find_resource(&resource.id.resource_type, &resource.id.name);
+        let current = &current_states[&resource.id];
+
+        if !current.exists {
+            println!("  {} {} (no longer exists)", "-".red(), address);
+            state.remove_resource(&resource.id.resource_type, &resource.id.name);
+            removed_count += 1;
+            continue;
+        }
+
+        let refreshed = resource_to_state(resource, current, existing);
+        let changes = existing
+            .map(|old| attribute_changes(&old.attributes, &refreshed.attributes))
+            .unwrap_or_default();
+        let identifier_changed = existing.is_some_and(|old| old.identifier != refreshed.identifier);
+        if !changes.is_empty() || identifier_changed {
+            println!("  {} {}", "~".yellow(), address);
+            if identifier_changed {
+                println!("      {} identifier", "~".yellow());
+            }
+            for (marker, key) in &changes {
+                let marker = match marker {
+                    '+' => "+".green(),
+                    '-' => "-".red(),
+                    _ => "~".yellow(),
+                };
+                println!("      {} {}", marker, key);
+            }
+            updated_count += 1;
+        }
+        state.upsert_resource(refreshed);
+    }
+
+    if updated_count == 0 && removed_count == 0 {
+        println!(
+            "{}",
+            "State is up to date with the real infrastructure.".green()
+        );
+        return Ok(());
+    }
+
+    // Increment serial and save
+    println!();
+    println!("{}", "Saving state...".cyan());
+    state.increment_serial();
+    backend
+        .write_state(&state)
+        .await
+        .map_err(|e| format!("Failed to write state: {}", e))?;
+    println!("  {} State saved (serial: {})", "✓".green(), state.serial);
+
+    println!();
+    println!(
+        "{}",
+        format!(
+            "Refresh complete! {} updated, {} removed.",
+            updated_count, removed_count
+        )
+        .green()
+        .bold()
+    );
+
+    Ok(())
+}
+
+/// Attribute keys that differ between two state entries, marked `+` (added),
+/// `-` (removed) or `~` (modified)
+fn attribute_changes(
+    old: &BTreeMap<String, serde_json::Value>,
+    new: &BTreeMap<String, serde_json::Value>,
+) -> Vec<(char, String)> {
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| match (old.get(key), new.get(key)) {
+            (None, Some(_)) => Some(('+', key.clone())),
+            (Some(_), None) => Some(('-', key.clone())),
+            (Some(a), Some(b)) if a != b => Some(('~', key.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Warn about attributes hardcoded to an ID that belongs to a managed resource.
+/// Such values hide the dependency from Carina, so the resources may be created
+/// in the wrong order; a reference (`binding.attr`) should be used instead.
+fn warn_hardcoded_references(resources: &[Resource], state_file: &Option<StateFile>) {
+    let Some(state_file) = state_file else {
+        return;
+    };
+    for warning in hardcoded_references(resources, state_file) {
+        notice!("{} {}", "Warning:".yellow().bold(), warning);
+    }
+}
+
+/// Describe each attribute hardcoded to the ID of another managed resource
+fn hardcoded_references(resources: &[Resource], state_file: &StateFile) -> Vec<String> {
+    // Map each resource's own ID (its identifier or `id` attribute) to the resource.
+    // Attributes like `vpc_id` refer to other resources, so they are not IDs of
+    // their own; the first resource to claim an ID keeps it.
+    let mut managed_ids: HashMap<&str, &ResourceState> = HashMap::new();
+    for resource_state in &state_file.resources {
+        let own_ids =
+            resource_state.identifier.as_deref().into_iter().chain(
+                match resource_state.attributes.get("id") {
+                    Some(serde_json::Value::String(id)) => Some(id.as_str()),
+                    _ => None,
+                },
+            );
+        for id in own_ids {
+            managed_ids.entry(id).or_insert(resource_state);
+        }
+    }
+
+    let mut warnings = Vec::new();
+
+    for resource in resources {
+        for (attr_name, value) in &resource.attributes {
+            if attr_name.starts_with('_') {
+                continue;
+            }
+            let Value::String(s) = value else {
+                continue;
+            };
+            let Some(owner) = managed_ids.get(s.as_str()) else {
+                continue;
+            };
+            if owner.resource_type == resource.id.resource_type && owner.name == resource.id.name {
+                continue;
+            }
+
+            // Prefer the owner's binding name in the suggestion when it has one
+            let binding = resources
+                .iter()
+                .find(|r| r.id.resource_type == owner.resource_type && r.id.name == owner.name)
+                .and_then(|r| match r.attributes.get("_binding") {
+                    Some(Value::String(b)) => Some(b.as_str()),
+                    _ => None,
+                })
+                .unwrap_or("<binding>");
+
+            warnings.push(format!(
+                "{}.{}: {} is hardcoded to \"{}\", which is the id of managed resource {}.{}. \
+                 Use a reference ({} = {}.id) so Carina orders them correctly.",
+                resource.id.resource_type,
+                resource.id.name,
+                attr_name,
+                s,
+                owner.resource_type,
+                owner.name,
+                attr_name,
+                binding
+            ));
+        }
+    }
+    warnings
+}
+
+/// Warn when a new resource looks like a renamed resource from state.
+/// The resource ID derives from `name`, so a rename silently recreates the resource.
+/// This is advisory only: a pair matches when the new resource is configured with the
+/// old resource's identifier, or when all of its literal attributes (other than `name`)
+/// equal the old resource's and no other resource from state matches as well.
+fn warn_possible_renames(resources: &[Resource], state_file: &Option<StateFile>) {
+    let Some(state_file) = state_file else {
+        return;
+    };
+    for (resource, old) in possible_renames(resources, state_file) {
+        notice!(
+            "{} {}.{} looks like a rename of {}.{}, which will recreate the resource. \
+             Consider `carina state mv` to avoid recreation.",
+            "Warning:".yellow().bold(),
+            resource.id.resource_type,
+            resource.id.name,
+            old.resource_type,
+            old.name
+        );
+    }
+}
+
+/// Pair each new resource with the resource from state it looks renamed from
+fn possible_renames<'a>(
+    resources: &'a [Resource],
+    state_file: &'a StateFile,
+) -> Vec<(&'a Resource, &'a ResourceState)> {
+    let mut renames = Vec::new();
+    let orphans: Vec<&ResourceState> = state_file
+        .resources
+        .iter()
+        .filter(|rs| {
+            rs.identifier.is_some()
+                && !resources
+                    .iter()
+                    .any(|r| r.id.resource_type == rs.resource_type && r.id.name == rs.name)
+        })
+        .collect();
+    if orphans.is_empty() {
+        return renames;
+    }
+
+    for resource in resources.iter().filter(|r| !r.read_only) {
+        if state_file
+            .find_resource(&resource.id.resource_type, &resource.id.name)
+            .is_some()
+        {
+            continue;
+        }
+
+        let literals: Vec<(&String, serde_json::Value)> = resource
+            .attributes
+            .iter()
+            .filter(|(k, _)| *k != "name" && !k.starts_with('_'))
+            .filter(|(_, v)| {
+                matches!(
+                    v,
+                    Value::String(_) | Value::Int(_) | Value::Float(_) | Value::Bool(_)
+                )
+            })
+            .map(|(k, v)| (k, value_to_json(v)))
+            .collect();
+        if literals.is_empty() {
+            continue;
+        }
+
+        let same_type = || {
+            orphans
+                .iter()
+                .filter(|rs| rs.resource_type == resource.id.resource_type)
+        };
+        let by_identifier = same_type().find(|rs| {
+            literals.iter().any(|(_, v)| {
+                v.as_str()
+                    .is_some_and(|v| rs.identifier.as_deref() == Some(v))
+            })
+        });
+        let by_attributes: Vec<_> = same_type()
+            .filter(|rs| {
+                literals
+                    .iter()
+                    .all(|(k, v)| rs.attributes.get(k.as_str()) == Some(v))
+            })
+            .collect();
+
+        match (by_identifier, by_attributes.as_slice()) {
+            (Some(old), _) | (None, &[old]) => renames.push((resource, *old)),
+            _ => {}
+        }
+    }
+    renames
+}
+
+/// A state lock held by a command. Dropping it (on an error or a panic) releases
+/// the lock, and so does Ctrl-C before exiting, unless `INTERRUPTS_HANDLED` says
+/// the command stops on its own.
+struct StateLock {
+    guard: Option<LockGuard>,
+    interrupt_handler: tokio::task::JoinHandle<()>,
+}
+
+impl StateLock {
+    fn new(backend: Arc<dyn StateBackend>, lock: LockInfo) -> Self {
+        let interrupt_handler = tokio::spawn({
+            let backend = backend.clone();
+            let lock = lock.clone();
+            async move {
+                loop {
+                    if tokio::signal::ctrl_c().await.is_err() {
+                        return;
+                    }
+                    if INTERRUPTS_HANDLED.load(Ordering::SeqCst) {
+                        continue;
+                    }
+                    eprintln!();
+                    eprintln!("{}", "Interrupted. Releasing state lock...".yellow());
+                    if let Err(e) = backend.release_lock(&lock).await {
+                        eprintln!(
+                            "Failed to release lock: {}\nTo unlock it, run: carina force-unlock {}",
+                            e, lock.id
+                        );
+                    }
+                    std::process::exit(130);
+                }
+            }
+        });
+        Self {
+            guard: Some(LockGuard::new(backend, lock)),
+            interrupt_handler,
+        }
+    }
+
+    /// Release the lock
+    async fn release(mut self) -> Result<(), String> {
+        self.interrupt_handler.abort();
+        match self.guard.take() {
+            Some(guard) => guard
+                .release()
+                .await
+                .map_err(|e| format!("Failed to release lock: {}", e)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        // Stop handling Ctrl-C before the guard releases the lock
+        self.interrupt_handler.abort();
+    }
+}
+
+/// Acquire the state lock, retrying with backoff while it is held by someone else
+/// until `timeout` elapses. A zero timeout fails immediately.
+async fn acquire_lock(
+    backend: &Arc<dyn StateBackend>,
+    operation: &str,
+    timeout: Duration,
+) -> Result<StateLock, String> {
+    let deadline = Instant::now() + timeout;
+    let mut delay = Duration::from_secs(1);
+
+    loop {
+        match backend.acquire_lock(operation).await {
+            Ok(lock) => return Ok(StateLock::new(backend.clone(), lock)),
+            Err(BackendError::Locked { who, .. }) if Instant::now() < deadline => {
+                println!("  {} Waiting for lock held by {}...", "⋯".yellow(), who);
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                tokio::time::sleep(delay.min(remaining)).await;
+                delay = (delay * 2).min(Duration::from_secs(10));
+            }
+            Err(BackendError::Locked {
+                who,
+                lock_id,
+                operation,
+                age,
+            }) => {
+                return Err(format!(
+                    "State is locked by {} (lock ID: {}, operation: {}, age: {})\n\
+                     If you believe this is stale, run: carina force-unlock {}",
+                    who, lock_id, operation, age, lock_id
+                ));
+            }
+            Err(e) => return Err(format!("Failed to acquire lock: {}", e)),
+        }
+    }
+}
+
+/// Parse a duration such as "500ms", "30s", "2m" or "1h" (a bare number means seconds)
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let value: u64 = number
+        .parse()
+        .map_err(|_| format!("Invalid duration '{}' (expected e.g. 30s, 2m)", s))?;
+    let seconds = |factor: u64| {
+        value
+            .checked_mul(factor)
+            .map(Duration::from_secs)
+            .ok_or_else(|| format!("Duration '{}' is too large", s))
+    };
+    match unit {
+        "ms" => Ok(Duration::from_millis(value)),
+        "" | "s" => Ok(Duration::from_secs(value)),
+        "m" => seconds(60),
+        "h" => seconds(3600),
+        _ => Err(format!(
+            "Invalid duration unit '{}' in '{}' (use ms, s, m or h)",
+            unit, s
+        )),
+    }
+}
+
+/// States of managed resources as recorded in the state file, for planning
+/// without reading them from the provider. Resources without an identifier in
+/// state don't exist.
+fn recorded_states(
+    resources: &[Resource],
+    state_file: Option<&StateFile>,
+) -> HashMap<ResourceId, State> {
+    resources
+        .iter()
+        .filter(|r| !r.read_only)
+        .map(|resource| {
+            let recorded = state_file
+                .and_then(|s| s.find_resource(&resource.id.resource_type, &resource.id.name));
+            let state = match recorded {
+                Some(recorded) if recorded.identifier.is_some() => State::existing(
+                    resource.id.clone(),
+                    recorded
+                        .attributes
+                        .iter()
+                        .map(|(k, v)| (k.clone(), json_to_value(v)))
+                        .collect(),
+                )
+                .with_identifier(recorded.identifier.clone().unwrap_or_default()),
+                _ => State::not_found(resource.id.clone()),
+            };
+            (resource.id.clone(), state)
+        })
+        .collect()
+}
+
+/// Attributes whose live value differs from the one recorded in state, i.e. changed outside
+/// carina. Attributes the provider does not return (such as write-only ones) are skipped.
+fn drifted_attributes(state: &State, recorded: &ResourceState) -> Vec<String> {
+    recorded
+        .attributes
+        .iter()
+        .filter(|(key, _)| !key.starts_with('_'))
+        .filter_map(|(key, value)| {
+            let live = state.attributes.get(key)?;
+            (!attribute_values_equal(key, live, &json_to_value(value))).then(|| key.clone())
+        })
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Get identifier from state file for a resource
+fn get_identifier_from_state(
+    state_file: &Option<StateFile>,
+    resource: &Resource,
+) -> Option<String> {
+    if let Some(state) = state_file
+        && let Some(resource_state) =
+            state.find_resource(&resource.id.resource_type, &resource.id.name)
+    {
+        return resource_state.identifier.clone();
+    }
+    None
+}
+
+fn get_aws_region_dsl(parsed: &ParsedFile) -> Option<String> {
+    for provider in &parsed.providers {
+        if provider.name == "aws"
+            && let Some(Value::String(region)) = provider.attributes.get("region")
+        {
+            return Some(region.clone());
+        }
+    }
+    None
+}
+
+/// Get a string attribute of a provider block (e.g., profile or endpoint_url)
+fn get_provider_string<'a>(parsed: &'a ParsedFile, name: &str, key: &str) -> Option<&'a str> {
+    parsed
+        .providers
+        .iter()
+        .find(|provider| provider.name == name)
+        .and_then(|provider| match provider.attributes.get(key) {
+            Some(Value::String(s)) => Some(s.as_str()),
+            _ => None,
+        })
+}
+
+/// Get region from provider configuration (AWS format: ap-northeast-1)
+fn get_aws_region(parsed: &ParsedFile) -> String {
+    if let Some(region) = get_aws_region_dsl(parsed) {
+        // Convert from aws.Region.ap_northeast_1 format to ap-northeast-1 format
+        if region.starts_with("aws.Region.") {
+            return region
+                .strip_prefix("aws.Region.")
+                .unwrap_or(&region)
+                .replace('_', "-");
+        }
+        return region;
+    }
+    // Default region
+    "ap-northeast-1".to_string()
+}
+
+/// Get region from awscc provider configuration (AWS format: ap-northeast-1)
+fn get_awscc_region(parsed: &ParsedFile) -> String {
+    for provider in &parsed.providers {
+        if provider.name == "awscc"
+            && let Some(Value::String(region)) = provider.attributes.get("region")
+        {
+            // Convert from aws.Region.ap_northeast_1 format to ap-northeast-1 format
+            if region.starts_with("aws.Region.") {
+                return region
+                    .strip_prefix("aws.Region.")
+                    .unwrap_or(region)
+                    .replace('_', "-");
+            }
+            return region.clone();
+        }
+    }
+    // Default region
+    "ap-northeast-1".to_string()
+}
 
 /// Apply default region from provider to resources that don't have a region specified
 fn apply_default_region(parsed: &mut ParsedFile) {
@@ -1571,24 +3733,46 @@ async fn get_provider(parsed: &ParsedFile) -> Box<dyn Provider> {
     for provider in &parsed.providers {
         if provider.name == "aws" {
             let region = get_aws_region(parsed);
-            println!(
-                "{}",
-                format!("Using AWS provider (region: {})", region).cyan()
+            if !JSON_OUTPUT.load(Ordering::Relaxed) {
+                println!(
+                    "{}",
+                    format!("Using AWS provider (region: {})", region).cyan()
+                );
+            }
+            return Box::new(
+                AwsProvider::with_options(
+                    &region,
+                    get_provider_string(parsed, "aws", "profile"),
+                    get_provider_string(parsed, "aws", "assume_role_arn"),
+                    get_provider_string(parsed, "aws", "endpoint_url"),
+                )
+                .await,
             );
-            return Box::new(AwsProvider::new(&region).await);
         }
         if provider.name == "awscc" {
             let region = get_awscc_region(parsed);
-            println!(
-                "{}",
-                format!("Using AWS Cloud Control provider (region: {})", region).cyan()
+            if !JSON_OUTPUT.load(Ordering::Relaxed) {
+                println!(
+                    "{}",
+                    format!("Using AWS Cloud Control provider (region: {})", region).cyan()
+                );
+            }
+            return Box::new(
+                AwsccProvider::with_options(
+                    &region,
+                    get_provider_string(parsed, "awscc", "profile"),
+                    get_provider_string(parsed, "awscc", "assume_role_arn"),
+                    get_provider_string(parsed, "awscc", "endpoint_url"),
+                )
+                .await,
             );
-            return Box::new(AwsccProvider::new(&region).await);
         }
     }
 
     // Use file-based mock for other cases
-    println!("{}", "Using file-based mock provider".cyan());
+    if !JSON_OUTPUT.load(Ordering::Relaxed) {
+        println!("{}", "Using file-based mock provider".cyan());
+    }
     Box::new(FileProvider::new())
 }
 
@@ -1644,6 +3828,33 @@ fn resolve_ref_value(
             // Keep as-is if not found
             value.clone()
         }
+        Value::SplatRef(base, attr_name) => {
+            // Collect the attribute of every copy of the base binding, ordered by
+            // index for count and by key for for_each
+            let mut replicas: Vec<(&Value, &String, &HashMap<String, Value>)> = binding_map
+                .iter()
+                .filter(|(_, attrs)| {
+                    matches!(attrs.get("_replica_of"), Some(Value::String(b)) if b == base)
+                })
+                .filter_map(|(binding, attrs)| {
+                    attrs.get("_replica_key").map(|key| (key, binding, attrs))
+                })
+                .collect();
+            replicas.sort_by(|(a, _, _), (b, _, _)| match (a, b) {
+                (Value::Int(a), Value::Int(b)) => a.cmp(b),
+                (Value::String(a), Value::String(b)) => a.cmp(b),
+                _ => std::cmp::Ordering::Equal,
+            });
+            Value::List(
+                replicas
+                    .into_iter()
+                    .map(|(_, binding, attrs)| match attrs.get(attr_name) {
+                        Some(attr_value) => resolve_ref_value(attr_value, binding_map),
+                        None => Value::ResourceRef(binding.clone(), attr_name.clone()),
+                    })
+                    .collect(),
+            )
+        }
         Value::TypedResourceRef {
             binding_name,
             attribute_name,
@@ -1669,16 +3880,102 @@ fn resolve_ref_value(
                 .map(|(k, v)| (k.clone(), resolve_ref_value(v, binding_map)))
                 .collect(),
         ),
+        Value::Interpolation(parts) => Value::interpolate(
+            parts
+                .iter()
+                .map(|v| resolve_ref_value(v, binding_map))
+                .collect(),
+        ),
+        Value::Operation(operator, operands) => {
+            let operands: Vec<Value> = operands
+                .iter()
+                .map(|v| resolve_ref_value(v, binding_map))
+                .collect();
+            Value::operate(*operator, operands.clone())
+                .unwrap_or(Value::Operation(*operator, operands))
+        }
         _ => value.clone(),
     }
 }
 
+/// Whether the configuration protects the resource with `lifecycle { prevent_destroy = true }`
+fn prevents_destroy(resource: &Resource) -> bool {
+    matches!(
+        resource.attributes.get("_prevent_destroy"),
+        Some(Value::Bool(true))
+    )
+}
+
+/// Fail when the plan replaces a resource whose configuration prevents destroying it
+fn check_prevent_destroy(plan: &Plan) -> Result<(), String> {
+    let replaced: Vec<String> = plan
+        .effects()
+        .iter()
+        .filter_map(|effect| match effect {
+            Effect::Replace { id, to, .. } if prevents_destroy(to) => {
+                Some(format!("{}.{}", id.resource_type, id.name))
+            }
+            _ => None,
+        })
+        .collect();
+    if replaced.is_empty() {
+        Ok(())
+    } else {
+        Err(prevent_destroy_error("replace", &replaced))
+    }
+}
+
+/// Fail when the plan creates (or replaces) a resource of a type the provider
+/// cannot create
+fn check_creatable(
+    plan: &Plan,
+    capabilities: &HashMap<String, Capabilities>,
+) -> Result<(), String> {
+    let uncreatable: Vec<String> = plan
+        .effects()
+        .iter()
+        .filter_map(|effect| match effect {
+            Effect::Create(Resource { id, .. }) | Effect::Replace { id, .. }
+                if capabilities
+                    .get(&id.resource_type)
+                    .is_some_and(|c| !c.create) =>
+            {
+                Some(format!("{}.{}", id.resource_type, id.name))
+            }
+            _ => None,
+        })
+        .collect();
+    if uncreatable.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Cannot create {}: the provider cannot create resources of this type",
+            uncreatable.join(", ")
+        ))
+    }
+}
+
+fn prevent_destroy_error(action: &str, addresses: &[String]) -> String {
+    format!(
+        "Cannot {} {}: lifecycle.prevent_destroy is set. Remove it from the configuration first.",
+        action,
+        addresses.join(", ")
+    )
+}
+
 /// Extract binding names that a resource depends on
 fn get_resource_dependencies(resource: &Resource) -> HashSet<String> {
     let mut deps = HashSet::new();
     for value in resource.attributes.values() {
         collect_dependencies(value, &mut deps);
     }
+    // Explicit `depends_on` bindings
+    if let Some(Value::List(bindings)) = resource.attributes.get("_depends_on") {
+        deps.extend(bindings.iter().filter_map(|binding| match binding {
+            Value::String(binding) => Some(binding.clone()),
+            _ => None,
+        }));
+    }
     deps
 }
 
@@ -1690,7 +3987,7 @@ fn collect_dependencies(value: &Value, deps: &mut HashSet<String>) {
         Value::TypedResourceRef { binding_name, .. } => {
             deps.insert(binding_name.clone());
         }
-        Value::List(items) => {
+        Value::List(items) | Value::Interpolation(items) | Value::Operation(_, items) => {
             for item in items {
                 collect_dependencies(item, deps);
             }
@@ -1704,77 +4001,342 @@ fn collect_dependencies(value: &Value, deps: &mut HashSet<String>) {
     }
 }
 
-/// Sort resources topologically based on dependencies
+/// Rebuild the resources recorded in state, for `destroy --from-state`. String
+/// attributes holding another resource's identifier become references to it,
+/// so the resources sort in dependency order like configured ones.
+fn resources_from_state(state: &StateFile) -> Vec<Resource> {
+    let binding = |r: &ResourceState| format!("{}.{}", r.resource_type, r.name);
+    let bindings: HashMap<&str, String> = state
+        .resources
+        .iter()
+        .filter_map(|r| r.identifier.as_deref().map(|id| (id, binding(r))))
+        .collect();
+
+    fn link(value: Value, bindings: &HashMap<&str, String>) -> Value {
+        match value {
+            Value::String(s) => match bindings.get(s.as_str()) {
+                Some(binding) => Value::ResourceRef(binding.clone(), "id".to_string()),
+                None => Value::String(s),
+            },
+            Value::List(items) => {
+                Value::List(items.into_iter().map(|v| link(v, bindings)).collect())
+            }
+            Value::Map(map) => Value::Map(
+                map.into_iter()
+                    .map(|(k, v)| (k, link(v, bindings)))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
+    state
+        .resources
+        .iter()
+        .map(|r| {
+            let mut resource = Resource::new(r.resource_type.clone(), r.name.clone());
+            for (key, value) in &r.attributes {
+                resource
+                    .attributes
+                    .insert(key.clone(), link(json_to_value(value), &bindings));
+            }
+            resource
+                .attributes
+                .insert("_binding".to_string(), Value::String(binding(r)));
+            resource
+        })
+        .collect()
+}
+
+/// Sort resources topologically based on dependencies.
+/// Resources that are ready at the same time are ordered by resource type, then name,
+/// so the order does not depend on file discovery or map iteration order.
 fn sort_resources_by_dependencies(resources: &[Resource]) -> Vec<Resource> {
-    // Build binding name to resource mapping
-    let mut binding_to_resource: HashMap<String, &Resource> = HashMap::new();
-    for resource in resources {
+    // Build binding name to resource index mapping
+    let mut binding_to_index: HashMap<String, usize> = HashMap::new();
+    for (idx, resource) in resources.iter().enumerate() {
         if let Some(Value::String(binding_name)) = resource.attributes.get("_binding") {
-            binding_to_resource.insert(binding_name.clone(), resource);
+            binding_to_index.insert(binding_name.clone(), idx);
         }
     }
 
     // Build dependency graph
-    let mut sorted = Vec::new();
-    let mut visited: HashSet<String> = HashSet::new();
-    let mut visiting: HashSet<String> = HashSet::new();
-
-    fn visit<'a>(
-        resource: &'a Resource,
-        binding_to_resource: &HashMap<String, &'a Resource>,
-        visited: &mut HashSet<String>,
-        visiting: &mut HashSet<String>,
-        sorted: &mut Vec<Resource>,
-    ) {
-        let binding_name = resource
-            .attributes
-            .get("_binding")
-            .and_then(|v| match v {
-                Value::String(s) => Some(s.clone()),
-                _ => None,
-            })
-            .unwrap_or_else(|| format!("{}:{}", resource.id.resource_type, resource.id.name));
+    let mut in_degree = vec![0usize; resources.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); resources.len()];
+    for (idx, resource) in resources.iter().enumerate() {
+        for dep in get_resource_dependencies(resource) {
+            if let Some(&dep_idx) = binding_to_index.get(&dep)
+                && dep_idx != idx
+            {
+                in_degree[idx] += 1;
+                dependents[dep_idx].push(idx);
+            }
+        }
+    }
 
-        if visited.contains(&binding_name) {
-            return;
+    let sort_key = |idx: usize| {
+        (
+            resources[idx].id.resource_type.clone(),
+            resources[idx].id.name.clone(),
+            idx,
+        )
+    };
+    let mut ready: BTreeSet<(String, String, usize)> = (0..resources.len())
+        .filter(|&idx| in_degree[idx] == 0)
+        .map(sort_key)
+        .collect();
+
+    let mut sorted = Vec::with_capacity(resources.len());
+    let mut emitted = vec![false; resources.len()];
+    loop {
+        let idx = match ready.pop_first() {
+            Some((_, _, idx)) => idx,
+            // Circular dependency (rejected by validate_resources before planning) -
+            // release the first remaining resource and continue
+            None => match (0..resources.len())
+                .filter(|&idx| !emitted[idx])
+                .min_by_key(|&idx| sort_key(idx))
+            {
+                Some(idx) => idx,
+                None => break,
+            },
+        };
+
+        emitted[idx] = true;
+        sorted.push(resources[idx].clone());
+        for &dependent in &dependents[idx] {
+            in_degree[dependent] = in_degree[dependent].saturating_sub(1);
+            if in_degree[dependent] == 0 && !emitted[dependent] {
+                ready.insert(sort_key(dependent));
+            }
         }
-        if visiting.contains(&binding_name) {
-            // Circular dependency - just continue
-            return;
+    }
+
+    sorted
+}
+
+/// Find a dependency cycle among resources, returned as the binding names along
+/// the cycle with the first repeated at the end (`a -> b -> a`)
+fn find_dependency_cycle(resources: &[Resource]) -> Option<Vec<String>> {
+    let binding_of = |resource: &Resource| match resource.attributes.get("_binding") {
+        Some(Value::String(binding)) => Some(binding.clone()),
+        _ => None,
+    };
+    let binding_to_index: HashMap<String, usize> = resources
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, resource)| binding_of(resource).map(|binding| (binding, idx)))
+        .collect();
+
+    // Bindings sort the same way everywhere, so the reported cycle is stable
+    let dependencies: Vec<Vec<usize>> = resources
+        .iter()
+        .map(|resource| {
+            let mut deps: Vec<usize> = get_resource_dependencies(resource)
+                .iter()
+                .filter_map(|dep| binding_to_index.get(dep).copied())
+                .collect();
+            deps.sort_by_key(|&dep_idx| binding_of(&resources[dep_idx]));
+            deps
+        })
+        .collect();
+    let mut roots: Vec<usize> = (0..resources.len()).collect();
+    roots.sort_by_key(|&idx| binding_of(&resources[idx]));
+
+    // Depth-first search; reaching a resource still on the path closes a cycle
+    let mut done = vec![false; resources.len()];
+    let mut path: Vec<usize> = Vec::new();
+    fn visit(
+        idx: usize,
+        dependencies: &[Vec<usize>],
+        done: &mut [bool],
+        path: &mut Vec<usize>,
+    ) -> Option<Vec<usize>> {
+        if let Some(start) = path.iter().position(|&on_path| on_path == idx) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(idx);
+            return Some(cycle);
+        }
+        if done[idx] {
+            return None;
+        }
+        path.push(idx);
+        for &dep in &dependencies[idx] {
+            if let Some(cycle) = visit(dep, dependencies, done, path) {
+                return Some(cycle);
+            }
         }
+        path.pop();
+        done[idx] = true;
+        None
+    }
 
-        visiting.insert(binding_name.clone());
+    roots.into_iter().find_map(|idx| {
+        visit(idx, &dependencies, &mut done, &mut path).map(|cycle| {
+            cycle
+                .into_iter()
+                .filter_map(|idx| binding_of(&resources[idx]))
+                .collect()
+        })
+    })
+}
 
-        // Visit dependencies first
-        let deps = get_resource_dependencies(resource);
-        for dep in deps {
-            if let Some(dep_resource) = binding_to_resource.get(&dep) {
-                visit(dep_resource, binding_to_resource, visited, visiting, sorted);
+/// Which related resources a `--target` selection pulls in
+#[derive(Clone, Copy)]
+enum TargetScope {
+    /// Resources the targets reference (they must exist before the targets)
+    Dependencies,
+    /// Resources that reference the targets (they must be removed before the targets)
+    Dependents,
+}
+
+/// Restrict resources to those matching `targets` (`resource_type.name` or binding name)
+/// plus their transitive dependencies or dependents. No targets selects every resource;
+/// a target that matches no resource prints a warning and is ignored.
+fn select_targets(resources: &[Resource], targets: &[String], scope: TargetScope) -> Vec<Resource> {
+    let (selected, unmatched) = target_selection(resources, targets, scope);
+    for target in unmatched {
+        notice!(
+            "{} Target '{}' does not match any resource.",
+            "Warning:".yellow().bold(),
+            target
+        );
+    }
+    selected
+}
+
+/// The resources selected by `targets`, and the targets that matched no resource
+fn target_selection<'a>(
+    resources: &[Resource],
+    targets: &'a [String],
+    scope: TargetScope,
+) -> (Vec<Resource>, Vec<&'a String>) {
+    if targets.is_empty() {
+        return (resources.to_vec(), Vec::new());
+    }
+
+    let binding_to_index: HashMap<&str, usize> = resources
+        .iter()
+        .enumerate()
+        .filter_map(
+            |(idx, resource)| match resource.attributes.get("_binding") {
+                Some(Value::String(binding_name)) => Some((binding_name.as_str(), idx)),
+                _ => None,
+            },
+        )
+        .collect();
+
+    // Edges point from each resource to the resources the scope pulls in with it
+    let mut edges: Vec<Vec<usize>> = vec![Vec::new(); resources.len()];
+    for (idx, resource) in resources.iter().enumerate() {
+        for dep in get_resource_dependencies(resource) {
+            if let Some(&dep_idx) = binding_to_index.get(dep.as_str()) {
+                match scope {
+                    TargetScope::Dependencies => edges[idx].push(dep_idx),
+                    TargetScope::Dependents => edges[dep_idx].push(idx),
+                }
             }
         }
+    }
 
-        visiting.remove(&binding_name);
-        visited.insert(binding_name);
-        sorted.push(resource.clone());
+    let mut pending: Vec<usize> = Vec::new();
+    let mut unmatched = Vec::new();
+    for target in targets {
+        let matched: Vec<usize> = resources
+            .iter()
+            .enumerate()
+            .filter(|(_, resource)| {
+                format!("{}.{}", resource.id.resource_type, resource.id.name) == *target
+                    || matches!(
+                        resource.attributes.get("_binding"),
+                        Some(Value::String(binding_name)) if binding_name == target
+                    )
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+        if matched.is_empty() {
+            unmatched.push(target);
+        }
+        pending.extend(matched);
     }
 
-    for resource in resources {
-        visit(
-            resource,
-            &binding_to_resource,
-            &mut visited,
-            &mut visiting,
-            &mut sorted,
-        );
+    let mut selected = vec![false; resources.len()];
+    while let Some(idx) = pending.pop() {
+        if !selected[idx] {
+            selected[idx] = true;
+            pending.extend(&edges[idx]);
+        }
     }
 
-    sorted
+    let selected = resources
+        .iter()
+        .zip(selected)
+        .filter(|(_, selected)| *selected)
+        .map(|(resource, _)| resource.clone())
+        .collect();
+    (selected, unmatched)
+}
+
+/// Capabilities of every resource type the provider handles, keyed by type name
+fn provider_capabilities(provider: &dyn Provider) -> HashMap<String, Capabilities> {
+    provider
+        .resource_types()
+        .iter()
+        .map(|t| (t.name().to_string(), t.capabilities()))
+        .collect()
+}
+
+/// Read the current state of every resource, running up to `parallelism` reads at once.
+/// Reads are independent and idempotent; the first failure (in resource order) is returned.
+async fn read_current_states(
+    provider: &dyn Provider,
+    resources: &[Resource],
+    state_file: &Option<StateFile>,
+    parallelism: usize,
+) -> Result<HashMap<ResourceId, State>, String> {
+    let states: Vec<State> = stream::iter(resources.iter().map(|resource| async move {
+        let result = if resource.read_only {
+            provider.read_data_source(resource).await
+        } else {
+            let identifier = get_identifier_from_state(state_file, resource);
+            provider
+                .read_managed_resource(resource, identifier.as_deref())
+                .await
+        };
+        result.map_err(|e| {
+            let e = if e.resource_id.is_none() {
+                e.for_resource(resource.id.clone())
+            } else {
+                e
+            };
+            format!("Failed to read state: {}", format_provider_error(&e))
+        })
+    }))
+    .buffered(parallelism.max(1))
+    .try_collect()
+    .await?;
+
+    Ok(resources
+        .iter()
+        .map(|resource| resource.id.clone())
+        .zip(states)
+        .collect())
 }
 
+/// Resources changed outside carina, each with the attributes that drifted
+type Drift = Vec<(ResourceId, Vec<String>)>;
+
+/// Compute the plan for a configuration. Also returns the resources in dependency
+/// order (references unresolved), the current states the plan was based on, and
+/// the drift found in them. Without `refresh`, managed resources are planned
+/// against their recorded state, only data sources are read from the provider,
+/// and no drift is detected.
 async fn create_plan_from_parsed(
     parsed: &ParsedFile,
     state_file: &Option<StateFile>,
-) -> Result<Plan, String> {
+    refresh: bool,
+    parallelism: usize,
+) -> Result<(Plan, Vec<Resource>, HashMap<ResourceId, State>, Drift), String> {
     let sorted_resources = sort_resources_by_dependencies(&parsed.resources);
 
     // Select appropriate Provider based on configuration
@@ -1782,26 +4344,156 @@ async fn create_plan_from_parsed(
 
     // Read states for all resources using identifier from state
     // In identifier-based approach, if there's no identifier in state, the resource doesn't exist
-    let mut current_states: HashMap<ResourceId, State> = HashMap::new();
+    let current_states = if refresh {
+        read_current_states(
+            provider.as_ref(),
+            &sorted_resources,
+            state_file,
+            parallelism,
+        )
+        .await?
+    } else {
+        let data_sources: Vec<Resource> = sorted_resources
+            .iter()
+            .filter(|r| r.read_only)
+            .cloned()
+            .collect();
+        let mut states =
+            read_current_states(provider.as_ref(), &data_sources, state_file, parallelism).await?;
+        states.extend(recorded_states(&sorted_resources, state_file.as_ref()));
+        states
+    };
     for resource in &sorted_resources {
-        let identifier = get_identifier_from_state(state_file, resource);
-        let state = provider
-            .read(&resource.id, identifier.as_deref())
-            .await
-            .map_err(|e| format!("Failed to read state: {}", e))?;
-        current_states.insert(resource.id.clone(), state);
+        if let Some(status) = current_states
+            .get(&resource.id)
+            .and_then(|state| state.transitional_status.as_ref())
+        {
+            notice!(
+                "{} {}.{} is not yet ready ({})",
+                "Note:".yellow().bold(),
+                resource.id.resource_type,
+                resource.id.name,
+                status
+            );
+        }
+    }
+    let mut drift = Drift::new();
+    if refresh {
+        for resource in sorted_resources.iter().filter(|r| !r.read_only) {
+            let recorded = state_file
+                .as_ref()
+                .and_then(|s| s.find_resource(&resource.id.resource_type, &resource.id.name));
+            let (Some(state), Some(recorded)) = (current_states.get(&resource.id), recorded) else {
+                continue;
+            };
+            if !state.exists {
+                continue;
+            }
+            let drifted = drifted_attributes(state, recorded);
+            if !drifted.is_empty() {
+                drift.push((resource.id.clone(), drifted));
+            }
+        }
     }
 
     // Resolve ResourceRef values using AWS state
-    let mut resources = sorted_resources;
+    let mut resources = sorted_resources.clone();
     resolve_refs_with_state(&mut resources, &current_states);
 
-    Ok(create_plan(&resources, &current_states))
+    let capabilities = provider_capabilities(provider.as_ref());
+    let plan = create_plan_with_capabilities(&resources, &current_states, &capabilities);
+    check_creatable(&plan, &capabilities)?;
+    Ok((plan, sorted_resources, current_states, drift))
+}
+
+/// A plan saved by `carina plan --out`, with everything apply needs to execute it
+/// without re-planning or reading the configuration again
+#[derive(Serialize, Deserialize)]
+struct SavedPlan {
+    providers: Vec<ProviderConfig>,
+    backend: Option<BackendConfig>,
+    /// File containing the backend block, where an auto-created state bucket is recorded
+    backend_file: Option<PathBuf>,
+    outputs: Vec<OutputParameter>,
+    /// Lineage of the state the plan was computed against
+    lineage: Option<String>,
+    /// Serial of the state the plan was computed against
+    serial: Option<u64>,
+    /// Resources in dependency order, with references left for apply to resolve
+    resources: Vec<Resource>,
+    /// Current states read while planning
+    current_states: Vec<State>,
+    plan: Plan,
+}
+
+impl SavedPlan {
+    fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize plan: {}", e))?;
+        fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Load a saved plan if `path` is a plan file rather than a .crn file or directory
+    fn load(path: &Path) -> Result<Option<Self>, String> {
+        if !path.is_file() || path.extension().is_some_and(|ext| ext == "crn") {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse plan file {}: {}", path.display(), e))
+    }
+
+    /// The configuration the plan was computed from, as far as apply needs it
+    fn configuration(&self) -> ParsedFile {
+        ParsedFile {
+            providers: self.providers.clone(),
+            resources: self.resources.clone(),
+            variables: HashMap::new(),
+            imports: vec![],
+            module_calls: vec![],
+            inputs: vec![],
+            outputs: self.outputs.clone(),
+            backend: self.backend.clone(),
+        }
+    }
+
+    /// Ensure the state has not changed since the plan was computed
+    fn check_current(&self, state_file: &Option<StateFile>) -> Result<(), String> {
+        let lineage = state_file.as_ref().map(|s| s.lineage.clone());
+        let serial = state_file.as_ref().map(|s| s.serial);
+        if lineage != self.lineage || serial != self.serial {
+            return Err(format!(
+                "Saved plan is stale: it was computed against state serial {}, but the current serial is {}. Run 'carina plan' again.",
+                self.serial.map_or("(none)".to_string(), |s| s.to_string()),
+                serial.map_or("(none)".to_string(), |s| s.to_string())
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Note each resource changed outside carina, ahead of the plan
+fn print_drift(drift: &Drift) {
+    for (id, attributes) in drift {
+        println!(
+            "{} {}.{} has drifted; changed outside carina: {}",
+            "Note:".yellow().bold(),
+            id.resource_type,
+            id.name,
+            attributes.join(", ")
+        );
+    }
 }
 
 fn print_plan(plan: &Plan) {
     if plan.is_empty() {
-        println!("{}", "No changes. Infrastructure is up-to-date.".green());
+        println!(
+            "{}{}",
+            "No changes. Infrastructure is up-to-date.".green(),
+            plan.summary().unchanged_suffix()
+        );
         return;
     }
 
@@ -1813,7 +4505,9 @@ fn print_plan(plan: &Plan) {
     for (idx, effect) in plan.effects().iter().enumerate() {
         let (resource, deps) = match effect {
             Effect::Create(r) => (Some(r), get_resource_dependencies(r)),
-            Effect::Update { to, .. } => (Some(to), get_resource_dependencies(to)),
+            Effect::Update { to, .. } | Effect::Replace { to, .. } => {
+                (Some(to), get_resource_dependencies(to))
+            }
             Effect::Read { resource } => (Some(resource), get_resource_dependencies(resource)),
             Effect::Delete(_) => (None, HashSet::new()),
         };
@@ -1847,6 +4541,10 @@ fn print_plan(plan: &Plan) {
         }
     }
 
+    for children in dependents.values_mut() {
+        children.sort();
+    }
+
     // Find root resources (no dependencies within the plan)
     let mut roots: Vec<usize> = Vec::new();
     for (idx, deps) in &effect_deps {
@@ -1881,6 +4579,7 @@ fn print_plan(plan: &Plan) {
         let colored_symbol = match effect {
             Effect::Create(_) => "+".green().bold(),
             Effect::Update { .. } => "~".yellow().bold(),
+            Effect::Replace { .. } => "-/+".red().bold(),
             Effect::Delete(_) => "-".red().bold(),
             Effect::Read { .. } => "<=".cyan().bold(),
         };
@@ -1931,6 +4630,15 @@ fn print_plan(plan: &Plan) {
                 });
                 for key in keys {
                     let value = &r.attributes[key];
+                    if let Some(blocks) = block_list(value).filter(|blocks| !blocks.is_empty()) {
+                        println!("{}{}:", attr_prefix, key);
+                        for block in blocks {
+                            for line in format_block("-", block) {
+                                println!("{}{}", attr_prefix, line.green());
+                            }
+                        }
+                        continue;
+                    }
                     if key == "name" {
                         println!(
                             "{}{}: {}",
@@ -1948,13 +4656,19 @@ fn print_plan(plan: &Plan) {
                     }
                 }
             }
-            Effect::Update { id, from, to, .. } => {
+            Effect::Update { id, from, to } | Effect::Replace { id, from, to } => {
+                let replace_note = if matches!(effect, Effect::Replace { .. }) {
+                    format!(" {}", "(must be replaced)".red())
+                } else {
+                    String::new()
+                };
                 println!(
-                    "{}{}{} {}",
+                    "{}{}{} {}{}",
                     base_indent,
                     connector,
                     colored_symbol,
-                    id.resource_type.cyan().bold()
+                    id.resource_type.cyan().bold(),
+                    replace_note
                 );
                 let attr_prefix = if indent == 0 {
                     format!("{}{}", base_indent, attr_base)
@@ -1979,27 +4693,43 @@ fn print_plan(plan: &Plan) {
                 for key in keys {
                     let new_value = &to.attributes[key];
                     let old_value = from.attributes.get(key);
-                    if old_value != Some(new_value) {
-                        let old_str = old_value
-                            .map(|v| format_value_with_key(v, Some(key)))
-                            .unwrap_or_else(|| "(none)".to_string());
-                        if key == "name" {
-                            println!(
-                                "{}{}: {} → {}",
-                                attr_prefix,
-                                key.bold(),
-                                old_str.red(),
-                                format_value_with_key(new_value, Some(key)).white().bold()
-                            );
-                        } else {
-                            println!(
-                                "{}{}: {} → {}",
-                                attr_prefix,
-                                key,
-                                old_str.red(),
-                                format_value_with_key(new_value, Some(key)).green()
-                            );
+                    if old_value.is_some_and(|old| attribute_values_equal(key, new_value, old)) {
+                        continue;
+                    }
+                    // Nested blocks show which blocks are removed and added
+                    if let Some((removed, added)) = block_list_changes(old_value, new_value) {
+                        println!("{}{}:", attr_prefix, key);
+                        for block in removed {
+                            for line in format_block("-", block) {
+                                println!("{}{}", attr_prefix, line.red());
+                            }
+                        }
+                        for block in added {
+                            for line in format_block("+", block) {
+                                println!("{}{}", attr_prefix, line.green());
+                            }
                         }
+                        continue;
+                    }
+                    let old_str = old_value
+                        .map(|v| format_value_with_key(v, Some(key)))
+                        .unwrap_or_else(|| "(none)".to_string());
+                    if key == "name" {
+                        println!(
+                            "{}{}: {} → {}",
+                            attr_prefix,
+                            key.bold(),
+                            old_str.red(),
+                            format_value_with_key(new_value, Some(key)).white().bold()
+                        );
+                    } else {
+                        println!(
+                            "{}{}: {} → {}",
+                            attr_prefix,
+                            key,
+                            old_str.red(),
+                            format_value_with_key(new_value, Some(key)).green()
+                        );
                     }
                 }
             }
@@ -2109,28 +4839,190 @@ fn print_plan(plan: &Plan) {
 
     println!();
     let summary = plan.summary();
+    let replace = if summary.replace > 0 {
+        format!(", {} to replace", summary.replace.to_string().red())
+    } else {
+        String::new()
+    };
     if summary.read > 0 {
         println!(
-            "Plan: {} to read, {} to add, {} to change, {} to destroy.",
+            "Plan: {} to read, {} to add, {} to change, {} to destroy{}.{}",
             summary.read.to_string().cyan(),
             summary.create.to_string().green(),
             summary.update.to_string().yellow(),
-            summary.delete.to_string().red()
+            summary.delete.to_string().red(),
+            replace,
+            summary.unchanged_suffix()
         );
     } else {
         println!(
-            "Plan: {} to add, {} to change, {} to destroy.",
+            "Plan: {} to add, {} to change, {} to destroy{}.{}",
             summary.create.to_string().green(),
             summary.update.to_string().yellow(),
-            summary.delete.to_string().red()
+            summary.delete.to_string().red(),
+            replace,
+            summary.unchanged_suffix()
         );
     }
 }
 
+/// One nested block's attributes
+type Block = BTreeMap<String, Value>;
+
+/// The blocks of a list of nested blocks (e.g. repeated `ingress {}`), or None when
+/// the value is not a list of maps
+fn block_list(value: &Value) -> Option<Vec<&Block>> {
+    match value {
+        Value::List(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::Map(map) => Some(map),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+/// Plan lines of one nested block: the first entry follows `marker`, the rest align under it
+fn format_block(marker: &str, block: &Block) -> Vec<String> {
+    if block.is_empty() {
+        return vec![format!("  {} {{}}", marker)];
+    }
+    let continuation = " ".repeat(marker.chars().count());
+    block
+        .iter()
+        .enumerate()
+        .map(|(i, (key, value))| {
+            let lead = if i == 0 { marker } else { &continuation };
+            format!("  {} {}: {}", lead, key, format_value(value))
+        })
+        .collect()
+}
+
+/// Blocks removed from and added to a list of nested blocks, in list order. A block on
+/// both sides is unchanged wherever it moved. None when either side is not a list of
+/// blocks, or when only the order changed.
+fn block_list_changes<'a>(
+    old: Option<&'a Value>,
+    new: &'a Value,
+) -> Option<(Vec<&'a Block>, Vec<&'a Block>)> {
+    let new_blocks = block_list(new)?;
+    let old_blocks = match old {
+        Some(old) => block_list(old)?,
+        None => Vec::new(),
+    };
+
+    let mut matched = vec![false; new_blocks.len()];
+    let mut removed = Vec::new();
+    for block in &old_blocks {
+        match (0..new_blocks.len()).find(|&i| !matched[i] && new_blocks[i] == *block) {
+            Some(i) => matched[i] = true,
+            None => removed.push(*block),
+        }
+    }
+    let added: Vec<_> = new_blocks
+        .iter()
+        .zip(&matched)
+        .filter(|(_, matched)| !**matched)
+        .map(|(block, _)| *block)
+        .collect();
+
+    if removed.is_empty() && added.is_empty() {
+        None
+    } else {
+        Some((removed, added))
+    }
+}
+
+/// Convert a plan to the `--json` schema: `effects` in plan order, each tagged with its
+/// `action`, the `drift` found while planning, plus the `summary` counts
+fn plan_to_json(plan: &Plan, drift: &Drift) -> serde_json::Value {
+    // Internal attributes (e.g. `_binding`) are not part of the output
+    let attributes_json = |attributes: &HashMap<String, Value>| -> serde_json::Value {
+        attributes
+            .iter()
+            .filter(|(k, _)| !k.starts_with('_'))
+            .map(|(k, v)| (k.clone(), value_to_json(v)))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    };
+
+    let effects: Vec<serde_json::Value> = plan
+        .effects()
+        .iter()
+        .map(|effect| {
+            let id = effect.resource_id();
+            let mut entry = serde_json::json!({
+                "action": effect.kind(),
+                "resource_type": id.resource_type,
+                "name": id.name,
+            });
+            match effect {
+                Effect::Create(resource) | Effect::Read { resource } => {
+                    entry["attributes"] = attributes_json(&resource.attributes);
+                }
+                Effect::Update { from, to, .. } | Effect::Replace { from, to, .. } => {
+                    let changes: serde_json::Map<_, _> = to
+                        .attributes
+                        .iter()
+                        .filter(|(k, v)| {
+                            !k.starts_with('_')
+                                && !from
+                                    .attributes
+                                    .get(*k)
+                                    .is_some_and(|old| attribute_values_equal(k, v, old))
+                        })
+                        .map(|(k, v)| {
+                            let before = from
+                                .attributes
+                                .get(k)
+                                .map_or(serde_json::Value::Null, value_to_json);
+                            (
+                                k.clone(),
+                                serde_json::json!({ "before": before, "after": value_to_json(v) }),
+                            )
+                        })
+                        .collect();
+                    entry["changes"] = changes.into();
+                }
+                Effect::Delete(_) => {}
+            }
+            entry
+        })
+        .collect();
+
+    let drift: Vec<serde_json::Value> = drift
+        .iter()
+        .map(|(id, attributes)| {
+            serde_json::json!({
+                "resource_type": id.resource_type,
+                "name": id.name,
+                "attributes": attributes,
+            })
+        })
+        .collect();
+
+    let summary = plan.summary();
+    serde_json::json!({
+        "effects": effects,
+        "drift": drift,
+        "summary": {
+            "read": summary.read,
+            "create": summary.create,
+            "update": summary.update,
+            "replace": summary.replace,
+            "delete": summary.delete,
+            "unchanged": summary.unchanged,
+        },
+    })
+}
+
 fn format_effect(effect: &Effect) -> String {
     match effect {
         Effect::Create(r) => format!("Create {}.{}", r.id.resource_type, r.id.name),
         Effect::Update { id, .. } => format!("Update {}.{}", id.resource_type, id.name),
+        Effect::Replace { id, .. } => format!("Replace {}.{}", id.resource_type, id.name),
         Effect::Delete(id) => format!("Delete {}.{}", id.resource_type, id.name),
         Effect::Read { resource } => {
             format!("Read {}.{}", resource.id.resource_type, resource.id.name)
@@ -2179,6 +5071,7 @@ fn format_value(value: &Value) -> String {
 
 fn format_value_with_key(value: &Value, _key: Option<&str>) -> String {
     match value {
+        Value::Null => "null".to_string(),
         Value::String(s) => {
             // DSL enum format (namespaced identifiers) - display without quotes
             if is_dsl_enum_format(s) {
@@ -2187,6 +5080,7 @@ fn format_value_with_key(value: &Value, _key: Option<&str>) -> String {
             format!("\"{}\"", s)
         }
         Value::Int(n) => n.to_string(),
+        Value::Float(f) => f.to_string(),
         Value::Bool(b) => b.to_string(),
         Value::List(items) => {
             let strs: Vec<_> = items.iter().map(format_value).collect();
@@ -2200,6 +5094,7 @@ fn format_value_with_key(value: &Value, _key: Option<&str>) -> String {
             format!("{{{}}}", strs.join(", "))
         }
         Value::ResourceRef(binding, attr) => format!("{}.{}", binding, attr),
+        Value::SplatRef(binding, attr) => format!("{}[*].{}", binding, attr),
         Value::TypedResourceRef {
             binding_name,
             attribute_name,
@@ -2209,6 +5104,11 @@ fn format_value_with_key(value: &Value, _key: Option<&str>) -> String {
             Some(m) => format!("{}.{}", name, m),
             None => name.clone(),
         },
+        Value::Interpolation(parts) => format!("\"{}\"", interpolation_template(parts)),
+        Value::Operation(operator, operands) => {
+            let operands: Vec<String> = operands.iter().map(format_value).collect();
+            operator.render(&operands)
+        }
     }
 }
 
@@ -2271,11 +5171,34 @@ fn resource_to_state(
     resource_state
 }
 
+/// Convert a state JSON value back to a Value
+fn json_to_value(json: &serde_json::Value) -> Value {
+    match json {
+        serde_json::Value::String(s) => Value::String(s.clone()),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Int(i),
+            None => Value::Float(n.as_f64().unwrap_or(0.0)),
+        },
+        serde_json::Value::Bool(b) => Value::Bool(*b),
+        serde_json::Value::Array(items) => Value::List(items.iter().map(json_to_value).collect()),
+        serde_json::Value::Object(map) => {
+            let m: BTreeMap<_, _> = map
+                .iter()
+                .map(|(k, v)| (k.clone(), json_to_value(v)))
+                .collect();
+            Value::Map(m)
+        }
+        serde_json::Value::Null => Value::Null,
+    }
+}
+
 /// Convert Value to serde_json::Value
 fn value_to_json(value: &Value) -> serde_json::Value {
     match value {
+        Value::Null => serde_json::Value::Null,
         Value::String(s) => serde_json::Value::String(s.clone()),
         Value::Int(n) => serde_json::Value::Number((*n).into()),
+        Value::Float(f) => float_to_json(*f),
         Value::Bool(b) => serde_json::Value::Bool(*b),
         Value::List(items) => serde_json::Value::Array(items.iter().map(value_to_json).collect()),
         Value::Map(map) => {
@@ -2288,6 +5211,9 @@ fn value_to_json(value: &Value) -> serde_json::Value {
         Value::ResourceRef(binding, attr) => {
             serde_json::Value::String(format!("${{{}.{}}}", binding, attr))
         }
+        Value::SplatRef(binding, attr) => {
+            serde_json::Value::String(format!("${{{}[*].{}}}", binding, attr))
+        }
         Value::TypedResourceRef {
             binding_name,
             attribute_name,
@@ -2297,9 +5223,30 @@ fn value_to_json(value: &Value) -> serde_json::Value {
             Some(m) => serde_json::Value::String(format!("{}.{}", name, m)),
             None => serde_json::Value::String(name.clone()),
         },
+        Value::Interpolation(parts) => serde_json::Value::String(interpolation_template(parts)),
+        Value::Operation(..) => serde_json::Value::String(format_value(value)),
     }
 }
 
+/// Convert a float to JSON; non-finite values have no JSON representation
+fn float_to_json(f: f64) -> serde_json::Value {
+    serde_json::Number::from_f64(f)
+        .map(serde_json::Value::Number)
+        .unwrap_or(serde_json::Value::Null)
+}
+
+/// Render interpolation parts back into `${...}` template form
+fn interpolation_template(parts: &[Value]) -> String {
+    parts
+        .iter()
+        .map(|part| match part {
+            Value::String(s) => s.clone(),
+            Value::ResourceRef(binding, attr) => format!("${{{}.{}}}", binding, attr),
+            other => format!("${{{}}}", format_value(other)),
+        })
+        .collect()
+}
+
 /// Run force-unlock command
 async fn run_force_unlock(lock_id: &str, path: &PathBuf) -> Result<(), String> {
     let parsed = load_configuration(path)?.parsed;
@@ -2318,7 +5265,13 @@ async fn run_force_unlock(lock_id: &str, path: &PathBuf) -> Result<(), String> {
     println!("Lock ID: {}", lock_id);
 
     match backend.force_unlock(lock_id).await {
-        Ok(()) => {
+        Ok(lock) => {
+            println!(
+                "Removed lock held by {} (operation: {}, age: {})",
+                lock.who,
+                lock.operation,
+                lock.age_description()
+            );
             println!("{}", "State has been successfully unlocked.".green().bold());
             Ok(())
         }
@@ -2331,21 +5284,347 @@ async fn run_force_unlock(lock_id: &str, path: &PathBuf) -> Result<(), String> {
     }
 }
 
+/// Run providers command
+async fn run_providers(path: &PathBuf, filter: Option<&str>) -> Result<(), String> {
+    // Providers are set up as configured, when run where there is a configuration
+    let has_configuration =
+        path.is_file() || (path.is_dir() && !find_crn_files_in_dir(path)?.is_empty());
+    let parsed = if has_configuration {
+        load_configuration(path)?.parsed
+    } else {
+        ParsedFile {
+            providers: vec![],
+            resources: vec![],
+            variables: HashMap::new(),
+            imports: vec![],
+            module_calls: vec![],
+            inputs: vec![],
+            outputs: vec![],
+            backend: None,
+        }
+    };
+    let providers: Vec<Box<dyn Provider>> = vec![
+        Box::new(
+            AwsProvider::with_options(
+                &get_aws_region(&parsed),
+                get_provider_string(&parsed, "aws", "profile"),
+                get_provider_string(&parsed, "aws", "assume_role_arn"),
+                get_provider_string(&parsed, "aws", "endpoint_url"),
+            )
+            .await,
+        ),
+        Box::new(
+            AwsccProvider::with_options(
+                &get_awscc_region(&parsed),
+                get_provider_string(&parsed, "awscc", "profile"),
+                get_provider_string(&parsed, "awscc", "assume_role_arn"),
+                get_provider_string(&parsed, "awscc", "endpoint_url"),
+            )
+            .await,
+        ),
+    ];
+
+    let providers: Vec<_> = providers
+        .into_iter()
+        .filter(|p| filter.is_none_or(|name| p.name() == name))
+        .collect();
+
+    if providers.is_empty() {
+        return Err(format!(
+            "Unknown provider '{}'. Available providers: aws, awscc",
+            filter.unwrap_or_default()
+        ));
+    }
+
+    for (i, provider) in providers.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{}", provider.name().cyan().bold());
+
+        let mut resource_types = provider.resource_types();
+        resource_types.sort_by_key(|t| t.name());
+        for resource_type in resource_types {
+            let full_name = format!("{}.{}", provider.name(), resource_type.name());
+            match resource_type.schema().description {
+                Some(description) => println!("  {}  {}", full_name.green(), description.dimmed()),
+                None => println!("  {}", full_name.green()),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// DSL name of a schema key ("s3.bucket" -> "aws.s3.bucket", awscc keys are already prefixed)
+fn dsl_resource_type(schema_key: &str) -> String {
+    if schema_key.starts_with("awscc.") {
+        schema_key.to_string()
+    } else {
+        format!("aws.{}", schema_key)
+    }
+}
+
+/// Run explain command: print a resource type's schema, or list all types
+fn run_explain(resource_type: Option<&str>) -> Result<(), String> {
+    let schemas = get_schemas();
+
+    let Some(resource_type) = resource_type else {
+        let mut keys: Vec<_> = schemas.keys().collect();
+        keys.sort_by_key(|k| dsl_resource_type(k));
+        for key in keys {
+            let name = dsl_resource_type(key);
+            match &schemas[key].description {
+                Some(description) => println!("{}  {}", name.green(), description.dimmed()),
+                None => println!("{}", name.green()),
+            }
+        }
+        return Ok(());
+    };
+
+    let key = resource_type.strip_prefix("aws.").unwrap_or(resource_type);
+    let schema = schemas.get(key).ok_or_else(|| {
+        format!(
+            "Unknown resource type '{}'. Run 'carina explain' to list available types.",
+            resource_type
+        )
+    })?;
+
+    println!("{}", dsl_resource_type(key).cyan().bold());
+    if let Some(description) = &schema.description {
+        println!("{}", description);
+    }
+    println!();
+    println!("{}", "Attributes:".bold());
+
+    let mut attributes: Vec<_> = schema.attributes.values().collect();
+    attributes.sort_by(|a, b| a.name.cmp(&b.name));
+    for attr in attributes {
+        let description = attr.description.as_deref().unwrap_or("").trim();
+        let kind = if attr.required {
+            "required".red().to_string()
+        } else if attr.read_only {
+            "read-only".dimmed().to_string()
+        } else {
+            "optional".to_string()
+        };
+        println!("  {}  {}  {}", attr.name.green(), attr.attr_type, kind);
+        if !description.is_empty() && description != "(read-only)" {
+            println!("      {}", description);
+        }
+        if let Some(default) = &attr.default {
+            println!("      Default: {}", format_value(default));
+        }
+        if attr.immutable {
+            println!("      Changing this attribute replaces the resource.");
+        }
+        if let Some(completions) = &attr.completions {
+            let values: Vec<_> = completions.iter().map(|c| c.value.as_str()).collect();
+            println!("      Values: {}", values.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
 /// Run state subcommands
-async fn run_state_command(command: StateCommands) -> Result<(), String> {
+async fn run_state_command(command: StateCommands, input: bool) -> Result<(), String> {
     match command {
         StateCommands::BucketDelete {
             bucket_name,
             force,
             path,
-        } => run_state_bucket_delete(&bucket_name, force, &path).await,
+        } => run_state_bucket_delete(&bucket_name, force, input, &path).await,
+        StateCommands::Pull { path } => run_state_pull(&path).await,
+        StateCommands::Push {
+            file,
+            path,
+            force,
+            lock_timeout,
+        } => run_state_push(&file, &path, force, lock_timeout).await,
+        StateCommands::Mv {
+            from_address,
+            to_address,
+            path,
+            lock_timeout,
+        } => run_state_mv(&from_address, &to_address, &path, lock_timeout).await,
+    }
+}
+
+/// Create the backend configured in the configuration at `path` (local if none)
+async fn load_backend(path: &PathBuf) -> Result<Box<dyn StateBackend>, String> {
+    let parsed = load_configuration(path)?.parsed;
+    match parsed.backend.as_ref() {
+        Some(config) => create_backend(&convert_backend_config(config))
+            .await
+            .map_err(|e| format!("Failed to create backend: {}", e)),
+        None => Ok(create_local_backend()),
+    }
+}
+
+/// Run state pull command
+async fn run_state_pull(path: &PathBuf) -> Result<(), String> {
+    let backend = load_backend(path).await?;
+    let state = backend
+        .read_state()
+        .await
+        .map_err(|e| format!("Failed to read state: {}", e))?
+        .ok_or("No state found in the configured backend.")?;
+
+    let json = serde_json::to_string_pretty(&state)
+        .map_err(|e| format!("Failed to serialize state: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// Run state push command
+async fn run_state_push(
+    file: &PathBuf,
+    path: &PathBuf,
+    force: bool,
+    lock_timeout: Duration,
+) -> Result<(), String> {
+    let content = fs::read_to_string(file)
+        .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+    let mut state: StateFile = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", file.display(), e))?;
+
+    let backend: Arc<dyn StateBackend> = load_backend(path).await?.into();
+
+    println!("{}", "Acquiring state lock...".cyan());
+    let lock = acquire_lock(&backend, "push", lock_timeout).await?;
+    println!("  {} Lock acquired", "✓".green());
+
+    let result = push_state(backend.as_ref(), &mut state, force).await;
+
+    lock.release().await?;
+    println!("  {} Lock released", "✓".green());
+
+    result?;
+    println!();
+    println!(
+        "{}",
+        format!("State pushed from {}.", file.display())
+            .green()
+            .bold()
+    );
+    Ok(())
+}
+
+/// Write `state` to the backend with a serial above both its own and the backend's.
+/// A state older than the backend's is rejected unless `force` is set.
+async fn push_state(
+    backend: &dyn StateBackend,
+    state: &mut StateFile,
+    force: bool,
+) -> Result<(), String> {
+    let current = backend
+        .read_state()
+        .await
+        .map_err(|e| format!("Failed to read state: {}", e))?;
+
+    if let Some(current) = &current {
+        if state.serial < current.serial && !force {
+            return Err(format!(
+                "State file serial {} is lower than the backend's serial {}; \
+                 use --force to overwrite it",
+                state.serial, current.serial
+            ));
+        }
+        state.serial = state.serial.max(current.serial);
+    }
+
+    state.increment_serial();
+    backend
+        .write_state(state)
+        .await
+        .map_err(|e| format!("Failed to write state: {}", e))?;
+    println!("  {} State saved (serial: {})", "✓".green(), state.serial);
+    Ok(())
+}
+
+/// Run state mv command
+async fn run_state_mv(
+    from_address: &str,
+    to_address: &str,
+    path: &PathBuf,
+    lock_timeout: Duration,
+) -> Result<(), String> {
+    let backend: Arc<dyn StateBackend> = load_backend(path).await?.into();
+
+    println!("{}", "Acquiring state lock...".cyan());
+    let lock = acquire_lock(&backend, "mv", lock_timeout).await?;
+    println!("  {} Lock acquired", "✓".green());
+
+    let result = async {
+        let mut state = backend
+            .read_state()
+            .await
+            .map_err(|e| format!("Failed to read state: {}", e))?
+            .ok_or("No state found in the configured backend.")?;
+        move_state_resource(&mut state, from_address, to_address)?;
+
+        state.increment_serial();
+        backend
+            .write_state(&state)
+            .await
+            .map_err(|e| format!("Failed to write state: {}", e))?;
+        println!("  {} State saved (serial: {})", "✓".green(), state.serial);
+        Ok::<_, String>(())
+    }
+    .await;
+
+    lock.release().await?;
+    println!("  {} Lock released", "✓".green());
+
+    result?;
+    println!();
+    println!(
+        "{}",
+        format!("Moved {} to {}.", from_address, to_address)
+            .green()
+            .bold()
+    );
+    Ok(())
+}
+
+/// Move the state entry at `from` (resource_type.name) to `to`, a free address of
+/// the same type. The identifier, attributes and protected flag move with it.
+fn move_state_resource(state: &mut StateFile, from: &str, to: &str) -> Result<(), String> {
+    let (resource_type, name) = state
+        .resources
+        .iter()
+        .find(|r| format!("{}.{}", r.resource_type, r.name) == from)
+        .map(|r| (r.resource_type.clone(), r.name.clone()))
+        .ok_or_else(|| format!("No resource '{}' in state", from))?;
+
+    let new_name = to
+        .strip_prefix(&resource_type)
+        .and_then(|rest| rest.strip_prefix('.'))
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| {
+            format!(
+                "Destination '{}' must be an address of the same type, e.g. {}.new-name",
+                to, resource_type
+            )
+        })?;
+
+    if state.find_resource(&resource_type, new_name).is_some() {
+        return Err(format!("Resource '{}' already exists in state", to));
     }
+
+    let resource = state
+        .find_resource_mut(&resource_type, &name)
+        .expect("resource was found above");
+    resource.name = new_name.to_string();
+    Ok(())
 }
 
 /// Run state bucket delete command
 async fn run_state_bucket_delete(
     bucket_name: &str,
     force: bool,
+    input: bool,
     path: &PathBuf,
 ) -> Result<(), String> {
     let parsed = load_configuration(path)?.parsed;
@@ -2381,6 +5660,8 @@ async fn run_state_bucket_delete(
     println!("Bucket: {}", bucket_name.yellow());
 
     if !force {
+        require_input(input, "Bucket name confirmation", "use --force to skip it")?;
+
         println!();
         println!("{}", "Type the bucket name to confirm deletion:".yellow());
         print!("  Enter bucket name: ");
@@ -2427,7 +5708,10 @@ async fn run_state_bucket_delete(
             );
             Ok(())
         }
-        Err(e) => Err(format!("Failed to delete bucket: {}", e)),
+        Err(e) => Err(format!(
+            "Failed to delete bucket: {}",
+            format_provider_error(&e)
+        )),
     }
 }
 
@@ -2480,8 +5764,10 @@ impl FileProvider {
 
     fn value_to_json(value: &Value) -> serde_json::Value {
         match value {
+            Value::Null => serde_json::Value::Null,
             Value::String(s) => serde_json::Value::String(s.clone()),
             Value::Int(n) => serde_json::Value::Number((*n).into()),
+            Value::Float(f) => float_to_json(*f),
             Value::Bool(b) => serde_json::Value::Bool(*b),
             Value::List(items) => {
                 serde_json::Value::Array(items.iter().map(Self::value_to_json).collect())
@@ -2497,36 +5783,24 @@ impl FileProvider {
             Value::ResourceRef(binding, attr) => {
                 serde_json::Value::String(format!("${{{}.{}}}", binding, attr))
             }
+            Value::SplatRef(binding, attr) => {
+                serde_json::Value::String(format!("${{{}[*].{}}}", binding, attr))
+            }
             // TypedResourceRef should be resolved before reaching here, but handle it as a string
             Value::TypedResourceRef {
                 binding_name,
                 attribute_name,
                 ..
-            } => serde_json::Value::String(format!("${{{}.{}}}", binding_name, attribute_name)),
-            // UnresolvedIdent should be resolved before reaching here, but handle it as a string
-            Value::UnresolvedIdent(name, member) => match member {
-                Some(m) => serde_json::Value::String(format!("{}.{}", name, m)),
-                None => serde_json::Value::String(name.clone()),
-            },
-        }
-    }
-
-    fn json_to_value(json: &serde_json::Value) -> Value {
-        match json {
-            serde_json::Value::String(s) => Value::String(s.clone()),
-            serde_json::Value::Number(n) => Value::Int(n.as_i64().unwrap_or(0)),
-            serde_json::Value::Bool(b) => Value::Bool(*b),
-            serde_json::Value::Array(items) => {
-                Value::List(items.iter().map(Self::json_to_value).collect())
-            }
-            serde_json::Value::Object(map) => {
-                let m: HashMap<_, _> = map
-                    .iter()
-                    .map(|(k, v)| (k.clone(), Self::json_to_value(v)))
-                    .collect();
-                Value::Map(m)
-            }
-            serde_json::Value::Null => Value::String("null".to_string()),
+            } => serde_json::Value::String(format!("${{{}.{}}}", binding_name, attribute_name)),
+            // UnresolvedIdent should be resolved before reaching here, but handle it as a string
+            Value::UnresolvedIdent(name, member) => match member {
+                Some(m) => serde_json::Value::String(format!("{}.{}", name, m)),
+                None => serde_json::Value::String(name.clone()),
+            },
+            // Interpolation should be resolved before reaching here, but handle it as a string
+            Value::Interpolation(parts) => serde_json::Value::String(interpolation_template(parts)),
+            // Operation should be evaluated before reaching here, but handle it as a string
+            Value::Operation(..) => serde_json::Value::String(format_value(value)),
         }
     }
 }
@@ -2553,7 +5827,7 @@ impl Provider for FileProvider {
             if let Some(attrs) = states.get(&key) {
                 let attributes: HashMap<String, Value> = attrs
                     .iter()
-                    .map(|(k, v)| (k.clone(), Self::json_to_value(v)))
+                    .map(|(k, v)| (k.clone(), json_to_value(v)))
                     .collect();
                 Ok(State::existing(id, attributes).with_identifier("file-id"))
             } else {
@@ -2629,7 +5903,7 @@ impl Provider for FileProvider {
 
 // Format command implementation
 fn run_fmt(path: &PathBuf, check: bool, show_diff: bool, recursive: bool) -> Result<(), String> {
-    let config = FormatConfig::default();
+    let config = FormatConfig::load(path)?;
 
     let files = if path.is_file() {
         vec![path.clone()]
@@ -2709,6 +5983,7 @@ fn run_fmt(path: &PathBuf, check: bool, show_diff: bool, recursive: bool) -> Res
 fn find_crn_files_recursive(dir: &PathBuf) -> Result<Vec<PathBuf>, String> {
     let mut files = Vec::new();
     collect_crn_files_recursive(dir, &mut files)?;
+    files.sort();
     Ok(files)
 }
 
@@ -2746,6 +6021,8 @@ fn find_crn_files_in_dir(dir: &PathBuf) -> Result<Vec<PathBuf>, String> {
             files.push(path);
         }
     }
+    // Merge files in a stable order regardless of directory listing order
+    files.sort();
     Ok(files)
 }
 
@@ -2762,3 +6039,1105 @@ fn print_diff(file: &Path, original: &str, formatted: &str) {
         print!("{}{}", sign, change);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bound(resource_type: &str, binding: &str, refs: &[&str]) -> Resource {
+        let mut resource = Resource::new(resource_type, binding)
+            .with_attribute("_binding", Value::String(binding.to_string()));
+        for (i, target) in refs.iter().enumerate() {
+            resource = resource.with_attribute(
+                format!("ref_{}", i),
+                Value::ResourceRef(target.to_string(), "id".to_string()),
+            );
+        }
+        resource
+    }
+
+    #[test]
+    fn provider_errors_render_by_kind() {
+        let error = |kind| {
+            ProviderError::new("Failed to create VPC: denied")
+                .with_kind(kind)
+                .for_resource(ResourceId::new("vpc", "main"))
+        };
+
+        assert_eq!(
+            format_provider_error(&error(ProviderErrorKind::Other)),
+            "[vpc.main] Failed to create VPC: denied"
+        );
+        assert!(
+            format_provider_error(&error(ProviderErrorKind::AccessDenied))
+                .ends_with("(access denied: check the permissions of the credentials, profile or assumed role in use)")
+        );
+        assert!(
+            format_provider_error(&error(ProviderErrorKind::Throttled))
+                .contains("lower --parallelism")
+        );
+    }
+
+    #[test]
+    fn recorded_states_trust_state_file() {
+        let resources = vec![
+            bound("vpc", "main", &[]),
+            bound("subnet", "app", &[]),
+            Resource {
+                read_only: true,
+                ..bound("ec2.ami", "ubuntu", &[])
+            },
+        ];
+        let mut state = StateFile::new();
+        state.upsert_resource(
+            ResourceState::new("vpc", "main", "aws")
+                .with_identifier("vpc-1")
+                .with_attribute("cidr_block", serde_json::json!("10.0.0.0/16")),
+        );
+
+        let states = recorded_states(&resources, Some(&state));
+        assert_eq!(states.len(), 2);
+        let vpc = &states[&ResourceId::new("vpc", "main")];
+        assert!(vpc.exists);
+        assert_eq!(vpc.identifier.as_deref(), Some("vpc-1"));
+        assert_eq!(
+            vpc.attributes.get("cidr_block"),
+            Some(&Value::String("10.0.0.0/16".to_string()))
+        );
+        assert!(!states[&ResourceId::new("subnet", "app")].exists);
+    }
+
+    #[test]
+    fn drifted_attributes_lists_out_of_band_changes() {
+        let recorded = ResourceState::new("vpc", "main", "aws")
+            .with_identifier("vpc-1")
+            .with_attribute("cidr_block", serde_json::json!("10.0.0.0/16"))
+            .with_attribute("enable_dns_support", serde_json::json!(true))
+            .with_attribute("ipv4_netmask_length", serde_json::json!(16));
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            "cidr_block".to_string(),
+            Value::String("10.0.0.0/16".to_string()),
+        );
+        attributes.insert("enable_dns_support".to_string(), Value::Bool(false));
+        let state = State::existing(ResourceId::new("vpc", "main"), attributes);
+
+        assert_eq!(
+            drifted_attributes(&state, &recorded),
+            vec!["enable_dns_support".to_string()]
+        );
+    }
+
+    #[test]
+    fn plan_json_lists_drift() {
+        let drift: Drift = vec![(
+            ResourceId::new("vpc", "main"),
+            vec!["enable_dns_support".to_string()],
+        )];
+
+        let json = plan_to_json(&Plan::new(), &drift);
+        assert_eq!(
+            json["drift"],
+            serde_json::json!([{
+                "resource_type": "vpc",
+                "name": "main",
+                "attributes": ["enable_dns_support"],
+            }])
+        );
+        assert_eq!(
+            plan_to_json(&Plan::new(), &Drift::new())["drift"],
+            serde_json::json!([])
+        );
+    }
+
+    #[test]
+    fn nested_block_changes_list_removed_and_added_blocks() {
+        let rule = |port: i64| {
+            Value::Map(BTreeMap::from([
+                ("from_port".to_string(), Value::Int(port)),
+                ("protocol".to_string(), Value::String("tcp".to_string())),
+            ]))
+        };
+        let old = Value::List(vec![rule(22), rule(80)]);
+        let new = Value::List(vec![rule(80), rule(443)]);
+
+        let (removed, added) = block_list_changes(Some(&old), &new).unwrap();
+        let lines = |marker, blocks: Vec<&Block>| -> Vec<String> {
+            blocks
+                .into_iter()
+                .flat_map(|block| format_block(marker, block))
+                .collect()
+        };
+        assert_eq!(
+            lines("-", removed),
+            vec!["  - from_port: 22", "    protocol: \"tcp\""]
+        );
+        assert_eq!(
+            lines("+", added),
+            vec!["  + from_port: 443", "    protocol: \"tcp\""]
+        );
+
+        // Reordering alone, and lists of scalars, are not shown per block
+        let reordered = Value::List(vec![rule(80), rule(22)]);
+        assert_eq!(block_list_changes(Some(&old), &reordered), None);
+        let scalars = Value::List(vec![Value::String("a".to_string())]);
+        assert_eq!(block_list_changes(None, &scalars), None);
+    }
+
+    #[test]
+    fn prevent_destroy_blocks_replacement() {
+        let subnet = Resource::new("subnet", "app")
+            .with_attribute("availability_zone", Value::String("a".to_string()));
+        let from = State::existing(ResourceId::new("subnet", "app"), HashMap::new());
+        let replace = |to: Resource| {
+            let mut plan = Plan::new();
+            plan.add(Effect::Replace {
+                id: to.id.clone(),
+                from: from.clone(),
+                to,
+            });
+            plan
+        };
+
+        assert!(check_prevent_destroy(&replace(subnet.clone())).is_ok());
+        assert_eq!(
+            check_prevent_destroy(&replace(
+                subnet.with_attribute("_prevent_destroy", Value::Bool(true))
+            )),
+            Err("Cannot replace subnet.app: lifecycle.prevent_destroy is set. Remove it from the configuration first.".to_string())
+        );
+    }
+
+    #[test]
+    fn plans_cannot_create_types_the_provider_cannot_create() {
+        let mut plan = Plan::new();
+        plan.add(Effect::Create(Resource::new("ec2.ami", "al2023")));
+        plan.add(Effect::Create(Resource::new("vpc", "main")));
+        let mut capabilities = HashMap::from([("vpc".to_string(), Capabilities::default())]);
+
+        assert!(check_creatable(&plan, &capabilities).is_ok());
+        capabilities.insert("ec2.ami".to_string(), Capabilities::read_only());
+        assert_eq!(
+            check_creatable(&plan, &capabilities),
+            Err(
+                "Cannot create ec2.ami.al2023: the provider cannot create resources of this type"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn module_init_refuses_invalid_names_and_non_empty_directories() {
+        let root = tempfile::tempdir().unwrap();
+
+        let err = run_module_init("web-tier", root.path()).unwrap_err();
+        assert!(err.starts_with("Invalid module name 'web-tier'"), "{}", err);
+        assert!(err.contains("web_tier"), "{}", err);
+        assert!(!root.path().join("web-tier").exists());
+
+        let existing = root.path().join("web_tier");
+        fs::create_dir(&existing).unwrap();
+        fs::write(existing.join("notes.txt"), "keep me").unwrap();
+        let err = run_module_init("web_tier", root.path()).unwrap_err();
+        assert!(err.contains("is not empty"), "{}", err);
+        assert!(!existing.join("main.crn").exists());
+
+        run_module_init("db_tier", root.path()).unwrap();
+        assert!(root.path().join("db_tier/main.crn").is_file());
+    }
+
+    #[test]
+    fn module_skeleton_parses_as_module() {
+        let parsed = carina_core::parser::parse(&module_skeleton("web_tier")).unwrap();
+        assert!(parsed.inputs.is_empty());
+        assert!(parsed.outputs.is_empty());
+        assert!(parsed.resources.is_empty());
+    }
+
+    #[test]
+    fn sort_orders_dependencies_first() {
+        let resources = vec![
+            bound("subnet", "subnet", &["vpc"]),
+            bound("bucket", "logs", &[]),
+            bound("vpc", "vpc", &[]),
+        ];
+
+        let sorted: Vec<String> = sort_resources_by_dependencies(&resources)
+            .into_iter()
+            .map(|r| r.id.name)
+            .collect();
+
+        assert_eq!(sorted, vec!["logs", "vpc", "subnet"]);
+        assert_eq!(find_dependency_cycle(&resources), None);
+    }
+
+    #[test]
+    fn sort_honors_explicit_depends_on() {
+        let parsed = carina_core::parser::parse(
+            r#"
+            let route = aws.ec2.route {
+                destination_cidr_block = "0.0.0.0/0"
+                depends_on = [attachment]
+            }
+            let attachment = aws.ec2.vpc_gateway_attachment {
+                vpc_id = "vpc-1"
+            }
+        "#,
+        )
+        .unwrap();
+
+        let sorted: Vec<String> = sort_resources_by_dependencies(&parsed.resources)
+            .into_iter()
+            .map(|r| r.id.name)
+            .collect();
+
+        assert_eq!(sorted, vec!["attachment", "route"]);
+    }
+
+    #[test]
+    fn splat_resolves_replicas_in_order() {
+        let parsed = carina_core::parser::parse(
+            r#"
+            let subnets = aws.subnet {
+                for_each = ["b", "a", "c"]
+            }
+            let lb = aws.elb {
+                subnet_ids = subnets[*].id
+            }
+        "#,
+        )
+        .unwrap();
+
+        let mut binding_map: HashMap<String, HashMap<String, Value>> = HashMap::new();
+        for resource in &parsed.resources {
+            let Some(Value::String(binding)) = resource.attributes.get("_binding") else {
+                continue;
+            };
+            let mut attrs = resource.attributes.clone();
+            // subnets_c has not been created yet
+            if binding != "subnets_c" {
+                attrs.insert("id".to_string(), Value::String(format!("id-{}", binding)));
+            }
+            binding_map.insert(binding.clone(), attrs);
+        }
+
+        let lb = parsed.resources.iter().find(|r| r.id.name == "lb").unwrap();
+        assert_eq!(
+            resolve_ref_value(&lb.attributes["subnet_ids"], &binding_map),
+            Value::List(vec![
+                Value::String("id-subnets_a".to_string()),
+                Value::String("id-subnets_b".to_string()),
+                Value::ResourceRef("subnets_c".to_string(), "id".to_string()),
+            ])
+        );
+
+        let sorted: Vec<String> = sort_resources_by_dependencies(&parsed.resources)
+            .into_iter()
+            .map(|r| r.id.name)
+            .collect();
+        assert_eq!(sorted.last().map(String::as_str), Some("lb"));
+    }
+
+    #[test]
+    fn state_resources_sort_by_identifier_links() {
+        let mut state = StateFile::new();
+        state.upsert_resource(
+            ResourceState::new("subnet", "app", "aws")
+                .with_identifier("subnet-1")
+                .with_attribute("vpc_id", serde_json::json!("vpc-1")),
+        );
+        state.upsert_resource(ResourceState::new("vpc", "main", "aws").with_identifier("vpc-1"));
+        state.upsert_resource(ResourceState::new("s3.bucket", "logs", "aws"));
+
+        let resources = resources_from_state(&state);
+        let subnet = resources.iter().find(|r| r.id.name == "app").unwrap();
+        assert_eq!(
+            subnet.attributes.get("vpc_id"),
+            Some(&Value::ResourceRef(
+                "vpc.main".to_string(),
+                "id".to_string()
+            ))
+        );
+
+        let destroy_order: Vec<String> = sort_resources_by_dependencies(&resources)
+            .into_iter()
+            .rev()
+            .map(|r| r.id.name)
+            .collect();
+        assert_eq!(destroy_order, vec!["app", "main", "logs"]);
+    }
+
+    #[test]
+    fn eval_resolves_against_state() {
+        let parsed = carina_core::parser::parse(
+            r#"
+            let vpc = aws.vpc {
+                cidr_block = "10.0.0.0/16"
+            }
+            let subnet = aws.subnet {
+                vpc_id = vpc.id
+            }
+        "#,
+        )
+        .unwrap();
+        let mut state = StateFile::new();
+        state.upsert_resource(ResourceState::new("vpc", "vpc", "aws").with_identifier("vpc-1"));
+        let binding_map = output_binding_map(&parsed.resources, Some(&state));
+        let eval = |expr: &str| {
+            let value = carina_core::parser::parse_expression_in(expr, &parsed).unwrap();
+            format_eval(&resolve_ref_value(&value, &binding_map))
+        };
+
+        assert_eq!(eval("vpc.id"), "\"vpc-1\"");
+        assert_eq!(eval("subnet.vpc_id"), "\"vpc-1\"");
+        assert_eq!(eval("\"${vpc.cidr_block}-x\""), "\"10.0.0.0/16-x\"");
+        assert_eq!(
+            eval("subnet.id"),
+            "(unresolved: subnet.id is not recorded in state; run `carina apply`)"
+        );
+    }
+
+    #[test]
+    fn outputs_resolve_against_state() {
+        let resources = vec![
+            bound("security_group", "web_sg", &[]),
+            bound("vpc", "vpc", &[]),
+        ];
+        let mut state = StateFile::new();
+        state.upsert_resource(
+            ResourceState::new("security_group", "web_sg", "aws").with_identifier("sg-123"),
+        );
+        let binding_map = output_binding_map(&resources, Some(&state));
+
+        let output = |name: &str, type_expr: TypeExpr, binding: &str| OutputParameter {
+            name: name.to_string(),
+            type_expr,
+            value: Some(Value::ResourceRef(binding.to_string(), "id".to_string())),
+        };
+
+        let security_group = output(
+            "security_group",
+            TypeExpr::Ref(carina_core::parser::ResourceTypePath::new(
+                "aws",
+                "security_group",
+            )),
+            "web_sg",
+        );
+        assert_eq!(
+            resolve_output(&security_group, &binding_map),
+            Value::String("sg-123".to_string())
+        );
+
+        let vpc_id = output("vpc_id", TypeExpr::String, "vpc");
+        assert_eq!(
+            format_output(&resolve_output(&vpc_id, &binding_map)),
+            "(unknown: vpc.id is not recorded in state; run `carina apply`)"
+        );
+    }
+
+    #[test]
+    fn recording_outputs_reports_changes() {
+        let resources = vec![bound("vpc", "vpc", &[])];
+        let outputs = vec![OutputParameter {
+            name: "vpc_id".to_string(),
+            type_expr: TypeExpr::String,
+            value: Some(Value::ResourceRef("vpc".to_string(), "id".to_string())),
+        }];
+
+        // Nothing is recorded until the output can be resolved
+        let mut state = StateFile::new();
+        assert!(!record_outputs(&mut state, &outputs, &resources));
+        assert!(state.outputs.is_empty());
+
+        state.upsert_resource(ResourceState::new("vpc", "vpc", "aws").with_identifier("vpc-1"));
+        assert!(record_outputs(&mut state, &outputs, &resources));
+        assert_eq!(state.outputs["vpc_id"], serde_json::json!("vpc-1"));
+        assert!(!record_outputs(&mut state, &outputs, &resources));
+
+        // Outputs no longer declared are dropped
+        assert!(record_outputs(&mut state, &[], &resources));
+        assert!(state.outputs.is_empty());
+    }
+
+    #[test]
+    fn input_vars_are_coerced_and_required() {
+        let mut parsed = parser::parse(
+            r#"
+            input {
+                env: string
+                instance_count: int = 1
+                cidrs: list(cidr) = []
+            }
+
+            let bucket = aws.s3.bucket {
+                name = "logs-${input.env}"
+            }
+            "#,
+        )
+        .unwrap();
+
+        let file_vars =
+            parse_var_file("# defaults\nenv = \"staging\"\ninstance_count = 2\n").unwrap();
+        let mut input_vars = file_vars.clone();
+        input_vars.insert("env".to_string(), Value::String("prod".to_string()));
+
+        let values = apply_input_vars(&mut parsed.clone(), &input_vars, false).unwrap();
+        assert_eq!(values["env"], Value::String("prod".to_string()));
+        assert_eq!(values["instance_count"], Value::Int(2));
+        assert_eq!(values["cidrs"], Value::List(vec![]));
+
+        apply_input_vars(&mut parsed, &file_vars, false).unwrap();
+        assert_eq!(
+            parsed.resources[0].attributes.get("name"),
+            Some(&Value::String("logs-staging".to_string()))
+        );
+
+        let err = apply_input_vars(&mut parsed.clone(), &HashMap::new(), false).unwrap_err();
+        assert_eq!(
+            err,
+            "Input 'env' required but interactive input is disabled by --input=false \
+             (set it with --var env=<value> or --var-file)"
+        );
+
+        let bad = HashMap::from([
+            ("env".to_string(), Value::String("prod".to_string())),
+            (
+                "instance_count".to_string(),
+                Value::String("two".to_string()),
+            ),
+            (
+                "cidrs".to_string(),
+                Value::String(r#"["10.0.0.0/33"]"#.to_string()),
+            ),
+        ]);
+        let err = apply_input_vars(&mut parsed.clone(), &bad, false).unwrap_err();
+        assert!(err.contains("Input 'instance_count': expected int, got \"two\""));
+        assert!(err.contains("Input 'cidrs': element 0:"));
+    }
+
+    #[test]
+    fn independent_effects_share_a_level() {
+        let effects = vec![
+            Effect::Create(bound("vpc", "vpc", &[])),
+            Effect::Create(bound("bucket", "logs", &[])),
+            Effect::Create(bound("subnet", "subnet_a", &["vpc"])),
+            Effect::Create(bound("subnet", "subnet_b", &["vpc"])),
+            Effect::Delete(ResourceId::new("subnet", "old")),
+            Effect::Create(bound("bucket", "archive", &[])),
+        ];
+
+        assert_eq!(
+            effect_levels(&effects),
+            vec![vec![0, 1], vec![2, 3], vec![4], vec![5]]
+        );
+    }
+
+    #[test]
+    fn dependents_of_failed_effects_are_skipped() {
+        let failed: HashSet<String> = ["vpc".to_string(), "igw".to_string()].into();
+
+        assert_eq!(
+            failed_dependency(&bound("subnet", "subnet", &["vpc", "igw"]), &failed),
+            Some("igw".to_string())
+        );
+        assert_eq!(
+            failed_dependency(&bound("bucket", "logs", &["archive"]), &failed),
+            None
+        );
+    }
+
+    #[test]
+    fn overlapping_subnets_are_reported() {
+        let subnet = |name: &str, vpc: &str, cidr: &str| {
+            Resource::new("subnet", name)
+                .with_attribute(
+                    "vpc_id",
+                    Value::ResourceRef(vpc.to_string(), "id".to_string()),
+                )
+                .with_attribute("cidr_block", Value::String(cidr.to_string()))
+        };
+        let resources = vec![
+            Resource::new("vpc", "main")
+                .with_attribute("_binding", Value::String("main_vpc".to_string()))
+                .with_attribute("cidr_block", Value::String("10.0.0.0/16".to_string())),
+            subnet("a", "main_vpc", "10.0.0.0/24"),
+            subnet("b", "main_vpc", "10.0.0.128/25"),
+            subnet("c", "main_vpc", "10.1.0.0/24"),
+            // Same range in another VPC is fine
+            subnet("d", "other_vpc", "10.0.0.0/24"),
+        ];
+
+        assert_eq!(
+            validate_network_topology(&resources),
+            vec![
+                "subnet.c: CIDR 10.1.0.0/24 is outside vpc.main (10.0.0.0/16)".to_string(),
+                "subnet.a (10.0.0.0/24) overlaps subnet.b (10.0.0.128/25)".to_string(),
+            ]
+        );
+
+        // A subnet in a secondary CIDR block is inside the VPC
+        let resources = vec![
+            Resource::new("vpc", "main")
+                .with_attribute("_binding", Value::String("main_vpc".to_string()))
+                .with_attribute("cidr_block", Value::String("10.0.0.0/16".to_string()))
+                .with_attribute(
+                    "secondary_cidr_blocks",
+                    Value::List(vec![Value::String("10.1.0.0/16".to_string())]),
+                ),
+            subnet("c", "main_vpc", "10.1.0.0/24"),
+            subnet("e", "main_vpc", "10.2.0.0/24"),
+        ];
+        assert_eq!(
+            validate_network_topology(&resources),
+            vec!["subnet.e: CIDR 10.2.0.0/24 is outside vpc.main (10.0.0.0/16, 10.1.0.0/16)"]
+        );
+    }
+
+    #[test]
+    fn instance_security_groups_must_share_the_subnet_vpc() {
+        let in_vpc = |resource_type: &str, binding: &str, vpc: &str| {
+            bound(resource_type, binding, &[]).with_attribute(
+                "vpc_id",
+                Value::ResourceRef(vpc.to_string(), "id".to_string()),
+            )
+        };
+        let instance = |name: &str, groups: &[&str]| {
+            Resource::new("ec2.instance", name)
+                .with_attribute(
+                    "subnet_id",
+                    Value::ResourceRef("app".to_string(), "id".to_string()),
+                )
+                .with_attribute(
+                    "security_group_ids",
+                    Value::List(
+                        groups
+                            .iter()
+                            .map(|g| Value::ResourceRef(g.to_string(), "id".to_string()))
+                            .collect(),
+                    ),
+                )
+        };
+        let resources = vec![
+            in_vpc("subnet", "app", "main_vpc"),
+            in_vpc("security_group", "web_sg", "main_vpc"),
+            in_vpc("security_group", "other_sg", "other_vpc"),
+            instance("web", &["web_sg"]),
+            // Groups not declared here cannot be checked
+            instance("db", &["other_sg", "web_sg", "external_sg"]),
+        ];
+
+        assert_eq!(
+            validate_network_topology(&resources),
+            vec![
+                "ec2.instance.db: security_group.other_sg is in VPC other_vpc, \
+                 but subnet.app is in VPC main_vpc"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn schema_errors_point_at_the_attribute() {
+        let mut parsed = parser::parse(
+            "let logs = aws.s3.bucket {\n  region = aws.Region.ap_northeast_1\n  expiration_days = \"soon\"\n}\n\naws.subnet {\n  name = \"app\"\n  region = aws.Region.ap_northeast_1\n  vpc_id = \"vpc-1\"\n}\n",
+        )
+        .unwrap();
+        parsed.set_source_file("main.crn");
+
+        let errors = validate_resources(&parsed.resources).unwrap_err();
+        let errors: Vec<&str> = errors.lines().collect();
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.starts_with("main.crn:3:3: s3.bucket.logs: expiration_days: ")),
+            "{:?}",
+            errors
+        );
+        assert!(
+            errors.iter().any(|e| e.starts_with("main.crn:6:1: subnet.app: Required attribute 'cidr_block'")),
+            "{:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn null_is_rejected_for_aws_resources() {
+        let mut parsed = parser::parse(
+            "aws.s3.bucket {\n  name = \"logs\"\n  acl = null\n}\n\nawscc.ec2_vpc {\n  cidr_block = \"10.0.0.0/16\"\n  instance_tenancy = null\n}\n",
+        )
+        .unwrap();
+        parsed.set_source_file("main.crn");
+
+        let errors = validate_resources(&parsed.resources).unwrap_err();
+        assert_eq!(
+            errors,
+            "main.crn:3:3: s3.bucket.logs: acl: null is not supported by the aws provider"
+        );
+    }
+
+    #[test]
+    fn state_mv_renames_resource() {
+        let mut state = StateFile::new();
+        state.upsert_resource(
+            ResourceState::new("s3.bucket", "logs", "aws")
+                .with_identifier("logs-bucket")
+                .with_protected(true),
+        );
+        state.upsert_resource(ResourceState::new("vpc", "main", "aws").with_identifier("vpc-1"));
+
+        move_state_resource(&mut state, "s3.bucket.logs", "s3.bucket.access-logs").unwrap();
+        assert!(state.find_resource("s3.bucket", "logs").is_none());
+        let moved = state.find_resource("s3.bucket", "access-logs").unwrap();
+        assert_eq!(moved.identifier.as_deref(), Some("logs-bucket"));
+        assert!(moved.protected);
+
+        assert_eq!(
+            move_state_resource(&mut state, "s3.bucket.logs", "s3.bucket.other").unwrap_err(),
+            "No resource 's3.bucket.logs' in state"
+        );
+        assert_eq!(
+            move_state_resource(&mut state, "vpc.main", "s3.bucket.access-logs").unwrap_err(),
+            "Destination 's3.bucket.access-logs' must be an address of the same type, e.g. vpc.new-name"
+        );
+        state.upsert_resource(ResourceState::new("vpc", "other", "aws"));
+        assert_eq!(
+            move_state_resource(&mut state, "vpc.main", "vpc.other").unwrap_err(),
+            "Resource 'vpc.other' already exists in state"
+        );
+    }
+
+    #[test]
+    fn circular_dependency_is_reported() {
+        let resources = vec![
+            bound("vpc", "a", &["b"]),
+            bound("vpc", "b", &["a"]),
+            bound("bucket", "logs", &[]),
+        ];
+
+        assert_eq!(
+            find_dependency_cycle(&resources),
+            Some(vec!["a".to_string(), "b".to_string(), "a".to_string()])
+        );
+        assert!(
+            validate_resources(&resources)
+                .unwrap_err()
+                .contains("Circular dependency: a -> b -> a")
+        );
+
+        // A resource referring to itself is a cycle of one
+        let resources = vec![bound("vpc", "a", &["a"])];
+        assert_eq!(
+            find_dependency_cycle(&resources),
+            Some(vec!["a".to_string(), "a".to_string()])
+        );
+    }
+
+    /// Resource type with fixed capabilities
+    struct TestType(&'static str, Capabilities);
+
+    impl ResourceType for TestType {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn capabilities(&self) -> Capabilities {
+            self.1.clone()
+        }
+    }
+
+    /// Provider that records the resources it deletes and fails every create.
+    /// Routes have no delete of their own.
+    #[derive(Default)]
+    struct RecordingProvider {
+        deleted: std::sync::Mutex<Vec<ResourceId>>,
+    }
+
+    impl Provider for RecordingProvider {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        fn resource_types(&self) -> Vec<Box<dyn ResourceType>> {
+            let route = Capabilities {
+                delete: false,
+                ..Capabilities::default()
+            };
+            vec![
+                Box::new(TestType("vpc", Capabilities::default())),
+                Box::new(TestType("route", route)),
+            ]
+        }
+
+        fn read(
+            &self,
+            id: &ResourceId,
+            _identifier: Option<&str>,
+        ) -> BoxFuture<'_, ProviderResult<State>> {
+            let id = id.clone();
+            Box::pin(async move { Ok(State::not_found(id)) })
+        }
+
+        fn create(&self, _resource: &Resource) -> BoxFuture<'_, ProviderResult<State>> {
+            Box::pin(async { Err(ProviderError::new("create failed")) })
+        }
+
+        fn update(
+            &self,
+            id: &ResourceId,
+            _identifier: &str,
+            _from: &State,
+            to: &Resource,
+        ) -> BoxFuture<'_, ProviderResult<State>> {
+            let state = State::existing(id.clone(), to.attributes.clone());
+            Box::pin(async move { Ok(state) })
+        }
+
+        fn delete(&self, id: &ResourceId, _identifier: &str) -> BoxFuture<'_, ProviderResult<()>> {
+            self.deleted.lock().unwrap().push(id.clone());
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn resources_without_a_delete_of_their_own_are_skipped() {
+        let provider = RecordingProvider::default();
+        let route = ResourceId::new("route", "default");
+        let vpc = ResourceId::new("vpc", "main");
+
+        delete_resource(&provider, &route, "").await.unwrap();
+        delete_resource(&provider, &vpc, "vpc-1").await.unwrap();
+        assert_eq!(*provider.deleted.lock().unwrap(), vec![vpc]);
+    }
+
+    #[tokio::test]
+    async fn failed_replacement_leaves_the_resource_deleted() {
+        let provider = RecordingProvider::default();
+        let id = ResourceId::new("vpc", "main");
+        let effect = Effect::Replace {
+            id: id.clone(),
+            from: State::existing(id.clone(), HashMap::new()).with_identifier("vpc-1"),
+            to: Resource::new("vpc", "main"),
+        };
+        let deleted = std::sync::Mutex::new(HashSet::new());
+
+        let result = apply_effect(
+            &provider,
+            &effect,
+            &HashMap::new(),
+            &HashMap::new(),
+            &deleted,
+        )
+        .await;
+        assert_eq!(result.unwrap_err(), "create failed");
+        assert_eq!(*provider.deleted.lock().unwrap(), vec![id.clone()]);
+        assert_eq!(deleted.into_inner().unwrap(), HashSet::from([id]));
+    }
+
+    #[tokio::test]
+    async fn operation_timeout_fails_slow_operations() {
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(())
+        };
+        let err = with_operation_timeout(Some(Duration::from_millis(10)), slow)
+            .await
+            .unwrap_err();
+        assert!(err.starts_with("timed out after 10ms"), "{}", err);
+
+        let quick = async { Ok::<_, String>(1) };
+        assert_eq!(with_operation_timeout(None, quick).await, Ok(1));
+    }
+
+    #[test]
+    fn hardcoded_references_point_at_the_owning_resource() {
+        let mut state = StateFile::new();
+        state.upsert_resource(ResourceState::new("vpc", "main", "aws").with_identifier("vpc-1"));
+        // A subnet's `vpc_id` is not an ID of its own and must not claim vpc-1
+        state.upsert_resource(
+            ResourceState::new("subnet", "app", "aws")
+                .with_identifier("subnet-1")
+                .with_attribute("vpc_id", serde_json::json!("vpc-1")),
+        );
+
+        let resources = vec![
+            bound("vpc", "main", &[]),
+            bound("subnet", "app", &[]),
+            Resource::new("security_group", "web")
+                .with_attribute("vpc_id", Value::String("vpc-1".to_string())),
+        ];
+
+        assert_eq!(
+            hardcoded_references(&resources, &state),
+            vec![
+                "security_group.web: vpc_id is hardcoded to \"vpc-1\", which is the id of managed \
+                 resource vpc.main. Use a reference (vpc_id = main.id) so Carina orders them correctly."
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn possible_renames_match_orphans_with_the_same_attributes() {
+        let mut state = StateFile::new();
+        state.upsert_resource(
+            ResourceState::new("s3.bucket", "old-logs", "aws")
+                .with_identifier("old-logs")
+                .with_attribute("acl", serde_json::json!("private"))
+                .with_attribute("versioning", serde_json::json!(true)),
+        );
+
+        let renamed = Resource::new("s3.bucket", "new-logs")
+            .with_attribute("name", Value::String("new-logs".to_string()))
+            .with_attribute("acl", Value::String("private".to_string()))
+            .with_attribute("versioning", Value::Bool(true));
+        let resources = [renamed];
+        let renames = possible_renames(&resources, &state);
+        assert_eq!(renames.len(), 1);
+        assert_eq!(renames[0].0.id.name, "new-logs");
+        assert_eq!(renames[0].1.name, "old-logs");
+    }
+
+    #[test]
+    fn possible_renames_ignore_unrelated_resources() {
+        let mut state = StateFile::new();
+        state.upsert_resource(
+            ResourceState::new("s3.bucket", "old-logs", "aws")
+                .with_identifier("old-logs")
+                .with_attribute("acl", serde_json::json!("private"))
+                .with_attribute("versioning", serde_json::json!(true)),
+        );
+
+        // Different attributes, some of the same ones, and a different type with the same ones
+        let resources = [
+            Resource::new("s3.bucket", "archive")
+                .with_attribute("acl", Value::String("private".to_string()))
+                .with_attribute("versioning", Value::Bool(false)),
+            Resource::new("s3.bucket", "assets")
+                .with_attribute("acl", Value::String("public-read".to_string()))
+                .with_attribute("versioning", Value::Bool(false)),
+            Resource::new("dynamodb.table", "locks")
+                .with_attribute("acl", Value::String("private".to_string()))
+                .with_attribute("versioning", Value::Bool(true)),
+        ];
+        assert!(possible_renames(&resources, &state).is_empty());
+    }
+
+    #[test]
+    fn possible_renames_match_by_identifier_or_a_unique_attribute_set() {
+        let mut state = StateFile::new();
+        for name in ["old-logs", "old-assets"] {
+            state.upsert_resource(
+                ResourceState::new("s3.bucket", name, "aws")
+                    .with_identifier(name)
+                    .with_attribute("acl", serde_json::json!("private")),
+            );
+        }
+
+        // Both orphans have the same attributes, so neither is the likely original
+        let resources = [Resource::new("s3.bucket", "new-logs")
+            .with_attribute("acl", Value::String("private".to_string()))];
+        assert!(possible_renames(&resources, &state).is_empty());
+
+        // An identifier settles it, whatever the other attributes
+        let resources = [Resource::new("s3.bucket", "new-logs")
+            .with_attribute("bucket", Value::String("old-logs".to_string()))
+            .with_attribute("acl", Value::String("public-read".to_string()))];
+        let renames = possible_renames(&resources, &state);
+        assert_eq!(renames.len(), 1);
+        assert_eq!(renames[0].1.name, "old-logs");
+    }
+
+    #[test]
+    fn target_selection_pulls_in_dependencies_or_dependents() {
+        let resources = vec![
+            bound("vpc", "vpc", &[]),
+            bound("subnet", "subnet", &["vpc"]),
+            bound("instance", "web", &["subnet"]),
+            bound("bucket", "logs", &[]),
+        ];
+        let names = |(selected, _): (Vec<Resource>, Vec<&String>)| -> Vec<String> {
+            selected.into_iter().map(|r| r.id.name).collect()
+        };
+
+        let targets = ["web".to_string()];
+        assert_eq!(
+            names(target_selection(
+                &resources,
+                &targets,
+                TargetScope::Dependencies
+            )),
+            ["vpc", "subnet", "web"]
+        );
+
+        let targets = ["subnet.subnet".to_string()];
+        assert_eq!(
+            names(target_selection(
+                &resources,
+                &targets,
+                TargetScope::Dependents
+            )),
+            ["subnet", "web"]
+        );
+
+        assert_eq!(
+            target_selection(&resources, &[], TargetScope::Dependencies)
+                .0
+                .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn target_selection_skips_unknown_targets() {
+        let resources = vec![
+            bound("vpc", "vpc", &[]),
+            bound("subnet", "subnet", &["vpc"]),
+            bound("bucket", "logs", &[]),
+        ];
+        let targets = ["vpc.missing".to_string(), "subnet".to_string()];
+
+        let (selected, unmatched) =
+            target_selection(&resources, &targets, TargetScope::Dependencies);
+        // The plan continues with the targets that matched
+        let selected: Vec<&str> = selected.iter().map(|r| r.id.name.as_str()).collect();
+        assert_eq!(selected, ["vpc", "subnet"]);
+        assert_eq!(unmatched, [&targets[0]]);
+
+        let plan = carina_core::differ::create_plan(
+            &target_selection(&resources, &targets, TargetScope::Dependencies).0,
+            &HashMap::new(),
+        );
+        assert_eq!(plan.summary().create, 2);
+    }
+
+    #[test]
+    fn durations_parse_and_reject_overflow() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("30"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+        assert!(parse_duration("2d").is_err());
+
+        let huge = format!("{}h", u64::MAX / 60);
+        assert_eq!(
+            parse_duration(&huge),
+            Err(format!("Duration '{}' is too large", huge))
+        );
+        assert!(parse_duration(&format!("{}m", u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn init_quotes_backend_values() {
+        let content = init_config(
+            "aws.Region.ap_northeast_1",
+            Some(("state \"bucket\"", r"envs\${env}\carina.crnstate")),
+        );
+        let backend = parser::parse(&content).unwrap().backend.unwrap();
+        assert_eq!(
+            backend.attributes.get("bucket"),
+            Some(&Value::String("state \"bucket\"".to_string()))
+        );
+        assert_eq!(
+            backend.attributes.get("key"),
+            Some(&Value::String(r"envs\${env}\carina.crnstate".to_string()))
+        );
+    }
+
+    #[test]
+    fn init_rejects_a_backend_key_without_a_bucket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.crn");
+        let err = run_init(
+            &path,
+            Some("ap-northeast-1".to_string()),
+            None,
+            Some("carina.crnstate".to_string()),
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(err, "--backend-key requires --backend-bucket");
+        assert!(!path.exists());
+    }
+
+    fn saved_plan(state_file: &Option<StateFile>) -> SavedPlan {
+        let vpc = bound("vpc", "vpc", &[]);
+        let mut plan = Plan::new();
+        plan.add(Effect::Create(vpc.clone()));
+        SavedPlan {
+            providers: vec![ProviderConfig {
+                name: "aws".to_string(),
+                attributes: HashMap::from([(
+                    "region".to_string(),
+                    Value::String("aws.Region.eu_west_1".to_string()),
+                )]),
+            }],
+            backend: None,
+            backend_file: None,
+            outputs: vec![OutputParameter {
+                name: "vpc_id".to_string(),
+                type_expr: TypeExpr::String,
+                value: Some(Value::ResourceRef("vpc".to_string(), "id".to_string())),
+            }],
+            lineage: state_file.as_ref().map(|s| s.lineage.clone()),
+            serial: state_file.as_ref().map(|s| s.serial),
+            resources: vec![vpc],
+            current_states: vec![],
+            plan,
+        }
+    }
+
+    #[test]
+    fn saved_plans_round_trip_with_their_configuration() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plan.json");
+        saved_plan(&None).save(&path).unwrap();
+
+        let loaded = SavedPlan::load(&path).unwrap().unwrap();
+        assert_eq!(loaded.plan.mutation_count(), 1);
+        assert!(loaded.check_current(&None).is_ok());
+
+        // Apply runs from what the plan recorded, not from the configuration on disk
+        let parsed = loaded.configuration();
+        assert_eq!(get_aws_region(&parsed), "eu-west-1");
+        assert_eq!(parsed.resources.len(), 1);
+        assert_eq!(parsed.outputs[0].name, "vpc_id");
+
+        // Configuration files are not plan files
+        let crn = dir.path().join("main.crn");
+        fs::write(&crn, "provider aws {}").unwrap();
+        assert!(SavedPlan::load(&crn).unwrap().is_none());
+        assert!(SavedPlan::load(dir.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn saved_plans_are_stale_once_state_changes() {
+        let mut state = StateFile::new();
+        let saved = saved_plan(&Some(state.clone()));
+        assert!(saved.check_current(&Some(state.clone())).is_ok());
+
+        let err = saved.check_current(&None).unwrap_err();
+        assert!(err.contains("Saved plan is stale"));
+
+        state.increment_serial();
+        let err = saved.check_current(&Some(state.clone())).unwrap_err();
+        assert!(err.contains(&format!(
+            "computed against state serial {}, but the current serial is {}",
+            state.serial - 1,
+            state.serial
+        )));
+
+        // Same serial in a different lineage
+        let other = StateFile::with_lineage("other".to_string());
+        let saved = saved_plan(&Some(StateFile::new()));
+        assert!(saved.check_current(&Some(other)).is_err());
+    }
+
+    #[test]
+    fn corrupt_saved_plans_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plan.json");
+        fs::write(&path, "{\"plan\": ").unwrap();
+        let err = SavedPlan::load(&path).err().unwrap();
+        assert!(err.starts_with(&format!("Failed to parse plan file {}", path.display())));
+    }
+}
@@ -7,10 +7,13 @@ use std::collections::HashMap;
 
 use crate::effect::Effect;
 use crate::plan::Plan;
+use crate::provider::Capabilities;
 use crate::resource::{Resource, ResourceId, State, Value};
+use crate::schema::canonicalize_enum;
 
 /// Result of a diff operation
 #[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
 pub enum Diff {
     /// Resource does not exist -> needs creation
     Create(Resource),
@@ -36,11 +39,22 @@ impl Diff {
 
 /// Compare desired state with current state to compute a Diff
 pub fn diff(desired: &Resource, current: &State) -> Diff {
+    diff_with_capabilities(desired, current, &Capabilities::default())
+}
+
+/// Like `diff`, but compares the list attributes that the capabilities mark as
+/// unordered without regard to element order and skips write-only attributes
+pub fn diff_with_capabilities(
+    desired: &Resource,
+    current: &State,
+    capabilities: &Capabilities,
+) -> Diff {
     if !current.exists {
         return Diff::Create(desired.clone());
     }
 
-    let changed = find_changed_attributes(&desired.attributes, &current.attributes);
+    let desired = &without_ignored_changes(desired, current);
+    let changed = find_changed_attributes(&desired.attributes, &current.attributes, capabilities);
 
     if changed.is_empty() {
         Diff::NoChange(desired.id.clone())
@@ -54,10 +68,55 @@ pub fn diff(desired: &Resource, current: &State) -> Diff {
     }
 }
 
+/// The desired resource with the attributes listed in its `_ignore_changes` (set by
+/// `lifecycle { ignore_changes = [...] }`) taken from the current state, so changes to
+/// them are neither planned nor applied. `attr` covers a whole attribute and
+/// `attr.key` one key of a map attribute.
+fn without_ignored_changes(desired: &Resource, current: &State) -> Resource {
+    let mut resource = desired.clone();
+    let Some(Value::List(paths)) = desired.attributes.get("_ignore_changes") else {
+        return resource;
+    };
+
+    for path in paths {
+        let Value::String(path) = path else {
+            continue;
+        };
+        match path.split_once('.') {
+            None => match current.attributes.get(path) {
+                Some(value) => {
+                    resource.attributes.insert(path.clone(), value.clone());
+                }
+                None => {
+                    resource.attributes.remove(path);
+                }
+            },
+            Some((attr, key)) => {
+                let current_value = match current.attributes.get(attr) {
+                    Some(Value::Map(map)) => map.get(key),
+                    _ => None,
+                };
+                if let Some(Value::Map(map)) = resource.attributes.get_mut(attr) {
+                    match current_value {
+                        Some(value) => {
+                            map.insert(key.to_string(), value.clone());
+                        }
+                        None => {
+                            map.remove(key);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    resource
+}
+
 /// Find changed attributes between desired and current state
 fn find_changed_attributes(
     desired: &HashMap<String, Value>,
     current: &HashMap<String, Value>,
+    capabilities: &Capabilities,
 ) -> Vec<String> {
     let mut changed = Vec::new();
 
@@ -66,18 +125,102 @@ fn find_changed_attributes(
         if key.starts_with('_') {
             continue;
         }
+        // Write-only attributes are never read back, so there is nothing to compare
+        if capabilities.write_only.contains(key) {
+            continue;
+        }
 
-        match current.get(key) {
-            Some(current_value) if current_value == desired_value => {}
+        // Enum values compare in the provider's spelling, whatever case or
+        // namespace the DSL used
+        let (desired_value, current_value) = match capabilities.enum_values.get(key) {
+            Some(valid_values) => (
+                canonicalize_enum(valid_values, desired_value),
+                current
+                    .get(key)
+                    .map(|value| canonicalize_enum(valid_values, value)),
+            ),
+            None => (desired_value.clone(), current.get(key).cloned()),
+        };
+
+        match (current_value.as_ref(), &desired_value) {
+            // Unsetting an attribute the resource does not have is not a change
+            (None | Some(Value::Null), Value::Null) => {}
+            (Some(Value::List(current_items)), Value::List(desired_items))
+                if capabilities.unordered.contains(key)
+                    && same_elements(desired_items, current_items) => {}
+            (Some(current_value), _)
+                if attribute_values_equal(key, &desired_value, current_value) => {}
             _ => changed.push(key.clone()),
         }
     }
 
+    changed.sort();
     changed
 }
 
+/// Whether two lists hold the same elements, in any order. Duplicates count:
+/// `[a, a, b]` and `[a, b, b]` differ.
+fn same_elements(a: &[Value], b: &[Value]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut matched = vec![false; b.len()];
+    a.iter().all(
+        |item| match (0..b.len()).find(|&i| !matched[i] && b[i] == *item) {
+            Some(i) => {
+                matched[i] = true;
+                true
+            }
+            None => false,
+        },
+    )
+}
+
+/// Attributes whose values are enum-style identifiers that providers may report in a
+/// different form than the DSL (e.g. `aws.Region.ap_northeast_1` vs `ap-northeast-1`)
+const NORMALIZED_ATTRIBUTES: &[&str] = &["region", "availability_zone"];
+
+/// Compare two values of the attribute `key`. Region and availability zone values are
+/// compared in canonical form; every other attribute compares exactly.
+pub fn attribute_values_equal(key: &str, a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) if NORMALIZED_ATTRIBUTES.contains(&key) => {
+            canonical_enum_value(a) == canonical_enum_value(b)
+        }
+        _ => a == b,
+    }
+}
+
+/// Canonical form of an enum-style value, following the providers' enum conversion:
+/// `aws.Region.ap_northeast_1`, `Region.ap_northeast_1` and `ap-northeast-1` all
+/// become `ap-northeast-1`
+fn canonical_enum_value(value: &str) -> String {
+    let parts: Vec<&str> = value.split('.').collect();
+    let starts_upper = |s: &str| s.chars().next().is_some_and(|c| c.is_uppercase());
+    let raw = match parts.as_slice() {
+        [type_name, value] if starts_upper(type_name) => value,
+        [provider, type_name, value]
+            if provider.chars().all(|c| c.is_lowercase()) && starts_upper(type_name) =>
+        {
+            value
+        }
+        _ => value,
+    };
+    raw.replace('_', "-")
+}
+
 /// Compute Diff for multiple resources and generate a Plan
 pub fn create_plan(desired: &[Resource], current_states: &HashMap<ResourceId, State>) -> Plan {
+    create_plan_with_capabilities(desired, current_states, &HashMap::new())
+}
+
+/// Like `create_plan`, but turns updates that a resource type cannot perform in
+/// place into replacements, using capabilities keyed by resource type.
+pub fn create_plan_with_capabilities(
+    desired: &[Resource],
+    current_states: &HashMap<ResourceId, State>,
+    capabilities: &HashMap<String, Capabilities>,
+) -> Plan {
     let mut plan = Plan::new();
 
     for resource in desired {
@@ -94,14 +237,29 @@ pub fn create_plan(desired: &[Resource], current_states: &HashMap<ResourceId, St
             .cloned()
             .unwrap_or_else(|| State::not_found(resource.id.clone()));
 
-        let d = diff(resource, &current);
+        let d = match capabilities.get(&resource.id.resource_type) {
+            Some(caps) => diff_with_capabilities(resource, &current, caps),
+            None => diff(resource, &current),
+        };
 
         match d {
             Diff::Create(r) => plan.add(Effect::Create(r)),
-            Diff::Update { id, from, to, .. } => {
-                plan.add(Effect::Update { id, from, to });
+            Diff::Update {
+                id,
+                from,
+                to,
+                changed_attributes,
+            } => {
+                let replace = capabilities
+                    .get(&id.resource_type)
+                    .is_some_and(|c| c.requires_replacement(&changed_attributes));
+                if replace {
+                    plan.add(Effect::Replace { id, from, to });
+                } else {
+                    plan.add(Effect::Update { id, from, to });
+                }
             }
-            Diff::NoChange(_) => {}
+            Diff::NoChange(id) => plan.add_unchanged(id),
             Diff::Delete(id) => plan.add(Effect::Delete(id)),
         }
     }
@@ -112,6 +270,7 @@ pub fn create_plan(desired: &[Resource], current_states: &HashMap<ResourceId, St
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::BTreeMap;
 
     #[test]
     fn diff_create_when_not_exists() {
@@ -138,6 +297,80 @@ mod tests {
         assert!(matches!(result, Diff::NoChange(_)));
     }
 
+    #[test]
+    fn diff_skips_ignored_changes() {
+        let desired = Resource::new("bucket", "test")
+            .with_attribute("description", Value::String("ours".to_string()))
+            .with_attribute(
+                "tags",
+                Value::Map(BTreeMap::from([(
+                    "Name".to_string(),
+                    Value::String("logs".to_string()),
+                )])),
+            )
+            .with_attribute(
+                "_ignore_changes",
+                Value::List(vec![
+                    Value::String("description".to_string()),
+                    Value::String("tags.scaled_by".to_string()),
+                ]),
+            );
+        let mut attrs = HashMap::new();
+        attrs.insert(
+            "description".to_string(),
+            Value::String("theirs".to_string()),
+        );
+        attrs.insert(
+            "tags".to_string(),
+            Value::Map(BTreeMap::from([
+                ("Name".to_string(), Value::String("logs".to_string())),
+                (
+                    "scaled_by".to_string(),
+                    Value::String("autoscaler".to_string()),
+                ),
+            ])),
+        );
+        let current = State::existing(ResourceId::new("bucket", "test"), attrs.clone());
+        assert!(matches!(diff(&desired, &current), Diff::NoChange(_)));
+
+        // Other changes are still planned, without reverting the ignored ones
+        let desired = desired.with_attribute("acl", Value::String("private".to_string()));
+        match diff(&desired, &current) {
+            Diff::Update {
+                to,
+                changed_attributes,
+                ..
+            } => {
+                assert_eq!(changed_attributes, vec!["acl"]);
+                assert_eq!(to.attributes.get("description"), attrs.get("description"));
+                assert_eq!(to.attributes.get("tags"), attrs.get("tags"));
+            }
+            other => panic!("Expected Update, got {:?}", other),
+        }
+
+        // Ignored attributes are still written on create
+        let missing = State::not_found(ResourceId::new("bucket", "test"));
+        match diff(&desired, &missing) {
+            Diff::Create(resource) => assert_eq!(
+                resource.attributes.get("description"),
+                Some(&Value::String("ours".to_string()))
+            ),
+            other => panic!("Expected Create, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn diff_null_matches_missing_attribute_only() {
+        let desired = Resource::new("bucket", "test").with_attribute("acl", Value::Null);
+        let current = State::existing(ResourceId::new("bucket", "test"), HashMap::new());
+        assert!(matches!(diff(&desired, &current), Diff::NoChange(_)));
+
+        let mut attrs = HashMap::new();
+        attrs.insert("acl".to_string(), Value::String("null".to_string()));
+        let current = State::existing(ResourceId::new("bucket", "test"), attrs);
+        assert!(matches!(diff(&desired, &current), Diff::Update { .. }));
+    }
+
     #[test]
     fn diff_update_when_different() {
         let desired = Resource::new("bucket", "test")
@@ -161,6 +394,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn diff_update_lists_changed_attributes_in_sorted_order() {
+        let desired = Resource::new("bucket", "test")
+            .with_attribute("versioning", Value::Bool(true))
+            .with_attribute("acl", Value::String("private".to_string()))
+            .with_attribute("region", Value::String("us-east-1".to_string()));
+        let current = State::existing(ResourceId::new("bucket", "test"), HashMap::new());
+
+        match diff(&desired, &current) {
+            Diff::Update {
+                changed_attributes, ..
+            } => {
+                assert_eq!(changed_attributes, vec!["acl", "region", "versioning"]);
+            }
+            _ => panic!("Expected Update"),
+        }
+    }
+
     #[test]
     fn create_plan_from_resources() {
         let resources = vec![
@@ -202,6 +453,29 @@ mod tests {
         assert!(matches!(plan.effects()[1], Effect::Create(_)));
     }
 
+    #[test]
+    fn transitional_resources_are_planned_as_existing() {
+        let id = ResourceId::new("vpc", "main");
+        let resources = vec![
+            Resource::new("vpc", "main")
+                .with_attribute("cidr_block", Value::String("10.0.0.0/16".to_string())),
+        ];
+        let attributes = HashMap::from([
+            (
+                "cidr_block".to_string(),
+                Value::String("10.0.0.0/16".to_string()),
+            ),
+            ("state".to_string(), Value::String("pending".to_string())),
+        ]);
+        let state = State::existing(id.clone(), attributes).with_transitional_status("pending");
+        assert!(!state.is_ready());
+
+        // A resource that is still coming up is neither created again nor changed
+        let plan = create_plan(&resources, &HashMap::from([(id.clone(), state)]));
+        assert!(plan.is_empty());
+        assert_eq!(plan.unchanged(), &[id]);
+    }
+
     #[test]
     fn read_only_resource_always_generates_read_effect() {
         // Even if the resource "exists", read-only resources should only generate Read effect
@@ -228,4 +502,167 @@ mod tests {
         assert_eq!(plan.effects().len(), 1);
         assert!(matches!(plan.effects()[0], Effect::Read { .. }));
     }
+
+    #[test]
+    fn update_of_immutable_attribute_plans_replacement() {
+        let id = ResourceId::new("subnet", "public");
+        let desired = vec![
+            Resource::new("subnet", "public")
+                .with_attribute("availability_zone", Value::String("ap_northeast_1c".into())),
+        ];
+
+        let mut attrs = HashMap::new();
+        attrs.insert(
+            "availability_zone".to_string(),
+            Value::String("ap_northeast_1a".to_string()),
+        );
+        let mut current_states = HashMap::new();
+        current_states.insert(id.clone(), State::existing(id, attrs));
+
+        // Without capabilities the change is a plain update
+        let plan = create_plan(&desired, &current_states);
+        assert!(matches!(plan.effects()[0], Effect::Update { .. }));
+
+        let mut capabilities = HashMap::new();
+        capabilities.insert(
+            "subnet".to_string(),
+            Capabilities::default().with_replace_on_change(&["availability_zone"]),
+        );
+        let plan = create_plan_with_capabilities(&desired, &current_states, &capabilities);
+        assert!(matches!(plan.effects()[0], Effect::Replace { .. }));
+        assert_eq!(plan.summary().replace, 1);
+    }
+
+    #[test]
+    fn region_values_compare_in_canonical_form() {
+        let region = |s: &str| Value::String(s.to_string());
+
+        for desired in [
+            "aws.Region.ap_northeast_1",
+            "Region.ap_northeast_1",
+            "ap-northeast-1",
+        ] {
+            assert!(attribute_values_equal(
+                "region",
+                &region(desired),
+                &region("aws.Region.ap_northeast_1")
+            ));
+        }
+        assert!(!attribute_values_equal(
+            "region",
+            &region("ap-northeast-1"),
+            &region("aws.Region.us_east_1")
+        ));
+
+        // Other attributes still compare exactly
+        assert!(!attribute_values_equal(
+            "description",
+            &region("ap-northeast-1"),
+            &region("ap_northeast_1")
+        ));
+    }
+
+    #[test]
+    fn diff_no_change_when_region_differs_only_in_format() {
+        let resource = Resource::new("vpc", "main")
+            .with_attribute("region", Value::String("ap-northeast-1".to_string()));
+        let mut attrs = HashMap::new();
+        attrs.insert(
+            "region".to_string(),
+            Value::String("aws.Region.ap_northeast_1".to_string()),
+        );
+        let current = State::existing(ResourceId::new("vpc", "main"), attrs);
+
+        assert!(matches!(diff(&resource, &current), Diff::NoChange(_)));
+    }
+
+    #[test]
+    fn unordered_list_ignores_order_but_not_duplicates() {
+        let cidrs = |items: &[&str]| {
+            Value::List(items.iter().map(|s| Value::String(s.to_string())).collect())
+        };
+        let id = ResourceId::new("security_group.ingress_rule", "http");
+        let current_with = |value: Value| {
+            let mut attrs = HashMap::new();
+            attrs.insert("cidr_blocks".to_string(), value);
+            State::existing(id.clone(), attrs)
+        };
+        let desired = Resource::new("security_group.ingress_rule", "http")
+            .with_attribute("cidr_blocks", cidrs(&["10.0.0.0/16", "0.0.0.0/0"]));
+        let caps = Capabilities {
+            unordered: vec!["cidr_blocks".to_string()],
+            ..Capabilities::default()
+        };
+
+        let reordered = current_with(cidrs(&["0.0.0.0/0", "10.0.0.0/16"]));
+        assert!(matches!(
+            diff_with_capabilities(&desired, &reordered, &caps),
+            Diff::NoChange(_)
+        ));
+        // Without the flag, order is significant
+        assert!(matches!(diff(&desired, &reordered), Diff::Update { .. }));
+
+        let duplicated = Resource::new("security_group.ingress_rule", "http")
+            .with_attribute("cidr_blocks", cidrs(&["0.0.0.0/0", "0.0.0.0/0"]));
+        let current = current_with(cidrs(&["0.0.0.0/0", "10.0.0.0/16"]));
+        assert!(matches!(
+            diff_with_capabilities(&duplicated, &current, &caps),
+            Diff::Update { .. }
+        ));
+    }
+
+    #[test]
+    fn write_only_attributes_are_not_compared() {
+        let id = ResourceId::new("rds.db_instance", "main");
+        let mut attrs = HashMap::new();
+        attrs.insert("engine".to_string(), Value::String("postgres".to_string()));
+        let current = State::existing(id, attrs);
+        let desired = Resource::new("rds.db_instance", "main")
+            .with_attribute("engine", Value::String("postgres".to_string()))
+            .with_attribute("master_user_password", Value::String("secret".to_string()));
+        let caps = Capabilities::default().with_write_only(&["master_user_password"]);
+
+        assert!(matches!(
+            diff_with_capabilities(&desired, &current, &caps),
+            Diff::NoChange(_)
+        ));
+        assert!(matches!(diff(&desired, &current), Diff::Update { .. }));
+    }
+
+    #[test]
+    fn enum_attributes_compare_in_canonical_spelling() {
+        let id = ResourceId::new("s3.bucket", "logs");
+        let mut attrs = HashMap::new();
+        attrs.insert(
+            "versioning".to_string(),
+            Value::String("Enabled".to_string()),
+        );
+        let current = State::existing(id, attrs);
+        let caps = Capabilities {
+            enum_values: HashMap::from([(
+                "versioning".to_string(),
+                vec!["Enabled".to_string(), "Suspended".to_string()],
+            )]),
+            ..Capabilities::default()
+        };
+        let desired = |value: &str| {
+            Resource::new("s3.bucket", "logs")
+                .with_attribute("versioning", Value::String(value.to_string()))
+        };
+
+        for same in [
+            "enabled",
+            "VersioningStatus.ENABLED",
+            "aws.s3.VersioningStatus.Enabled",
+        ] {
+            assert!(matches!(
+                diff_with_capabilities(&desired(same), &current, &caps),
+                Diff::NoChange(_)
+            ));
+        }
+        assert!(matches!(
+            diff_with_capabilities(&desired("suspended"), &current, &caps),
+            Diff::Update { .. }
+        ));
+    }
 }
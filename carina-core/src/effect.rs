@@ -3,10 +3,12 @@
 //! An Effect describes "what to do" without actually performing the side effect.
 //! Side effects only occur when the Interpreter executes the Effect.
 
+use serde::{Deserialize, Serialize};
+
 use crate::resource::{Resource, ResourceId, State};
 
 /// Effect representing an operation on a resource
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Effect {
     /// Read the current state of a resource (data source)
     Read { resource: Resource },
@@ -21,6 +23,14 @@ pub enum Effect {
         to: Resource,
     },
 
+    /// Replace an existing resource (delete, then create) because the change
+    /// cannot be applied in place
+    Replace {
+        id: ResourceId,
+        from: State,
+        to: Resource,
+    },
+
     /// Delete a resource
     Delete(ResourceId),
 }
@@ -32,6 +42,7 @@ impl Effect {
             Effect::Read { .. } => "read",
             Effect::Create(_) => "create",
             Effect::Update { .. } => "update",
+            Effect::Replace { .. } => "replace",
             Effect::Delete(_) => "delete",
         }
     }
@@ -47,6 +58,7 @@ impl Effect {
             Effect::Read { resource } => &resource.id,
             Effect::Create(r) => &r.id,
             Effect::Update { id, .. } => id,
+            Effect::Replace { id, .. } => id,
             Effect::Delete(id) => id,
         }
     }
@@ -55,6 +67,8 @@ impl Effect {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::resource::Value;
+    use std::collections::HashMap;
 
     #[test]
     fn read_is_not_mutating() {
@@ -78,4 +92,23 @@ mod tests {
         };
         assert_eq!(effect.resource_id(), &resource.id);
     }
+
+    #[test]
+    fn effect_round_trips_through_json() {
+        let resource = Resource::new("ec2.subnet", "private")
+            .with_attribute(
+                "vpc_id",
+                Value::ResourceRef("vpc".to_string(), "id".to_string()),
+            )
+            .with_attribute("weight", Value::Float(0.5));
+        let effect = Effect::Update {
+            id: resource.id.clone(),
+            from: State::existing(resource.id.clone(), HashMap::new()).with_identifier("subnet-1"),
+            to: resource,
+        };
+
+        let json = serde_json::to_string(&effect).unwrap();
+        let restored: Effect = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, effect);
+    }
 }
@@ -1,5 +1,10 @@
 //! Formatting configuration
 
+use std::path::{Path, PathBuf};
+
+/// Name of the per-project formatter configuration file
+pub const CONFIG_FILE_NAME: &str = ".carinafmt";
+
 /// Formatting options
 #[derive(Debug, Clone)]
 pub struct FormatConfig {
@@ -12,8 +17,14 @@ pub struct FormatConfig {
     /// Number of blank lines between top-level blocks (default: 1)
     pub blank_lines_between_blocks: usize,
 
-    /// Align attribute values in a block
+    /// Align the `=` of attributes in a block by padding keys to the longest
+    /// key. Groups separated by blank lines and nested maps align on their own.
+    /// When disabled, files that are already aligned are left as they are.
     pub align_attributes: bool,
+
+    /// Order attributes within each group of a block: `name` first, then the
+    /// rest alphabetically (default: false)
+    pub sort_attributes: bool,
 }
 
 impl Default for FormatConfig {
@@ -23,11 +34,76 @@ impl Default for FormatConfig {
             use_tabs: false,
             blank_lines_between_blocks: 1,
             align_attributes: true,
+            sort_attributes: false,
         }
     }
 }
 
 impl FormatConfig {
+    /// Parse a `.carinafmt` file: `key = value` lines named after the fields of
+    /// `FormatConfig`, with `#` comments. Unset fields keep their defaults.
+    ///
+    /// ```text
+    /// indent_size = 4
+    /// sort_attributes = true
+    /// ```
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut config = Self::default();
+        for (index, line) in source.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let line_no = index + 1;
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected `key = value`", line_no))?;
+            let (key, value) = (key.trim(), value.trim());
+            let invalid = |expected: &str| {
+                format!(
+                    "line {}: invalid value for {}: expected {}, got `{}`",
+                    line_no, key, expected, value
+                )
+            };
+            let as_bool = || value.parse::<bool>().map_err(|_| invalid("true or false"));
+            let as_usize = || value.parse::<usize>().map_err(|_| invalid("a number"));
+            match key {
+                "indent_size" => config.indent_size = as_usize()?,
+                "use_tabs" => config.use_tabs = as_bool()?,
+                "blank_lines_between_blocks" => config.blank_lines_between_blocks = as_usize()?,
+                "align_attributes" => config.align_attributes = as_bool()?,
+                "sort_attributes" => config.sort_attributes = as_bool()?,
+                _ => return Err(format!("line {}: unknown option `{}`", line_no, key)),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Find the nearest `.carinafmt`, looking in `start` (or its directory
+    /// when it is a file) and then in each parent directory
+    pub fn find_file(start: &Path) -> Option<PathBuf> {
+        let start = std::fs::canonicalize(start).unwrap_or_else(|_| start.to_path_buf());
+        let dir = if start.is_file() {
+            start.parent()?
+        } else {
+            start.as_path()
+        };
+        dir.ancestors()
+            .map(|d| d.join(CONFIG_FILE_NAME))
+            .find(|p| p.is_file())
+    }
+
+    /// Load the configuration that applies to `start`: the nearest
+    /// `.carinafmt`, or the defaults when there is none
+    pub fn load(start: &Path) -> Result<Self, String> {
+        let Some(path) = Self::find_file(start) else {
+            return Ok(Self::default());
+        };
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&source).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+    }
+
     /// Get the string to use for a single level of indentation
     pub fn indent_string(&self) -> String {
         if self.use_tabs {
@@ -49,6 +125,7 @@ mod tests {
         assert!(!config.use_tabs);
         assert_eq!(config.blank_lines_between_blocks, 1);
         assert!(config.align_attributes);
+        assert!(!config.sort_attributes);
     }
 
     #[test]
@@ -57,6 +134,52 @@ mod tests {
         assert_eq!(config.indent_string(), "  ");
     }
 
+    #[test]
+    fn test_parse_config_file() {
+        let config = FormatConfig::parse(
+            "# project style\nindent_size = 4\n\nsort_attributes = true  # name first\n",
+        )
+        .unwrap();
+        assert_eq!(config.indent_size, 4);
+        assert!(config.sort_attributes);
+        assert!(config.align_attributes);
+    }
+
+    #[test]
+    fn test_parse_config_file_errors() {
+        assert_eq!(
+            FormatConfig::parse("indent_size = four").unwrap_err(),
+            "line 1: invalid value for indent_size: expected a number, got `four`"
+        );
+        assert_eq!(
+            FormatConfig::parse("\nalign_equals = true").unwrap_err(),
+            "line 2: unknown option `align_equals`"
+        );
+        assert_eq!(
+            FormatConfig::parse("use_tabs").unwrap_err(),
+            "line 1: expected `key = value`"
+        );
+    }
+
+    #[test]
+    fn test_load_walks_up_to_nearest_config_file() {
+        let root = std::env::temp_dir().join(format!("carinafmt-test-{}", std::process::id()));
+        let nested = root.join("envs").join("prod");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(root.join(CONFIG_FILE_NAME), "indent_size = 4\n").unwrap();
+        std::fs::write(nested.join("main.crn"), "").unwrap();
+
+        let from_file = FormatConfig::load(&nested.join("main.crn")).unwrap();
+        assert_eq!(from_file.indent_size, 4);
+
+        std::fs::write(nested.join(CONFIG_FILE_NAME), "use_tabs = yes\n").unwrap();
+        let err = FormatConfig::load(&nested).unwrap_err();
+        assert!(err.contains("Invalid"), "{}", err);
+        assert!(err.contains("expected true or false"), "{}", err);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
     #[test]
     fn test_indent_string_tabs() {
         let config = FormatConfig {
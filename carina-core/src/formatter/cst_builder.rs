@@ -100,8 +100,16 @@ impl<'a> CstBuilder<'a> {
                 let inner = pair.into_inner().next()?;
                 self.build_child(inner)
             }
+            // Operator levels without an operator collapse into their operand
+            Rule::conditional | Rule::comparison | Rule::sum | Rule::product | Rule::unary => {
+                let mut inner = pair.clone().into_inner();
+                match (inner.next(), inner.next()) {
+                    (Some(operand), None) => self.build_child(operand),
+                    _ => Some(CstChild::Node(self.build_node(NodeKind::Expression, pair))),
+                }
+            }
             Rule::pipe_expr => Some(CstChild::Node(self.build_node(NodeKind::PipeExpr, pair))),
-            Rule::function_call => Some(CstChild::Node(
+            Rule::function_call | Rule::call_expr => Some(CstChild::Node(
                 self.build_node(NodeKind::FunctionCall, pair),
             )),
             Rule::primary => {
@@ -118,7 +126,7 @@ impl<'a> CstBuilder<'a> {
             }
 
             // Atoms - these become tokens
-            Rule::namespaced_id => {
+            Rule::namespaced_id | Rule::indexed_ref => {
                 Some(CstChild::Token(Token::new(pair.as_str().to_string(), span)))
             }
             Rule::identifier => Some(CstChild::Token(Token::new(pair.as_str().to_string(), span))),
@@ -138,6 +146,11 @@ impl<'a> CstBuilder<'a> {
             Rule::colon => Some(CstChild::Token(Token::new(":".to_string(), span))),
             Rule::comma => Some(CstChild::Token(Token::new(",".to_string(), span))),
             Rule::pipe_op => Some(CstChild::Token(Token::new("|>".to_string(), span))),
+            Rule::question => Some(CstChild::Token(Token::new("?".to_string(), span))),
+            Rule::compare_op | Rule::add_op | Rule::mul_op => {
+                Some(CstChild::Token(Token::new(pair.as_str().to_string(), span)))
+            }
+            Rule::not_op => Some(CstChild::Token(Token::new("!".to_string(), span))),
 
             // Keywords
             Rule::kw_import => Some(CstChild::Token(Token::new("import".to_string(), span))),
@@ -9,8 +9,21 @@ use super::parser::{self, FormatParseError};
 pub fn format(source: &str, config: &FormatConfig) -> Result<String, FormatParseError> {
     let pairs = parser::parse(source)?;
     let cst = build_cst(source, pairs);
-    let formatter = Formatter::new(config.clone());
-    Ok(formatter.format(&cst))
+    let formatted = Formatter::new(config.clone()).format(&cst);
+
+    // Without alignment, a file that is already formatted with aligned
+    // attributes is accepted as it is
+    if !config.align_attributes && formatted != source {
+        let aligned = FormatConfig {
+            align_attributes: true,
+            ..config.clone()
+        };
+        if Formatter::new(aligned).format(&cst) == source {
+            return Ok(source.to_string());
+        }
+    }
+
+    Ok(formatted)
 }
 
 /// Check if a file needs formatting
@@ -109,6 +122,7 @@ impl Formatter {
             NodeKind::Attribute => self.format_attribute(node, 0),
             NodeKind::InputParam => self.format_input_param(node, 0),
             NodeKind::OutputParam => self.format_output_param(node, 0),
+            NodeKind::Expression => self.format_expression(node),
             NodeKind::PipeExpr => self.format_pipe_expr(node),
             NodeKind::FunctionCall => self.format_function_call(node),
             NodeKind::EnvVar => self.format_env_var(node),
@@ -617,11 +631,25 @@ impl Formatter {
                 0
             };
 
+            // Pair each attribute with its inline comment before any reordering
+            let mut entries: Vec<(&CstNode, Option<&Trivia>)> = group
+                .iter()
+                .map(|attr| {
+                    let inline_comment = inline_comments.get(&global_attr_index).copied();
+                    global_attr_index += 1;
+                    (*attr, inline_comment)
+                })
+                .collect();
+            if self.config.sort_attributes {
+                entries.sort_by_cached_key(|(attr, _)| {
+                    let key = self.get_attribute_key(attr).unwrap_or_default();
+                    (key != "name", key)
+                });
+            }
+
             // Format each attribute in this group
-            for attr in group {
-                let inline_comment = inline_comments.get(&global_attr_index);
-                self.format_attribute_aligned(attr, max_key_len, inline_comment.copied());
-                global_attr_index += 1;
+            for (attr, inline_comment) in entries {
+                self.format_attribute_aligned(attr, max_key_len, inline_comment);
             }
         }
 
@@ -698,6 +726,22 @@ impl Formatter {
         self.write_newline();
     }
 
+    fn format_expression(&mut self, node: &CstNode) {
+        for child in &node.children {
+            match child {
+                CstChild::Token(token) => {
+                    if token.text == "!" {
+                        self.write("!");
+                    } else {
+                        self.write(&format!(" {} ", token.text));
+                    }
+                }
+                CstChild::Node(n) => self.format_node(n),
+                CstChild::Trivia(_) => {}
+            }
+        }
+    }
+
     fn format_pipe_expr(&mut self, node: &CstNode) {
         let mut first = true;
         for child in &node.children {
@@ -1021,6 +1065,45 @@ mod tests {
         assert_eq!(name_eq_pos, vers_eq_pos);
     }
 
+    #[test]
+    fn test_format_sorts_attributes_name_first() {
+        let input = "aws.s3.bucket {\n  versioning = true # keep\n  acl = private\n  name = \"test\"\n\n  tags = {}\n  expiration_days = 30\n}\n";
+        let config = FormatConfig {
+            sort_attributes: true,
+            ..Default::default()
+        };
+        let result = format(input, &config).unwrap();
+
+        assert_eq!(
+            result,
+            "aws.s3.bucket {\n  name       = \"test\"\n  acl        = private\n  versioning = true  # keep\n\n  expiration_days = 30\n  tags            = {}\n}\n"
+        );
+        assert_eq!(format(&result, &config).unwrap(), result);
+    }
+
+    #[test]
+    fn test_format_keeps_order_by_default() {
+        let input = "aws.s3.bucket {\n  versioning = true\n  name       = \"test\"\n}\n";
+        let result = format(input, &FormatConfig::default()).unwrap();
+        assert_eq!(result, input);
+    }
+
+    #[test]
+    fn test_format_without_alignment_accepts_aligned_file() {
+        let config = FormatConfig {
+            align_attributes: false,
+            ..Default::default()
+        };
+        let aligned = "aws.s3.bucket {\n  name       = \"test\"\n  versioning = true\n}\n";
+        assert!(!needs_format(aligned, &config).unwrap());
+
+        let messy = "aws.s3.bucket {\n  name  = \"test\"\n  versioning = true\n}\n";
+        assert_eq!(
+            format(messy, &config).unwrap(),
+            "aws.s3.bucket {\n  name = \"test\"\n  versioning = true\n}\n"
+        );
+    }
+
     #[test]
     fn test_format_idempotent() {
         let input = "provider aws {\n  region = aws.Region.ap_northeast_1\n}\n";
@@ -1041,6 +1124,15 @@ mod tests {
         assert!(result.contains("let bucket = aws.s3.bucket {"));
     }
 
+    #[test]
+    fn test_format_preserves_float_literal() {
+        let input = "aws.s3.bucket {\nname=\"test\"\nweight=0.5\n}";
+        let config = FormatConfig::default();
+        let result = format(input, &config).unwrap();
+
+        assert!(result.contains("weight = 0.5"));
+    }
+
     #[test]
     fn test_needs_format() {
         let config = FormatConfig::default();
@@ -1193,4 +1285,41 @@ mod tests {
             "tags should have minimal padding"
         );
     }
+
+    #[test]
+    fn test_format_conditional_expression() {
+        let input = "let versioning = !input.prod?\"Suspended\":\"Enabled\"\nlet same = a+1==b*2\n";
+        let config = FormatConfig::default();
+
+        let result = format(input, &config).unwrap();
+
+        assert_eq!(
+            result,
+            "let versioning = !input.prod ? \"Suspended\" : \"Enabled\"\n\nlet same = a + 1 == b * 2\n"
+        );
+    }
+
+    #[test]
+    fn test_format_function_call_expression() {
+        let input = "let any = length( cidrs )>0\n";
+        let config = FormatConfig::default();
+
+        let result = format(input, &config).unwrap();
+
+        assert_eq!(result, "let any = length(cidrs) > 0\n");
+    }
+
+    #[test]
+    fn test_format_indexed_references() {
+        let input = "aws.elb {\n  subnet_ids = subnets[*].id\n  first = subnets[0].id\n  zone = zones[\"a\"].id\n}\n";
+        let config = FormatConfig::default();
+
+        let result = format(input, &config).unwrap();
+
+        assert_eq!(
+            result,
+            "aws.elb {\n  subnet_ids = subnets[*].id\n  first      = subnets[0].id\n  zone       = zones[\"a\"].id\n}\n"
+        );
+        assert_eq!(format(&result, &config).unwrap(), result);
+    }
 }
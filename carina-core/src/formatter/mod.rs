@@ -21,6 +21,6 @@ mod cst_builder;
 mod format;
 mod parser;
 
-pub use config::FormatConfig;
+pub use config::{CONFIG_FILE_NAME, FormatConfig};
 pub use format::{format, needs_format};
 pub use parser::FormatParseError;
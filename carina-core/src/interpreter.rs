@@ -17,6 +17,8 @@ pub enum EffectOutcome {
     Created { state: State },
     /// Update succeeded
     Updated { state: State },
+    /// Replace (delete + create) succeeded
+    Replaced { state: State },
     /// Delete succeeded
     Deleted,
     /// Skipped (e.g., dry-run)
@@ -105,8 +107,8 @@ impl<P: Provider> Interpreter<P> {
 
         match effect {
             Effect::Read { resource } => {
-                // Read without identifier (fall back to name-based lookup)
-                let state = self.provider.read(&resource.id, None).await?;
+                // Data sources are looked up by their attributes, never by identifier
+                let state = self.provider.read_data_source(resource).await?;
                 Ok(EffectOutcome::Read { state })
             }
             Effect::Create(resource) => {
@@ -119,6 +121,12 @@ impl<P: Provider> Interpreter<P> {
                 let state = self.provider.update(id, identifier, from, to).await?;
                 Ok(EffectOutcome::Updated { state })
             }
+            Effect::Replace { id, from, to } => {
+                let identifier = from.identifier.as_deref().unwrap_or("");
+                self.provider.delete(id, identifier).await?;
+                let state = self.provider.create(to).await?;
+                Ok(EffectOutcome::Replaced { state })
+            }
             Effect::Delete(id) => {
                 // Delete without identifier - this won't work for identifier-based providers
                 // CLI handles identifier extraction from state directly
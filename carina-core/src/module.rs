@@ -118,6 +118,7 @@ impl DependencyGraph {
 /// Format a Value for display
 fn format_value(value: &Value) -> String {
     match value {
+        Value::Null => "null".to_string(),
         Value::String(s) => {
             if s.len() > 50 {
                 format!("\"{}...\"", &s[..47])
@@ -126,6 +127,7 @@ fn format_value(value: &Value) -> String {
             }
         }
         Value::Int(n) => n.to_string(),
+        Value::Float(f) => f.to_string(),
         Value::Bool(b) => b.to_string(),
         Value::List(items) => {
             if items.is_empty() {
@@ -145,6 +147,7 @@ fn format_value(value: &Value) -> String {
             }
         }
         Value::ResourceRef(binding, attr) => format!("{}.{}", binding, attr),
+        Value::SplatRef(binding, attr) => format!("{}[*].{}", binding, attr),
         Value::TypedResourceRef {
             binding_name,
             attribute_name,
@@ -154,6 +157,20 @@ fn format_value(value: &Value) -> String {
             Some(m) => format!("{}.{}", name, m),
             None => name.clone(),
         },
+        Value::Interpolation(parts) => {
+            let template: String = parts
+                .iter()
+                .map(|part| match part {
+                    Value::String(s) => s.clone(),
+                    other => format!("${{{}}}", format_value(other)),
+                })
+                .collect();
+            format!("\"{}\"", template)
+        }
+        Value::Operation(operator, operands) => {
+            let operands: Vec<String> = operands.iter().map(format_value).collect();
+            operator.render(&operands)
+        }
     }
 }
 
@@ -442,7 +459,7 @@ impl RootConfigSignature {
                     },
                 );
             }
-            Value::List(items) => {
+            Value::List(items) | Value::Interpolation(items) | Value::Operation(_, items) => {
                 for item in items {
                     Self::collect_typed_dependencies(from, attr_key, item, graph, binding_types);
                 }
@@ -893,7 +910,7 @@ impl ModuleSignature {
                     },
                 );
             }
-            Value::List(items) => {
+            Value::List(items) | Value::Interpolation(items) | Value::Operation(_, items) => {
                 for item in items {
                     Self::collect_typed_dependencies(
                         from,
@@ -1289,6 +1306,35 @@ mod tests {
         assert!(display.contains("http_rule: aws.security_group.ingress_rule"));
     }
 
+    #[test]
+    fn test_dependencies_through_operations() {
+        use crate::parser::parse;
+
+        let input = r#"
+            let base = aws.ec2.volume {
+                name = "base"
+                size = 8
+            }
+
+            let copy = aws.ec2.volume {
+                name = "copy"
+                size = base.size * 2
+            }
+        "#;
+
+        let parsed = parse(input).unwrap();
+        let signature = ModuleSignature::from_directory_module(&parsed, "volumes");
+        let deps = signature.dependency_graph.dependencies_of("copy");
+        assert_eq!(deps.len(), 1);
+        assert_eq!(deps[0].target, "base");
+        assert_eq!(deps[0].used_in, "size");
+
+        let root = RootConfigSignature::from_parsed_file(&parsed, "main.crn");
+        let deps = root.dependency_graph.dependencies_of("copy");
+        assert_eq!(deps.len(), 1);
+        assert_eq!(deps[0].target, "base");
+    }
+
     #[test]
     fn test_typed_dependency_graph() {
         use crate::parser::ResourceTypePath;
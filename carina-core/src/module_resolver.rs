@@ -261,10 +261,50 @@ fn substitute_inputs(value: &Value, inputs: &HashMap<String, Value>) -> Value {
                 .map(|(k, v)| (k.clone(), substitute_inputs(v, inputs)))
                 .collect(),
         ),
+        Value::Interpolation(parts) => Value::interpolate(
+            parts
+                .iter()
+                .map(|part| substitute_inputs(part, inputs))
+                .collect(),
+        ),
+        Value::Operation(operator, operands) => {
+            let operands: Vec<Value> = operands
+                .iter()
+                .map(|operand| substitute_inputs(operand, inputs))
+                .collect();
+            // Operands of the wrong type are reported by schema validation
+            Value::operate(*operator, operands.clone())
+                .unwrap_or(Value::Operation(*operator, operands))
+        }
         _ => value.clone(),
     }
 }
 
+/// Substitute `input.*` references in a root configuration with the given values,
+/// before its module calls are expanded
+pub fn substitute_root_inputs(parsed: &mut ParsedFile, inputs: &HashMap<String, Value>) {
+    let substitute_all = |attributes: &mut HashMap<String, Value>| {
+        for value in attributes.values_mut() {
+            *value = substitute_inputs(value, inputs);
+        }
+    };
+
+    for provider in &mut parsed.providers {
+        substitute_all(&mut provider.attributes);
+    }
+    for resource in &mut parsed.resources {
+        substitute_all(&mut resource.attributes);
+    }
+    for call in &mut parsed.module_calls {
+        substitute_all(&mut call.arguments);
+    }
+    for output in &mut parsed.outputs {
+        if let Some(value) = &output.value {
+            output.value = Some(substitute_inputs(value, inputs));
+        }
+    }
+}
+
 /// Resolve all modules in a parsed file
 pub fn resolve_modules(parsed: &mut ParsedFile, base_dir: &Path) -> Result<(), ModuleError> {
     let mut resolver = ModuleResolver::new(base_dir);
@@ -298,6 +338,7 @@ pub fn get_parsed_file(path: &Path) -> Result<ParsedFile, ModuleError> {
 mod tests {
     use super::*;
     use crate::parser::{InputParameter, TypeExpr};
+    use crate::resource::Operator;
 
     fn create_test_module() -> ParsedFile {
         ParsedFile {
@@ -318,6 +359,8 @@ mod tests {
                     attrs
                 },
                 read_only: false,
+                location: None,
+                attribute_locations: HashMap::new(),
             }],
             variables: HashMap::new(),
             imports: vec![],
@@ -371,6 +414,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_substitute_inputs_evaluates_conditional() {
+        let mut inputs = HashMap::new();
+        inputs.insert("prod".to_string(), Value::Bool(false));
+
+        let value = Value::Operation(
+            Operator::Conditional,
+            vec![
+                Value::ResourceRef("input".to_string(), "prod".to_string()),
+                Value::String("Enabled".to_string()),
+                Value::String("Suspended".to_string()),
+            ],
+        );
+        let result = substitute_inputs(&value, &inputs);
+
+        assert_eq!(result, Value::String("Suspended".to_string()));
+    }
+
+    #[test]
+    fn test_substitute_root_inputs() {
+        let mut parsed = create_test_module();
+        parsed.module_calls.push(ModuleCall {
+            module_name: "web_tier".to_string(),
+            binding_name: None,
+            arguments: HashMap::from([(
+                "vpc".to_string(),
+                Value::ResourceRef("input".to_string(), "vpc_id".to_string()),
+            )]),
+        });
+
+        let inputs = HashMap::from([("vpc_id".to_string(), Value::String("vpc-123".to_string()))]);
+        substitute_root_inputs(&mut parsed, &inputs);
+
+        let vpc_id = Value::String("vpc-123".to_string());
+        assert_eq!(parsed.resources[0].attributes.get("vpc_id"), Some(&vpc_id));
+        assert_eq!(parsed.module_calls[0].arguments.get("vpc"), Some(&vpc_id));
+    }
+
     #[test]
     fn test_expand_module_call() {
         let resolver = {
@@ -0,0 +1,172 @@
+//! Builtin functions for the pipe operator and function calls
+//!
+//! `value |> func(args)` calls `func` with the piped value followed by `args`,
+//! the same as `func(value, args)`.
+
+use crate::resource::Value;
+
+/// A builtin function: receives the piped value and the call arguments
+type BuiltinFn = fn(&Value, &[Value]) -> Result<Value, String>;
+
+/// Look up a builtin function by name
+fn lookup(name: &str) -> Option<BuiltinFn> {
+    match name {
+        "upper" => Some(upper),
+        "lower" => Some(lower),
+        "trim" => Some(trim),
+        "replace" => Some(replace),
+        "length" => Some(length),
+        "contains" => Some(contains),
+        "keys" => Some(keys),
+        _ => None,
+    }
+}
+
+/// Whether `name` is a builtin function
+pub fn exists(name: &str) -> bool {
+    lookup(name).is_some()
+}
+
+/// Apply the builtin function `name` to `value`
+pub fn call(name: &str, value: &Value, args: &[Value]) -> Result<Value, String> {
+    let func = lookup(name).ok_or_else(|| format!("Unknown function '{}'", name))?;
+    func(value, args).map_err(|e| format!("{}: {}", name, e))
+}
+
+fn expect_arity(args: &[Value], expected: usize) -> Result<(), String> {
+    if args.len() == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "expected {} argument(s), got {}",
+            expected,
+            args.len()
+        ))
+    }
+}
+
+fn expect_string(value: &Value) -> Result<&str, String> {
+    match value {
+        Value::String(s) => Ok(s),
+        Value::ResourceRef(..) | Value::TypedResourceRef { .. } | Value::Interpolation(_) => {
+            Err("cannot be applied to a resource reference".to_string())
+        }
+        _ => Err(format!("expected a string, got {:?}", value)),
+    }
+}
+
+fn upper(value: &Value, args: &[Value]) -> Result<Value, String> {
+    expect_arity(args, 0)?;
+    Ok(Value::String(expect_string(value)?.to_uppercase()))
+}
+
+fn lower(value: &Value, args: &[Value]) -> Result<Value, String> {
+    expect_arity(args, 0)?;
+    Ok(Value::String(expect_string(value)?.to_lowercase()))
+}
+
+fn trim(value: &Value, args: &[Value]) -> Result<Value, String> {
+    expect_arity(args, 0)?;
+    Ok(Value::String(expect_string(value)?.trim().to_string()))
+}
+
+fn replace(value: &Value, args: &[Value]) -> Result<Value, String> {
+    expect_arity(args, 2)?;
+    let from = expect_string(&args[0])?;
+    let to = expect_string(&args[1])?;
+    Ok(Value::String(expect_string(value)?.replace(from, to)))
+}
+
+fn length(value: &Value, args: &[Value]) -> Result<Value, String> {
+    expect_arity(args, 0)?;
+    let len = match value {
+        Value::List(items) => items.len(),
+        Value::Map(map) => map.len(),
+        Value::String(s) => s.chars().count(),
+        _ => return Err(format!("expected a list, map or string, got {:?}", value)),
+    };
+    Ok(Value::Int(len as i64))
+}
+
+fn contains(value: &Value, args: &[Value]) -> Result<Value, String> {
+    expect_arity(args, 1)?;
+    match value {
+        Value::List(items) => Ok(Value::Bool(items.contains(&args[0]))),
+        _ => Err(format!("expected a list, got {:?}", value)),
+    }
+}
+
+fn keys(value: &Value, args: &[Value]) -> Result<Value, String> {
+    expect_arity(args, 0)?;
+    match value {
+        Value::Map(map) => Ok(Value::List(
+            map.keys().map(|key| Value::String(key.clone())).collect(),
+        )),
+        _ => Err(format!("expected a map, got {:?}", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+
+    #[test]
+    fn string_functions() {
+        assert_eq!(call("upper", &s("Hello"), &[]), Ok(s("HELLO")));
+        assert_eq!(call("lower", &s("Hello"), &[]), Ok(s("hello")));
+        assert_eq!(call("trim", &s("  hi  "), &[]), Ok(s("hi")));
+        assert_eq!(
+            call("replace", &s("a-b-c"), &[s("-"), s("_")]),
+            Ok(s("a_b_c"))
+        );
+    }
+
+    #[test]
+    fn collection_functions() {
+        let list = Value::List(vec![s("10.0.0.0/24"), s("10.0.1.0/24")]);
+        let map = Value::Map(
+            [
+                ("b".to_string(), Value::Int(2)),
+                ("a".to_string(), Value::Int(1)),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        assert_eq!(call("length", &list, &[]), Ok(Value::Int(2)));
+        assert_eq!(call("length", &map, &[]), Ok(Value::Int(2)));
+        assert_eq!(call("length", &s("héllo"), &[]), Ok(Value::Int(5)));
+        assert_eq!(
+            call("contains", &list, &[s("10.0.1.0/24")]),
+            Ok(Value::Bool(true))
+        );
+        assert_eq!(call("contains", &list, &[s("a")]), Ok(Value::Bool(false)));
+        assert_eq!(
+            call("keys", &map, &[]),
+            Ok(Value::List(vec![s("a"), s("b")]))
+        );
+    }
+
+    #[test]
+    fn invalid_calls() {
+        assert!(call("shout", &s("hi"), &[]).is_err());
+        assert!(call("upper", &Value::Int(1), &[]).is_err());
+        assert!(call("upper", &s("hi"), &[s("extra")]).is_err());
+        assert!(call("replace", &s("hi"), &[s("h")]).is_err());
+        assert!(call("length", &Value::Int(1), &[]).is_err());
+        assert!(call("contains", &s("hi"), &[s("h")]).is_err());
+        assert!(call("keys", &Value::List(vec![]), &[]).is_err());
+        assert!(
+            call(
+                "upper",
+                &Value::ResourceRef("vpc".to_string(), "id".to_string()),
+                &[]
+            )
+            .is_err()
+        );
+    }
+}
@@ -2,12 +2,15 @@
 //!
 //! Convert DSL to AST using pest
 
+pub(crate) mod functions;
+
 use pest::Parser;
 use pest_derive::Parser;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::env;
 
-use crate::resource::{Resource, ResourceId, Value};
+use crate::resource::{Operator, Resource, ResourceId, SourceLocation, Value};
 
 #[derive(Parser)]
 #[grammar = "parser/carina.pest"]
@@ -25,6 +28,9 @@ pub enum ParseError {
     #[error("Undefined variable: {0}")]
     UndefinedVariable(String),
 
+    #[error("Undefined references:\n  {}", .0.join("\n  "))]
+    UndefinedReferences(Vec<String>),
+
     #[error("Environment variable not set: {0}")]
     EnvVarNotSet(String),
 
@@ -39,7 +45,7 @@ pub enum ParseError {
 }
 
 /// Resource type path for typed references (e.g., aws.vpc, aws.security_group)
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ResourceTypePath {
     /// Provider name (e.g., "aws")
     pub provider: String,
@@ -76,7 +82,7 @@ impl std::fmt::Display for ResourceTypePath {
 }
 
 /// Type expression for input/output parameters
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TypeExpr {
     String,
     Bool,
@@ -112,7 +118,7 @@ pub struct InputParameter {
 }
 
 /// Output parameter definition
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OutputParameter {
     pub name: String,
     pub type_expr: TypeExpr,
@@ -135,14 +141,14 @@ pub struct ModuleCall {
 }
 
 /// Provider configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderConfig {
     pub name: String,
     pub attributes: HashMap<String, Value>,
 }
 
 /// Backend configuration for state storage
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackendConfig {
     /// Backend type (e.g., "s3", "gcs", "local")
     pub backend_type: String,
@@ -168,6 +174,15 @@ pub struct ParsedFile {
     pub backend: Option<BackendConfig>,
 }
 
+impl ParsedFile {
+    /// Record the file the resources were parsed from in their source locations
+    pub fn set_source_file(&mut self, file: &str) {
+        for resource in &mut self.resources {
+            resource.set_source_file(file);
+        }
+    }
+}
+
 /// Parse context (variable scope)
 struct ParseContext {
     variables: HashMap<String, Value>,
@@ -221,6 +236,8 @@ pub fn parse(input: &str) -> Result<ParsedFile, ParseError> {
     let mut inputs = Vec::new();
     let mut outputs = Vec::new();
     let mut backend = None;
+    // Bindings of `count`/`for_each` copies -> the binding they are copies of
+    let mut replica_bindings: HashMap<String, String> = HashMap::new();
 
     for pair in pairs {
         if pair.as_rule() == Rule::file {
@@ -255,12 +272,42 @@ pub fn parse(input: &str) -> Result<ParsedFile, ParseError> {
                                 outputs.extend(parsed_outputs);
                             }
                             Rule::let_binding => {
+                                let line = stmt.as_span().start_pos().line_col().0;
                                 let (name, value, maybe_resource, maybe_module_call) =
                                     parse_let_binding_extended(stmt, &ctx)?;
+                                if let Some(base) = replica_bindings.get(&name) {
+                                    return Err(ParseError::InvalidExpression {
+                                        line,
+                                        message: format!(
+                                            "Binding '{}' clashes with a copy of '{}'",
+                                            name, base
+                                        ),
+                                    });
+                                }
                                 ctx.set_variable(name.clone(), value);
                                 if let Some(resource) = maybe_resource {
-                                    ctx.set_resource_binding(name.clone(), resource.clone());
-                                    resources.push(resource);
+                                    for resource in expand_replicas(resource)? {
+                                        let binding = match resource.attributes.get("_binding") {
+                                            Some(Value::String(binding)) => binding.clone(),
+                                            _ => name.clone(),
+                                        };
+                                        if binding != name {
+                                            if ctx.get_variable(&binding).is_some()
+                                                || ctx.is_resource_binding(&binding)
+                                            {
+                                                return Err(ParseError::InvalidExpression {
+                                                    line,
+                                                    message: format!(
+                                                        "Copy '{}' of '{}' clashes with an existing binding",
+                                                        binding, name
+                                                    ),
+                                                });
+                                            }
+                                            replica_bindings.insert(binding.clone(), name.clone());
+                                        }
+                                        ctx.set_resource_binding(binding, resource.clone());
+                                        resources.push(resource);
+                                    }
                                 }
                                 if let Some(mut call) = maybe_module_call {
                                     call.binding_name = Some(name);
@@ -273,7 +320,7 @@ pub fn parse(input: &str) -> Result<ParsedFile, ParseError> {
                             }
                             Rule::anonymous_resource => {
                                 let resource = parse_anonymous_resource(stmt, &ctx)?;
-                                resources.push(resource);
+                                resources.extend(expand_replicas(resource)?);
                             }
                             _ => {}
                         }
@@ -283,7 +330,9 @@ pub fn parse(input: &str) -> Result<ParsedFile, ParseError> {
         }
     }
 
-    Ok(ParsedFile {
+    resolve_depends_on(&mut resources)?;
+    resolve_lifecycle(&mut resources)?;
+    let parsed = ParsedFile {
         providers,
         resources,
         variables: ctx.variables,
@@ -292,7 +341,328 @@ pub fn parse(input: &str) -> Result<ParsedFile, ParseError> {
         inputs,
         outputs,
         backend,
-    })
+    };
+    check_references(&parsed, ctx.in_module)?;
+
+    Ok(parsed)
+}
+
+/// Check that every resource reference names a resource binding, a module call
+/// binding or, inside a module, `input`, and that every splat names a binding
+/// declared with `count` or `for_each`. All unknown references are reported at once.
+fn check_references(parsed: &ParsedFile, in_module: bool) -> Result<(), ParseError> {
+    let mut known: HashSet<&str> = parsed
+        .resources
+        .iter()
+        .filter_map(|resource| match resource.attributes.get("_binding") {
+            Some(Value::String(binding)) => Some(binding.as_str()),
+            _ => None,
+        })
+        .collect();
+    known.extend(
+        parsed
+            .module_calls
+            .iter()
+            .filter_map(|call| call.binding_name.as_deref()),
+    );
+    if in_module {
+        known.insert("input");
+    }
+
+    // A binding with `count = 0` has no copies, but is still a binding of one
+    // whose splat is the empty list
+    let mut replicated: HashSet<&str> = parsed
+        .resources
+        .iter()
+        .filter_map(|resource| match resource.attributes.get("_replica_of") {
+            Some(Value::String(binding)) => Some(binding.as_str()),
+            _ => None,
+        })
+        .collect();
+    replicated.extend(
+        parsed
+            .variables
+            .iter()
+            .filter(|(name, value)| {
+                !known.contains(name.as_str()) && **value == Value::String(format!("${{{}}}", name))
+            })
+            .map(|(name, _)| name.as_str()),
+    );
+
+    let mut undefined = Vec::new();
+    let mut check = |owner: String, key: &str, value: &Value| {
+        let mut refs = Vec::new();
+        collect_references(value, &mut refs);
+        for (binding, attr) in refs {
+            if !known.contains(binding) {
+                undefined.push(format!(
+                    "{}: '{}' references undefined '{}.{}'",
+                    owner, key, binding, attr
+                ));
+            }
+        }
+        let mut splats = Vec::new();
+        collect_splat_refs(value, &mut splats);
+        for (binding, attr) in splats {
+            if !replicated.contains(binding) {
+                undefined.push(format!(
+                    "{}: '{}' references undefined '{}[*].{}' ({} is not declared with count or for_each)",
+                    owner, key, binding, attr, binding
+                ));
+            }
+        }
+    };
+
+    for resource in &parsed.resources {
+        let owner = format!("{}.{}", resource.id.resource_type, resource.id.name);
+        let mut keys: Vec<&String> = resource.attributes.keys().collect();
+        keys.sort();
+        for key in keys {
+            check(owner.clone(), key, &resource.attributes[key]);
+        }
+    }
+    for call in &parsed.module_calls {
+        let owner = format!("module {}", call.module_name);
+        let mut keys: Vec<&String> = call.arguments.keys().collect();
+        keys.sort();
+        for key in keys {
+            check(owner.clone(), key, &call.arguments[key]);
+        }
+    }
+    for output in &parsed.outputs {
+        if let Some(value) = &output.value {
+            check("output".to_string(), &output.name, value);
+        }
+    }
+
+    if undefined.is_empty() {
+        Ok(())
+    } else {
+        Err(ParseError::UndefinedReferences(undefined))
+    }
+}
+
+/// Replace each resource's `depends_on` list with a `_depends_on` list of the
+/// bindings it names, so that it orders the resource without being diffed or sent
+/// to providers. A binding declared with `count` or `for_each` stands for all of
+/// its replicas, and a splat `base[*].attr` depends on all replicas of `base`.
+fn resolve_depends_on(resources: &mut [Resource]) -> Result<(), ParseError> {
+    let bindings: Vec<(String, Option<String>)> = resources
+        .iter()
+        .filter_map(|resource| match resource.attributes.get("_binding") {
+            Some(Value::String(binding)) => {
+                let replica_of = match resource.attributes.get("_replica_of") {
+                    Some(Value::String(base)) => Some(base.clone()),
+                    _ => None,
+                };
+                Some((binding.clone(), replica_of))
+            }
+            _ => None,
+        })
+        .collect();
+    let replicas_of = |name: &str| -> Vec<&String> {
+        bindings
+            .iter()
+            .filter(|(_, replica_of)| replica_of.as_deref() == Some(name))
+            .map(|(binding, _)| binding)
+            .collect()
+    };
+
+    let mut undefined = Vec::new();
+    for resource in resources.iter_mut() {
+        let mut splats = Vec::new();
+        for value in resource.attributes.values() {
+            collect_splat_refs(value, &mut splats);
+        }
+        let splat_depends_on: Vec<Value> = splats
+            .iter()
+            .flat_map(|(base, _)| replicas_of(base))
+            .map(|binding| Value::String(binding.clone()))
+            .collect();
+
+        let Some(value) = resource.attributes.remove("depends_on") else {
+            if !splat_depends_on.is_empty() {
+                resource
+                    .attributes
+                    .insert("_depends_on".to_string(), Value::List(splat_depends_on));
+            }
+            continue;
+        };
+        let owner = format!("{}.{}", resource.id.resource_type, resource.id.name);
+        let invalid = |message: String| ParseError::InvalidExpression {
+            line: resource.location.as_ref().map_or(0, |l| l.line),
+            message,
+        };
+        let Value::List(items) = value else {
+            return Err(invalid(format!(
+                "depends_on of {} must be a list of resource bindings",
+                owner
+            )));
+        };
+
+        let mut depends_on = Vec::new();
+        for item in items {
+            // A binding declared earlier evaluates to its `${binding}` placeholder,
+            // one declared later is still an unresolved identifier
+            let name = match &item {
+                Value::String(s) if s.starts_with("${") && s.ends_with('}') => {
+                    s[2..s.len() - 1].to_string()
+                }
+                Value::UnresolvedIdent(name, None) => name.clone(),
+                Value::ResourceRef(binding, _) => binding.clone(),
+                other => {
+                    return Err(invalid(format!(
+                        "depends_on of {} must contain resource bindings, got {:?}",
+                        owner, other
+                    )));
+                }
+            };
+            let matched: Vec<&String> = match bindings.iter().find(|(b, _)| *b == name) {
+                Some((binding, _)) => vec![binding],
+                None => replicas_of(&name),
+            };
+            if matched.is_empty() {
+                undefined.push(format!(
+                    "{}: 'depends_on' references undefined '{}'",
+                    owner, name
+                ));
+            }
+            depends_on.extend(matched.into_iter().map(|b| Value::String(b.clone())));
+        }
+        depends_on.extend(splat_depends_on);
+        resource
+            .attributes
+            .insert("_depends_on".to_string(), Value::List(depends_on));
+    }
+
+    if undefined.is_empty() {
+        Ok(())
+    } else {
+        Err(ParseError::UndefinedReferences(undefined))
+    }
+}
+
+/// Replace each resource's `lifecycle { ... }` block with the internal attributes
+/// it sets, so that it is neither diffed nor sent to providers:
+/// - `ignore_changes = [description, tags.Name]` becomes `_ignore_changes`, a list
+///   of attribute paths
+/// - `prevent_destroy = true` becomes `_prevent_destroy`
+fn resolve_lifecycle(resources: &mut [Resource]) -> Result<(), ParseError> {
+    for resource in resources.iter_mut() {
+        let Some(value) = resource.attributes.remove("lifecycle") else {
+            continue;
+        };
+        let owner = format!("{}.{}", resource.id.resource_type, resource.id.name);
+        let line = resource
+            .attribute_locations
+            .get("lifecycle")
+            .or(resource.location.as_ref())
+            .map_or(0, |l| l.line);
+        let invalid = |message: String| ParseError::InvalidExpression { line, message };
+
+        let block = match value {
+            Value::Map(block) => block,
+            Value::List(mut blocks) if blocks.len() == 1 => match blocks.pop() {
+                Some(Value::Map(block)) => block,
+                _ => return Err(invalid(format!("lifecycle of {} must be a block", owner))),
+            },
+            _ => {
+                return Err(invalid(format!(
+                    "{} must have at most one lifecycle block",
+                    owner
+                )));
+            }
+        };
+
+        for (key, value) in block {
+            match key.as_str() {
+                "ignore_changes" => {
+                    let Value::List(items) = value else {
+                        return Err(invalid(format!(
+                            "ignore_changes of {} must be a list of attributes",
+                            owner
+                        )));
+                    };
+                    let paths = items
+                        .into_iter()
+                        .map(|item| match item {
+                            Value::UnresolvedIdent(name, None) => Ok(name),
+                            Value::UnresolvedIdent(name, Some(key))
+                            | Value::ResourceRef(name, key) => Ok(format!("{}.{}", name, key)),
+                            Value::String(path) => Ok(path),
+                            other => Err(invalid(format!(
+                                "ignore_changes of {} must contain attribute names, got {:?}",
+                                owner, other
+                            ))),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    resource.attributes.insert(
+                        "_ignore_changes".to_string(),
+                        Value::List(paths.into_iter().map(Value::String).collect()),
+                    );
+                }
+                "prevent_destroy" => {
+                    let Value::Bool(prevent) = value else {
+                        return Err(invalid(format!(
+                            "prevent_destroy of {} must be true or false",
+                            owner
+                        )));
+                    };
+                    resource
+                        .attributes
+                        .insert("_prevent_destroy".to_string(), Value::Bool(prevent));
+                }
+                other => {
+                    return Err(invalid(format!(
+                        "Unknown lifecycle option '{}' in {}",
+                        other, owner
+                    )));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Collect the `(binding, attribute)` of every reference within a value
+fn collect_references<'a>(value: &'a Value, refs: &mut Vec<(&'a str, &'a str)>) {
+    match value {
+        Value::ResourceRef(binding, attr) => refs.push((binding, attr)),
+        Value::TypedResourceRef {
+            binding_name,
+            attribute_name,
+            ..
+        } => refs.push((binding_name, attribute_name)),
+        Value::List(items) | Value::Interpolation(items) | Value::Operation(_, items) => {
+            for item in items {
+                collect_references(item, refs);
+            }
+        }
+        Value::Map(map) => {
+            for item in map.values() {
+                collect_references(item, refs);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collect the `(binding, attribute)` of every splat within a value
+fn collect_splat_refs<'a>(value: &'a Value, refs: &mut Vec<(&'a str, &'a str)>) {
+    match value {
+        Value::SplatRef(binding, attr) => refs.push((binding, attr)),
+        Value::List(items) | Value::Interpolation(items) | Value::Operation(_, items) => {
+            for item in items {
+                collect_splat_refs(item, refs);
+            }
+        }
+        Value::Map(map) => {
+            for item in map.values() {
+                collect_splat_refs(item, refs);
+            }
+        }
+        _ => {}
+    }
 }
 
 /// Parse input block
@@ -444,8 +814,25 @@ fn parse_expression_with_resource_or_module(
     ctx: &ParseContext,
     binding_name: &str,
 ) -> Result<(Value, Option<Resource>, Option<ModuleCall>), ParseError> {
-    let inner = pair.into_inner().next().unwrap();
-    parse_pipe_expr_with_resource_or_module(inner, ctx, binding_name)
+    // Resources and module calls can only appear as a bare pipe expression,
+    // anything with operators is an ordinary value
+    match bare_pipe_expr(pair.clone()) {
+        Some(pipe_expr) => parse_pipe_expr_with_resource_or_module(pipe_expr, ctx, binding_name),
+        None => Ok((parse_expression(pair, ctx)?, None, None)),
+    }
+}
+
+/// The pipe expression an expression consists of, if it uses no operators
+fn bare_pipe_expr(pair: pest::iterators::Pair<Rule>) -> Option<pest::iterators::Pair<Rule>> {
+    let mut pair = pair;
+    while pair.as_rule() != Rule::pipe_expr {
+        let mut inner = pair.into_inner();
+        pair = inner.next()?;
+        if inner.next().is_some() {
+            return None;
+        }
+    }
+    Some(pair)
 }
 
 fn parse_pipe_expr_with_resource_or_module(
@@ -458,10 +845,16 @@ fn parse_pipe_expr_with_resource_or_module(
     let (value, maybe_resource, maybe_module_call) =
         parse_primary_with_resource_or_module(primary, ctx, binding_name)?;
 
-    // Pipe operator handling (for future extension)
-    for _func_call in inner {
-        // TODO: Implement pipe operator
+    let mut stages = inner.peekable();
+    if let Some(func_call) = stages.peek()
+        && (maybe_resource.is_some() || maybe_module_call.is_some())
+    {
+        return Err(ParseError::InvalidExpression {
+            line: func_call.as_span().start_pos().line_col().0,
+            message: "Pipe functions cannot be applied to a resource or module call".to_string(),
+        });
     }
+    let value = apply_pipe_stages(value, stages, ctx)?;
 
     Ok((value, maybe_resource, maybe_module_call))
 }
@@ -540,6 +933,7 @@ fn parse_anonymous_resource(
     pair: pest::iterators::Pair<Rule>,
     ctx: &ParseContext,
 ) -> Result<Resource, ParseError> {
+    let location = location_of(&pair);
     let mut inner = pair.into_inner();
 
     let namespaced_type = inner.next().unwrap().as_str().to_string();
@@ -553,7 +947,7 @@ fn parse_anonymous_resource(
     let provider = parts[0];
     let resource_type = parts[1..].join(".");
 
-    let attributes = parse_block_contents(inner, ctx)?;
+    let (attributes, attribute_locations) = parse_block_contents(inner, ctx)?;
 
     // Get resource name from name attribute
     // Anonymous resources (not bound with let) require a name attribute for identification
@@ -566,7 +960,7 @@ fn parse_anonymous_resource(
         }
         _ => {
             return Err(ParseError::InvalidExpression {
-                line: 0,
+                line: location.line,
                 message: "Anonymous resource must have a 'name' attribute for identification. Use 'let' binding instead if you don't want to specify a name.".to_string(),
             });
         }
@@ -581,16 +975,29 @@ fn parse_anonymous_resource(
         id: ResourceId::new(resource_type, resource_name),
         attributes,
         read_only: false,
+        location: Some(location),
+        attribute_locations,
     })
 }
 
+/// Attributes of a block and where each of them is declared
+type BlockContents = (HashMap<String, Value>, HashMap<String, SourceLocation>);
+
+/// Position of the start of a pair in the source
+fn location_of(pair: &pest::iterators::Pair<Rule>) -> SourceLocation {
+    let (line, column) = pair.as_span().start_pos().line_col();
+    SourceLocation::new(line, column)
+}
+
 /// Parse block contents (attributes and nested blocks)
 /// Nested blocks with the same name are collected into a list
+/// Also returns where each attribute (or first nested block of a name) starts
 fn parse_block_contents(
     pairs: pest::iterators::Pairs<Rule>,
     ctx: &ParseContext,
-) -> Result<HashMap<String, Value>, ParseError> {
+) -> Result<BlockContents, ParseError> {
     let mut attributes: HashMap<String, Value> = HashMap::new();
+    let mut locations: HashMap<String, SourceLocation> = HashMap::new();
     let mut nested_blocks: HashMap<String, Vec<Value>> = HashMap::new();
 
     for content_pair in pairs {
@@ -600,16 +1007,22 @@ fn parse_block_contents(
                 match inner.as_rule() {
                     Rule::attribute => {
                         let mut attr_inner = inner.into_inner();
-                        let key = attr_inner.next().unwrap().as_str().to_string();
+                        let key_pair = attr_inner.next().unwrap();
+                        let key = key_pair.as_str().to_string();
                         let value = parse_expression(attr_inner.next().unwrap(), ctx)?;
+                        locations.insert(key.clone(), location_of(&key_pair));
                         attributes.insert(key, value);
                     }
                     Rule::nested_block => {
                         let mut block_inner = inner.into_inner();
-                        let block_name = block_inner.next().unwrap().as_str().to_string();
+                        let name_pair = block_inner.next().unwrap();
+                        let block_name = name_pair.as_str().to_string();
+                        locations
+                            .entry(block_name.clone())
+                            .or_insert_with(|| location_of(&name_pair));
 
                         // Parse nested block attributes into a map
-                        let mut block_attrs = HashMap::new();
+                        let mut block_attrs = BTreeMap::new();
                         for attr_pair in block_inner {
                             if attr_pair.as_rule() == Rule::attribute {
                                 let mut attr_inner = attr_pair.into_inner();
@@ -630,8 +1043,10 @@ fn parse_block_contents(
             }
             Rule::attribute => {
                 let mut attr_inner = content_pair.into_inner();
-                let key = attr_inner.next().unwrap().as_str().to_string();
+                let key_pair = attr_inner.next().unwrap();
+                let key = key_pair.as_str().to_string();
                 let value = parse_expression(attr_inner.next().unwrap(), ctx)?;
+                locations.insert(key.clone(), location_of(&key_pair));
                 attributes.insert(key, value);
             }
             _ => {}
@@ -643,7 +1058,7 @@ fn parse_block_contents(
         attributes.insert(name, Value::List(blocks));
     }
 
-    Ok(attributes)
+    Ok((attributes, locations))
 }
 
 fn parse_resource_expr(
@@ -651,6 +1066,7 @@ fn parse_resource_expr(
     ctx: &ParseContext,
     binding_name: &str,
 ) -> Result<Resource, ParseError> {
+    let location = location_of(&pair);
     let mut inner = pair.into_inner();
 
     let namespaced_type = inner.next().unwrap().as_str().to_string();
@@ -665,7 +1081,7 @@ fn parse_resource_expr(
     let provider = parts[0];
     let resource_type = parts[1..].join(".");
 
-    let mut attributes = parse_block_contents(inner, ctx)?;
+    let (mut attributes, attribute_locations) = parse_block_contents(inner, ctx)?;
 
     // Get resource name from name attribute (same as anonymous resources)
     // In module context, name can be input.param which is a ResourceRef
@@ -696,17 +1112,24 @@ fn parse_resource_expr(
         id: ResourceId::new(resource_type, resource_name),
         attributes,
         read_only: false,
+        location: Some(location),
+        attribute_locations,
     })
 }
 
 /// Parse a read resource expression (data source): read aws.s3.bucket { ... }
+/// The `data` keyword is accepted as an alias for `read`.
 fn parse_read_resource_expr(
     pair: pest::iterators::Pair<Rule>,
     ctx: &ParseContext,
     binding_name: &str,
 ) -> Result<Resource, ParseError> {
+    let location = location_of(&pair);
     let mut inner = pair.into_inner();
 
+    // Skip the read/data keyword
+    inner.next();
+
     let namespaced_type = inner.next().unwrap().as_str().to_string();
 
     // Extract resource type from namespace (aws.s3.bucket -> s3.bucket)
@@ -719,7 +1142,7 @@ fn parse_read_resource_expr(
     let provider = parts[0];
     let resource_type = parts[1..].join(".");
 
-    let mut attributes = parse_block_contents(inner, ctx)?;
+    let (mut attributes, attribute_locations) = parse_block_contents(inner, ctx)?;
 
     // Get resource name from name attribute (required for data sources)
     // In module context, name can be input.param which is a ResourceRef
@@ -731,7 +1154,7 @@ fn parse_read_resource_expr(
         }
         _ => {
             return Err(ParseError::InvalidExpression {
-                line: 0,
+                line: location.line,
                 message: format!(
                     "Data source '{}' must have a 'name' attribute to identify the existing resource",
                     binding_name
@@ -755,6 +1178,220 @@ fn parse_read_resource_expr(
         id: ResourceId::new(resource_type, resource_name),
         attributes,
         read_only: true,
+        location: Some(location),
+        attribute_locations,
+    })
+}
+
+/// The binding of the copy of `binding` with the given index or key
+fn replica_binding(binding: &str, suffix: &str) -> String {
+    let suffix: String = suffix
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("{}_{}", binding, suffix)
+}
+
+/// Expand a resource declared with `count = N` or `for_each = {...}` into one
+/// resource per copy. `count.index` is replaced by each copy's index; `each.key`
+/// and `each.value` by each entry of the map (or each string of a list). Copies
+/// get the binding name suffixed with `_<index or key>`, and the name suffixed
+/// with `-<index or key>` unless it already refers to the index or key.
+fn expand_replicas(resource: Resource) -> Result<Vec<Resource>, ParseError> {
+    let line = resource.location.as_ref().map_or(0, |l| l.line);
+    let invalid = |message: String| ParseError::InvalidExpression { line, message };
+    let address = format!("{}.{}", resource.id.resource_type, resource.id.name);
+
+    let replicas: Vec<(String, ReplicaValues)> = match (
+        resource.attributes.get("count"),
+        resource.attributes.get("for_each"),
+    ) {
+        (None, None) => return Ok(vec![resource]),
+        (Some(_), Some(_)) => {
+            return Err(invalid(format!(
+                "{} cannot use both count and for_each",
+                address
+            )));
+        }
+        (Some(Value::Int(count)), None) if *count >= 0 => (0..*count)
+            .map(|index| (index.to_string(), ReplicaValues::Count(index)))
+            .collect(),
+        (Some(other), None) => {
+            return Err(invalid(format!(
+                "count of {} must be a non-negative integer, got {:?}",
+                address, other
+            )));
+        }
+        (None, Some(Value::Map(entries))) => entries
+            .iter()
+            .map(|(key, value)| (key.clone(), ReplicaValues::Each(key.clone(), value.clone())))
+            .collect(),
+        (None, Some(Value::List(items))) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(key) => {
+                    Ok((key.clone(), ReplicaValues::Each(key.clone(), item.clone())))
+                }
+                other => Err(invalid(format!(
+                    "for_each list of {} must contain only strings, got {:?}",
+                    address, other
+                ))),
+            })
+            .collect::<Result<_, _>>()?,
+        (None, Some(other)) => {
+            return Err(invalid(format!(
+                "for_each of {} must be a map or a list of strings, got {:?}",
+                address, other
+            )));
+        }
+    };
+
+    // Each copy needs its own binding, so keys must stay distinct once sanitized
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    for (key, _) in &replicas {
+        if let Some(previous) = seen.insert(replica_binding("", key), key) {
+            return Err(invalid(if previous == key {
+                format!("for_each of {} contains '{}' more than once", address, key)
+            } else {
+                format!(
+                    "for_each keys '{}' and '{}' of {} produce the same binding",
+                    previous, key, address
+                )
+            }));
+        }
+    }
+
+    let name_uses_replica = resource
+        .attributes
+        .get("name")
+        .is_some_and(uses_replica_ref);
+
+    let expanded = replicas
+        .into_iter()
+        .map(|(suffix, values)| {
+            let mut attributes: HashMap<String, Value> = resource
+                .attributes
+                .iter()
+                .filter(|(key, _)| key.as_str() != "count" && key.as_str() != "for_each")
+                .map(|(key, value)| {
+                    substitute_replica_refs(value, &values)
+                        .map(|value| (key.clone(), value))
+                        .map_err(|e| invalid(format!("{}: {}", key, e)))
+                })
+                .collect::<Result<_, ParseError>>()?;
+
+            // Bindings must stay identifiers, whatever characters the key has. Each
+            // copy remembers its base binding and key so `base[*].attr` can collect
+            // the copies in order.
+            if let Some(Value::String(binding)) = attributes.get("_binding").cloned() {
+                attributes.insert(
+                    "_binding".to_string(),
+                    Value::String(replica_binding(&binding, &suffix)),
+                );
+                attributes.insert("_replica_of".to_string(), Value::String(binding));
+                let key = match &values {
+                    ReplicaValues::Count(index) => Value::Int(*index),
+                    ReplicaValues::Each(key, _) => Value::String(key.clone()),
+                };
+                attributes.insert("_replica_key".to_string(), key);
+            }
+
+            let resource_name = match attributes.get_mut("name") {
+                Some(Value::String(name)) => {
+                    if !name_uses_replica {
+                        *name = format!("{}-{}", name, suffix);
+                    }
+                    name.clone()
+                }
+                // Unnamed resources are named after their binding, which now carries the suffix
+                _ => match attributes.get("_binding") {
+                    Some(Value::String(binding)) => binding.clone(),
+                    _ => format!("{}-{}", resource.id.name, suffix),
+                },
+            };
+
+            Ok(Resource {
+                id: ResourceId::new(resource.id.resource_type.clone(), resource_name),
+                attributes,
+                read_only: resource.read_only,
+                location: resource.location.clone(),
+                attribute_locations: resource.attribute_locations.clone(),
+            })
+        })
+        .collect::<Result<Vec<_>, ParseError>>()?;
+
+    Ok(expanded)
+}
+
+/// Values of `count.index`, or of `each.key` and `each.value`, for one copy
+enum ReplicaValues {
+    Count(i64),
+    Each(String, Value),
+}
+
+impl ReplicaValues {
+    fn get(&self, name: &str, member: &str) -> Option<Value> {
+        match (self, name, member) {
+            (ReplicaValues::Count(index), "count", "index") => Some(Value::Int(*index)),
+            (ReplicaValues::Each(key, _), "each", "key") => Some(Value::String(key.clone())),
+            (ReplicaValues::Each(_, value), "each", "value") => Some(value.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// The `count.index`, `each.key` or `each.value` reference a value is, if any
+fn replica_ref(value: &Value) -> Option<(&str, &str)> {
+    let (name, member) = match value {
+        Value::ResourceRef(name, member) => (name, member),
+        Value::UnresolvedIdent(name, Some(member)) => (name, member),
+        _ => return None,
+    };
+    matches!(
+        (name.as_str(), member.as_str()),
+        ("count", "index") | ("each", "key") | ("each", "value")
+    )
+    .then_some((name.as_str(), member.as_str()))
+}
+
+/// Whether a value refers to `count.index`, `each.key` or `each.value`, also
+/// through operators and function calls
+fn uses_replica_ref(value: &Value) -> bool {
+    match value {
+        Value::List(items) | Value::Interpolation(items) | Value::Operation(_, items) => {
+            items.iter().any(uses_replica_ref)
+        }
+        Value::Map(map) => map.values().any(uses_replica_ref),
+        other => replica_ref(other).is_some(),
+    }
+}
+
+/// Replace every `count.index`, `each.key` and `each.value` in a value with
+/// this copy's values. Fails if an operation on them has operands of the wrong type.
+fn substitute_replica_refs(value: &Value, values: &ReplicaValues) -> Result<Value, String> {
+    if let Some((name, member)) = replica_ref(value)
+        && let Some(substituted) = values.get(name, member)
+    {
+        return Ok(substituted);
+    }
+    let substitute_all = |items: &[Value]| -> Result<Vec<Value>, String> {
+        items
+            .iter()
+            .map(|item| substitute_replica_refs(item, values))
+            .collect()
+    };
+    Ok(match value {
+        Value::List(items) => Value::List(substitute_all(items)?),
+        Value::Map(map) => Value::Map(
+            map.iter()
+                .map(|(k, v)| Ok((k.clone(), substitute_replica_refs(v, values)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        Value::Interpolation(parts) => Value::interpolate(substitute_all(parts)?),
+        Value::Operation(operator, operands) => {
+            Value::operate(*operator, substitute_all(operands)?)?
+        }
+        other => other.clone(),
     })
 }
 
@@ -763,7 +1400,92 @@ fn parse_expression(
     ctx: &ParseContext,
 ) -> Result<Value, ParseError> {
     let inner = pair.into_inner().next().unwrap();
-    parse_pipe_expr(inner, ctx)
+    parse_conditional(inner, ctx)
+}
+
+fn parse_conditional(
+    pair: pest::iterators::Pair<Rule>,
+    ctx: &ParseContext,
+) -> Result<Value, ParseError> {
+    let line = pair.as_span().start_pos().line_col().0;
+    let mut inner = pair.into_inner();
+    let condition = parse_comparison(inner.next().unwrap(), ctx)?;
+    let Some(then) = inner.next() else {
+        return Ok(condition);
+    };
+    let then = parse_expression(then, ctx)?;
+    let otherwise = parse_expression(inner.next().unwrap(), ctx)?;
+
+    Value::operate(Operator::Conditional, vec![condition, then, otherwise])
+        .map_err(|message| ParseError::InvalidExpression { line, message })
+}
+
+fn parse_comparison(
+    pair: pest::iterators::Pair<Rule>,
+    ctx: &ParseContext,
+) -> Result<Value, ParseError> {
+    let line = pair.as_span().start_pos().line_col().0;
+    let mut inner = pair.into_inner();
+    let left = parse_binary_chain(inner.next().unwrap(), ctx)?;
+    let Some(op) = inner.next() else {
+        return Ok(left);
+    };
+    let operator = match op.as_str() {
+        "==" => Operator::Equal,
+        _ => Operator::NotEqual,
+    };
+    let right = parse_binary_chain(inner.next().unwrap(), ctx)?;
+
+    Value::operate(operator, vec![left, right])
+        .map_err(|message| ParseError::InvalidExpression { line, message })
+}
+
+/// Parse a `sum` or `product`: operands joined by operators of one precedence
+/// level, folded from the left
+fn parse_binary_chain(
+    pair: pest::iterators::Pair<Rule>,
+    ctx: &ParseContext,
+) -> Result<Value, ParseError> {
+    let parse_operand = |operand: pest::iterators::Pair<Rule>| match operand.as_rule() {
+        Rule::unary => parse_unary(operand, ctx),
+        _ => parse_binary_chain(operand, ctx),
+    };
+
+    let mut inner = pair.into_inner();
+    let mut value = parse_operand(inner.next().unwrap())?;
+    while let Some(op) = inner.next() {
+        let line = op.as_span().start_pos().line_col().0;
+        let operator = match op.as_str() {
+            "+" => Operator::Add,
+            "-" => Operator::Subtract,
+            "*" => Operator::Multiply,
+            _ => Operator::Divide,
+        };
+        let right = parse_operand(inner.next().unwrap())?;
+        value = Value::operate(operator, vec![value, right])
+            .map_err(|message| ParseError::InvalidExpression { line, message })?;
+    }
+
+    Ok(value)
+}
+
+fn parse_unary(pair: pest::iterators::Pair<Rule>, ctx: &ParseContext) -> Result<Value, ParseError> {
+    let line = pair.as_span().start_pos().line_col().0;
+    let mut inner = pair.into_inner();
+    let mut negations = 0;
+    let mut value = loop {
+        let next = inner.next().unwrap();
+        match next.as_rule() {
+            Rule::not_op => negations += 1,
+            _ => break parse_pipe_expr(next, ctx)?,
+        }
+    };
+    for _ in 0..negations {
+        value = Value::operate(Operator::Not, vec![value])
+            .map_err(|message| ParseError::InvalidExpression { line, message })?;
+    }
+
+    Ok(value)
 }
 
 fn parse_pipe_expr(
@@ -774,9 +1496,26 @@ fn parse_pipe_expr(
     let primary = inner.next().unwrap();
     let value = parse_primary_value(primary, ctx)?;
 
-    // Pipe operator handling (for future extension)
-    for _func_call in inner {
-        // TODO: Implement pipe operator
+    apply_pipe_stages(value, inner, ctx)
+}
+
+/// Thread a value through each `|> func(args)` stage, left to right
+fn apply_pipe_stages<'a>(
+    mut value: Value,
+    stages: impl Iterator<Item = pest::iterators::Pair<'a, Rule>>,
+    ctx: &ParseContext,
+) -> Result<Value, ParseError> {
+    for func_call in stages {
+        let line = func_call.as_span().start_pos().line_col().0;
+        let mut inner = func_call.into_inner();
+        let name = inner.next().unwrap().as_str();
+        let args = inner
+            .map(|arg| parse_expression(arg, ctx))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let operands = [vec![Value::String(name.to_string()), value], args].concat();
+        value = Value::operate(Operator::Call, operands)
+            .map_err(|message| ParseError::InvalidExpression { line, message })?;
     }
 
     Ok(value)
@@ -805,7 +1544,7 @@ fn parse_primary_value(
         Rule::resource_expr => {
             // Resource expressions cannot be used as attribute values (only valid in top-level let bindings)
             Err(ParseError::InvalidExpression {
-                line: 0,
+                line: location_of(&inner).line,
                 message: "Resource expressions can only be used in let bindings".to_string(),
             })
         }
@@ -817,7 +1556,7 @@ fn parse_primary_value(
             Ok(Value::List(items?))
         }
         Rule::map => {
-            let mut map = HashMap::new();
+            let mut map = BTreeMap::new();
             for entry in inner.into_inner() {
                 if entry.as_rule() == Rule::map_entry {
                     let mut entry_inner = entry.into_inner();
@@ -848,7 +1587,7 @@ fn parse_primary_value(
                 {
                     // Variable exists but trying to access attribute on non-resource
                     Err(ParseError::InvalidExpression {
-                        line: 0,
+                        line: location_of(&inner).line,
                         message: format!(
                             "'{}' is not a resource, cannot access attribute '{}'",
                             parts[0], parts[1]
@@ -877,13 +1616,62 @@ fn parse_primary_value(
             let b = inner.as_str() == "true";
             Ok(Value::Bool(b))
         }
+        Rule::null => Ok(Value::Null),
         Rule::number => {
-            let n: i64 = inner.as_str().parse().unwrap();
-            Ok(Value::Int(n))
+            let s = inner.as_str();
+            let line = inner.as_span().start_pos().line_col().0;
+            let invalid = |e: &dyn std::fmt::Display| ParseError::InvalidExpression {
+                line,
+                message: format!("Invalid number '{}': {}", s, e),
+            };
+            if s.contains('.') {
+                let f: f64 = s.parse().map_err(|e| invalid(&e))?;
+                Ok(Value::Float(f))
+            } else {
+                let n: i64 = s.parse().map_err(|e| invalid(&e))?;
+                Ok(Value::Int(n))
+            }
         }
-        Rule::string => {
-            let s = parse_string(inner);
-            Ok(Value::String(s))
+        Rule::string => parse_interpolated_string(inner, ctx),
+        Rule::call_expr => {
+            let line = inner.as_span().start_pos().line_col().0;
+            let mut parts = inner.into_inner();
+            let name = parts.next().unwrap().as_str().to_string();
+            let mut operands = vec![Value::String(name)];
+            for arg in parts {
+                operands.push(parse_expression(arg, ctx)?);
+            }
+            Value::operate(Operator::Call, operands)
+                .map_err(|message| ParseError::InvalidExpression { line, message })
+        }
+        Rule::indexed_ref => {
+            // binding[*].attr, binding[0].attr or binding["key"].attr
+            let line = inner.as_span().start_pos().line_col().0;
+            let mut parts = inner.into_inner();
+            let binding = parts.next().unwrap().as_str();
+            let index = parts.next().unwrap();
+            let attr_name = parts.next().unwrap().as_str().to_string();
+            match index.as_rule() {
+                Rule::splat => Ok(Value::SplatRef(binding.to_string(), attr_name)),
+                Rule::number => match index.as_str().parse::<u64>() {
+                    Ok(_) => Ok(Value::ResourceRef(
+                        replica_binding(binding, index.as_str()),
+                        attr_name,
+                    )),
+                    Err(_) => Err(ParseError::InvalidExpression {
+                        line,
+                        message: format!(
+                            "index of {} must be a non-negative integer, got {}",
+                            binding,
+                            index.as_str()
+                        ),
+                    }),
+                },
+                _ => Ok(Value::ResourceRef(
+                    replica_binding(binding, &parse_string(index)),
+                    attr_name,
+                )),
+            }
         }
         Rule::variable_ref => {
             // variable_ref can be "identifier" or "identifier.identifier" (member access)
@@ -934,9 +1722,119 @@ fn parse_string(pair: pest::iterators::Pair<Rule>) -> String {
         .replace("\\r", "\r")
         .replace("\\t", "\t")
         .replace("\\\"", "\"")
+        .replace("\\$", "$")
         .replace("\\\\", "\\")
 }
 
+/// Parse a string literal, resolving `${...}` segments against the parse context.
+/// Variables are substituted immediately; resource references are kept and the
+/// string becomes a `Value::Interpolation` (or a plain `ResourceRef` for `"${vpc.id}"`).
+fn parse_interpolated_string(
+    pair: pest::iterators::Pair<Rule>,
+    ctx: &ParseContext,
+) -> Result<Value, ParseError> {
+    let line = pair.as_span().start_pos().line_col().0;
+    let s = pair.as_str();
+    let inner = &s[1..s.len() - 1];
+
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => literal.push('\n'),
+                Some('r') => literal.push('\r'),
+                Some('t') => literal.push('\t'),
+                Some(other) => literal.push(other),
+                None => literal.push('\\'),
+            },
+            '$' if chars.peek() == Some(&'{') => {
+                chars.next();
+                let mut expr = String::new();
+                let mut terminated = false;
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        terminated = true;
+                        break;
+                    }
+                    expr.push(c);
+                }
+                if !terminated {
+                    return Err(ParseError::InvalidExpression {
+                        line,
+                        message: "Unterminated '${' in string".to_string(),
+                    });
+                }
+
+                if !literal.is_empty() {
+                    parts.push(Value::String(std::mem::take(&mut literal)));
+                }
+                parts.push(parse_interpolation(expr.trim(), ctx, line)?);
+            }
+            _ => literal.push(c),
+        }
+    }
+    if !literal.is_empty() || parts.is_empty() {
+        parts.push(Value::String(literal));
+    }
+
+    // A string that is exactly one reference is just that reference
+    if let [Value::ResourceRef(..)] = parts.as_slice() {
+        return Ok(parts.pop().unwrap());
+    }
+    Ok(Value::interpolate(parts))
+}
+
+/// Resolve the expression inside `${...}`: a variable or a `binding.attribute` reference
+fn parse_interpolation(expr: &str, ctx: &ParseContext, line: usize) -> Result<Value, ParseError> {
+    let invalid = |message: String| ParseError::InvalidExpression { line, message };
+    let is_identifier = |s: &str| {
+        s.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+            && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+
+    let parts: Vec<&str> = expr.split('.').collect();
+    if !parts.iter().all(|p| is_identifier(p)) {
+        return Err(invalid(format!("Invalid interpolation '${{{}}}'", expr)));
+    }
+
+    match parts.as_slice() {
+        [name] => {
+            if ctx.is_resource_binding(name) {
+                return Err(invalid(format!(
+                    "'{}' is a resource; interpolate one of its attributes, e.g. '${{{}.id}}'",
+                    name, name
+                )));
+            }
+            match ctx.get_variable(name) {
+                Some(
+                    value @ (Value::String(_) | Value::Int(_) | Value::Float(_) | Value::Bool(_)),
+                ) => Ok(value.clone()),
+                Some(_) => Err(invalid(format!(
+                    "Variable '{}' cannot be interpolated into a string",
+                    name
+                ))),
+                None => Err(invalid(format!(
+                    "Undefined variable '{}' in interpolation",
+                    name
+                ))),
+            }
+        }
+        [binding, attr] => {
+            if ctx.get_variable(binding).is_some() && !ctx.is_resource_binding(binding) {
+                return Err(invalid(format!(
+                    "'{}' is not a resource, cannot access attribute '{}'",
+                    binding, attr
+                )));
+            }
+            // Validated and resolved later, like other resource references
+            Ok(Value::ResourceRef(binding.to_string(), attr.to_string()))
+        }
+        _ => Err(invalid(format!("Invalid interpolation '${{{}}}'", expr))),
+    }
+}
+
 /// Resolve resource references in a ParsedFile
 /// This replaces ResourceRef values with the actual attribute values from referenced resources
 pub fn resolve_resource_refs(parsed: &mut ParsedFile) -> Result<(), ParseError> {
@@ -953,7 +1851,8 @@ pub fn resolve_resource_refs(parsed: &mut ParsedFile) -> Result<(), ParseError>
         let mut resolved_attrs: HashMap<String, Value> = HashMap::new();
 
         for (key, value) in &resource.attributes {
-            let resolved = resolve_value(value, &binding_map)?;
+            let line = resource.location_of(key).map_or(0, |l| l.line);
+            let resolved = resolve_value(value, &binding_map, line)?;
             resolved_attrs.insert(key.clone(), resolved);
         }
 
@@ -966,6 +1865,7 @@ pub fn resolve_resource_refs(parsed: &mut ParsedFile) -> Result<(), ParseError>
 fn resolve_value(
     value: &Value,
     binding_map: &HashMap<String, HashMap<String, Value>>,
+    line: usize,
 ) -> Result<Value, ParseError> {
     match value {
         Value::ResourceRef(binding_name, attr_name) => {
@@ -974,7 +1874,7 @@ fn resolve_value(
                     match attributes.get(attr_name) {
                         Some(attr_value) => {
                             // Recursively resolve in case the attribute itself is a reference
-                            resolve_value(attr_value, binding_map)
+                            resolve_value(attr_value, binding_map, line)
                         }
                         None => {
                             // Attribute not found, keep as reference (might be resolved at runtime)
@@ -996,7 +1896,7 @@ fn resolve_value(
             Some(attributes) => match attributes.get(attribute_name) {
                 Some(attr_value) => {
                     // Recursively resolve in case the attribute itself is a reference
-                    resolve_value(attr_value, binding_map)
+                    resolve_value(attr_value, binding_map, line)
                 }
                 None => {
                     // Attribute not found, keep as reference (might be resolved at runtime)
@@ -1011,23 +1911,56 @@ fn resolve_value(
         Value::List(items) => {
             let resolved: Result<Vec<Value>, ParseError> = items
                 .iter()
-                .map(|item| resolve_value(item, binding_map))
+                .map(|item| resolve_value(item, binding_map, line))
                 .collect();
             Ok(Value::List(resolved?))
         }
         Value::Map(map) => {
-            let mut resolved = HashMap::new();
+            let mut resolved = BTreeMap::new();
             for (k, v) in map {
-                resolved.insert(k.clone(), resolve_value(v, binding_map)?);
+                resolved.insert(k.clone(), resolve_value(v, binding_map, line)?);
             }
             Ok(Value::Map(resolved))
         }
+        Value::Interpolation(parts) => {
+            let resolved: Result<Vec<Value>, ParseError> = parts
+                .iter()
+                .map(|part| resolve_value(part, binding_map, line))
+                .collect();
+            Ok(Value::interpolate(resolved?))
+        }
+        Value::Operation(operator, operands) => {
+            let resolved = operands
+                .iter()
+                .map(|operand| resolve_value(operand, binding_map, line))
+                .collect::<Result<Vec<_>, _>>()?;
+            Value::operate(*operator, resolved)
+                .map_err(|message| ParseError::InvalidExpression { line, message })
+        }
         // UnresolvedIdent is kept as-is for later resolution during schema validation
         Value::UnresolvedIdent(_, _) => Ok(value.clone()),
         _ => Ok(value.clone()),
     }
 }
 
+/// Parse a single expression in the scope of a parsed file, so that its `let`
+/// variables are visible and `vpc.id` is a reference to the `vpc` resource
+pub fn parse_expression_in(input: &str, parsed: &ParsedFile) -> Result<Value, ParseError> {
+    let mut ctx = ParseContext::new();
+    ctx.variables = parsed.variables.clone();
+    for resource in &parsed.resources {
+        if let Some(Value::String(binding)) = resource.attributes.get("_binding") {
+            ctx.set_resource_binding(binding.clone(), resource.clone());
+        }
+    }
+
+    let expression = CarinaParser::parse(Rule::standalone_expression, input)?
+        .next()
+        .and_then(|pair| pair.into_inner().next())
+        .unwrap();
+    parse_expression(expression, &ctx)
+}
+
 /// Parse a .crn file and resolve resource references
 pub fn parse_and_resolve(input: &str) -> Result<ParsedFile, ParseError> {
     let mut parsed = parse(input)?;
@@ -1302,6 +2235,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_expression_in_sees_file_scope() {
+        let parsed = parse(
+            r#"
+            let env = "prod"
+            let vpc = aws.vpc {
+                cidr_block = "10.0.0.0/16"
+            }
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            parse_expression_in("vpc.id", &parsed).unwrap(),
+            Value::ResourceRef("vpc".to_string(), "id".to_string())
+        );
+        assert_eq!(
+            parse_expression_in(r#"env == "prod" ? "large" : "small""#, &parsed).unwrap(),
+            Value::String("large".to_string())
+        );
+        assert!(parse_expression_in("vpc.id extra", &parsed).is_err());
+    }
+
     #[test]
     fn parse_undefined_resource_reference_becomes_unresolved() {
         // When a 2-part identifier references an unknown binding,
@@ -1751,10 +2707,35 @@ mod tests {
     }
 
     #[test]
-    fn parse_read_resource_requires_name() {
+    fn parse_data_keyword_as_read_alias() {
         let input = r#"
-            let existing = read aws.s3.bucket {
-                region = aws.Region.ap_northeast_1
+            let shared_vpc = data aws.vpc {
+                name = "existing-vpc"
+            }
+
+            let db = database.cluster {
+                name = "main-db"
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        assert_eq!(result.resources.len(), 2);
+
+        let data_source = &result.resources[0];
+        assert_eq!(data_source.id.resource_type, "vpc");
+        assert_eq!(data_source.id.name, "existing-vpc");
+        assert!(data_source.is_data_source());
+
+        // Resource types that merely start with a keyword are not data sources
+        assert_eq!(result.resources[1].id.resource_type, "cluster");
+        assert!(!result.resources[1].read_only);
+    }
+
+    #[test]
+    fn parse_read_resource_requires_name() {
+        let input = r#"
+            let existing = read aws.s3.bucket {
+                region = aws.Region.ap_northeast_1
             }
         "#;
 
@@ -1787,4 +2768,929 @@ mod tests {
         assert!(!result.resources[1].read_only);
         assert_eq!(result.resources[1].id.name, "new-bucket");
     }
+
+    #[test]
+    fn parse_records_resource_and_attribute_locations() {
+        let input = "provider aws {\n  region = aws.Region.ap_northeast_1\n}\n\nlet logs = aws.s3.bucket {\n  versioning = true\n}\n";
+
+        let mut result = parse(input).unwrap();
+        result.set_source_file("main.crn");
+        let resource = &result.resources[0];
+        assert_eq!(
+            resource.location.as_ref().map(ToString::to_string),
+            Some("main.crn:5:12".to_string())
+        );
+        assert_eq!(
+            resource.location_of("versioning").map(ToString::to_string),
+            Some("main.crn:6:3".to_string())
+        );
+        assert_eq!(resource.location_of("region"), resource.location.as_ref());
+    }
+
+    #[test]
+    fn anonymous_resource_without_name_reports_its_line() {
+        let input = "provider aws {\n  region = aws.Region.ap_northeast_1\n}\n\naws.s3.bucket {\n  versioning = true\n}\n";
+
+        match parse(input) {
+            Err(ParseError::InvalidExpression { line, .. }) => assert_eq!(line, 5),
+            other => panic!("expected an invalid expression error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_pipe_applies_functions_left_to_right() {
+        let input = r#"
+            let greeting = "  hello  " | upper | trim
+            let bucket_name = "my_app_logs" |> replace("_", "-")
+
+            let bucket = aws.s3.bucket {
+                name = bucket_name
+                tag = greeting |> lower()
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        assert_eq!(
+            result.variables.get("greeting"),
+            Some(&Value::String("HELLO".to_string()))
+        );
+        let resource = &result.resources[0];
+        assert_eq!(resource.id.name, "my-app-logs");
+        assert_eq!(
+            resource.attributes.get("tag"),
+            Some(&Value::String("hello".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_pipe_unknown_function_is_error() {
+        let input = r#"
+            let name = "hello"
+                |> shout
+        "#;
+
+        match parse(input) {
+            Err(ParseError::InvalidExpression { line, message }) => {
+                assert_eq!(line, 3);
+                assert!(message.contains("shout"));
+            }
+            other => panic!("Expected InvalidExpression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_string_interpolation_with_variables() {
+        let input = r#"
+            let env = "prod"
+            let bucket = aws.s3.bucket {
+                name = "bucket-${env}-logs"
+                tag = "\${literal}"
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        let resource = &result.resources[0];
+        assert_eq!(resource.id.name, "bucket-prod-logs");
+        assert_eq!(
+            resource.attributes.get("tag"),
+            Some(&Value::String("${literal}".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_string_interpolation_with_resource_ref() {
+        let input = r#"
+            let vpc = aws.ec2.vpc {
+                name = "main"
+            }
+            let subnet = aws.ec2.subnet {
+                name = "subnet"
+                vpc_id = "${vpc.id}"
+                description = "${vpc.id}-private"
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        let subnet = &result.resources[1];
+        assert_eq!(
+            subnet.attributes.get("vpc_id"),
+            Some(&Value::ResourceRef("vpc".to_string(), "id".to_string()))
+        );
+        assert_eq!(
+            subnet.attributes.get("description"),
+            Some(&Value::Interpolation(vec![
+                Value::ResourceRef("vpc".to_string(), "id".to_string()),
+                Value::String("-private".to_string()),
+            ]))
+        );
+    }
+
+    #[test]
+    fn parse_unterminated_interpolation_is_error() {
+        let input = r#"
+            let bucket = aws.s3.bucket {
+                name = "bucket-${env"
+            }
+        "#;
+
+        match parse(input) {
+            Err(ParseError::InvalidExpression { line, message }) => {
+                assert_eq!(line, 3);
+                assert!(message.contains("Unterminated"));
+            }
+            other => panic!("Expected InvalidExpression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_float_and_int_literals() {
+        let input = r#"
+            let listener = aws.elb.listener {
+                name = "weighted"
+                weight = 0.5
+                offset = -1.25
+                port = 443
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        let attrs = &result.resources[0].attributes;
+        assert_eq!(attrs.get("weight"), Some(&Value::Float(0.5)));
+        assert_eq!(attrs.get("offset"), Some(&Value::Float(-1.25)));
+        assert_eq!(attrs.get("port"), Some(&Value::Int(443)));
+    }
+
+    #[test]
+    fn parse_count_expands_resources() {
+        let input = r#"
+            let vpc = aws.vpc {
+                name = "main"
+                cidr_block = "10.0.0.0/16"
+            }
+
+            let subnets = aws.subnet {
+                count = 3
+                name = "subnet-${count.index}"
+                vpc_id = vpc.id
+                cidr_block = "10.0.${count.index}.0/24"
+                az_index = count.index
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        assert_eq!(result.resources.len(), 4);
+        for (index, subnet) in result.resources[1..].iter().enumerate() {
+            assert_eq!(subnet.id.name, format!("subnet-{}", index));
+            assert_eq!(
+                subnet.attributes.get("_binding"),
+                Some(&Value::String(format!("subnets_{}", index)))
+            );
+            assert_eq!(
+                subnet.attributes.get("cidr_block"),
+                Some(&Value::String(format!("10.0.{}.0/24", index)))
+            );
+            assert_eq!(
+                subnet.attributes.get("az_index"),
+                Some(&Value::Int(index as i64))
+            );
+            assert!(!subnet.attributes.contains_key("count"));
+        }
+    }
+
+    #[test]
+    fn parse_count_suffixes_fixed_names_and_allows_zero() {
+        let input = r#"
+            aws.s3.bucket {
+                count = 2
+                name = "logs"
+            }
+
+            let unused = aws.s3.bucket {
+                count = 0
+                name = "never"
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        let names: Vec<&str> = result
+            .resources
+            .iter()
+            .map(|r| r.id.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["logs-0", "logs-1"]);
+        assert_eq!(
+            result.resources[1].attributes.get("name"),
+            Some(&Value::String("logs-1".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_count_must_be_non_negative_integer() {
+        let input = r#"
+            aws.s3.bucket {
+                count = "two"
+                name = "logs"
+            }
+        "#;
+
+        assert!(matches!(
+            parse(input),
+            Err(ParseError::InvalidExpression { .. })
+        ));
+    }
+
+    #[test]
+    fn parse_for_each_expands_resources_per_key() {
+        let input = r#"
+            let subnets = aws.subnet {
+                for_each = {
+                    a = "10.0.1.0/24"
+                    c = "10.0.2.0/24"
+                }
+                name = "public-${each.key}"
+                cidr_block = each.value
+            }
+
+            let buckets = aws.s3.bucket {
+                for_each = ["app-logs", "audit"]
+                name = each.key
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        let ids: Vec<(&str, &Value, &Value)> = result
+            .resources
+            .iter()
+            .map(|r| {
+                (
+                    r.id.name.as_str(),
+                    &r.attributes["_binding"],
+                    r.attributes
+                        .get("cidr_block")
+                        .unwrap_or(&Value::Bool(false)),
+                )
+            })
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                (
+                    "public-a",
+                    &Value::String("subnets_a".to_string()),
+                    &Value::String("10.0.1.0/24".to_string())
+                ),
+                (
+                    "public-c",
+                    &Value::String("subnets_c".to_string()),
+                    &Value::String("10.0.2.0/24".to_string())
+                ),
+                (
+                    "app-logs",
+                    &Value::String("buckets_app_logs".to_string()),
+                    &Value::Bool(false)
+                ),
+                (
+                    "audit",
+                    &Value::String("buckets_audit".to_string()),
+                    &Value::Bool(false)
+                ),
+            ]
+        );
+        assert!(!result.resources[0].attributes.contains_key("for_each"));
+    }
+
+    #[test]
+    fn parse_for_each_names_built_by_expressions_get_no_suffix() {
+        let input = r#"
+            let logs = aws.s3.bucket {
+                for_each = ["a", "b"]
+                name = "logs-" + each.key
+            }
+
+            let shouting = aws.s3.bucket {
+                for_each = ["a", "b"]
+                name = each.key | upper
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        let names: Vec<&str> = result
+            .resources
+            .iter()
+            .map(|r| r.id.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["logs-a", "logs-b", "A", "B"]);
+    }
+
+    #[test]
+    fn parse_reports_type_errors_in_replica_expressions() {
+        let input = r#"
+            let logs = aws.s3.bucket {
+                count = 2
+                name = "logs-" + count.index
+            }
+        "#;
+
+        match parse(input) {
+            Err(ParseError::InvalidExpression { message, .. }) => {
+                assert!(message.starts_with("name: "), "{}", message);
+            }
+            other => panic!("Expected InvalidExpression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_rejects_bindings_that_clash_with_copies() {
+        let copies = r#"
+            let s = aws.s3.bucket {
+                count = 1
+                name = "logs"
+            }
+        "#;
+        let clash = r#"
+            let s_0 = aws.s3.bucket {
+                name = "other"
+            }
+        "#;
+
+        for input in [
+            format!("{}{}", copies, clash),
+            format!("{}{}", clash, copies),
+        ] {
+            match parse(&input) {
+                Err(ParseError::InvalidExpression { message, .. }) => {
+                    assert!(message.contains("'s_0'"), "{}", message);
+                }
+                other => panic!("Expected InvalidExpression, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn parse_rejects_count_with_for_each() {
+        let input = r#"
+            aws.s3.bucket {
+                count = 2
+                for_each = ["a", "b"]
+                name = "logs"
+            }
+        "#;
+
+        match parse(input) {
+            Err(ParseError::InvalidExpression { message, .. }) => {
+                assert!(message.contains("both count and for_each"), "{}", message);
+            }
+            other => panic!("Expected InvalidExpression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_rejects_for_each_keys_with_the_same_binding() {
+        let duplicate = r#"
+            let buckets = aws.s3.bucket {
+                for_each = ["logs", "logs"]
+                name = "bucket"
+            }
+        "#;
+        match parse(duplicate) {
+            Err(ParseError::InvalidExpression { message, .. }) => {
+                assert!(message.contains("'logs' more than once"), "{}", message);
+            }
+            other => panic!("Expected InvalidExpression, got {:?}", other),
+        }
+
+        let colliding = r#"
+            let buckets = aws.s3.bucket {
+                for_each = ["a-b", "a_b"]
+                name = "bucket"
+            }
+        "#;
+        match parse(colliding) {
+            Err(ParseError::InvalidExpression { message, .. }) => {
+                assert!(message.contains("produce the same binding"), "{}", message);
+            }
+            other => panic!("Expected InvalidExpression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_conditional_expressions() {
+        let input = r#"
+            let env = "prod"
+            let is_prod = env == "prod"
+
+            let bucket = aws.s3.bucket {
+                name = is_prod ? "prod-logs" : "dev-logs"
+                versioning = !is_prod ? "Suspended" : "Enabled"
+                public = env != "prod"
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        assert_eq!(result.variables.get("is_prod"), Some(&Value::Bool(true)));
+        let resource = &result.resources[0];
+        assert_eq!(resource.id.name, "prod-logs");
+        assert_eq!(
+            resource.attributes.get("versioning"),
+            Some(&Value::String("Enabled".to_string()))
+        );
+        assert_eq!(resource.attributes.get("public"), Some(&Value::Bool(false)));
+    }
+
+    #[test]
+    fn parse_conditional_defers_unresolved_condition() {
+        let input = r#"
+            input {
+                prod: bool
+            }
+
+            aws.s3.bucket {
+                name = "logs"
+                versioning = input.prod ? "Enabled" : "Suspended"
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        assert_eq!(
+            result.resources[0].attributes.get("versioning"),
+            Some(&Value::Operation(
+                Operator::Conditional,
+                vec![
+                    Value::ResourceRef("input".to_string(), "prod".to_string()),
+                    Value::String("Enabled".to_string()),
+                    Value::String("Suspended".to_string()),
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_conditional_requires_bool_condition() {
+        let input = r#"
+            let name = "logs" ? "a" : "b"
+        "#;
+
+        match parse(input) {
+            Err(ParseError::InvalidExpression { line, message }) => {
+                assert_eq!(line, 2);
+                assert!(message.contains("expects a bool"), "{}", message);
+            }
+            other => panic!("Expected InvalidExpression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_depends_on_becomes_internal_attribute() {
+        let input = r#"
+            let web = aws.ec2.instance {
+                count = 2
+            }
+            let route = aws.ec2.route {
+                depends_on = [web, attachment]
+            }
+            let attachment = aws.ec2.vpc_gateway_attachment {
+                vpc_id = "vpc-1"
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        let route = result
+            .resources
+            .iter()
+            .find(|r| r.id.name == "route")
+            .unwrap();
+        assert!(!route.attributes.contains_key("depends_on"));
+        assert_eq!(
+            route.attributes.get("_depends_on"),
+            Some(&Value::List(vec![
+                Value::String("web_0".to_string()),
+                Value::String("web_1".to_string()),
+                Value::String("attachment".to_string()),
+            ]))
+        );
+    }
+
+    #[test]
+    fn parse_lifecycle_block() {
+        let input = r#"
+            let bucket = aws.s3.bucket {
+                name = "logs"
+                lifecycle {
+                    ignore_changes = [description, tags.Name]
+                    prevent_destroy = true
+                }
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        let attributes = &result.resources[0].attributes;
+        assert!(!attributes.contains_key("lifecycle"));
+        assert_eq!(attributes.get("_prevent_destroy"), Some(&Value::Bool(true)));
+        assert_eq!(
+            attributes.get("_ignore_changes"),
+            Some(&Value::List(vec![
+                Value::String("description".to_string()),
+                Value::String("tags.Name".to_string()),
+            ]))
+        );
+
+        let unknown = r#"
+            let bucket = aws.s3.bucket {
+                lifecycle {
+                    keep = true
+                }
+            }
+        "#;
+        match parse(unknown) {
+            Err(ParseError::InvalidExpression { message, .. }) => {
+                assert!(
+                    message.contains("Unknown lifecycle option 'keep'"),
+                    "{}",
+                    message
+                );
+            }
+            other => panic!("Expected InvalidExpression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_depends_on_matches_only_copies_of_the_binding() {
+        // `web_extra` starts with `web_` but is not a copy of `web`
+        let input = r#"
+            let web = aws.ec2.instance {
+                count = 2
+            }
+            let web_extra = aws.ec2.instance {
+                name = "extra"
+            }
+            let route = aws.ec2.route {
+                depends_on = [web]
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        let route = result
+            .resources
+            .iter()
+            .find(|r| r.id.name == "route")
+            .unwrap();
+        assert_eq!(
+            route.attributes.get("_depends_on"),
+            Some(&Value::List(vec![
+                Value::String("web_0".to_string()),
+                Value::String("web_1".to_string()),
+            ]))
+        );
+    }
+
+    #[test]
+    fn parse_depends_on_rejects_undefined_binding() {
+        let input = r#"
+            let route = aws.ec2.route {
+                depends_on = [missing]
+            }
+        "#;
+
+        match parse(input) {
+            Err(ParseError::UndefinedReferences(errors)) => {
+                assert_eq!(
+                    errors,
+                    vec!["ec2.route.route: 'depends_on' references undefined 'missing'"]
+                );
+            }
+            other => panic!("Expected UndefinedReferences, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_splat_and_index_refs() {
+        let input = r#"
+            let subnets = aws.subnet {
+                count = 2
+                cidr_block = "10.0.${count.index}.0/24"
+            }
+            let zones = aws.subnet {
+                for_each = ["us-east-1a"]
+            }
+            let lb = aws.elb {
+                subnet_ids = subnets[*].id
+                first = subnets[0].id
+                zone = zones["us-east-1a"].id
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        assert_eq!(
+            result.resources[0].attributes.get("_replica_of"),
+            Some(&Value::String("subnets".to_string()))
+        );
+        assert_eq!(
+            result.resources[1].attributes.get("_replica_key"),
+            Some(&Value::Int(1))
+        );
+        let lb = result.resources.iter().find(|r| r.id.name == "lb").unwrap();
+        assert_eq!(
+            lb.attributes.get("subnet_ids"),
+            Some(&Value::SplatRef("subnets".to_string(), "id".to_string()))
+        );
+        assert_eq!(
+            lb.attributes.get("first"),
+            Some(&Value::ResourceRef(
+                "subnets_0".to_string(),
+                "id".to_string()
+            ))
+        );
+        assert_eq!(
+            lb.attributes.get("zone"),
+            Some(&Value::ResourceRef(
+                "zones_us_east_1a".to_string(),
+                "id".to_string()
+            ))
+        );
+        assert_eq!(
+            lb.attributes.get("_depends_on"),
+            Some(&Value::List(vec![
+                Value::String("subnets_0".to_string()),
+                Value::String("subnets_1".to_string()),
+            ]))
+        );
+    }
+
+    #[test]
+    fn parse_splat_requires_replicated_binding() {
+        let input = r#"
+            let vpc = aws.vpc {
+                cidr_block = "10.0.0.0/16"
+            }
+            let none = aws.subnet {
+                count = 0
+            }
+            let lb = aws.elb {
+                subnet_ids = none[*].id
+                vpc_ids = vpc[*].id
+            }
+        "#;
+
+        match parse(input) {
+            Err(ParseError::UndefinedReferences(errors)) => {
+                assert_eq!(
+                    errors,
+                    vec![
+                        "elb.lb: 'vpc_ids' references undefined 'vpc[*].id' (vpc is not declared with count or for_each)"
+                    ]
+                );
+            }
+            other => panic!("Expected UndefinedReferences, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_null_keyword() {
+        let input = r#"
+            let bucket = aws.s3.bucket {
+                name = "logs"
+                acl = null
+                nullable = "null"
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        let attributes = &result.resources[0].attributes;
+        assert_eq!(attributes.get("acl"), Some(&Value::Null));
+        assert_eq!(
+            attributes.get("nullable"),
+            Some(&Value::String("null".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_arithmetic_respects_precedence() {
+        let input = r#"
+            let base_days = 30
+            let prefix = "app"
+
+            let bucket = aws.s3.bucket {
+                name = prefix + "-logs"
+                expiration_days = base_days + 30 * 2
+                grouped = (base_days + 30) / 4
+                ratio = base_days / 4.0 - 1
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        let resource = &result.resources[0];
+        assert_eq!(resource.id.name, "app-logs");
+        assert_eq!(
+            resource.attributes.get("expiration_days"),
+            Some(&Value::Int(90))
+        );
+        assert_eq!(resource.attributes.get("grouped"), Some(&Value::Int(15)));
+        assert_eq!(resource.attributes.get("ratio"), Some(&Value::Float(6.5)));
+    }
+
+    #[test]
+    fn parse_arithmetic_defers_unresolved_operands() {
+        let input = r#"
+            input {
+                base_days: int
+            }
+
+            aws.s3.bucket {
+                name = "logs"
+                expiration_days = input.base_days + 30
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        assert_eq!(
+            result.resources[0].attributes.get("expiration_days"),
+            Some(&Value::Operation(
+                Operator::Add,
+                vec![
+                    Value::ResourceRef("input".to_string(), "base_days".to_string()),
+                    Value::Int(30),
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_arithmetic_errors_report_line() {
+        let cases = [
+            ("let days = 30\n  / 0", 2, "Division by zero"),
+            ("let days = 1 + true", 1, "Cannot apply '+' to Bool"),
+            (
+                "let days = input.x - \"a\"",
+                1,
+                "Cannot apply '-' to String",
+            ),
+        ];
+
+        for (input, expected_line, expected_message) in cases {
+            match parse(input) {
+                Err(ParseError::InvalidExpression { line, message }) => {
+                    assert_eq!(line, expected_line, "{}", input);
+                    assert!(message.contains(expected_message), "{}", message);
+                }
+                other => panic!("Expected InvalidExpression, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn parse_out_of_range_number_is_error() {
+        match parse("let big = 1\nlet bigger = 99999999999999999999") {
+            Err(ParseError::InvalidExpression { line, message }) => {
+                assert_eq!(line, 2);
+                assert!(message.contains("99999999999999999999"), "{}", message);
+            }
+            other => panic!("Expected InvalidExpression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn resolved_operation_errors_report_line() {
+        let input = r#"
+            let logs = aws.s3.bucket {
+                name = "logs"
+            }
+            aws.s3.bucket {
+                name = "copy"
+                size = logs.name - 1
+            }
+        "#;
+
+        match parse_and_resolve(input) {
+            Err(ParseError::InvalidExpression { line, message }) => {
+                assert_eq!(line, 7);
+                assert!(
+                    message.contains("Cannot apply '-' to String"),
+                    "{}",
+                    message
+                );
+            }
+            other => panic!("Expected InvalidExpression, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_builtin_function_calls() {
+        let input = r#"
+            let cidrs = ["10.0.0.0/24", "10.0.1.0/24"]
+            let tags = { Name = "main", Env = "prod" }
+
+            let count = length(cidrs)
+            let piped = cidrs | length
+            let has_first = contains(cidrs, "10.0.0.0/24")
+            let tag_keys = keys(tags)
+            let non_empty = length(cidrs) > 0
+        "#;
+
+        let result = parse(input).unwrap();
+        assert_eq!(result.variables.get("count"), Some(&Value::Int(2)));
+        assert_eq!(result.variables.get("piped"), Some(&Value::Int(2)));
+        assert_eq!(result.variables.get("has_first"), Some(&Value::Bool(true)));
+        assert_eq!(
+            result.variables.get("tag_keys"),
+            Some(&Value::List(vec![
+                Value::String("Env".to_string()),
+                Value::String("Name".to_string()),
+            ]))
+        );
+        assert_eq!(result.variables.get("non_empty"), Some(&Value::Bool(true)));
+    }
+
+    #[test]
+    fn parse_function_call_defers_unresolved_arguments() {
+        let input = r#"
+            input {
+                cidrs: list(cidr)
+            }
+
+            aws.ec2.vpc {
+                name = "main"
+                subnet_count = length(input.cidrs)
+            }
+        "#;
+
+        let result = parse(input).unwrap();
+        assert_eq!(
+            result.resources[0].attributes.get("subnet_count"),
+            Some(&Value::Operation(
+                Operator::Call,
+                vec![
+                    Value::String("length".to_string()),
+                    Value::ResourceRef("input".to_string(), "cidrs".to_string()),
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_function_call_errors() {
+        let cases = [
+            ("let n = size([1])", "Unknown function 'size'"),
+            (
+                "let n = length(true)",
+                "length: expected a list, map or string",
+            ),
+            (
+                "let n = contains(\"abc\", \"a\")",
+                "contains: expected a list",
+            ),
+        ];
+
+        for (input, expected_message) in cases {
+            match parse(input) {
+                Err(ParseError::InvalidExpression { message, .. }) => {
+                    assert!(message.contains(expected_message), "{}", message);
+                }
+                other => panic!("Expected InvalidExpression, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn parse_reports_all_undefined_references() {
+        let input = r#"
+            let vpc = aws.ec2.vpc {
+                name = "main"
+            }
+
+            aws.ec2.subnet {
+                name = "subnet"
+                vpc_id = "${vpcc.id}"
+                tags = { Owner = "${input.owner}", Vpc = "${vpc.id}" }
+            }
+        "#;
+
+        match parse(input) {
+            Err(ParseError::UndefinedReferences(undefined)) => {
+                assert_eq!(
+                    undefined,
+                    vec![
+                        "ec2.subnet.subnet: 'tags' references undefined 'input.owner'".to_string(),
+                        "ec2.subnet.subnet: 'vpc_id' references undefined 'vpcc.id'".to_string(),
+                    ]
+                );
+            }
+            other => panic!("Expected UndefinedReferences, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_accepts_input_references_in_modules() {
+        let input = r#"
+            input {
+                owner: string
+            }
+
+            aws.s3.bucket {
+                name = "logs"
+                owner = "${input.owner}"
+            }
+        "#;
+
+        assert!(parse(input).is_ok());
+    }
 }
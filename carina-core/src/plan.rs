@@ -5,14 +5,18 @@
 
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
 use crate::effect::Effect;
 use crate::module::DependencyGraph;
-use crate::resource::Value;
+use crate::resource::{ResourceId, Value};
 
 /// Plan containing Effects to be executed
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Plan {
     effects: Vec<Effect>,
+    /// Resources that were evaluated and already match the desired state
+    unchanged: Vec<ResourceId>,
 }
 
 impl Plan {
@@ -28,6 +32,16 @@ impl Plan {
         &self.effects
     }
 
+    /// Record a resource that exists and needs no change
+    pub fn add_unchanged(&mut self, id: ResourceId) {
+        self.unchanged.push(id);
+    }
+
+    /// Resources that exist and need no change
+    pub fn unchanged(&self) -> &[ResourceId] {
+        &self.unchanged
+    }
+
     pub fn is_empty(&self) -> bool {
         self.effects.is_empty()
     }
@@ -39,12 +53,16 @@ impl Plan {
 
     /// Generate a summary of the Plan for display
     pub fn summary(&self) -> PlanSummary {
-        let mut summary = PlanSummary::default();
+        let mut summary = PlanSummary {
+            unchanged: self.unchanged.len(),
+            ..Default::default()
+        };
         for effect in &self.effects {
             match effect {
                 Effect::Read { .. } => summary.read += 1,
                 Effect::Create(_) => summary.create += 1,
                 Effect::Update { .. } => summary.update += 1,
+                Effect::Replace { .. } => summary.replace += 1,
                 Effect::Delete(_) => summary.delete += 1,
             }
         }
@@ -57,7 +75,20 @@ pub struct PlanSummary {
     pub read: usize,
     pub create: usize,
     pub update: usize,
+    pub replace: usize,
     pub delete: usize,
+    pub unchanged: usize,
+}
+
+impl PlanSummary {
+    /// " (N unchanged)" suffix for summary lines, or empty when nothing was unchanged
+    pub fn unchanged_suffix(&self) -> String {
+        if self.unchanged > 0 {
+            format!(" ({} unchanged)", self.unchanged)
+        } else {
+            String::new()
+        }
+    }
 }
 
 impl std::fmt::Display for PlanSummary {
@@ -74,7 +105,11 @@ impl std::fmt::Display for PlanSummary {
                 "Plan: {} to create, {} to update, {} to delete",
                 self.create, self.update, self.delete
             )
+        }?;
+        if self.replace > 0 {
+            write!(f, ", {} to replace", self.replace)?;
         }
+        write!(f, "{}", self.unchanged_suffix())
     }
 }
 
@@ -141,7 +176,9 @@ impl ModularPlan {
         for (idx, effect) in plan.effects().iter().enumerate() {
             let source = match effect {
                 Effect::Create(r) => Self::extract_source(&r.attributes),
-                Effect::Update { to, .. } => Self::extract_source(&to.attributes),
+                Effect::Update { to, .. } | Effect::Replace { to, .. } => {
+                    Self::extract_source(&to.attributes)
+                }
                 Effect::Read { resource } => Self::extract_source(&resource.attributes),
                 Effect::Delete(_) => ModuleSource::Root,
             };
@@ -240,8 +277,11 @@ impl ModularPlan {
         // Add summary
         let summary = self.plan.summary();
         output.push_str(&format!(
-            "Summary: {} to create, {} to update, {} to delete\n",
-            summary.create, summary.update, summary.delete
+            "Summary: {} to create, {} to update, {} to delete{}\n",
+            summary.create,
+            summary.update,
+            summary.delete,
+            summary.unchanged_suffix()
         ));
 
         output
@@ -253,6 +293,7 @@ fn format_effect_brief(effect: &Effect) -> String {
     match effect {
         Effect::Create(r) => format!("+ {}.{}", r.id.resource_type, r.id.name),
         Effect::Update { id, .. } => format!("~ {}.{}", id.resource_type, id.name),
+        Effect::Replace { id, .. } => format!("-/+ {}.{}", id.resource_type, id.name),
         Effect::Delete(id) => format!("- {}.{}", id.resource_type, id.name),
         Effect::Read { resource } => format!(
             "<= {}.{} (data source)",
@@ -286,6 +327,26 @@ mod tests {
         let summary = plan.summary();
         assert_eq!(summary.create, 2);
         assert_eq!(summary.delete, 1);
+        assert_eq!(summary.unchanged, 0);
+    }
+
+    #[test]
+    fn plan_summary_counts_unchanged() {
+        let mut plan = Plan::new();
+        plan.add_unchanged(crate::resource::ResourceId::new("vpc", "main"));
+        plan.add_unchanged(crate::resource::ResourceId::new("subnet", "public"));
+
+        assert!(plan.is_empty());
+        assert_eq!(plan.summary().unchanged, 2);
+        assert_eq!(
+            plan.summary().to_string(),
+            "Plan: 0 to create, 0 to update, 0 to delete (2 unchanged)"
+        );
+        assert!(
+            ModularPlan::from_plan(plan)
+                .display_by_module()
+                .ends_with("Summary: 0 to create, 0 to update, 0 to delete (2 unchanged)\n")
+        );
     }
 
     #[test]
@@ -3,15 +3,71 @@
 //! A Provider defines operations for a specific infrastructure (AWS, GCP, etc.).
 //! It is responsible for converting Effects into actual API calls.
 
+use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 
 use crate::resource::{Resource, ResourceId, State};
 
+/// What kind of failure a provider error is, so callers can react to it
+/// without parsing the message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProviderErrorKind {
+    /// The resource (or something it refers to) does not exist
+    NotFound,
+    /// The credentials in use are not allowed to perform the operation
+    AccessDenied,
+    /// The request was rate limited
+    Throttled,
+    /// The resource already exists or is busy with another operation
+    Conflict,
+    #[default]
+    Other,
+}
+
+impl ProviderErrorKind {
+    /// Classify a cloud API error code, e.g. `AccessDenied`,
+    /// `InvalidVpcID.NotFound` or `RequestLimitExceeded`
+    pub fn from_error_code(code: &str) -> Self {
+        match code {
+            "AccessDenied"
+            | "AccessDeniedException"
+            | "UnauthorizedOperation"
+            | "AuthFailure"
+            | "Forbidden"
+            | "InvalidClientTokenId"
+            | "ExpiredToken" => Self::AccessDenied,
+            "Throttling"
+            | "ThrottlingException"
+            | "RequestLimitExceeded"
+            | "TooManyRequestsException"
+            | "RequestThrottled"
+            | "SlowDown" => Self::Throttled,
+            "ResourceConflict"
+            | "ResourceConflictException"
+            | "ConcurrentOperationException"
+            | "AlreadyExists"
+            | "AlreadyExistsException"
+            | "BucketAlreadyExists"
+            | "BucketAlreadyOwnedByYou"
+            | "DependencyViolation"
+            | "OperationAborted"
+            | "IncorrectState" => Self::Conflict,
+            "NotFound" | "ResourceNotFoundException" | "NoSuchBucket" | "NoSuchKey" => {
+                Self::NotFound
+            }
+            _ if code.ends_with(".NotFound") => Self::NotFound,
+            _ if code.ends_with(".Duplicate") => Self::Conflict,
+            _ => Self::Other,
+        }
+    }
+}
+
 /// Error type for Provider operations
 #[derive(Debug)]
 pub struct ProviderError {
     pub message: String,
+    pub kind: ProviderErrorKind,
     pub resource_id: Option<ResourceId>,
     pub cause: Option<Box<dyn std::error::Error + Send + Sync>>,
 }
@@ -38,11 +94,17 @@ impl ProviderError {
     pub fn new(message: impl Into<String>) -> Self {
         Self {
             message: message.into(),
+            kind: ProviderErrorKind::Other,
             resource_id: None,
             cause: None,
         }
     }
 
+    pub fn with_kind(mut self, kind: ProviderErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
     pub fn for_resource(mut self, id: ResourceId) -> Self {
         self.resource_id = Some(id);
         self
@@ -68,14 +130,127 @@ pub trait ResourceType: Send + Sync {
     fn schema(&self) -> ResourceSchema {
         ResourceSchema::default()
     }
+
+    /// Operations this resource type really supports
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+}
+
+/// Operations supported by a resource type
+///
+/// The differ uses this to plan a replacement (delete + create) instead of an
+/// update that the provider cannot actually perform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Whether the provider can create the resource; plans that would create
+    /// one are rejected otherwise
+    pub create: bool,
+    /// Whether any attribute can be changed in place
+    pub update: bool,
+    /// Whether the provider deletes the resource on its own; otherwise it goes
+    /// away with another resource and deleting it is skipped
+    pub delete: bool,
+    /// Attributes that cannot be changed in place; changing one replaces the resource
+    pub replace_on_change: Vec<String>,
+    /// List attributes compared as multisets, ignoring element order
+    pub unordered: Vec<String>,
+    /// Attributes the provider never reads back; they are not compared for drift
+    pub write_only: Vec<String>,
+    /// Allowed values of enum attributes, compared in their canonical spelling
+    pub enum_values: HashMap<String, Vec<String>>,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self {
+            create: true,
+            update: true,
+            delete: true,
+            replace_on_change: Vec::new(),
+            unordered: Vec::new(),
+            write_only: Vec::new(),
+            enum_values: HashMap::new(),
+        }
+    }
+}
+
+impl Capabilities {
+    /// Capabilities of a resource whose every change requires replacement
+    pub fn replace_only() -> Self {
+        Self {
+            update: false,
+            ..Self::default()
+        }
+    }
+
+    /// Capabilities of a data source, which can only be read
+    pub fn read_only() -> Self {
+        Self {
+            create: false,
+            update: false,
+            delete: false,
+            ..Self::default()
+        }
+    }
+
+    /// Capabilities of an updatable resource whose immutable schema attributes
+    /// force a replacement when changed, whose unordered lists ignore order,
+    /// whose write-only attributes are not compared and whose enum attributes
+    /// compare in canonical spelling
+    pub fn from_schema(schema: &crate::schema::ResourceSchema) -> Self {
+        Self {
+            unordered: schema
+                .unordered_attributes()
+                .iter()
+                .map(|a| a.to_string())
+                .collect(),
+            enum_values: schema.enum_attributes(),
+            ..Self::default()
+        }
+        .with_replace_on_change(&schema.immutable_attributes())
+        .with_write_only(&schema.write_only_attributes())
+    }
+
+    /// Mark attributes that cannot be changed in place
+    pub fn with_replace_on_change(mut self, attributes: &[&str]) -> Self {
+        self.replace_on_change
+            .extend(attributes.iter().map(|a| a.to_string()));
+        self
+    }
+
+    /// Mark attributes that the provider never reads back
+    pub fn with_write_only(mut self, attributes: &[&str]) -> Self {
+        self.write_only
+            .extend(attributes.iter().map(|a| a.to_string()));
+        self
+    }
+
+    /// Whether changing these attributes requires replacing the resource
+    pub fn requires_replacement(&self, changed_attributes: &[String]) -> bool {
+        !self.update
+            || changed_attributes
+                .iter()
+                .any(|a| self.replace_on_change.contains(a))
+    }
 }
 
 /// Resource attribute schema (for type validation, to be extended)
 #[derive(Debug, Default)]
 pub struct ResourceSchema {
+    /// Human-readable description of the resource type
+    pub description: Option<String>,
     // Attribute type definitions to be added later
 }
 
+impl From<&crate::schema::ResourceSchema> for ResourceSchema {
+    fn from(schema: &crate::schema::ResourceSchema) -> Self {
+        Self {
+            description: schema.description.clone(),
+        }
+    }
+}
+
 /// Main Provider trait
 ///
 /// Each infrastructure provider (AWS, GCP, etc.) implements this trait.
@@ -92,12 +267,37 @@ pub trait Provider: Send + Sync {
     /// If identifier is provided, use it to read the resource directly.
     /// Otherwise, fall back to name-based lookup (for backwards compatibility).
     /// Returns `State::not_found()` if the resource does not exist.
+    ///
+    /// Reads must return immediately and never wait for a resource to become
+    /// ready; waiting belongs to create/update/delete. A resource found in a
+    /// transitional state is reported via `State::with_transitional_status()`.
     fn read(
         &self,
         id: &ResourceId,
         identifier: Option<&str>,
     ) -> BoxFuture<'_, ProviderResult<State>>;
 
+    /// Get the current state of a managed resource
+    ///
+    /// Like `read`, but the full resource is passed so that providers can
+    /// derive the identifier from its attributes when none is recorded.
+    /// Defaults to `read`.
+    fn read_managed_resource(
+        &self,
+        resource: &Resource,
+        identifier: Option<&str>,
+    ) -> BoxFuture<'_, ProviderResult<State>> {
+        self.read(&resource.id, identifier)
+    }
+
+    /// Read a data source (`data` resource)
+    ///
+    /// Unlike `read`, the full resource is passed so that data sources can use
+    /// their attributes as lookup filters. Defaults to a name-based `read`.
+    fn read_data_source(&self, resource: &Resource) -> BoxFuture<'_, ProviderResult<State>> {
+        self.read(&resource.id, None)
+    }
+
     /// Create a resource
     ///
     /// Returns State with identifier set to the AWS internal ID (e.g., vpc-xxx)
@@ -139,6 +339,18 @@ impl Provider for Box<dyn Provider> {
         (**self).read(id, identifier)
     }
 
+    fn read_managed_resource(
+        &self,
+        resource: &Resource,
+        identifier: Option<&str>,
+    ) -> BoxFuture<'_, ProviderResult<State>> {
+        (**self).read_managed_resource(resource, identifier)
+    }
+
+    fn read_data_source(&self, resource: &Resource) -> BoxFuture<'_, ProviderResult<State>> {
+        (**self).read_data_source(resource)
+    }
+
     fn create(&self, resource: &Resource) -> BoxFuture<'_, ProviderResult<State>> {
         (**self).create(resource)
     }
@@ -162,6 +374,32 @@ impl Provider for Box<dyn Provider> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn error_codes_are_classified() {
+        let kind = ProviderErrorKind::from_error_code;
+        assert_eq!(kind("InvalidVpcID.NotFound"), ProviderErrorKind::NotFound);
+        assert_eq!(kind("NoSuchBucket"), ProviderErrorKind::NotFound);
+        assert_eq!(
+            kind("UnauthorizedOperation"),
+            ProviderErrorKind::AccessDenied
+        );
+        assert_eq!(kind("RequestLimitExceeded"), ProviderErrorKind::Throttled);
+        assert_eq!(kind("InvalidGroup.Duplicate"), ProviderErrorKind::Conflict);
+        assert_eq!(kind("InvalidParameterValue"), ProviderErrorKind::Other);
+    }
+
+    #[test]
+    fn error_display_ignores_kind() {
+        let err = ProviderError::new("Failed to create VPC: AccessDenied")
+            .with_kind(ProviderErrorKind::AccessDenied)
+            .for_resource(ResourceId::new("vpc", "main"));
+        assert_eq!(
+            err.to_string(),
+            "[vpc.main] Failed to create VPC: AccessDenied"
+        );
+        assert_eq!(ProviderError::new("boom").kind, ProviderErrorKind::Other);
+    }
+
     // Mock Provider for testing
     struct MockProvider;
 
@@ -222,4 +460,44 @@ mod tests {
         assert!(state.exists);
         assert_eq!(state.identifier, Some("mock-id-123".to_string()));
     }
+
+    #[test]
+    fn capabilities_require_replacement() {
+        let changed = vec!["availability_zone".to_string()];
+
+        assert!(!Capabilities::default().requires_replacement(&changed));
+        assert!(Capabilities::replace_only().requires_replacement(&changed));
+
+        let caps = Capabilities::default().with_replace_on_change(&["availability_zone"]);
+        assert!(caps.requires_replacement(&changed));
+        assert!(!caps.requires_replacement(&["tags".to_string()]));
+    }
+
+    #[test]
+    fn capabilities_from_schema_replace_on_immutable_attributes() {
+        use crate::schema::{AttributeSchema, AttributeType, ResourceSchema};
+
+        let schema = ResourceSchema::new("subnet")
+            .attribute(AttributeSchema::new("cidr_block", AttributeType::String).immutable())
+            .attribute(AttributeSchema::new("tags", AttributeType::String));
+
+        let caps = Capabilities::from_schema(&schema);
+        assert_eq!(caps.replace_on_change, vec!["cidr_block".to_string()]);
+        assert!(caps.requires_replacement(&["cidr_block".to_string()]));
+        assert!(!caps.requires_replacement(&["tags".to_string()]));
+    }
+
+    #[test]
+    fn capabilities_from_schema_collect_write_only_attributes() {
+        use crate::schema::{AttributeSchema, AttributeType, ResourceSchema};
+
+        let schema = ResourceSchema::new("db_instance")
+            .attribute(
+                AttributeSchema::new("master_user_password", AttributeType::String).write_only(),
+            )
+            .attribute(AttributeSchema::new("engine", AttributeType::String));
+
+        let caps = Capabilities::from_schema(&schema);
+        assert_eq!(caps.write_only, vec!["master_user_password".to_string()]);
+    }
 }
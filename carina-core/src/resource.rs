@@ -1,11 +1,13 @@
 //! Resource - Representing resources and their state
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use crate::parser::ResourceTypePath;
+use serde::{Deserialize, Serialize};
+
+use crate::parser::{ResourceTypePath, functions};
 
 /// Unique identifier for a resource
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ResourceId {
     /// Resource type (e.g., "s3_bucket", "ec2_instance")
     pub resource_type: String,
@@ -23,15 +25,23 @@ impl ResourceId {
 }
 
 /// Attribute value of a resource
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Value {
+    /// Explicitly unset (`null` in the DSL), distinct from the string `"null"`
+    Null,
     String(String),
     Int(i64),
+    Float(f64),
     Bool(bool),
     List(Vec<Value>),
-    Map(HashMap<String, Value>),
+    /// Map with keys kept in sorted order so that display and state are stable
+    Map(BTreeMap<String, Value>),
     /// Reference to another resource's attribute (binding_name, attribute_name)
     ResourceRef(String, String),
+    /// Reference to an attribute of every copy of a `count` or `for_each` binding
+    /// (binding_name, attribute_name), e.g. `subnets[*].id`. Resolves to a list
+    /// ordered by index or key.
+    SplatRef(String, String),
     /// Typed reference to another resource's attribute with optional type information
     TypedResourceRef {
         /// Binding name of the referenced resource (e.g., "vpc", "web_sg")
@@ -48,15 +58,328 @@ pub enum Value {
     /// - `dedicated` -> ("dedicated", None)
     /// - `InstanceTenancy.dedicated` -> ("InstanceTenancy", Some("dedicated"))
     UnresolvedIdent(String, Option<String>),
+    /// String interpolation that still contains resource references, e.g.
+    /// `"${vpc.id}-logs"` -> [ResourceRef(vpc, id), String("-logs")].
+    /// Parts are concatenated into a `String` once every reference is resolved.
+    Interpolation(Vec<Value>),
+    /// Operator applied to operands that are not known yet (references or
+    /// module inputs), e.g. `input.prod ? "Enabled" : "Suspended"`.
+    /// Evaluated by `Value::operate` once its operands are substituted.
+    Operation(Operator, Vec<Value>),
+}
+
+/// Operator of a conditional, boolean or arithmetic expression, or a function call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Operator {
+    /// `condition ? then : else`, operands in that order
+    Conditional,
+    /// `!value`
+    Not,
+    /// `left == right`
+    Equal,
+    /// `left != right`
+    NotEqual,
+    /// `left < right`
+    Less,
+    /// `left <= right`
+    LessEqual,
+    /// `left > right`
+    Greater,
+    /// `left >= right`
+    GreaterEqual,
+    /// `left + right`, also concatenates strings
+    Add,
+    /// `left - right`
+    Subtract,
+    /// `left * right`
+    Multiply,
+    /// `left / right`
+    Divide,
+    /// `func(value, args...)`, operands are the function name followed by its arguments
+    Call,
+}
+
+impl Operator {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Operator::Conditional => "?:",
+            Operator::Not => "!",
+            Operator::Equal => "==",
+            Operator::NotEqual => "!=",
+            Operator::Less => "<",
+            Operator::LessEqual => "<=",
+            Operator::Greater => ">",
+            Operator::GreaterEqual => ">=",
+            Operator::Add => "+",
+            Operator::Subtract => "-",
+            Operator::Multiply => "*",
+            Operator::Divide => "/",
+            Operator::Call => "call",
+        }
+    }
+
+    /// Render the expression in DSL syntax from already rendered operands
+    pub fn render(self, operands: &[String]) -> String {
+        match (self, operands) {
+            (Operator::Conditional, [condition, then, otherwise]) => {
+                format!("{} ? {} : {}", condition, then, otherwise)
+            }
+            (Operator::Not, [value]) => format!("!{}", value),
+            (Operator::Call, [name, args @ ..]) => {
+                format!("{}({})", name.trim_matches('"'), args.join(", "))
+            }
+            (_, [left, right]) => format!("{} {} {}", left, self.symbol(), right),
+            _ => format!("{}({})", self.symbol(), operands.join(", ")),
+        }
+    }
+}
+
+impl Value {
+    /// Build a value from interpolation parts: a plain `String` when every part
+    /// is a literal, otherwise an `Interpolation` that keeps the references
+    pub fn interpolate(parts: Vec<Value>) -> Value {
+        let mut merged: Vec<Value> = Vec::new();
+        for part in parts {
+            let part = match part {
+                Value::Int(n) => Value::String(n.to_string()),
+                Value::Float(f) => Value::String(f.to_string()),
+                Value::Bool(b) => Value::String(b.to_string()),
+                other => other,
+            };
+            match (merged.last_mut(), part) {
+                (Some(Value::String(prev)), Value::String(s)) => prev.push_str(&s),
+                (_, part) => merged.push(part),
+            }
+        }
+
+        match merged.len() {
+            0 => Value::String(String::new()),
+            1 if matches!(merged[0], Value::String(_)) => merged.pop().unwrap(),
+            _ => Value::Interpolation(merged),
+        }
+    }
+
+    /// Apply an operator. The result is computed when the operands it depends on
+    /// are known, and kept as an `Operation` otherwise. Fails when a known
+    /// operand has the wrong type, e.g. a non-bool condition or `1 + true`.
+    pub fn operate(operator: Operator, mut operands: Vec<Value>) -> Result<Value, String> {
+        let as_bool = |value: &Value| match value {
+            Value::Bool(b) => Ok(Some(*b)),
+            value if value.is_known() => Err(format!(
+                "'{}' expects a bool, got {}",
+                operator.symbol(),
+                value.type_name()
+            )),
+            _ => Ok(None),
+        };
+
+        let result = match (operator, operands.as_slice()) {
+            (Operator::Conditional, [condition, _, _]) => match as_bool(condition)? {
+                Some(true) => Some(operands.swap_remove(1)),
+                Some(false) => operands.pop(),
+                None => None,
+            },
+            (Operator::Not, [value]) => as_bool(value)?.map(|b| Value::Bool(!b)),
+            (Operator::Equal | Operator::NotEqual, [left, right])
+                if left.is_known() && right.is_known() =>
+            {
+                Some(Value::Bool(
+                    (left == right) == (operator == Operator::Equal),
+                ))
+            }
+            (Operator::Equal | Operator::NotEqual, [_, _]) => None,
+            (
+                Operator::Less | Operator::LessEqual | Operator::Greater | Operator::GreaterEqual,
+                [left, right],
+            ) => {
+                if left.is_known() && right.is_known() {
+                    Some(compare(operator, left, right)?)
+                } else {
+                    None
+                }
+            }
+            (
+                Operator::Add | Operator::Subtract | Operator::Multiply | Operator::Divide,
+                [left, right],
+            ) => {
+                // Reject a bad operand even when the other one is still unknown
+                for operand in [left, right] {
+                    let accepted = match operand {
+                        Value::Int(_) | Value::Float(_) => true,
+                        Value::String(_) => operator == Operator::Add,
+                        other => !other.is_known(),
+                    };
+                    if !accepted {
+                        return Err(format!(
+                            "Cannot apply '{}' to {}",
+                            operator.symbol(),
+                            operand.type_name()
+                        ));
+                    }
+                }
+                if left.is_known() && right.is_known() {
+                    Some(arithmetic(operator, left, right)?)
+                } else {
+                    None
+                }
+            }
+            (Operator::Call, [Value::String(name), args @ ..]) => {
+                if !functions::exists(name) {
+                    return Err(format!("Unknown function '{}'", name));
+                }
+                match args {
+                    [] => return Err(format!("{}: expected at least 1 argument", name)),
+                    [value, rest @ ..] if args.iter().all(Value::is_known) => {
+                        Some(functions::call(name, value, rest)?)
+                    }
+                    _ => None,
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "'{}' given {} operands",
+                    operator.symbol(),
+                    operands.len()
+                ));
+            }
+        };
+
+        Ok(result.unwrap_or(Value::Operation(operator, operands)))
+    }
+
+    /// Whether the value is fully known: no references, unresolved identifiers
+    /// or pending interpolations and operations
+    pub fn is_known(&self) -> bool {
+        match self {
+            Value::Null | Value::String(_) | Value::Int(_) | Value::Float(_) | Value::Bool(_) => {
+                true
+            }
+            Value::List(items) => items.iter().all(Value::is_known),
+            Value::Map(map) => map.values().all(Value::is_known),
+            Value::ResourceRef(..)
+            | Value::SplatRef(..)
+            | Value::TypedResourceRef { .. }
+            | Value::UnresolvedIdent(..)
+            | Value::Interpolation(_)
+            | Value::Operation(..) => false,
+        }
+    }
+}
+
+/// Evaluate `<`, `<=`, `>` or `>=` on two known numbers or two strings
+fn compare(operator: Operator, left: &Value, right: &Value) -> Result<Value, String> {
+    let ordering = match (left, right) {
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+        (Value::Int(_) | Value::Float(_), Value::Int(_) | Value::Float(_)) => {
+            let as_float = |value: &Value| match value {
+                Value::Int(n) => *n as f64,
+                Value::Float(f) => *f,
+                _ => unreachable!(),
+            };
+            as_float(left).partial_cmp(&as_float(right))
+        }
+        _ => None,
+    }
+    .ok_or_else(|| {
+        format!(
+            "Cannot compare {} and {} with '{}'",
+            left.type_name(),
+            right.type_name(),
+            operator.symbol()
+        )
+    })?;
+
+    Ok(Value::Bool(match operator {
+        Operator::Less => ordering.is_lt(),
+        Operator::LessEqual => ordering.is_le(),
+        Operator::Greater => ordering.is_gt(),
+        _ => ordering.is_ge(),
+    }))
+}
+
+/// Evaluate `+`, `-`, `*` or `/` on known numbers, or `+` on two strings.
+/// Int arithmetic stays Int; mixing in a Float yields a Float.
+fn arithmetic(operator: Operator, left: &Value, right: &Value) -> Result<Value, String> {
+    let as_float = |value: &Value| match value {
+        Value::Int(n) => Some(*n as f64),
+        Value::Float(f) => Some(*f),
+        _ => None,
+    };
+
+    match (left, right) {
+        (Value::String(a), Value::String(b)) => Ok(Value::String(format!("{}{}", a, b))),
+        (Value::Int(a), Value::Int(b)) => {
+            let result = match operator {
+                Operator::Add => a.checked_add(*b),
+                Operator::Subtract => a.checked_sub(*b),
+                Operator::Multiply => a.checked_mul(*b),
+                _ if *b == 0 => return Err("Division by zero".to_string()),
+                _ => a.checked_div(*b),
+            };
+            result
+                .map(Value::Int)
+                .ok_or_else(|| format!("Integer overflow in '{}'", operator.symbol()))
+        }
+        _ => match (as_float(left), as_float(right)) {
+            (Some(a), Some(b)) => Ok(Value::Float(match operator {
+                Operator::Add => a + b,
+                Operator::Subtract => a - b,
+                Operator::Multiply => a * b,
+                _ if b == 0.0 => return Err("Division by zero".to_string()),
+                _ => a / b,
+            })),
+            _ => Err(format!(
+                "Cannot apply '{}' to {} and {}",
+                operator.symbol(),
+                left.type_name(),
+                right.type_name()
+            )),
+        },
+    }
+}
+
+/// Position of a declaration in a .crn file (1-based line and column)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// File the declaration is in, when known
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourceLocation {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self {
+            file: None,
+            line,
+            column,
+        }
+    }
+}
+
+impl std::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}:{}", file, self.line, self.column),
+            None => write!(f, "{}:{}", self.line, self.column),
+        }
+    }
 }
 
 /// Desired state declared in DSL
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Resource {
     pub id: ResourceId,
     pub attributes: HashMap<String, Value>,
     /// If true, this is a data source (read-only) that won't be modified
     pub read_only: bool,
+    /// Where the resource is declared, for resources parsed from source
+    #[serde(skip)]
+    pub location: Option<SourceLocation>,
+    /// Where each attribute is declared, for resources parsed from source
+    #[serde(skip)]
+    pub attribute_locations: HashMap<String, SourceLocation>,
 }
 
 impl Resource {
@@ -65,6 +388,26 @@ impl Resource {
             id: ResourceId::new(resource_type, name),
             attributes: HashMap::new(),
             read_only: false,
+            location: None,
+            attribute_locations: HashMap::new(),
+        }
+    }
+
+    /// Where an attribute is declared, falling back to the resource itself
+    pub fn location_of(&self, attribute: &str) -> Option<&SourceLocation> {
+        self.attribute_locations
+            .get(attribute)
+            .or(self.location.as_ref())
+    }
+
+    /// Record the file the resource was parsed from in all of its locations
+    pub fn set_source_file(&mut self, file: &str) {
+        for location in self
+            .location
+            .iter_mut()
+            .chain(self.attribute_locations.values_mut())
+        {
+            location.file = Some(file.to_string());
         }
     }
 
@@ -85,7 +428,7 @@ impl Resource {
 }
 
 /// Current state fetched from actual infrastructure
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct State {
     pub id: ResourceId,
     /// AWS internal identifier (e.g., vpc-xxx, subnet-xxx)
@@ -93,6 +436,9 @@ pub struct State {
     pub attributes: HashMap<String, Value>,
     /// Whether this state exists
     pub exists: bool,
+    /// Transitional status reported by the provider (e.g., "pending") when the
+    /// resource exists but is not yet ready. Reads never wait for readiness.
+    pub transitional_status: Option<String>,
 }
 
 impl State {
@@ -102,6 +448,7 @@ impl State {
             identifier: None,
             attributes: HashMap::new(),
             exists: false,
+            transitional_status: None,
         }
     }
 
@@ -111,6 +458,7 @@ impl State {
             identifier: None,
             attributes,
             exists: true,
+            transitional_status: None,
         }
     }
 
@@ -118,4 +466,14 @@ impl State {
         self.identifier = Some(identifier.into());
         self
     }
+
+    pub fn with_transitional_status(mut self, status: impl Into<String>) -> Self {
+        self.transitional_status = Some(status.into());
+        self
+    }
+
+    /// Returns true unless the resource exists but is still transitioning
+    pub fn is_ready(&self) -> bool {
+        self.transitional_status.is_none()
+    }
 }
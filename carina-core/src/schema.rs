@@ -6,7 +6,9 @@
 use std::collections::HashMap;
 use std::fmt;
 
-use crate::resource::Value;
+use regex::Regex;
+
+use crate::resource::{Operator, Value};
 
 /// Attribute type
 #[derive(Debug, Clone)]
@@ -38,8 +40,45 @@ impl AttributeType {
     /// Check if a value conforms to this type
     pub fn validate(&self, value: &Value) -> Result<(), TypeError> {
         match (self, value) {
-            // ResourceRef values resolve to strings at runtime, so they're valid for String types
-            (AttributeType::String, Value::String(_) | Value::ResourceRef(_, _)) => Ok(()),
+            // An explicitly unset attribute has no value to check
+            (_, Value::Null) => Ok(()),
+            (_, Value::Operation(operator, operands)) => {
+                // Catches known operands of the wrong type, e.g. a string condition
+                Value::operate(*operator, operands.clone())
+                    .map_err(|message| TypeError::ValidationFailed { message })?;
+                match operator {
+                    // A deferred conditional takes one of its branches, so both must fit the type
+                    Operator::Conditional => operands
+                        .iter()
+                        .skip(1)
+                        .try_for_each(|branch| self.validate(branch)),
+                    Operator::Not
+                    | Operator::Equal
+                    | Operator::NotEqual
+                    | Operator::Less
+                    | Operator::LessEqual
+                    | Operator::Greater
+                    | Operator::GreaterEqual => self.validate(&Value::Bool(true)),
+                    // A function result's type is only known once it is called
+                    Operator::Call => Ok(()),
+                    // Arithmetic takes the type of its known operand, and a concatenation
+                    // resolves to a string once its references do, like an interpolation
+                    _ => match operands.iter().find(|operand| operand.is_known()) {
+                        Some(Value::String(_)) => {
+                            self.validate(&Value::Interpolation(operands.clone()))
+                        }
+                        Some(known) => self.validate(known),
+                        None => Ok(()),
+                    },
+                }
+            }
+
+            // ResourceRef values and interpolations resolve to strings at runtime,
+            // so they're valid for String types
+            (
+                AttributeType::String,
+                Value::String(_) | Value::ResourceRef(_, _) | Value::Interpolation(_),
+            ) => Ok(()),
             (AttributeType::Int, Value::Int(_)) => Ok(()),
             (AttributeType::Bool, Value::Bool(_)) => Ok(()),
 
@@ -56,6 +95,13 @@ impl AttributeType {
                 }
             }
 
+            // Interpolated references are only known at apply time
+            (AttributeType::Custom { base, .. }, Value::Interpolation(_))
+                if matches!(**base, AttributeType::String) =>
+            {
+                Ok(())
+            }
+
             (
                 AttributeType::Custom {
                     validate,
@@ -94,6 +140,9 @@ impl AttributeType {
                     .map_err(|msg| TypeError::ValidationFailed { message: msg })
             }
 
+            // A splat resolves to the list of the attribute across copies at runtime
+            (AttributeType::List(_), Value::SplatRef(..)) => Ok(()),
+
             (AttributeType::List(inner), Value::List(items)) => {
                 for (i, item) in items.iter().enumerate() {
                     inner.validate(item).map_err(|e| TypeError::ListItemError {
@@ -166,17 +215,34 @@ pub enum TypeError {
 
     #[error("Map value for key '{key}': {inner}")]
     MapValueError { key: String, inner: Box<TypeError> },
+
+    #[error("'{name}' must be {bound}, got {value}")]
+    OutOfRange {
+        name: String,
+        value: i64,
+        bound: String,
+    },
+
+    #[error("'{name}' value \"{value}\" does not match pattern {pattern}")]
+    PatternMismatch {
+        name: String,
+        value: String,
+        pattern: String,
+    },
 }
 
 impl Value {
-    fn type_name(&self) -> String {
+    pub(crate) fn type_name(&self) -> String {
         match self {
+            Value::Null => "Null".to_string(),
             Value::String(_) => "String".to_string(),
             Value::Int(_) => "Int".to_string(),
+            Value::Float(_) => "Float".to_string(),
             Value::Bool(_) => "Bool".to_string(),
             Value::List(_) => "List".to_string(),
             Value::Map(_) => "Map".to_string(),
             Value::ResourceRef(binding, attr) => format!("ResourceRef({}.{})", binding, attr),
+            Value::SplatRef(binding, attr) => format!("SplatRef({}[*].{})", binding, attr),
             Value::TypedResourceRef {
                 binding_name,
                 attribute_name,
@@ -186,6 +252,8 @@ impl Value {
                 Some(m) => format!("UnresolvedIdent({}.{})", name, m),
                 None => format!("UnresolvedIdent({})", name),
             },
+            Value::Interpolation(_) => "Interpolation".to_string(),
+            Value::Operation(operator, _) => format!("Operation({})", operator.symbol()),
         }
     }
 }
@@ -220,6 +288,22 @@ pub struct AttributeSchema {
     pub completions: Option<Vec<CompletionValue>>,
     /// Provider-side property name (e.g., "VpcId" for AWS Cloud Control)
     pub provider_name: Option<String>,
+    /// Whether the attribute cannot be changed in place (changing it replaces the resource)
+    pub immutable: bool,
+    /// Whether a list attribute's element order is insignificant
+    pub unordered: bool,
+    /// Whether the attribute is only sent to the provider and never read back
+    pub write_only: bool,
+    /// Whether the attribute is set by the provider and cannot be configured
+    pub read_only: bool,
+    /// Smallest allowed value of an int attribute
+    pub min: Option<i64>,
+    /// Largest allowed value of an int attribute
+    pub max: Option<i64>,
+    /// Regex a string attribute must match
+    pub pattern: Option<String>,
+    /// Allowed values of an enum attribute, spelled as the provider expects them
+    pub enum_values: Option<Vec<String>>,
 }
 
 impl AttributeSchema {
@@ -232,6 +316,14 @@ impl AttributeSchema {
             description: None,
             completions: None,
             provider_name: None,
+            immutable: false,
+            unordered: false,
+            write_only: false,
+            read_only: false,
+            min: None,
+            max: None,
+            pattern: None,
+            enum_values: None,
         }
     }
 
@@ -259,6 +351,98 @@ impl AttributeSchema {
         self.provider_name = Some(name.into());
         self
     }
+
+    /// Mark the attribute as immutable: changing it replaces the resource
+    pub fn immutable(mut self) -> Self {
+        self.immutable = true;
+        self
+    }
+
+    /// Mark a list attribute as unordered: lists with the same elements in any
+    /// order are equal
+    pub fn unordered(mut self) -> Self {
+        self.unordered = true;
+        self
+    }
+
+    /// Mark the attribute as write-only: the provider accepts it but never
+    /// returns it, so it is left out of drift comparison
+    pub fn write_only(mut self) -> Self {
+        self.write_only = true;
+        self
+    }
+
+    /// Mark the attribute as read-only: the provider sets it (an ID, ARN or
+    /// state) and it is only read back
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    /// Require an int value to be at least `min`
+    pub fn with_min(mut self, min: i64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Require an int value to be at most `max`
+    pub fn with_max(mut self, max: i64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Require a string value to match the regex `pattern`
+    pub fn with_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    /// Mark the attribute as an enum whose values are sent and compared in the
+    /// spelling of `values`, whatever case or namespace the DSL used
+    pub fn with_enum_values(mut self, values: &[&str]) -> Self {
+        self.enum_values = Some(values.iter().map(|v| v.to_string()).collect());
+        self
+    }
+
+    /// Check `min`/`max` and `pattern` against a value that already has the right type.
+    /// Values that are not known yet (references, interpolations) are not checked.
+    pub fn check_constraints(&self, value: &Value) -> Result<(), TypeError> {
+        match value {
+            Value::Int(n) => {
+                let bound = match (self.min, self.max) {
+                    (Some(min), Some(max)) if *n < min || *n > max => {
+                        format!("between {} and {}", min, max)
+                    }
+                    (Some(min), None) if *n < min => format!("at least {}", min),
+                    (None, Some(max)) if *n > max => format!("at most {}", max),
+                    _ => return Ok(()),
+                };
+                Err(TypeError::OutOfRange {
+                    name: self.name.clone(),
+                    value: *n,
+                    bound,
+                })
+            }
+            Value::String(s) => {
+                let Some(pattern) = &self.pattern else {
+                    return Ok(());
+                };
+                let regex = Regex::new(pattern).map_err(|e| TypeError::ValidationFailed {
+                    message: format!("Invalid pattern for '{}': {}", self.name, e),
+                })?;
+                if regex.is_match(s) {
+                    Ok(())
+                } else {
+                    Err(TypeError::PatternMismatch {
+                        name: self.name.clone(),
+                        value: s.clone(),
+                        pattern: pattern.clone(),
+                    })
+                }
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 /// Resource schema
@@ -288,32 +472,94 @@ impl ResourceSchema {
         self
     }
 
+    /// Names of the attributes that cannot be changed in place, sorted
+    pub fn immutable_attributes(&self) -> Vec<&str> {
+        self.attribute_names_where(|attr| attr.immutable)
+    }
+
+    /// Names of the list attributes whose element order is insignificant, sorted
+    pub fn unordered_attributes(&self) -> Vec<&str> {
+        self.attribute_names_where(|attr| attr.unordered)
+    }
+
+    /// Names of the attributes the provider never reads back, sorted
+    pub fn write_only_attributes(&self) -> Vec<&str> {
+        self.attribute_names_where(|attr| attr.write_only)
+    }
+
+    /// Allowed values of each enum attribute, by attribute name
+    pub fn enum_attributes(&self) -> HashMap<String, Vec<String>> {
+        self.attributes
+            .values()
+            .filter_map(|attr| {
+                attr.enum_values
+                    .as_ref()
+                    .map(|values| (attr.name.clone(), values.clone()))
+            })
+            .collect()
+    }
+
+    fn attribute_names_where(&self, predicate: impl Fn(&AttributeSchema) -> bool) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .attributes
+            .values()
+            .filter(|attr| predicate(attr))
+            .map(|attr| attr.name.as_str())
+            .collect();
+        names.sort();
+        names
+    }
+
     /// Validate resource attributes
     pub fn validate(&self, attributes: &HashMap<String, Value>) -> Result<(), Vec<TypeError>> {
+        let errors: Vec<TypeError> = self
+            .attribute_errors(attributes)
+            .into_iter()
+            .map(|(_, error)| error)
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Validation errors of resource attributes, each with the name of the
+    /// attribute it is about, sorted by attribute name
+    pub fn attribute_errors(
+        &self,
+        attributes: &HashMap<String, Value>,
+    ) -> Vec<(String, TypeError)> {
         let mut errors = Vec::new();
 
         // Check required attributes
         for (name, schema) in &self.attributes {
-            if schema.required && !attributes.contains_key(name) && schema.default.is_none() {
-                errors.push(TypeError::MissingRequired { name: name.clone() });
+            let unset = matches!(attributes.get(name), None | Some(Value::Null));
+            if schema.required && unset && schema.default.is_none() {
+                errors.push((
+                    name.clone(),
+                    TypeError::MissingRequired { name: name.clone() },
+                ));
             }
         }
 
-        // Type check each attribute
+        // Type check each attribute, then its value constraints
         for (name, value) in attributes {
             if let Some(schema) = self.attributes.get(name)
-                && let Err(e) = schema.attr_type.validate(value)
+                && *value != Value::Null
+                && let Err(e) = schema
+                    .attr_type
+                    .validate(value)
+                    .and_then(|()| schema.check_constraints(value))
             {
-                errors.push(e);
+                errors.push((name.clone(), e));
             }
             // Unknown attributes are allowed (for flexibility)
         }
 
-        if errors.is_empty() {
-            Ok(())
-        } else {
-            Err(errors)
-        }
+        errors.sort_by(|a, b| a.0.cmp(&b.0));
+        errors
     }
 }
 
@@ -356,6 +602,47 @@ pub mod types {
             namespace: None,
         }
     }
+
+    /// IPv6 CIDR block type (e.g., "2001:db8::/56")
+    pub fn ipv6_cidr() -> AttributeType {
+        AttributeType::Custom {
+            name: "Ipv6Cidr".to_string(),
+            base: Box::new(AttributeType::String),
+            validate: |value| {
+                if let Value::String(s) = value {
+                    validate_ipv6_cidr(s)
+                } else {
+                    Err("Expected string".to_string())
+                }
+            },
+            namespace: None,
+        }
+    }
+}
+
+/// The spelling in `valid_values` of an enum value written as `value`,
+/// `TypeName.value` or `namespace.TypeName.value` in any case. Lists are mapped
+/// element by element; anything that matches no valid value is returned as is.
+pub fn canonicalize_enum(valid_values: &[String], value: &Value) -> Value {
+    match value {
+        Value::String(s) => {
+            let raw = s.rsplit('.').next().unwrap_or(s);
+            match valid_values
+                .iter()
+                .find(|valid| valid.eq_ignore_ascii_case(raw))
+            {
+                Some(valid) => Value::String(valid.clone()),
+                None => value.clone(),
+            }
+        }
+        Value::List(items) => Value::List(
+            items
+                .iter()
+                .map(|item| canonicalize_enum(valid_values, item))
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
 }
 
 /// Validate CIDR block format (e.g., "10.0.0.0/16")
@@ -400,6 +687,53 @@ pub fn validate_cidr(cidr: &str) -> Result<(), String> {
     }
 }
 
+/// Validate IPv6 CIDR block format (e.g., "2001:db8::/56")
+pub fn validate_ipv6_cidr(cidr: &str) -> Result<(), String> {
+    let (ip, prefix) = cidr
+        .split_once('/')
+        .ok_or_else(|| format!("Invalid IPv6 CIDR format '{}': expected IP/prefix", cidr))?;
+
+    if ip.parse::<std::net::Ipv6Addr>().is_err() {
+        return Err(format!("Invalid IPv6 address '{}'", ip));
+    }
+
+    match prefix.parse::<u8>() {
+        Ok(p) if p <= 128 => Ok(()),
+        Ok(p) => Err(format!("Invalid prefix length '{}': must be 0-128", p)),
+        Err(_) => Err(format!(
+            "Invalid prefix length '{}': must be a number",
+            prefix
+        )),
+    }
+}
+
+/// Address range `(first, last)` covered by an IPv4 CIDR block
+fn ipv4_cidr_range(cidr: &str) -> Result<(u32, u32), String> {
+    validate_cidr(cidr)?;
+    let (ip, prefix) = cidr.split_once('/').unwrap();
+    let address = u32::from(
+        ip.parse::<std::net::Ipv4Addr>()
+            .map_err(|e| format!("Invalid IP address '{}': {}", ip, e))?,
+    );
+    let host_bits = 32 - prefix.parse::<u32>().unwrap();
+    let mask = u32::MAX.checked_shl(host_bits).unwrap_or(0);
+    Ok((address & mask, (address & mask) | !mask))
+}
+
+/// Whether two IPv4 CIDR blocks share any address
+pub fn cidrs_overlap(a: &str, b: &str) -> Result<bool, String> {
+    let (a_first, a_last) = ipv4_cidr_range(a)?;
+    let (b_first, b_last) = ipv4_cidr_range(b)?;
+    Ok(a_first <= b_last && b_first <= a_last)
+}
+
+/// Whether every address of the IPv4 CIDR block `inner` is within `outer`
+pub fn cidr_contains(outer: &str, inner: &str) -> Result<bool, String> {
+    let (outer_first, outer_last) = ipv4_cidr_range(outer)?;
+    let (inner_first, inner_last) = ipv4_cidr_range(inner)?;
+    Ok(outer_first <= inner_first && inner_last <= outer_last)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -486,4 +820,113 @@ mod tests {
         assert!(t.validate(&Value::String("invalid".to_string())).is_err()); // not a CIDR
         assert!(t.validate(&Value::Int(42)).is_err()); // wrong type
     }
+
+    #[test]
+    fn validate_ipv6_cidr_type() {
+        let t = types::ipv6_cidr();
+
+        assert!(
+            t.validate(&Value::String("2001:db8::/56".to_string()))
+                .is_ok()
+        );
+        assert!(
+            t.validate(&Value::String("2001:db8:1234:1a00::/64".to_string()))
+                .is_ok()
+        );
+
+        assert!(t.validate(&Value::String("2001:db8::".to_string())).is_err()); // no prefix
+        assert!(
+            t.validate(&Value::String("2001:db8::/129".to_string()))
+                .is_err()
+        ); // prefix too large
+        assert!(
+            t.validate(&Value::String("10.0.0.0/16".to_string()))
+                .is_err()
+        ); // IPv4
+    }
+
+    #[test]
+    fn validate_deferred_conditional_checks_both_branches() {
+        let t = AttributeType::Int;
+        let condition = Value::ResourceRef("input".to_string(), "prod".to_string());
+        let conditional = |then: Value, otherwise: Value| {
+            Value::Operation(
+                Operator::Conditional,
+                vec![condition.clone(), then, otherwise],
+            )
+        };
+
+        assert!(
+            t.validate(&conditional(Value::Int(3), Value::Int(1)))
+                .is_ok()
+        );
+        assert!(
+            t.validate(&conditional(
+                Value::Int(3),
+                Value::String("one".to_string())
+            ))
+            .is_err()
+        );
+        assert!(
+            AttributeType::Bool
+                .validate(&Value::Operation(Operator::Not, vec![condition.clone()]))
+                .is_ok()
+        );
+        assert!(
+            t.validate(&Value::Operation(
+                Operator::Conditional,
+                vec![
+                    Value::String("yes".to_string()),
+                    Value::Int(3),
+                    Value::Int(1)
+                ]
+            ))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn attribute_constraints() {
+        let days = AttributeSchema::new("days", AttributeType::Int)
+            .with_min(1)
+            .with_max(365);
+        assert!(days.check_constraints(&Value::Int(30)).is_ok());
+        assert!(days.check_constraints(&Value::Int(0)).is_err());
+        assert_eq!(
+            days.check_constraints(&Value::Int(400))
+                .unwrap_err()
+                .to_string(),
+            "'days' must be between 1 and 365, got 400"
+        );
+
+        let name = AttributeSchema::new("name", AttributeType::String).with_pattern("^[a-z-]+$");
+        assert!(
+            name.check_constraints(&Value::String("logs".to_string()))
+                .is_ok()
+        );
+        assert!(
+            name.check_constraints(&Value::String("Logs".to_string()))
+                .is_err()
+        );
+        // References are only known at apply time
+        assert!(
+            name.check_constraints(&Value::ResourceRef("b".to_string(), "name".to_string()))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn cidr_overlap_and_containment() {
+        assert!(cidrs_overlap("10.0.0.0/24", "10.0.0.128/25").unwrap());
+        assert!(!cidrs_overlap("10.0.0.0/24", "10.0.1.0/24").unwrap());
+        assert!(cidrs_overlap("0.0.0.0/0", "192.168.1.0/24").unwrap());
+
+        assert!(cidr_contains("10.0.0.0/16", "10.0.255.0/24").unwrap());
+        assert!(!cidr_contains("10.0.0.0/16", "10.1.0.0/24").unwrap());
+        assert!(!cidr_contains("10.0.0.0/24", "10.0.0.0/16").unwrap());
+        // Host bits are ignored
+        assert!(cidr_contains("10.0.0.5/16", "10.0.3.0/24").unwrap());
+
+        assert!(cidrs_overlap("10.0.0.0", "10.0.0.0/24").is_err());
+    }
 }
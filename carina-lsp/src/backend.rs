@@ -160,9 +160,21 @@ impl LanguageServer for Backend {
     async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
         let uri = &params.text_document.uri;
 
+        // Use the nearest .carinafmt, like `carina fmt`; an invalid one is reported
+        // and leaves the document unformatted
+        let config = match uri.to_file_path() {
+            Ok(path) => match FormatConfig::load(&path) {
+                Ok(config) => config,
+                Err(e) => {
+                    self.client.show_message(MessageType::ERROR, e).await;
+                    return Ok(None);
+                }
+            },
+            Err(()) => FormatConfig::default(),
+        };
+
         if let Some(doc) = self.documents.get(uri) {
             let text = doc.text();
-            let config = FormatConfig::default();
 
             match formatter::format(&text, &config) {
                 Ok(formatted) => {
@@ -163,10 +163,17 @@ impl CompletionProvider {
         // Pattern: "aws.xxx.yyy {" or "let name = aws.xxx.yyy {"
         // Maps DSL format to schema resource_type
         for (pattern, schema_type) in [
+            ("aws.s3.bucket_policy", "s3.bucket_policy"),
             ("aws.s3.bucket", "s3.bucket"),
+            ("aws.ec2.ami", "ec2.ami"),
+            ("aws.ec2.eip", "ec2.eip"),
+            ("aws.ec2.instance", "ec2.instance"),
+            ("aws.vpc_endpoint_services", "vpc_endpoint_services"),
             ("aws.vpc", "vpc"),
             ("aws.subnet", "subnet"),
             ("aws.internet_gateway", "internet_gateway"),
+            ("aws.nat_gateway", "nat_gateway"),
+            ("aws.route_table_association", "route_table_association"),
             ("aws.route_table", "route_table"),
             ("aws.route", "route"),
             (
@@ -177,6 +184,7 @@ impl CompletionProvider {
                 "aws.security_group.egress_rule",
                 "security_group.egress_rule",
             ),
+            ("aws.security_group.rule", "security_group.rule"),
             ("aws.security_group", "security_group"),
             // awscc resources
             ("awscc.ec2_vpc", "awscc.ec2_vpc"),
@@ -337,6 +345,17 @@ impl CompletionProvider {
                 detail: Some("Internet Gateway resource".to_string()),
                 ..Default::default()
             },
+            CompletionItem {
+                label: "aws.nat_gateway".to_string(),
+                kind: Some(CompletionItemKind::CLASS),
+                text_edit: Some(tower_lsp::lsp_types::CompletionTextEdit::Edit(TextEdit {
+                    range: replacement_range,
+                    new_text: "aws.nat_gateway {\n    name          = \"${1:nat-name}\"\n    subnet_id     = ${2:subnet.id}\n    allocation_id = \"${3:eipalloc-id}\"\n}".to_string(),
+                })),
+                insert_text_format: Some(InsertTextFormat::SNIPPET),
+                detail: Some("NAT Gateway resource".to_string()),
+                ..Default::default()
+            },
             CompletionItem {
                 label: "aws.route_table".to_string(),
                 kind: Some(CompletionItemKind::CLASS),
@@ -386,12 +405,23 @@ impl CompletionProvider {
                 kind: Some(CompletionItemKind::CLASS),
                 text_edit: Some(tower_lsp::lsp_types::CompletionTextEdit::Edit(TextEdit {
                     range: replacement_range,
-                    new_text: "aws.security_group.egress_rule {\n    name              = \"${1:rule-name}\"\n    security_group_id = ${2:sg.id}\n    protocol          = aws.Protocol.${3:all}\n    from_port         = ${4:0}\n    to_port           = ${5:0}\n    cidr              = \"${6:0.0.0.0/0}\"\n}".to_string(),
+                    new_text: "aws.security_group.egress_rule {\n    name              = \"${1:rule-name}\"\n    security_group_id = ${2:sg.id}\n    protocol          = aws.Protocol.${3:all}\n    cidr              = \"${4:0.0.0.0/0}\"\n}".to_string(),
                 })),
                 insert_text_format: Some(InsertTextFormat::SNIPPET),
                 detail: Some("Security Group Egress Rule".to_string()),
                 ..Default::default()
             },
+            CompletionItem {
+                label: "aws.security_group.rule".to_string(),
+                kind: Some(CompletionItemKind::CLASS),
+                text_edit: Some(tower_lsp::lsp_types::CompletionTextEdit::Edit(TextEdit {
+                    range: replacement_range,
+                    new_text: "aws.security_group.rule {\n    name              = \"${1:rule-name}\"\n    type              = \"${2|ingress,egress|}\"\n    security_group_id = ${3:sg.id}\n    protocol          = aws.Protocol.${4:tcp}\n    from_port         = ${5:80}\n    to_port           = ${6:80}\n    cidr_block        = \"${7:0.0.0.0/0}\"\n}".to_string(),
+                })),
+                insert_text_format: Some(InsertTextFormat::SNIPPET),
+                detail: Some("Security Group Rule (ingress or egress)".to_string()),
+                ..Default::default()
+            },
             // AWS Cloud Control resources
             CompletionItem {
                 label: "awscc.ec2_vpc".to_string(),
@@ -863,6 +893,7 @@ impl CompletionProvider {
                 "aws.internet_gateway",
                 "Internet Gateway resource reference",
             ),
+            ("aws.nat_gateway", "NAT Gateway resource reference"),
             ("aws.route_table", "Route Table resource reference"),
             ("aws.security_group", "Security Group resource reference"),
             (
@@ -873,6 +904,7 @@ impl CompletionProvider {
                 "aws.security_group.egress_rule",
                 "Security Group Egress Rule reference",
             ),
+            ("aws.security_group.rule", "Security Group Rule reference"),
             ("aws.s3.bucket", "S3 Bucket resource reference"),
             // awscc resources
             (
@@ -6,7 +6,7 @@ use crate::document::Document;
 use carina_core::parser::{InputParameter, ParseError, ParsedFile, TypeExpr};
 use carina_core::resource::Value;
 use carina_core::schema::validate_cidr;
-use carina_provider_aws::schemas::{s3, types as aws_types, vpc};
+use carina_provider_aws::schemas::{ec2, s3, types as aws_types, vpc};
 use carina_provider_awscc::schemas::generated::vpc as awscc_vpc;
 
 pub struct DiagnosticEngine {
@@ -19,16 +19,28 @@ impl DiagnosticEngine {
 
         // S3 resources
         valid_resource_types.insert("s3.bucket".to_string());
+        valid_resource_types.insert("s3.bucket_policy".to_string());
 
         // VPC resources
         valid_resource_types.insert("vpc".to_string());
         valid_resource_types.insert("subnet".to_string());
         valid_resource_types.insert("internet_gateway".to_string());
+        valid_resource_types.insert("nat_gateway".to_string());
         valid_resource_types.insert("route_table".to_string());
+        valid_resource_types.insert("route_table_association".to_string());
         valid_resource_types.insert("route".to_string());
         valid_resource_types.insert("security_group".to_string());
         valid_resource_types.insert("security_group.ingress_rule".to_string());
         valid_resource_types.insert("security_group.egress_rule".to_string());
+        valid_resource_types.insert("security_group.rule".to_string());
+
+        // EC2 resources
+        valid_resource_types.insert("ec2.eip".to_string());
+        valid_resource_types.insert("ec2.instance".to_string());
+
+        // Data sources
+        valid_resource_types.insert("vpc_endpoint_services".to_string());
+        valid_resource_types.insert("ec2.ami".to_string());
 
         // AWS Cloud Control resources
         valid_resource_types.insert("awscc.ec2_vpc".to_string());
@@ -271,14 +283,22 @@ impl DiagnosticEngine {
     ) -> Option<carina_core::schema::ResourceSchema> {
         match resource_type {
             "s3_bucket" => Some(s3::bucket_schema()),
+            "s3.bucket_policy" => Some(s3::bucket_policy_schema()),
             "vpc" => Some(vpc::vpc_schema()),
             "subnet" => Some(vpc::subnet_schema()),
             "internet_gateway" => Some(vpc::internet_gateway_schema()),
+            "nat_gateway" => Some(vpc::nat_gateway_schema()),
             "route_table" => Some(vpc::route_table_schema()),
+            "route_table_association" => Some(vpc::route_table_association_schema()),
             "route" => Some(vpc::route_schema()),
             "security_group" => Some(vpc::security_group_schema()),
             "security_group.ingress_rule" => Some(vpc::security_group_ingress_rule_schema()),
             "security_group.egress_rule" => Some(vpc::security_group_egress_rule_schema()),
+            "security_group.rule" => Some(vpc::security_group_rule_schema()),
+            "vpc_endpoint_services" => Some(vpc::vpc_endpoint_services_schema()),
+            "ec2.ami" => Some(ec2::ami_schema()),
+            "ec2.eip" => Some(ec2::eip_schema()),
+            "ec2.instance" => Some(ec2::instance_schema()),
             // AWS Cloud Control resources
             "awscc.ec2_vpc" => Some(awscc_vpc::ec2_vpc_config().schema),
             _ => None,
@@ -865,6 +885,13 @@ fn parse_error_to_diagnostic(error: &ParseError) -> Diagnostic {
             message: format!("Undefined variable: {}", name),
             ..Default::default()
         },
+        ParseError::UndefinedReferences(_) => Diagnostic {
+            range: Range::default(),
+            severity: Some(DiagnosticSeverity::ERROR),
+            source: Some("carina".to_string()),
+            message: error.to_string(),
+            ..Default::default()
+        },
         ParseError::EnvVarNotSet(name) => Diagnostic {
             range: Range::default(),
             severity: Some(DiagnosticSeverity::WARNING),
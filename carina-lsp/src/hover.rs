@@ -97,10 +97,21 @@ impl HoverProvider {
             return self.schema_hover("aws.subnet", &vpc::subnet_schema());
         }
 
+        if word == "aws.nat_gateway" || word.contains("nat_gateway") {
+            return self.schema_hover("aws.nat_gateway", &vpc::nat_gateway_schema());
+        }
+
         if word == "aws.internet_gateway" || word.contains("internet_gateway") {
             return self.schema_hover("aws.internet_gateway", &vpc::internet_gateway_schema());
         }
 
+        if word == "aws.route_table_association" || word.contains("route_table_association") {
+            return self.schema_hover(
+                "aws.route_table_association",
+                &vpc::route_table_association_schema(),
+            );
+        }
+
         if word == "aws.route_table" || word.contains("route_table") {
             return self.schema_hover("aws.route_table", &vpc::route_table_schema());
         }
@@ -120,6 +131,13 @@ impl HoverProvider {
             );
         }
 
+        if word == "aws.security_group.rule" || word.contains("security_group.rule") {
+            return self.schema_hover(
+                "aws.security_group.rule",
+                &vpc::security_group_rule_schema(),
+            );
+        }
+
         if word == "aws.security_group" || word.contains("security_group") {
             return self.schema_hover("aws.security_group", &vpc::security_group_schema());
         }
@@ -160,10 +178,12 @@ impl HoverProvider {
             vpc::vpc_schema(),
             vpc::subnet_schema(),
             vpc::internet_gateway_schema(),
+            vpc::nat_gateway_schema(),
             vpc::route_table_schema(),
             vpc::security_group_schema(),
             vpc::security_group_ingress_rule_schema(),
             vpc::security_group_egress_rule_schema(),
+            vpc::security_group_rule_schema(),
         ];
 
         for schema in schemas {
@@ -113,10 +113,13 @@ impl SemanticTokensProvider {
                         tokens.push(((let_start + 4) as u32, name.len() as u32, 2)); // VARIABLE
                     }
                 }
-                // Check for "read" keyword after "let name = read ..."
-                if let Some(read_pos) = after_let.find("= read ") {
-                    let read_start = let_start + 4 + read_pos + 2; // position of "read"
-                    tokens.push((read_start as u32, 4, 0)); // KEYWORD: read
+                // Check for "read"/"data" keyword after "let name = read ..."
+                if let Some(read_pos) = after_let
+                    .find("= read ")
+                    .or_else(|| after_let.find("= data "))
+                {
+                    let read_start = let_start + 4 + read_pos + 2; // position of "read"/"data"
+                    tokens.push((read_start as u32, 4, 0)); // KEYWORD: read/data
                 }
             }
         }
@@ -205,9 +208,10 @@ impl SemanticTokensProvider {
             }
         }
 
-        // Boolean literals
+        // Boolean and null literals
         self.find_and_add_pattern(line, "true", 0, &mut tokens);
         self.find_and_add_pattern(line, "false", 0, &mut tokens);
+        self.find_and_add_pattern(line, "null", 0, &mut tokens);
 
         // Sort by position and deduplicate
         tokens.sort_by_key(|(start, _, _)| *start);
@@ -2,18 +2,23 @@
 //!
 //! AWS Provider implementation
 
+mod retry;
 pub mod schemas;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use aws_config::Region;
 use aws_sdk_ec2::Client as Ec2Client;
+use aws_sdk_ec2::error::{ProvideErrorMetadata, SdkError};
 use aws_sdk_s3::Client as S3Client;
+use aws_sdk_sts::Client as StsClient;
+use carina_aws_config::{WithSdkCause, format_sdk_error, load_sdk_config};
 use carina_core::provider::{
-    BoxFuture, Provider, ProviderError, ProviderResult, ResourceSchema, ResourceType,
+    BoxFuture, Capabilities, Provider, ProviderError, ProviderResult, ResourceSchema, ResourceType,
 };
 use carina_core::resource::{Resource, ResourceId, State, Value};
 
+use retry::{RetryConfig, retry_throttled, retry_until_visible};
+
 /// S3 Bucket resource type
 pub struct S3BucketType;
 
@@ -23,7 +28,28 @@ impl ResourceType for S3BucketType {
     }
 
     fn schema(&self) -> ResourceSchema {
-        ResourceSchema::default()
+        ResourceSchema::from(&schemas::s3::bucket_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::from_schema(&schemas::s3::bucket_schema())
+    }
+}
+
+/// S3 Bucket Policy resource type
+pub struct S3BucketPolicyType;
+
+impl ResourceType for S3BucketPolicyType {
+    fn name(&self) -> &'static str {
+        "s3.bucket_policy"
+    }
+
+    fn schema(&self) -> ResourceSchema {
+        ResourceSchema::from(&schemas::s3::bucket_policy_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::from_schema(&schemas::s3::bucket_policy_schema())
     }
 }
 
@@ -36,7 +62,11 @@ impl ResourceType for VpcType {
     }
 
     fn schema(&self) -> ResourceSchema {
-        ResourceSchema::default()
+        ResourceSchema::from(&schemas::vpc::vpc_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::from_schema(&schemas::vpc::vpc_schema())
     }
 }
 
@@ -49,7 +79,11 @@ impl ResourceType for SubnetType {
     }
 
     fn schema(&self) -> ResourceSchema {
-        ResourceSchema::default()
+        ResourceSchema::from(&schemas::vpc::subnet_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::from_schema(&schemas::vpc::subnet_schema())
     }
 }
 
@@ -62,7 +96,62 @@ impl ResourceType for InternetGatewayType {
     }
 
     fn schema(&self) -> ResourceSchema {
-        ResourceSchema::default()
+        ResourceSchema::from(&schemas::vpc::internet_gateway_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::from_schema(&schemas::vpc::internet_gateway_schema())
+    }
+}
+
+/// NAT Gateway resource type
+pub struct NatGatewayType;
+
+impl ResourceType for NatGatewayType {
+    fn name(&self) -> &'static str {
+        "nat_gateway"
+    }
+
+    fn schema(&self) -> ResourceSchema {
+        ResourceSchema::from(&schemas::vpc::nat_gateway_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::from_schema(&schemas::vpc::nat_gateway_schema())
+    }
+}
+
+/// Elastic IP resource type
+pub struct ElasticIpType;
+
+impl ResourceType for ElasticIpType {
+    fn name(&self) -> &'static str {
+        "ec2.eip"
+    }
+
+    fn schema(&self) -> ResourceSchema {
+        ResourceSchema::from(&schemas::ec2::eip_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::from_schema(&schemas::ec2::eip_schema())
+    }
+}
+
+/// EC2 Instance resource type
+pub struct Ec2InstanceType;
+
+impl ResourceType for Ec2InstanceType {
+    fn name(&self) -> &'static str {
+        "ec2.instance"
+    }
+
+    fn schema(&self) -> ResourceSchema {
+        ResourceSchema::from(&schemas::ec2::instance_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::from_schema(&schemas::ec2::instance_schema())
     }
 }
 
@@ -75,7 +164,28 @@ impl ResourceType for RouteTableType {
     }
 
     fn schema(&self) -> ResourceSchema {
-        ResourceSchema::default()
+        ResourceSchema::from(&schemas::vpc::route_table_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::from_schema(&schemas::vpc::route_table_schema())
+    }
+}
+
+/// Route Table Association resource type
+pub struct RouteTableAssociationType;
+
+impl ResourceType for RouteTableAssociationType {
+    fn name(&self) -> &'static str {
+        "route_table_association"
+    }
+
+    fn schema(&self) -> ResourceSchema {
+        ResourceSchema::from(&schemas::vpc::route_table_association_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::replace_only()
     }
 }
 
@@ -88,7 +198,15 @@ impl ResourceType for RouteType {
     }
 
     fn schema(&self) -> ResourceSchema {
-        ResourceSchema::default()
+        ResourceSchema::from(&schemas::vpc::route_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        // Routes are removed together with their route table
+        Capabilities {
+            delete: false,
+            ..Capabilities::default()
+        }
     }
 }
 
@@ -101,7 +219,11 @@ impl ResourceType for SecurityGroupType {
     }
 
     fn schema(&self) -> ResourceSchema {
-        ResourceSchema::default()
+        ResourceSchema::from(&schemas::vpc::security_group_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::from_schema(&schemas::vpc::security_group_schema())
     }
 }
 
@@ -114,7 +236,11 @@ impl ResourceType for SecurityGroupIngressRuleType {
     }
 
     fn schema(&self) -> ResourceSchema {
-        ResourceSchema::default()
+        ResourceSchema::from(&schemas::vpc::security_group_ingress_rule_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::from_schema(&schemas::vpc::security_group_ingress_rule_schema())
     }
 }
 
@@ -127,7 +253,62 @@ impl ResourceType for SecurityGroupEgressRuleType {
     }
 
     fn schema(&self) -> ResourceSchema {
-        ResourceSchema::default()
+        ResourceSchema::from(&schemas::vpc::security_group_egress_rule_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::from_schema(&schemas::vpc::security_group_egress_rule_schema())
+    }
+}
+
+/// Unified Security Group Rule resource type
+pub struct SecurityGroupRuleType;
+
+impl ResourceType for SecurityGroupRuleType {
+    fn name(&self) -> &'static str {
+        "security_group.rule"
+    }
+
+    fn schema(&self) -> ResourceSchema {
+        ResourceSchema::from(&schemas::vpc::security_group_rule_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::replace_only()
+    }
+}
+
+/// VPC Endpoint Services data source type
+pub struct VpcEndpointServicesType;
+
+impl ResourceType for VpcEndpointServicesType {
+    fn name(&self) -> &'static str {
+        "vpc_endpoint_services"
+    }
+
+    fn schema(&self) -> ResourceSchema {
+        ResourceSchema::from(&schemas::vpc::vpc_endpoint_services_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::read_only()
+    }
+}
+
+/// AMI lookup data source type
+pub struct AmiType;
+
+impl ResourceType for AmiType {
+    fn name(&self) -> &'static str {
+        "ec2.ami"
+    }
+
+    fn schema(&self) -> ResourceSchema {
+        ResourceSchema::from(&schemas::ec2::ami_schema())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::read_only()
     }
 }
 
@@ -135,31 +316,174 @@ impl ResourceType for SecurityGroupEgressRuleType {
 pub struct AwsProvider {
     s3_client: S3Client,
     ec2_client: Ec2Client,
+    sts_client: StsClient,
     region: String,
+    /// Account id of the caller, fetched once on first use (see `account_id`)
+    account_id: tokio::sync::OnceCell<String>,
+    retry: RetryConfig,
 }
 
 impl AwsProvider {
-    /// Create a new AWS Provider
+    /// Create a new AWS Provider using the default credential chain
     pub async fn new(region: &str) -> Self {
-        let config = aws_config::defaults(aws_config::BehaviorVersion::latest())
-            .region(Region::new(region.to_string()))
-            .load()
-            .await;
+        Self::with_options(region, None, None, None).await
+    }
 
-        Self {
-            s3_client: S3Client::new(&config),
-            ec2_client: Ec2Client::new(&config),
-            region: region.to_string(),
-        }
+    /// Create a new AWS Provider with credentials from a named profile and/or
+    /// an assumed role. Either can be omitted to use the default credential chain.
+    /// `endpoint_url` points the S3, EC2 and STS clients at another endpoint
+    /// (e.g., LocalStack), with path-style S3 addressing.
+    pub async fn with_options(
+        region: &str,
+        profile: Option<&str>,
+        assume_role_arn: Option<&str>,
+        endpoint_url: Option<&str>,
+    ) -> Self {
+        let config = load_sdk_config(region, profile, assume_role_arn).await;
+        // EC2 calls that change resources are retried on throttling by `retry`
+        let ec2_config = aws_sdk_ec2::config::Builder::from(&config)
+            .retry_config(aws_sdk_ec2::config::retry::RetryConfig::disabled());
+
+        let Some(endpoint_url) = endpoint_url else {
+            return Self::with_clients(
+                S3Client::new(&config),
+                Ec2Client::from_conf(ec2_config.build()),
+                StsClient::new(&config),
+                region.to_string(),
+            );
+        };
+        let s3_config = aws_sdk_s3::config::Builder::from(&config)
+            .endpoint_url(endpoint_url)
+            .force_path_style(true)
+            .build();
+        let ec2_config = ec2_config.endpoint_url(endpoint_url).build();
+        let sts_config = aws_sdk_sts::config::Builder::from(&config)
+            .endpoint_url(endpoint_url)
+            .build();
+        Self::with_clients(
+            S3Client::from_conf(s3_config),
+            Ec2Client::from_conf(ec2_config),
+            StsClient::from_conf(sts_config),
+            region.to_string(),
+        )
     }
 
     /// Create with specific clients (for testing)
-    pub fn with_clients(s3_client: S3Client, ec2_client: Ec2Client, region: String) -> Self {
+    pub fn with_clients(
+        s3_client: S3Client,
+        ec2_client: Ec2Client,
+        sts_client: StsClient,
+        region: String,
+    ) -> Self {
         Self {
             s3_client,
             ec2_client,
+            sts_client,
             region,
+            account_id: tokio::sync::OnceCell::new(),
+            retry: RetryConfig::from_env(),
+        }
+    }
+
+    /// Account id of the credentials in use. Fetched via STS on first use and
+    /// memoized, so a provider calls `GetCallerIdentity` at most once.
+    async fn account_id(&self, id: &ResourceId) -> ProviderResult<&str> {
+        self.account_id
+            .get_or_try_init(|| async {
+                let identity = self
+                    .sts_client
+                    .get_caller_identity()
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        ProviderError::new(format!(
+                            "Failed to get caller identity: {}",
+                            format_sdk_error(&e)
+                        ))
+                        .with_sdk_cause(e)
+                        .for_resource(id.clone())
+                    })?;
+                identity.account().map(String::from).ok_or_else(|| {
+                    ProviderError::new("Caller identity has no account id").for_resource(id.clone())
+                })
+            })
+            .await
+            .map(String::as_str)
+    }
+
+    /// Build the ARN of an EC2 resource, e.g. `kind` "vpc" and `resource_id`
+    /// "vpc-123".
+    async fn ec2_arn(
+        &self,
+        id: &ResourceId,
+        kind: &str,
+        resource_id: &str,
+    ) -> ProviderResult<String> {
+        let account_id = self.account_id(id).await?;
+        Ok(ec2_arn(&self.region, account_id, kind, resource_id))
+    }
+
+    /// Bring the tags of an EC2 resource in line with the desired `tags` map:
+    /// create added or changed tags and delete removed ones. Resources that
+    /// don't declare `tags` keep whatever tags they have.
+    async fn sync_ec2_tags(
+        &self,
+        id: &ResourceId,
+        resource_id: &str,
+        from: &State,
+        to: &Resource,
+    ) -> ProviderResult<()> {
+        if !to.attributes.contains_key("tags") {
+            return Ok(());
+        }
+
+        let current = tag_entries(&from.attributes);
+        let desired = tag_entries(&to.attributes);
+
+        let changed: Vec<_> = desired
+            .iter()
+            .filter(|(key, value)| current.get(*key) != Some(*value))
+            .map(|(key, value)| {
+                aws_sdk_ec2::types::Tag::builder()
+                    .key(key)
+                    .value(value)
+                    .build()
+            })
+            .collect();
+        if !changed.is_empty() {
+            self.ec2_client
+                .create_tags()
+                .resources(resource_id)
+                .set_tags(Some(changed))
+                .send()
+                .await
+                .map_err(|e| {
+                    ProviderError::new(format!("Failed to update tags: {}", format_sdk_error(&e)))
+                        .with_sdk_cause(e)
+                        .for_resource(id.clone())
+                })?;
+        }
+
+        let removed: Vec<_> = current
+            .keys()
+            .filter(|key| !desired.contains_key(*key))
+            .map(|key| aws_sdk_ec2::types::Tag::builder().key(key).build())
+            .collect();
+        if !removed.is_empty() {
+            self.ec2_client
+                .delete_tags()
+                .resources(resource_id)
+                .set_tags(Some(removed))
+                .send()
+                .await
+                .map_err(|e| {
+                    ProviderError::new(format!("Failed to delete tags: {}", format_sdk_error(&e)))
+                        .with_sdk_cause(e)
+                        .for_resource(id.clone())
+                })?;
         }
+
+        Ok(())
     }
 
     /// Read an S3 bucket
@@ -173,6 +497,7 @@ impl AwsProvider {
                 // Return region in DSL format
                 let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
                 attributes.insert("region".to_string(), Value::String(region_dsl));
+                attributes.insert("arn".to_string(), Value::String(s3_bucket_arn(name)));
 
                 // Get versioning status
                 if let Ok(versioning) = self
@@ -197,14 +522,88 @@ impl AwsProvider {
                     .send()
                     .await
                 {
+                    let mut rules = Vec::new();
                     for rule in lifecycle.rules() {
-                        if rule.id() == Some("auto-expiration")
-                            && let Some(expiration) = rule.expiration()
-                            && let Some(days) = expiration.days
+                        // The rule `expiration_days` creates reads back as the shorthand
+                        if rule.id() == Some(AUTO_EXPIRATION_RULE_ID)
+                            && rule.transitions().is_empty()
+                            && lifecycle_rule_prefix(rule).is_empty()
+                            && let Some(days) = rule.expiration().and_then(|e| e.days)
                         {
                             attributes
                                 .insert("expiration_days".to_string(), Value::Int(days as i64));
+                        } else {
+                            rules.push(lifecycle_rule_value(rule));
+                        }
+                    }
+                    if !rules.is_empty() {
+                        attributes.insert("lifecycle_rules".to_string(), Value::List(rules));
+                    }
+                }
+
+                // Get CORS configuration (absent when not configured)
+                match self.s3_client.get_bucket_cors().bucket(name).send().await {
+                    Ok(cors) => {
+                        let rules: Vec<Value> =
+                            cors.cors_rules().iter().map(cors_rule_value).collect();
+                        if !rules.is_empty() {
+                            attributes.insert("cors_rules".to_string(), Value::List(rules));
+                        }
+                    }
+                    Err(e) if e.code() == Some("NoSuchCORSConfiguration") => {}
+                    Err(e) => {
+                        return Err(ProviderError::new(format!(
+                            "Failed to read CORS configuration: {}",
+                            format_sdk_error(&e)
+                        ))
+                        .with_sdk_cause(e)
+                        .for_resource(id));
+                    }
+                }
+
+                // Get website configuration (absent when not configured)
+                match self
+                    .s3_client
+                    .get_bucket_website()
+                    .bucket(name)
+                    .send()
+                    .await
+                {
+                    Ok(website) => {
+                        let mut block = BTreeMap::new();
+                        if let Some(index) = website.index_document() {
+                            block.insert(
+                                "index_document".to_string(),
+                                Value::String(index.suffix().to_string()),
+                            );
+                        }
+                        if let Some(error) = website.error_document() {
+                            block.insert(
+                                "error_document".to_string(),
+                                Value::String(error.key().to_string()),
+                            );
                         }
+                        if let Some(redirect) = website.redirect_all_requests_to() {
+                            block.insert(
+                                "redirect_all_requests_to".to_string(),
+                                Value::String(redirect.host_name().to_string()),
+                            );
+                        }
+                        attributes
+                            .insert("website".to_string(), Value::List(vec![Value::Map(block)]));
+                        attributes.insert(
+                            "website_endpoint".to_string(),
+                            Value::String(schemas::types::s3_website_endpoint(name, &self.region)),
+                        );
+                    }
+                    Err(e) if e.code() == Some("NoSuchWebsiteConfiguration") => {}
+                    Err(e) => {
+                        return Err(ProviderError::new(format!(
+                            "Failed to read website configuration: {}",
+                            format_sdk_error(&e)
+                        ))
+                        .with_sdk_cause(e)
+                        .for_resource(id));
                     }
                 }
 
@@ -213,8 +612,6 @@ impl AwsProvider {
             }
             Err(err) => {
                 // Handle bucket not found
-                use aws_sdk_s3::error::SdkError;
-
                 let is_not_found = match &err {
                     SdkError::ServiceError(service_err) => {
                         // NotFound error or 301/403/404 status codes
@@ -231,10 +628,12 @@ impl AwsProvider {
                 if is_not_found {
                     Ok(State::not_found(id))
                 } else {
-                    Err(
-                        ProviderError::new(format!("Failed to read bucket: {:?}", err))
-                            .for_resource(id),
-                    )
+                    Err(ProviderError::new(format!(
+                        "Failed to read bucket: {}",
+                        format_sdk_error(&err)
+                    ))
+                    .with_sdk_cause(err)
+                    .for_resource(id))
                 }
             }
         }
@@ -274,7 +673,8 @@ impl AwsProvider {
         }
 
         req.send().await.map_err(|e| {
-            ProviderError::new(format!("Failed to create bucket: {:?}", e))
+            ProviderError::new(format!("Failed to create bucket: {}", format_sdk_error(&e)))
+                .with_sdk_cause(e)
                 .for_resource(resource.id.clone())
         })?;
 
@@ -302,33 +702,10 @@ impl AwsProvider {
                 })?;
         }
 
-        // Configure lifecycle rule (expiration_days)
-        if let Some(Value::Int(days)) = resource.attributes.get("expiration_days") {
-            use aws_sdk_s3::types::{
-                BucketLifecycleConfiguration, ExpirationStatus, LifecycleExpiration, LifecycleRule,
-                LifecycleRuleFilter,
-            };
-            let expiration = LifecycleExpiration::builder().days(*days as i32).build();
-            let filter = LifecycleRuleFilter::builder().prefix("").build();
-            let rule = LifecycleRule::builder()
-                .id("auto-expiration")
-                .status(ExpirationStatus::Enabled)
-                .filter(filter)
-                .expiration(expiration)
-                .build()
-                .map_err(|e| {
-                    ProviderError::new(format!("Failed to build lifecycle rule: {}", e))
-                        .for_resource(resource.id.clone())
-                })?;
-
-            let config = BucketLifecycleConfiguration::builder()
-                .rules(rule)
-                .build()
-                .map_err(|e| {
-                    ProviderError::new(format!("Failed to build lifecycle config: {}", e))
-                        .for_resource(resource.id.clone())
-                })?;
-
+        // Configure lifecycle rules (lifecycle_rules and expiration_days)
+        if let Some(config) = s3_lifecycle_configuration(&resource.attributes)
+            .map_err(|e| ProviderError::new(e).for_resource(resource.id.clone()))?
+        {
             self.s3_client
                 .put_bucket_lifecycle_configuration()
                 .bucket(&bucket_name)
@@ -341,12 +718,31 @@ impl AwsProvider {
                 })?;
         }
 
+        // Configure CORS
+        if let Some(Value::List(rules)) = resource.attributes.get("cors_rules")
+            && !rules.is_empty()
+        {
+            self.put_s3_bucket_cors(&resource.id, &bucket_name, rules)
+                .await?;
+        }
+
+        // Configure static website hosting
+        if let Some(website) = resource.attributes.get("website") {
+            self.put_s3_bucket_website(&resource.id, &bucket_name, website)
+                .await?;
+        }
+
         // Return state after creation
         self.read_s3_bucket(&bucket_name).await
     }
 
     /// Update an S3 bucket
-    async fn update_s3_bucket(&self, id: ResourceId, to: Resource) -> ProviderResult<State> {
+    async fn update_s3_bucket(
+        &self,
+        id: ResourceId,
+        from: State,
+        to: Resource,
+    ) -> ProviderResult<State> {
         let bucket_name = id.name.clone();
 
         // Update versioning configuration
@@ -373,43 +769,91 @@ impl AwsProvider {
                 })?;
         }
 
-        // Update lifecycle rule (expiration_days)
-        if let Some(Value::Int(days)) = to.attributes.get("expiration_days") {
-            use aws_sdk_s3::types::{
-                BucketLifecycleConfiguration, ExpirationStatus, LifecycleExpiration, LifecycleRule,
-                LifecycleRuleFilter,
-            };
-            let expiration = LifecycleExpiration::builder().days(*days as i32).build();
-            let filter = LifecycleRuleFilter::builder().prefix("").build();
-            let rule = LifecycleRule::builder()
-                .id("auto-expiration")
-                .status(ExpirationStatus::Enabled)
-                .filter(filter)
-                .expiration(expiration)
-                .build()
-                .map_err(|e| {
-                    ProviderError::new(format!("Failed to build lifecycle rule: {}", e))
+        // Update lifecycle rules (removing both attributes removes the configuration)
+        match s3_lifecycle_configuration(&to.attributes)
+            .map_err(|e| ProviderError::new(e).for_resource(id.clone()))?
+        {
+            Some(config) => {
+                self.s3_client
+                    .put_bucket_lifecycle_configuration()
+                    .bucket(&bucket_name)
+                    .lifecycle_configuration(config)
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        ProviderError::new(format!("Failed to set lifecycle: {}", e))
+                            .for_resource(id.clone())
+                    })?;
+            }
+            None if from.attributes.contains_key("expiration_days")
+                || from.attributes.contains_key("lifecycle_rules") =>
+            {
+                self.s3_client
+                    .delete_bucket_lifecycle()
+                    .bucket(&bucket_name)
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        ProviderError::new(format!(
+                            "Failed to delete lifecycle: {}",
+                            format_sdk_error(&e)
+                        ))
+                        .with_sdk_cause(e)
                         .for_resource(id.clone())
-                })?;
+                    })?;
+            }
+            None => {}
+        }
 
-            let config = BucketLifecycleConfiguration::builder()
-                .rules(rule)
-                .build()
-                .map_err(|e| {
-                    ProviderError::new(format!("Failed to build lifecycle config: {}", e))
+        // Update CORS (removing every rule deletes the configuration)
+        let cors_rules = |state: &HashMap<String, Value>| match state.get("cors_rules") {
+            Some(Value::List(rules)) if !rules.is_empty() => Some(rules.clone()),
+            _ => None,
+        };
+        match (cors_rules(&from.attributes), cors_rules(&to.attributes)) {
+            (current, Some(desired)) if current.as_ref() != Some(&desired) => {
+                self.put_s3_bucket_cors(&id, &bucket_name, &desired).await?;
+            }
+            (Some(_), None) => {
+                self.s3_client
+                    .delete_bucket_cors()
+                    .bucket(&bucket_name)
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        ProviderError::new(format!(
+                            "Failed to delete CORS configuration: {}",
+                            format_sdk_error(&e)
+                        ))
+                        .with_sdk_cause(e)
                         .for_resource(id.clone())
-                })?;
+                    })?;
+            }
+            _ => {}
+        }
 
-            self.s3_client
-                .put_bucket_lifecycle_configuration()
-                .bucket(&bucket_name)
-                .lifecycle_configuration(config)
-                .send()
-                .await
-                .map_err(|e| {
-                    ProviderError::new(format!("Failed to set lifecycle: {}", e))
+        // Update static website hosting (removing the block disables it)
+        match to.attributes.get("website") {
+            Some(website) if from.attributes.get("website") != Some(website) => {
+                self.put_s3_bucket_website(&id, &bucket_name, website)
+                    .await?;
+            }
+            None if from.attributes.contains_key("website") => {
+                self.s3_client
+                    .delete_bucket_website()
+                    .bucket(&bucket_name)
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        ProviderError::new(format!(
+                            "Failed to delete website configuration: {}",
+                            format_sdk_error(&e)
+                        ))
+                        .with_sdk_cause(e)
                         .for_resource(id.clone())
-                })?;
+                    })?;
+            }
+            _ => {}
         }
 
         self.read_s3_bucket(&bucket_name).await
@@ -430,27 +874,293 @@ impl AwsProvider {
         Ok(())
     }
 
-    // ========== EC2 VPC Operations ==========
+    // ========== S3 Bucket Policy Operations ==========
 
-    /// Find VPC ID by Name tag
-    async fn find_vpc_id_by_name(&self, name: &str) -> ProviderResult<Option<String>> {
-        use aws_sdk_ec2::types::Filter;
+    /// Read the policy of a bucket. Bucket policies are identified by their
+    /// bucket name; a bucket without a policy reads as not found.
+    async fn read_s3_bucket_policy(
+        &self,
+        name: &str,
+        identifier: Option<&str>,
+    ) -> ProviderResult<State> {
+        let id = ResourceId::new("s3.bucket_policy", name);
 
-        let filter = Filter::builder().name("tag:Name").values(name).build();
+        let Some(bucket) = identifier else {
+            return Ok(State::not_found(id));
+        };
 
-        let result = self
-            .ec2_client
-            .describe_vpcs()
-            .filters(filter)
+        let result = match self
+            .s3_client
+            .get_bucket_policy()
+            .bucket(bucket)
             .send()
             .await
-            .map_err(|e| ProviderError::new(format!("Failed to describe VPCs: {:?}", e)))?;
-
-        Ok(result
-            .vpcs()
-            .first()
-            .and_then(|vpc| vpc.vpc_id().map(String::from)))
-    }
+        {
+            Ok(result) => result,
+            Err(e) if matches!(e.code(), Some("NoSuchBucketPolicy" | "NoSuchBucket")) => {
+                return Ok(State::not_found(id));
+            }
+            Err(e) => {
+                return Err(ProviderError::new(format!(
+                    "Failed to get bucket policy: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone()));
+            }
+        };
+        let Some(policy) = result.policy() else {
+            return Ok(State::not_found(id));
+        };
+
+        let mut attributes = HashMap::new();
+        attributes.insert("name".to_string(), Value::String(name.to_string()));
+        let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
+        attributes.insert("region".to_string(), Value::String(region_dsl));
+        attributes.insert("bucket".to_string(), Value::String(bucket.to_string()));
+        attributes.insert(
+            "policy".to_string(),
+            Value::String(normalize_policy(policy)),
+        );
+
+        Ok(State::existing(id, attributes).with_identifier(bucket))
+    }
+
+    /// Attach a policy to a bucket
+    async fn create_s3_bucket_policy(&self, resource: Resource) -> ProviderResult<State> {
+        let bucket = match resource.attributes.get("bucket") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(ProviderError::new("Bucket policy requires bucket")
+                    .for_resource(resource.id.clone()));
+            }
+        };
+
+        self.put_s3_bucket_policy(&resource.id, &bucket, &resource)
+            .await?;
+        self.read_s3_bucket_policy(&resource.id.name, Some(&bucket))
+            .await
+    }
+
+    /// Replace the policy of a bucket
+    async fn update_s3_bucket_policy(
+        &self,
+        id: ResourceId,
+        identifier: &str,
+        to: Resource,
+    ) -> ProviderResult<State> {
+        self.put_s3_bucket_policy(&id, identifier, &to).await?;
+        self.read_s3_bucket_policy(&id.name, Some(identifier)).await
+    }
+
+    /// Remove the policy from a bucket
+    async fn delete_s3_bucket_policy(
+        &self,
+        id: ResourceId,
+        identifier: &str,
+    ) -> ProviderResult<()> {
+        match self
+            .s3_client
+            .delete_bucket_policy()
+            .bucket(identifier)
+            .send()
+            .await
+        {
+            Ok(_) => Ok(()),
+            // The bucket is already gone, and its policy with it
+            Err(e) if e.code() == Some("NoSuchBucket") => Ok(()),
+            Err(e) => Err(ProviderError::new(format!(
+                "Failed to delete bucket policy: {}",
+                format_sdk_error(&e)
+            ))
+            .with_sdk_cause(e)
+            .for_resource(id)),
+        }
+    }
+
+    /// Put the `policy` attribute of a resource on a bucket via PutBucketPolicy
+    async fn put_s3_bucket_policy(
+        &self,
+        id: &ResourceId,
+        bucket: &str,
+        resource: &Resource,
+    ) -> ProviderResult<()> {
+        let policy = match resource.attributes.get("policy") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(
+                    ProviderError::new("Bucket policy requires policy").for_resource(id.clone())
+                );
+            }
+        };
+
+        self.s3_client
+            .put_bucket_policy()
+            .bucket(bucket)
+            .policy(policy)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to put bucket policy: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
+            })?;
+
+        Ok(())
+    }
+
+    /// Apply `cors_rules` to a bucket via PutBucketCors
+    async fn put_s3_bucket_cors(
+        &self,
+        id: &ResourceId,
+        bucket_name: &str,
+        rules: &[Value],
+    ) -> ProviderResult<()> {
+        let config = s3_cors_configuration(rules)
+            .map_err(|e| ProviderError::new(e).for_resource(id.clone()))?;
+
+        self.s3_client
+            .put_bucket_cors()
+            .bucket(bucket_name)
+            .cors_configuration(config)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to configure CORS: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
+            })?;
+
+        Ok(())
+    }
+
+    /// Apply a `website { ... }` block to a bucket via PutBucketWebsite
+    async fn put_s3_bucket_website(
+        &self,
+        id: &ResourceId,
+        bucket_name: &str,
+        website: &Value,
+    ) -> ProviderResult<()> {
+        use aws_sdk_s3::types::{
+            ErrorDocument, IndexDocument, RedirectAllRequestsTo, WebsiteConfiguration,
+        };
+
+        let block = match website {
+            Value::List(blocks) => match blocks.first() {
+                Some(Value::Map(block)) => block,
+                _ => {
+                    return Err(
+                        ProviderError::new("Invalid website block").for_resource(id.clone())
+                    );
+                }
+            },
+            _ => return Err(ProviderError::new("Invalid website block").for_resource(id.clone())),
+        };
+        let get = |key: &str| match block.get(key) {
+            Some(Value::String(s)) => Some(s.clone()),
+            _ => None,
+        };
+        let build_error = |e: aws_sdk_s3::error::BuildError| {
+            ProviderError::new(format!("Failed to build website configuration: {}", e))
+                .for_resource(id.clone())
+        };
+
+        let mut config = WebsiteConfiguration::builder();
+        if let Some(host) = get("redirect_all_requests_to") {
+            config = config.redirect_all_requests_to(
+                RedirectAllRequestsTo::builder()
+                    .host_name(host)
+                    .build()
+                    .map_err(build_error)?,
+            );
+        } else {
+            if let Some(index) = get("index_document") {
+                config = config.index_document(
+                    IndexDocument::builder()
+                        .suffix(index)
+                        .build()
+                        .map_err(build_error)?,
+                );
+            }
+            if let Some(error) = get("error_document") {
+                config = config.error_document(
+                    ErrorDocument::builder()
+                        .key(error)
+                        .build()
+                        .map_err(build_error)?,
+                );
+            }
+        }
+
+        self.s3_client
+            .put_bucket_website()
+            .bucket(bucket_name)
+            .website_configuration(config.build())
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to configure website: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
+            })?;
+
+        Ok(())
+    }
+
+    // ========== EC2 VPC Operations ==========
+
+    /// Find VPC ID by Name tag
+    async fn find_vpc_id_by_name(&self, name: &str) -> ProviderResult<Option<String>> {
+        use aws_sdk_ec2::types::Filter;
+
+        let filter = Filter::builder().name("tag:Name").values(name).build();
+
+        let result = self
+            .ec2_client
+            .describe_vpcs()
+            .filters(filter)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!("Failed to describe VPCs: {}", format_sdk_error(&e)))
+                    .with_sdk_cause(e)
+            })?;
+
+        Ok(result
+            .vpcs()
+            .first()
+            .and_then(|vpc| vpc.vpc_id().map(String::from)))
+    }
+
+    /// Read a resource right after creating it. `describe_*` calls are eventually
+    /// consistent, so the read is retried until it finds the created `identifier`. If it
+    /// never does, the state is built from the created resource's attributes so that
+    /// the resource is still recorded.
+    async fn read_after_create<F, Fut>(
+        &self,
+        resource: &Resource,
+        identifier: &str,
+        read: F,
+    ) -> ProviderResult<State>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = ProviderResult<State>>,
+    {
+        let state = retry_until_visible(&self.retry, read, |state: &State| {
+            state.exists && state.identifier.as_deref() == Some(identifier)
+        })
+        .await?;
+        Ok(state.unwrap_or_else(|| created_state(resource, identifier)))
+    }
 
     /// Read an EC2 VPC
     async fn read_ec2_vpc(&self, name: &str) -> ProviderResult<State> {
@@ -467,13 +1177,15 @@ impl AwsProvider {
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to describe VPCs: {:?}", e))
+                ProviderError::new(format!("Failed to describe VPCs: {}", format_sdk_error(&e)))
+                    .with_sdk_cause(e)
                     .for_resource(id.clone())
             })?;
 
         if let Some(vpc) = result.vpcs().first() {
             let mut attributes = HashMap::new();
             attributes.insert("name".to_string(), Value::String(name.to_string()));
+            attributes.insert("tags".to_string(), tags_attribute(vpc.tags()));
 
             // Return region in DSL format
             let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
@@ -483,10 +1195,45 @@ impl AwsProvider {
                 attributes.insert("cidr_block".to_string(), Value::String(cidr.to_string()));
             }
 
+            let secondary = vpc_secondary_cidr_blocks(vpc);
+            if !secondary.is_empty() {
+                attributes.insert(
+                    "secondary_cidr_blocks".to_string(),
+                    Value::List(
+                        secondary
+                            .into_iter()
+                            .map(|(cidr, _)| Value::String(cidr))
+                            .collect(),
+                    ),
+                );
+            }
+
+            // IPv6 CIDR block (Amazon-provided /56)
+            let ipv6_cidr = vpc
+                .ipv6_cidr_block_association_set()
+                .iter()
+                .filter(|assoc| {
+                    assoc.ipv6_cidr_block_state().and_then(|s| s.state())
+                        == Some(&aws_sdk_ec2::types::VpcCidrBlockStateCode::Associated)
+                })
+                .find_map(|assoc| assoc.ipv6_cidr_block());
+            attributes.insert(
+                "assign_generated_ipv6_cidr_block".to_string(),
+                Value::Bool(ipv6_cidr.is_some()),
+            );
+            if let Some(cidr) = ipv6_cidr {
+                attributes.insert(
+                    "ipv6_cidr_block".to_string(),
+                    Value::String(cidr.to_string()),
+                );
+            }
+
             // Store VPC ID as public attribute and as identifier
             let vpc_id_str = vpc.vpc_id().map(String::from);
             if let Some(ref vpc_id) = vpc_id_str {
                 attributes.insert("id".to_string(), Value::String(vpc_id.clone()));
+                let arn = self.ec2_arn(&id, "vpc", vpc_id).await?;
+                attributes.insert("arn".to_string(), Value::String(arn));
             }
 
             // Instance tenancy - convert to DSL format
@@ -536,7 +1283,9 @@ impl AwsProvider {
                 }
             }
 
-            let state = State::existing(id, attributes);
+            // Current lifecycle state; reads report it without waiting
+            let vpc_state = vpc.state().map(|s| s.as_str().to_string());
+            let state = lifecycle_state(id, attributes, vpc_state, "available");
             Ok(if let Some(vpc_id) = vpc_id_str {
                 state.with_identifier(vpc_id)
             } else {
@@ -587,29 +1336,34 @@ impl AwsProvider {
             create_vpc_builder = create_vpc_builder.instance_tenancy(tenancy_enum);
         }
 
-        let result = create_vpc_builder.send().await.map_err(|e| {
-            ProviderError::new(format!("Failed to create VPC: {:?}", e))
-                .for_resource(resource.id.clone())
-        })?;
+        // Request an Amazon-provided IPv6 CIDR block (/56)
+        if let Some(Value::Bool(true)) = resource.attributes.get("assign_generated_ipv6_cidr_block")
+        {
+            create_vpc_builder = create_vpc_builder.amazon_provided_ipv6_cidr_block(true);
+        }
+
+        let result = retry_throttled(&self.retry, || create_vpc_builder.clone().send())
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!("Failed to create VPC: {}", format_sdk_error(&e)))
+                    .with_sdk_cause(e)
+                    .for_resource(resource.id.clone())
+            })?;
 
         let vpc_id = result.vpc().and_then(|v| v.vpc_id()).ok_or_else(|| {
             ProviderError::new("VPC created but no ID returned").for_resource(resource.id.clone())
         })?;
 
-        // Tag with Name
+        // Tag with Name and any additional tags
         self.ec2_client
             .create_tags()
             .resources(vpc_id)
-            .tags(
-                aws_sdk_ec2::types::Tag::builder()
-                    .key("Name")
-                    .value(&name)
-                    .build(),
-            )
+            .set_tags(Some(ec2_tags(&name, &resource.attributes)))
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to tag VPC: {:?}", e))
+                ProviderError::new(format!("Failed to tag VPC: {}", format_sdk_error(&e)))
+                    .with_sdk_cause(e)
                     .for_resource(resource.id.clone())
             })?;
 
@@ -626,8 +1380,12 @@ impl AwsProvider {
                 .send()
                 .await
                 .map_err(|e| {
-                    ProviderError::new(format!("Failed to set DNS support: {:?}", e))
-                        .for_resource(resource.id.clone())
+                    ProviderError::new(format!(
+                        "Failed to set DNS support: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(resource.id.clone())
                 })?;
         }
 
@@ -644,21 +1402,72 @@ impl AwsProvider {
                 .send()
                 .await
                 .map_err(|e| {
-                    ProviderError::new(format!("Failed to set DNS hostnames: {:?}", e))
-                        .for_resource(resource.id.clone())
+                    ProviderError::new(format!(
+                        "Failed to set DNS hostnames: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(resource.id.clone())
                 })?;
         }
 
-        self.read_ec2_vpc(&name).await
+        if let Some(cidrs) = string_list(&resource.attributes, "secondary_cidr_blocks")
+            && !cidrs.is_empty()
+        {
+            self.sync_vpc_secondary_cidr_blocks(&resource.id, vpc_id, &cidrs)
+                .await?;
+        }
+
+        self.read_after_create(&resource, vpc_id, || self.read_ec2_vpc(&name))
+            .await
     }
 
     /// Update an EC2 VPC
-    async fn update_ec2_vpc(&self, id: ResourceId, to: Resource) -> ProviderResult<State> {
+    async fn update_ec2_vpc(
+        &self,
+        id: ResourceId,
+        from: State,
+        to: Resource,
+    ) -> ProviderResult<State> {
         let vpc_id = self
             .find_vpc_id_by_name(&id.name)
             .await?
             .ok_or_else(|| ProviderError::new("VPC not found").for_resource(id.clone()))?;
 
+        self.sync_ec2_tags(&id, &vpc_id, &from, &to).await?;
+
+        // Associate an Amazon-provided IPv6 CIDR block if newly requested
+        let has_ipv6 = from.attributes.contains_key("ipv6_cidr_block");
+        if let Some(Value::Bool(true)) = to.attributes.get("assign_generated_ipv6_cidr_block")
+            && !has_ipv6
+        {
+            self.ec2_client
+                .associate_vpc_cidr_block()
+                .vpc_id(&vpc_id)
+                .amazon_provided_ipv6_cidr_block(true)
+                .send()
+                .await
+                .map_err(|e| {
+                    ProviderError::new(format!(
+                        "Failed to associate IPv6 CIDR block: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(id.clone())
+                })?;
+        }
+
+        // Secondary CIDR blocks; removing the attribute disassociates them all
+        let current_cidrs = string_list(&from.attributes, "secondary_cidr_blocks");
+        let desired_cidrs = string_list(&to.attributes, "secondary_cidr_blocks")
+            .or_else(|| current_cidrs.as_ref().map(|_| Vec::new()));
+        if let Some(desired_cidrs) = desired_cidrs
+            && desired_cidrs != current_cidrs.unwrap_or_default()
+        {
+            self.sync_vpc_secondary_cidr_blocks(&id, &vpc_id, &desired_cidrs)
+                .await?;
+        }
+
         // Update DNS support
         if let Some(Value::Bool(enabled)) = to.attributes.get("enable_dns_support") {
             self.ec2_client
@@ -672,8 +1481,12 @@ impl AwsProvider {
                 .send()
                 .await
                 .map_err(|e| {
-                    ProviderError::new(format!("Failed to update DNS support: {:?}", e))
-                        .for_resource(id.clone())
+                    ProviderError::new(format!(
+                        "Failed to update DNS support: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(id.clone())
                 })?;
         }
 
@@ -690,14 +1503,88 @@ impl AwsProvider {
                 .send()
                 .await
                 .map_err(|e| {
-                    ProviderError::new(format!("Failed to update DNS hostnames: {:?}", e))
-                        .for_resource(id.clone())
+                    ProviderError::new(format!(
+                        "Failed to update DNS hostnames: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(id.clone())
                 })?;
         }
 
         self.read_ec2_vpc(&id.name).await
     }
 
+    /// Associate and disassociate secondary IPv4 CIDR blocks so the VPC has
+    /// exactly `desired` besides its primary block. Removed blocks are
+    /// disassociated first, by their association id.
+    async fn sync_vpc_secondary_cidr_blocks(
+        &self,
+        id: &ResourceId,
+        vpc_id: &str,
+        desired: &[String],
+    ) -> ProviderResult<()> {
+        let result = self
+            .ec2_client
+            .describe_vpcs()
+            .vpc_ids(vpc_id)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!("Failed to describe VPC: {}", format_sdk_error(&e)))
+                    .with_sdk_cause(e)
+                    .for_resource(id.clone())
+            })?;
+        let current = result
+            .vpcs()
+            .first()
+            .map(vpc_secondary_cidr_blocks)
+            .unwrap_or_default();
+
+        for (cidr, association_id) in &current {
+            if desired.contains(cidr) {
+                continue;
+            }
+            self.ec2_client
+                .disassociate_vpc_cidr_block()
+                .association_id(association_id)
+                .send()
+                .await
+                .map_err(|e| {
+                    ProviderError::new(format!(
+                        "Failed to disassociate CIDR block {}: {}",
+                        cidr,
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(id.clone())
+                })?;
+        }
+
+        for cidr in desired {
+            if current.iter().any(|(c, _)| c == cidr) {
+                continue;
+            }
+            self.ec2_client
+                .associate_vpc_cidr_block()
+                .vpc_id(vpc_id)
+                .cidr_block(cidr)
+                .send()
+                .await
+                .map_err(|e| {
+                    ProviderError::new(format!(
+                        "Failed to associate CIDR block {}: {}",
+                        cidr,
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(id.clone())
+                })?;
+        }
+
+        Ok(())
+    }
+
     /// Delete an EC2 VPC
     async fn delete_ec2_vpc(&self, id: ResourceId) -> ProviderResult<()> {
         let vpc_id = self
@@ -705,15 +1592,15 @@ impl AwsProvider {
             .await?
             .ok_or_else(|| ProviderError::new("VPC not found").for_resource(id.clone()))?;
 
-        self.ec2_client
-            .delete_vpc()
-            .vpc_id(&vpc_id)
-            .send()
-            .await
-            .map_err(|e| {
-                ProviderError::new(format!("Failed to delete VPC: {:?}", e))
-                    .for_resource(id.clone())
-            })?;
+        retry_throttled(&self.retry, || {
+            self.ec2_client.delete_vpc().vpc_id(&vpc_id).send()
+        })
+        .await
+        .map_err(|e| {
+            ProviderError::new(format!("Failed to delete VPC: {}", format_sdk_error(&e)))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
+        })?;
 
         Ok(())
     }
@@ -732,7 +1619,13 @@ impl AwsProvider {
             .filters(filter)
             .send()
             .await
-            .map_err(|e| ProviderError::new(format!("Failed to describe subnets: {:?}", e)))?;
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to describe subnets: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+            })?;
 
         Ok(result
             .subnets()
@@ -755,13 +1648,18 @@ impl AwsProvider {
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to describe subnets: {:?}", e))
-                    .for_resource(id.clone())
+                ProviderError::new(format!(
+                    "Failed to describe subnets: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
             })?;
 
         if let Some(subnet) = result.subnets().first() {
             let mut attributes = HashMap::new();
             attributes.insert("name".to_string(), Value::String(name.to_string()));
+            attributes.insert("tags".to_string(), tags_attribute(subnet.tags()));
 
             let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
             attributes.insert("region".to_string(), Value::String(region_dsl));
@@ -770,16 +1668,38 @@ impl AwsProvider {
                 attributes.insert("cidr_block".to_string(), Value::String(cidr.to_string()));
             }
 
+            if let Some(ipv6_cidr) = subnet
+                .ipv6_cidr_block_association_set()
+                .iter()
+                .filter(|assoc| {
+                    assoc.ipv6_cidr_block_state().and_then(|s| s.state())
+                        == Some(&aws_sdk_ec2::types::SubnetCidrBlockStateCode::Associated)
+                })
+                .find_map(|assoc| assoc.ipv6_cidr_block())
+            {
+                attributes.insert(
+                    "ipv6_cidr_block".to_string(),
+                    Value::String(ipv6_cidr.to_string()),
+                );
+            }
+
             if let Some(az) = subnet.availability_zone() {
                 // Return availability_zone in DSL format
                 let az_dsl = format!("aws.AvailabilityZone.{}", az.replace('-', "_"));
                 attributes.insert("availability_zone".to_string(), Value::String(az_dsl));
             }
 
+            attributes.insert(
+                "map_public_ip_on_launch".to_string(),
+                Value::Bool(subnet.map_public_ip_on_launch().unwrap_or(false)),
+            );
+
             // Store subnet ID
             let subnet_id_str = subnet.subnet_id().map(String::from);
             if let Some(ref subnet_id) = subnet_id_str {
                 attributes.insert("id".to_string(), Value::String(subnet_id.clone()));
+                let arn = self.ec2_arn(&id, "subnet", subnet_id).await?;
+                attributes.insert("arn".to_string(), Value::String(arn));
             }
 
             // Store VPC ID
@@ -787,7 +1707,9 @@ impl AwsProvider {
                 attributes.insert("vpc_id".to_string(), Value::String(vpc_id.to_string()));
             }
 
-            let state = State::existing(id, attributes);
+            // Current lifecycle state; reads report it without waiting
+            let subnet_state = subnet.state().map(|s| s.as_str().to_string());
+            let state = lifecycle_state(id, attributes, subnet_state, "available");
             Ok(if let Some(subnet_id) = subnet_id_str {
                 state.with_identifier(subnet_id)
             } else {
@@ -837,59 +1759,141 @@ impl AwsProvider {
             req = req.availability_zone(convert_enum_value(az));
         }
 
-        let result = req.send().await.map_err(|e| {
-            ProviderError::new(format!("Failed to create subnet: {:?}", e))
-                .for_resource(resource.id.clone())
-        })?;
+        if let Some(Value::String(ipv6_cidr)) = resource.attributes.get("ipv6_cidr_block") {
+            req = req.ipv6_cidr_block(ipv6_cidr);
+        }
+
+        let result = retry_throttled(&self.retry, || req.clone().send())
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!("Failed to create subnet: {}", format_sdk_error(&e)))
+                    .with_sdk_cause(e)
+                    .for_resource(resource.id.clone())
+            })?;
 
         let subnet_id = result.subnet().and_then(|s| s.subnet_id()).ok_or_else(|| {
             ProviderError::new("Subnet created but no ID returned")
                 .for_resource(resource.id.clone())
         })?;
 
-        // Tag with Name
+        // Tag with Name and any additional tags
         self.ec2_client
             .create_tags()
             .resources(subnet_id)
-            .tags(
-                aws_sdk_ec2::types::Tag::builder()
-                    .key("Name")
-                    .value(&name)
-                    .build(),
-            )
+            .set_tags(Some(ec2_tags(&name, &resource.attributes)))
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to tag subnet: {:?}", e))
+                ProviderError::new(format!("Failed to tag subnet: {}", format_sdk_error(&e)))
+                    .with_sdk_cause(e)
                     .for_resource(resource.id.clone())
             })?;
 
-        self.read_ec2_subnet(&name).await
-    }
+        if let Some(Value::Bool(true)) = resource.attributes.get("map_public_ip_on_launch") {
+            self.set_subnet_map_public_ip_on_launch(&resource.id, subnet_id, true)
+                .await?;
+        }
 
-    /// Update an EC2 Subnet (limited - most attributes are immutable)
-    async fn update_ec2_subnet(&self, id: ResourceId, _to: Resource) -> ProviderResult<State> {
-        // Subnet attributes (cidr_block, vpc, availability_zone) are immutable
-        // Only tags can be updated
-        self.read_ec2_subnet(&id.name).await
+        self.read_after_create(&resource, subnet_id, || self.read_ec2_subnet(&name))
+            .await
     }
 
-    /// Delete an EC2 Subnet
-    async fn delete_ec2_subnet(&self, id: ResourceId) -> ProviderResult<()> {
+    /// Update an EC2 Subnet (tags, public IP on launch and IPv6 association;
+    /// the rest is immutable)
+    async fn update_ec2_subnet(
+        &self,
+        id: ResourceId,
+        from: State,
+        to: Resource,
+    ) -> ProviderResult<State> {
         let subnet_id = self
             .find_subnet_id_by_name(&id.name)
             .await?
             .ok_or_else(|| ProviderError::new("Subnet not found").for_resource(id.clone()))?;
 
+        self.sync_ec2_tags(&id, &subnet_id, &from, &to).await?;
+
+        // Subnet attributes (cidr_block, vpc, availability_zone) are immutable
+        let map_public_ip = matches!(
+            to.attributes.get("map_public_ip_on_launch"),
+            Some(Value::Bool(true))
+        );
+        let had_public_ip = matches!(
+            from.attributes.get("map_public_ip_on_launch"),
+            Some(Value::Bool(true))
+        );
+        if map_public_ip != had_public_ip {
+            self.set_subnet_map_public_ip_on_launch(&id, &subnet_id, map_public_ip)
+                .await?;
+        }
+
+        // An IPv6 CIDR block can be associated after creation
+        if let Some(Value::String(ipv6_cidr)) = to.attributes.get("ipv6_cidr_block")
+            && !from.attributes.contains_key("ipv6_cidr_block")
+        {
+            self.ec2_client
+                .associate_subnet_cidr_block()
+                .subnet_id(&subnet_id)
+                .ipv6_cidr_block(ipv6_cidr)
+                .send()
+                .await
+                .map_err(|e| {
+                    ProviderError::new(format!(
+                        "Failed to associate IPv6 CIDR block: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(id.clone())
+                })?;
+        }
+
+        self.read_ec2_subnet(&id.name).await
+    }
+
+    /// Turn public IPv4 addresses for instances launched in a subnet on or off
+    async fn set_subnet_map_public_ip_on_launch(
+        &self,
+        id: &ResourceId,
+        subnet_id: &str,
+        enabled: bool,
+    ) -> ProviderResult<()> {
         self.ec2_client
-            .delete_subnet()
-            .subnet_id(&subnet_id)
+            .modify_subnet_attribute()
+            .subnet_id(subnet_id)
+            .map_public_ip_on_launch(
+                aws_sdk_ec2::types::AttributeBooleanValue::builder()
+                    .value(enabled)
+                    .build(),
+            )
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to delete subnet: {:?}", e))
-                    .for_resource(id.clone())
+                ProviderError::new(format!(
+                    "Failed to set map_public_ip_on_launch: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
             })?;
+        Ok(())
+    }
+
+    /// Delete an EC2 Subnet
+    async fn delete_ec2_subnet(&self, id: ResourceId) -> ProviderResult<()> {
+        let subnet_id = self
+            .find_subnet_id_by_name(&id.name)
+            .await?
+            .ok_or_else(|| ProviderError::new("Subnet not found").for_resource(id.clone()))?;
+
+        retry_throttled(&self.retry, || {
+            self.ec2_client.delete_subnet().subnet_id(&subnet_id).send()
+        })
+        .await
+        .map_err(|e| {
+            ProviderError::new(format!("Failed to delete subnet: {}", format_sdk_error(&e)))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
+        })?;
 
         Ok(())
     }
@@ -911,13 +1915,18 @@ impl AwsProvider {
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to describe internet gateways: {:?}", e))
-                    .for_resource(id.clone())
+                ProviderError::new(format!(
+                    "Failed to describe internet gateways: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
             })?;
 
         if let Some(igw) = result.internet_gateways().first() {
             let mut attributes = HashMap::new();
             attributes.insert("name".to_string(), Value::String(name.to_string()));
+            attributes.insert("tags".to_string(), tags_attribute(igw.tags()));
 
             let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
             attributes.insert("region".to_string(), Value::String(region_dsl));
@@ -926,6 +1935,8 @@ impl AwsProvider {
             let igw_id_str = igw.internet_gateway_id().map(String::from);
             if let Some(ref igw_id) = igw_id_str {
                 attributes.insert("id".to_string(), Value::String(igw_id.clone()));
+                let arn = self.ec2_arn(&id, "internet-gateway", igw_id).await?;
+                attributes.insert("arn".to_string(), Value::String(arn));
             }
 
             // Store attached VPC ID
@@ -963,8 +1974,12 @@ impl AwsProvider {
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to create internet gateway: {:?}", e))
-                    .for_resource(resource.id.clone())
+                ProviderError::new(format!(
+                    "Failed to create internet gateway: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
             })?;
 
         let igw_id = result
@@ -975,21 +1990,20 @@ impl AwsProvider {
                     .for_resource(resource.id.clone())
             })?;
 
-        // Tag with Name
+        // Tag with Name and any additional tags
         self.ec2_client
             .create_tags()
             .resources(igw_id)
-            .tags(
-                aws_sdk_ec2::types::Tag::builder()
-                    .key("Name")
-                    .value(&name)
-                    .build(),
-            )
+            .set_tags(Some(ec2_tags(&name, &resource.attributes)))
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to tag internet gateway: {:?}", e))
-                    .for_resource(resource.id.clone())
+                ProviderError::new(format!(
+                    "Failed to tag internet gateway: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
             })?;
 
         // Attach to VPC if specified
@@ -1001,22 +2015,34 @@ impl AwsProvider {
                 .send()
                 .await
                 .map_err(|e| {
-                    ProviderError::new(format!("Failed to attach internet gateway: {:?}", e))
-                        .for_resource(resource.id.clone())
+                    ProviderError::new(format!(
+                        "Failed to attach internet gateway: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(resource.id.clone())
                 })?;
         }
 
-        self.read_ec2_internet_gateway(&name).await
+        self.read_after_create(&resource, igw_id, || self.read_ec2_internet_gateway(&name))
+            .await
     }
 
     /// Update an EC2 Internet Gateway
     async fn update_ec2_internet_gateway(
         &self,
         id: ResourceId,
-        _to: Resource,
+        from: State,
+        to: Resource,
     ) -> ProviderResult<State> {
-        // Internet Gateway attributes are mostly immutable
-        // VPC attachment changes would require detach/attach
+        // The VPC attachment is immutable (it would require detach/attach),
+        // so only tags change in place
+        let igw_id = from.identifier.clone().ok_or_else(|| {
+            ProviderError::new("Internet Gateway not found").for_resource(id.clone())
+        })?;
+
+        self.sync_ec2_tags(&id, &igw_id, &from, &to).await?;
+
         self.read_ec2_internet_gateway(&id.name).await
     }
 
@@ -1033,8 +2059,12 @@ impl AwsProvider {
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to describe internet gateway: {:?}", e))
-                    .for_resource(id.clone())
+                ProviderError::new(format!(
+                    "Failed to describe internet gateway: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
             })?;
 
         let igw = result.internet_gateways().first().ok_or_else(|| {
@@ -1056,8 +2086,12 @@ impl AwsProvider {
                 .send()
                 .await
                 .map_err(|e| {
-                    ProviderError::new(format!("Failed to detach internet gateway: {:?}", e))
-                        .for_resource(id.clone())
+                    ProviderError::new(format!(
+                        "Failed to detach internet gateway: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(id.clone())
                 })?;
         }
 
@@ -1068,93 +2102,72 @@ impl AwsProvider {
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to delete internet gateway: {:?}", e))
-                    .for_resource(id.clone())
+                ProviderError::new(format!(
+                    "Failed to delete internet gateway: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
             })?;
 
         Ok(())
     }
 
-    // ========== EC2 Route Table Operations ==========
-
-    /// Find Route Table ID by Name tag
-    async fn find_route_table_id_by_name(&self, name: &str) -> ProviderResult<Option<String>> {
-        use aws_sdk_ec2::types::Filter;
-
-        let filter = Filter::builder().name("tag:Name").values(name).build();
-
-        let result = self
-            .ec2_client
-            .describe_route_tables()
-            .filters(filter)
-            .send()
-            .await
-            .map_err(|e| ProviderError::new(format!("Failed to describe route tables: {:?}", e)))?;
-
-        Ok(result
-            .route_tables()
-            .first()
-            .and_then(|rt| rt.route_table_id().map(String::from)))
-    }
+    // ========== EC2 Elastic IP Operations ==========
 
-    /// Read an EC2 Route Table
-    async fn read_ec2_route_table(&self, name: &str) -> ProviderResult<State> {
+    /// Read an EC2 Elastic IP. Released addresses are no longer described,
+    /// so they read as not found.
+    async fn read_ec2_eip(&self, name: &str) -> ProviderResult<State> {
         use aws_sdk_ec2::types::Filter;
 
-        let id = ResourceId::new("route_table", name);
+        let id = ResourceId::new("ec2.eip", name);
 
         let filter = Filter::builder().name("tag:Name").values(name).build();
 
         let result = self
             .ec2_client
-            .describe_route_tables()
+            .describe_addresses()
             .filters(filter)
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to describe route tables: {:?}", e))
-                    .for_resource(id.clone())
+                ProviderError::new(format!(
+                    "Failed to describe addresses: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
             })?;
 
-        if let Some(rt) = result.route_tables().first() {
+        if let Some(address) = result.addresses().first() {
             let mut attributes = HashMap::new();
             attributes.insert("name".to_string(), Value::String(name.to_string()));
+            attributes.insert("tags".to_string(), tags_attribute(address.tags()));
 
             let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
             attributes.insert("region".to_string(), Value::String(region_dsl));
 
-            // Store route table ID
-            let rt_id_str = rt.route_table_id().map(String::from);
-            if let Some(ref rt_id) = rt_id_str {
-                attributes.insert("id".to_string(), Value::String(rt_id.clone()));
-            }
-
-            // Store VPC ID
-            if let Some(vpc_id) = rt.vpc_id() {
-                attributes.insert("vpc_id".to_string(), Value::String(vpc_id.to_string()));
+            // Store allocation ID as public attribute and as identifier
+            let allocation_id_str = address.allocation_id().map(String::from);
+            if let Some(ref allocation_id) = allocation_id_str {
+                attributes.insert(
+                    "allocation_id".to_string(),
+                    Value::String(allocation_id.clone()),
+                );
+                let arn = self.ec2_arn(&id, "elastic-ip", allocation_id).await?;
+                attributes.insert("arn".to_string(), Value::String(arn));
             }
 
-            // Convert routes to list
-            let mut routes_list = Vec::new();
-            for route in rt.routes() {
-                let mut route_map = HashMap::new();
-                if let Some(dest) = route.destination_cidr_block() {
-                    route_map.insert("destination".to_string(), Value::String(dest.to_string()));
-                }
-                if let Some(gw) = route.gateway_id() {
-                    route_map.insert("gateway_id".to_string(), Value::String(gw.to_string()));
-                }
-                if !route_map.is_empty() {
-                    routes_list.push(Value::Map(route_map));
-                }
-            }
-            if !routes_list.is_empty() {
-                attributes.insert("routes".to_string(), Value::List(routes_list));
+            if let Some(public_ip) = address.public_ip() {
+                attributes.insert(
+                    "public_ip".to_string(),
+                    Value::String(public_ip.to_string()),
+                );
             }
 
             let state = State::existing(id, attributes);
-            Ok(if let Some(rt_id) = rt_id_str {
-                state.with_identifier(rt_id)
+            Ok(if let Some(allocation_id) = allocation_id_str {
+                state.with_identifier(allocation_id)
             } else {
                 state
             })
@@ -1163,368 +2176,237 @@ impl AwsProvider {
         }
     }
 
-    /// Create an EC2 Route Table
-    async fn create_ec2_route_table(&self, resource: Resource) -> ProviderResult<State> {
+    /// Allocate an EC2 Elastic IP for use in a VPC
+    async fn create_ec2_eip(&self, resource: Resource) -> ProviderResult<State> {
         let name = match resource.attributes.get("name") {
             Some(Value::String(s)) => s.clone(),
             _ => {
-                return Err(ProviderError::new("Route Table name is required")
+                return Err(ProviderError::new("Elastic IP name is required")
                     .for_resource(resource.id.clone()));
             }
         };
 
-        let vpc_id = match resource.attributes.get("vpc_id") {
-            Some(Value::String(s)) => s.clone(),
-            _ => {
-                return Err(
-                    ProviderError::new("VPC ID is required").for_resource(resource.id.clone())
-                );
-            }
-        };
-
-        // Create Route Table
         let result = self
             .ec2_client
-            .create_route_table()
-            .vpc_id(&vpc_id)
+            .allocate_address()
+            .domain(aws_sdk_ec2::types::DomainType::Vpc)
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to create route table: {:?}", e))
-                    .for_resource(resource.id.clone())
+                ProviderError::new(format!(
+                    "Failed to allocate Elastic IP: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
             })?;
 
-        let rt_id = result
-            .route_table()
-            .and_then(|rt| rt.route_table_id())
-            .ok_or_else(|| {
-                ProviderError::new("Route Table created but no ID returned")
-                    .for_resource(resource.id.clone())
-            })?;
+        let allocation_id = result.allocation_id().ok_or_else(|| {
+            ProviderError::new("Elastic IP allocated but no allocation ID returned")
+                .for_resource(resource.id.clone())
+        })?;
 
-        // Tag with Name
+        // Tag with Name and any additional tags
         self.ec2_client
             .create_tags()
-            .resources(rt_id)
-            .tags(
-                aws_sdk_ec2::types::Tag::builder()
-                    .key("Name")
-                    .value(&name)
-                    .build(),
-            )
+            .resources(allocation_id)
+            .set_tags(Some(ec2_tags(&name, &resource.attributes)))
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to tag route table: {:?}", e))
-                    .for_resource(resource.id.clone())
+                ProviderError::new(format!(
+                    "Failed to tag Elastic IP: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
             })?;
 
-        // Add routes
-        if let Some(Value::List(routes)) = resource.attributes.get("routes") {
-            for route in routes {
-                if let Value::Map(route_map) = route {
-                    let destination = route_map.get("destination").and_then(|v| {
-                        if let Value::String(s) = v {
-                            Some(s)
-                        } else {
-                            None
-                        }
-                    });
-                    let gateway_id = route_map.get("gateway_id").and_then(|v| {
-                        if let Value::String(s) = v {
-                            Some(s)
-                        } else {
-                            None
-                        }
-                    });
-
-                    if let (Some(dest), Some(gw_id)) = (destination, gateway_id) {
-                        self.ec2_client
-                            .create_route()
-                            .route_table_id(rt_id)
-                            .destination_cidr_block(dest)
-                            .gateway_id(gw_id)
-                            .send()
-                            .await
-                            .map_err(|e| {
-                                ProviderError::new(format!("Failed to create route: {:?}", e))
-                                    .for_resource(resource.id.clone())
-                            })?;
-                    }
-                }
-            }
-        }
-
-        self.read_ec2_route_table(&name).await
+        self.read_ec2_eip(&name).await
     }
 
-    /// Update an EC2 Route Table
-    async fn update_ec2_route_table(&self, id: ResourceId, _to: Resource) -> ProviderResult<State> {
-        // Route updates would require deleting and recreating routes
-        // For now, just return current state
-        self.read_ec2_route_table(&id.name).await
+    /// Update an EC2 Elastic IP
+    async fn update_ec2_eip(
+        &self,
+        id: ResourceId,
+        from: State,
+        to: Resource,
+    ) -> ProviderResult<State> {
+        // The address itself never changes; only tags change in place
+        let allocation_id = from
+            .identifier
+            .clone()
+            .ok_or_else(|| ProviderError::new("Elastic IP not found").for_resource(id.clone()))?;
+
+        self.sync_ec2_tags(&id, &allocation_id, &from, &to).await?;
+
+        self.read_ec2_eip(&id.name).await
     }
 
-    /// Delete an EC2 Route Table
-    async fn delete_ec2_route_table(&self, id: ResourceId) -> ProviderResult<()> {
-        let rt_id = self
-            .find_route_table_id_by_name(&id.name)
+    /// Release an EC2 Elastic IP
+    async fn delete_ec2_eip(&self, id: ResourceId) -> ProviderResult<()> {
+        let allocation_id = self
+            .read_ec2_eip(&id.name)
             .await?
-            .ok_or_else(|| ProviderError::new("Route Table not found").for_resource(id.clone()))?;
+            .identifier
+            .ok_or_else(|| ProviderError::new("Elastic IP not found").for_resource(id.clone()))?;
 
         self.ec2_client
-            .delete_route_table()
-            .route_table_id(&rt_id)
+            .release_address()
+            .allocation_id(&allocation_id)
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to delete route table: {:?}", e))
-                    .for_resource(id.clone())
+                ProviderError::new(format!(
+                    "Failed to release Elastic IP: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
             })?;
 
         Ok(())
     }
 
-    // ========== EC2 Route Operations ==========
-
-    /// Read an EC2 Route (routes are identified by route_table_id + destination)
-    async fn read_ec2_route(&self, name: &str) -> ProviderResult<State> {
-        // Routes don't have a "name" in AWS - we use the name for identification
-        // The actual route is identified by route_table_id + destination_cidr_block
-        // For read, we return not_found since we can't look up by name alone
-        let id = ResourceId::new("route", name);
-        Ok(State::not_found(id))
-    }
+    // ========== EC2 NAT Gateway Operations ==========
 
-    /// Read an EC2 Route by route_table_id and destination_cidr_block
-    pub async fn read_ec2_route_by_key(
+    /// Find the NAT Gateway with the given Name tag, ignoring deleted gateways
+    /// (AWS keeps them visible for a while after deletion)
+    async fn find_nat_gateway_by_name(
         &self,
         name: &str,
-        route_table_id: &str,
-        destination_cidr_block: &str,
-    ) -> ProviderResult<State> {
-        let id = ResourceId::new("route", name);
+    ) -> ProviderResult<Option<aws_sdk_ec2::types::NatGateway>> {
+        use aws_sdk_ec2::types::Filter;
+
+        let name_filter = Filter::builder().name("tag:Name").values(name).build();
+        let state_filter = Filter::builder()
+            .name("state")
+            .values("pending")
+            .values("available")
+            .build();
 
-        // Describe the route table to get its routes
         let result = self
             .ec2_client
-            .describe_route_tables()
-            .route_table_ids(route_table_id)
+            .describe_nat_gateways()
+            .filter(name_filter)
+            .filter(state_filter)
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to describe route table: {:?}", e))
-                    .for_resource(id.clone())
+                ProviderError::new(format!(
+                    "Failed to describe NAT gateways: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
             })?;
 
-        if let Some(rt) = result.route_tables().first() {
-            // Find the route matching destination_cidr_block
-            for route in rt.routes() {
-                if route.destination_cidr_block() == Some(destination_cidr_block) {
-                    let mut attributes = HashMap::new();
-                    attributes.insert("name".to_string(), Value::String(name.to_string()));
-                    attributes.insert(
-                        "route_table_id".to_string(),
-                        Value::String(route_table_id.to_string()),
-                    );
-                    attributes.insert(
-                        "destination_cidr_block".to_string(),
-                        Value::String(destination_cidr_block.to_string()),
-                    );
-
-                    let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
-                    attributes.insert("region".to_string(), Value::String(region_dsl));
+        Ok(result.nat_gateways().first().cloned())
+    }
 
-                    if let Some(gw_id) = route.gateway_id() {
-                        attributes
-                            .insert("gateway_id".to_string(), Value::String(gw_id.to_string()));
-                    }
-                    if let Some(nat_gw_id) = route.nat_gateway_id() {
-                        attributes.insert(
-                            "nat_gateway_id".to_string(),
-                            Value::String(nat_gw_id.to_string()),
-                        );
-                    }
+    /// Wait until a NAT Gateway reaches the given state. NAT Gateways take
+    /// minutes to provision and to delete.
+    async fn wait_for_nat_gateway_state(
+        &self,
+        id: &ResourceId,
+        nat_gateway_id: &str,
+        target: aws_sdk_ec2::types::NatGatewayState,
+    ) -> ProviderResult<()> {
+        use aws_sdk_ec2::types::NatGatewayState;
 
-                    // Route identifier is route_table_id|destination_cidr_block
-                    let identifier = format!("{}|{}", route_table_id, destination_cidr_block);
-                    return Ok(State::existing(id, attributes).with_identifier(identifier));
-                }
-            }
-        }
+        let max_attempts = 120;
+        let delay = std::time::Duration::from_secs(5);
 
-        Ok(State::not_found(id))
-    }
-
-    /// Create an EC2 Route
-    async fn create_ec2_route(&self, resource: Resource) -> ProviderResult<State> {
-        let name = match resource.attributes.get("name") {
-            Some(Value::String(s)) => s.clone(),
-            _ => {
-                return Err(
-                    ProviderError::new("Route name is required").for_resource(resource.id.clone())
-                );
-            }
-        };
-
-        let route_table_id = match resource.attributes.get("route_table_id") {
-            Some(Value::String(s)) => s.clone(),
-            _ => {
-                return Err(ProviderError::new("route_table_id is required")
-                    .for_resource(resource.id.clone()));
-            }
-        };
-
-        let destination_cidr = match resource.attributes.get("destination_cidr_block") {
-            Some(Value::String(s)) => s.clone(),
-            _ => {
-                return Err(ProviderError::new("destination_cidr_block is required")
-                    .for_resource(resource.id.clone()));
-            }
-        };
-
-        let mut req = self
-            .ec2_client
-            .create_route()
-            .route_table_id(&route_table_id)
-            .destination_cidr_block(&destination_cidr);
-
-        // Add gateway_id if specified
-        if let Some(Value::String(gw_id)) = resource.attributes.get("gateway_id") {
-            req = req.gateway_id(gw_id);
-        }
-
-        // Add nat_gateway_id if specified
-        if let Some(Value::String(nat_gw_id)) = resource.attributes.get("nat_gateway_id") {
-            req = req.nat_gateway_id(nat_gw_id);
-        }
-
-        req.send().await.map_err(|e| {
-            ProviderError::new(format!("Failed to create route: {:?}", e))
-                .for_resource(resource.id.clone())
-        })?;
-
-        // Return state with the route attributes
-        let mut attributes = resource.attributes.clone();
-        attributes.insert("name".to_string(), Value::String(name));
-
-        // Route identifier is route_table_id|destination_cidr_block
-        let identifier = format!("{}|{}", route_table_id, destination_cidr);
-        Ok(State::existing(resource.id, attributes).with_identifier(identifier))
-    }
-
-    /// Update an EC2 Route (replace the route)
-    async fn update_ec2_route(&self, id: ResourceId, to: Resource) -> ProviderResult<State> {
-        let route_table_id = match to.attributes.get("route_table_id") {
-            Some(Value::String(s)) => s.clone(),
-            _ => {
-                return Err(
-                    ProviderError::new("route_table_id is required").for_resource(id.clone())
-                );
-            }
-        };
+        for _ in 0..max_attempts {
+            let result = self
+                .ec2_client
+                .describe_nat_gateways()
+                .nat_gateway_ids(nat_gateway_id)
+                .send()
+                .await
+                .map_err(|e| {
+                    ProviderError::new(format!(
+                        "Failed to describe NAT gateway: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(id.clone())
+                })?;
 
-        let destination_cidr = match to.attributes.get("destination_cidr_block") {
-            Some(Value::String(s)) => s.clone(),
-            _ => {
-                return Err(ProviderError::new("destination_cidr_block is required")
+            let nat = result.nat_gateways().first();
+            match nat.and_then(|n| n.state()) {
+                Some(state) if *state == target => return Ok(()),
+                Some(NatGatewayState::Failed) => {
+                    let message = nat
+                        .and_then(|n| n.failure_message())
+                        .unwrap_or("Unknown error");
+                    return Err(ProviderError::new(format!(
+                        "NAT Gateway {} failed: {}",
+                        nat_gateway_id, message
+                    ))
                     .for_resource(id.clone()));
+                }
+                _ => tokio::time::sleep(delay).await,
             }
-        };
-
-        let mut req = self
-            .ec2_client
-            .replace_route()
-            .route_table_id(&route_table_id)
-            .destination_cidr_block(&destination_cidr);
-
-        // Add gateway_id if specified
-        if let Some(Value::String(gw_id)) = to.attributes.get("gateway_id") {
-            req = req.gateway_id(gw_id);
-        }
-
-        // Add nat_gateway_id if specified
-        if let Some(Value::String(nat_gw_id)) = to.attributes.get("nat_gateway_id") {
-            req = req.nat_gateway_id(nat_gw_id);
         }
 
-        req.send().await.map_err(|e| {
-            ProviderError::new(format!("Failed to update route: {:?}", e)).for_resource(id.clone())
-        })?;
-
-        // Route identifier is route_table_id|destination_cidr_block
-        let identifier = format!("{}|{}", route_table_id, destination_cidr);
-        Ok(State::existing(id, to.attributes.clone()).with_identifier(identifier))
-    }
-
-    // ========== EC2 Security Group Operations ==========
-
-    /// Find Security Group ID by Name tag (not group-name)
-    async fn find_security_group_id_by_name(&self, name: &str) -> ProviderResult<Option<String>> {
-        use aws_sdk_ec2::types::Filter;
-
-        let filter = Filter::builder().name("tag:Name").values(name).build();
-
-        let result = self
-            .ec2_client
-            .describe_security_groups()
-            .filters(filter)
-            .send()
-            .await
-            .map_err(|e| {
-                ProviderError::new(format!("Failed to describe security groups: {:?}", e))
-            })?;
-
-        Ok(result
-            .security_groups()
-            .first()
-            .and_then(|sg| sg.group_id().map(String::from)))
+        Err(ProviderError::new(format!(
+            "Timed out waiting for NAT Gateway {} to become {}",
+            nat_gateway_id,
+            target.as_str()
+        ))
+        .for_resource(id.clone()))
     }
 
-    /// Read an EC2 Security Group
-    async fn read_ec2_security_group(&self, name: &str) -> ProviderResult<State> {
-        use aws_sdk_ec2::types::Filter;
-
-        let id = ResourceId::new("security_group", name);
-
-        let filter = Filter::builder().name("tag:Name").values(name).build();
+    /// Read an EC2 NAT Gateway
+    async fn read_ec2_nat_gateway(&self, name: &str) -> ProviderResult<State> {
+        let id = ResourceId::new("nat_gateway", name);
 
-        let result = self
-            .ec2_client
-            .describe_security_groups()
-            .filters(filter)
-            .send()
-            .await
-            .map_err(|e| {
-                ProviderError::new(format!("Failed to describe security groups: {:?}", e))
-                    .for_resource(id.clone())
-            })?;
+        let nat = self.find_nat_gateway_by_name(name).await?;
 
-        if let Some(sg) = result.security_groups().first() {
+        if let Some(nat) = nat {
             let mut attributes = HashMap::new();
             attributes.insert("name".to_string(), Value::String(name.to_string()));
+            attributes.insert("tags".to_string(), tags_attribute(nat.tags()));
 
             let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
             attributes.insert("region".to_string(), Value::String(region_dsl));
 
-            if let Some(desc) = sg.description() {
-                attributes.insert("description".to_string(), Value::String(desc.to_string()));
+            // Store NAT Gateway ID
+            let nat_id_str = nat.nat_gateway_id().map(String::from);
+            if let Some(ref nat_id) = nat_id_str {
+                attributes.insert("id".to_string(), Value::String(nat_id.clone()));
+                let arn = self.ec2_arn(&id, "natgateway", nat_id).await?;
+                attributes.insert("arn".to_string(), Value::String(arn));
             }
 
-            // Store security group ID
-            let sg_id_str = sg.group_id().map(String::from);
-            if let Some(ref sg_id) = sg_id_str {
-                attributes.insert("id".to_string(), Value::String(sg_id.clone()));
+            if let Some(subnet_id) = nat.subnet_id() {
+                attributes.insert(
+                    "subnet_id".to_string(),
+                    Value::String(subnet_id.to_string()),
+                );
             }
 
-            // Store VPC ID
-            if let Some(vpc_id) = sg.vpc_id() {
-                attributes.insert("vpc_id".to_string(), Value::String(vpc_id.to_string()));
+            // Elastic IP association
+            if let Some(address) = nat.nat_gateway_addresses().first() {
+                if let Some(allocation_id) = address.allocation_id() {
+                    attributes.insert(
+                        "allocation_id".to_string(),
+                        Value::String(allocation_id.to_string()),
+                    );
+                }
+                if let Some(public_ip) = address.public_ip() {
+                    attributes.insert(
+                        "public_ip".to_string(),
+                        Value::String(public_ip.to_string()),
+                    );
+                }
             }
 
-            let state = State::existing(id, attributes);
-            Ok(if let Some(sg_id) = sg_id_str {
-                state.with_identifier(sg_id)
+            // Current lifecycle state; reads report it without waiting
+            let nat_state = nat.state().map(|s| s.as_str().to_string());
+            let state = lifecycle_state(id, attributes, nat_state, "available");
+            Ok(if let Some(nat_id) = nat_id_str {
+                state.with_identifier(nat_id)
             } else {
                 state
             })
@@ -1533,254 +2415,2136 @@ impl AwsProvider {
         }
     }
 
-    /// Create an EC2 Security Group
-    async fn create_ec2_security_group(&self, resource: Resource) -> ProviderResult<State> {
+    /// Create an EC2 NAT Gateway and wait until it is available
+    async fn create_ec2_nat_gateway(&self, resource: Resource) -> ProviderResult<State> {
         let name = match resource.attributes.get("name") {
             Some(Value::String(s)) => s.clone(),
             _ => {
-                return Err(ProviderError::new("Security Group name is required")
+                return Err(ProviderError::new("NAT Gateway name is required")
                     .for_resource(resource.id.clone()));
             }
         };
 
-        let vpc_id = match resource.attributes.get("vpc_id") {
+        let subnet_id = match resource.attributes.get("subnet_id") {
             Some(Value::String(s)) => s.clone(),
             _ => {
                 return Err(
-                    ProviderError::new("VPC ID is required").for_resource(resource.id.clone())
+                    ProviderError::new("Subnet ID is required").for_resource(resource.id.clone())
                 );
             }
         };
 
-        let description = match resource.attributes.get("description") {
+        let allocation_id = match resource.attributes.get("allocation_id") {
             Some(Value::String(s)) => s.clone(),
-            _ => name.clone(), // Use name as description if not specified
+            _ => {
+                return Err(ProviderError::new("Allocation ID is required")
+                    .for_resource(resource.id.clone()));
+            }
         };
 
-        // Create Security Group
+        // Tag at creation so the gateway is findable by name while pending
+        let tag_spec = aws_sdk_ec2::types::TagSpecification::builder()
+            .resource_type(aws_sdk_ec2::types::ResourceType::Natgateway)
+            .set_tags(Some(ec2_tags(&name, &resource.attributes)))
+            .build();
+
         let result = self
             .ec2_client
-            .create_security_group()
-            .group_name(&name)
-            .description(&description)
-            .vpc_id(&vpc_id)
+            .create_nat_gateway()
+            .subnet_id(&subnet_id)
+            .allocation_id(&allocation_id)
+            .tag_specifications(tag_spec)
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to create security group: {:?}", e))
-                    .for_resource(resource.id.clone())
-            })?;
-
-        let sg_id = result.group_id().ok_or_else(|| {
-            ProviderError::new("Security Group created but no ID returned")
+                ProviderError::new(format!(
+                    "Failed to create NAT gateway: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
                 .for_resource(resource.id.clone())
-        })?;
+            })?;
 
-        // Tag with Name
-        self.ec2_client
-            .create_tags()
-            .resources(sg_id)
-            .tags(
-                aws_sdk_ec2::types::Tag::builder()
-                    .key("Name")
-                    .value(&name)
-                    .build(),
-            )
-            .send()
-            .await
-            .map_err(|e| {
-                ProviderError::new(format!("Failed to tag security group: {:?}", e))
+        let nat_id = result
+            .nat_gateway()
+            .and_then(|nat| nat.nat_gateway_id())
+            .ok_or_else(|| {
+                ProviderError::new("NAT Gateway created but no ID returned")
                     .for_resource(resource.id.clone())
             })?;
 
-        self.read_ec2_security_group(&name).await
+        self.wait_for_nat_gateway_state(
+            &resource.id,
+            nat_id,
+            aws_sdk_ec2::types::NatGatewayState::Available,
+        )
+        .await?;
+
+        self.read_ec2_nat_gateway(&name).await
     }
 
-    /// Update an EC2 Security Group
-    async fn update_ec2_security_group(
+    /// Update an EC2 NAT Gateway
+    async fn update_ec2_nat_gateway(
         &self,
         id: ResourceId,
-        _to: Resource,
+        from: State,
+        to: Resource,
     ) -> ProviderResult<State> {
-        // Security group rule updates would require revoking and re-adding rules
-        // For now, just return current state
-        self.read_ec2_security_group(&id.name).await
+        // Subnet and Elastic IP are immutable; only tags change in place
+        let nat_id = from
+            .identifier
+            .clone()
+            .ok_or_else(|| ProviderError::new("NAT Gateway not found").for_resource(id.clone()))?;
+
+        self.sync_ec2_tags(&id, &nat_id, &from, &to).await?;
+
+        self.read_ec2_nat_gateway(&id.name).await
     }
 
-    /// Delete an EC2 Security Group
-    async fn delete_ec2_security_group(&self, id: ResourceId) -> ProviderResult<()> {
-        let sg_id = self
-            .find_security_group_id_by_name(&id.name)
-            .await?
-            .ok_or_else(|| {
-                ProviderError::new("Security Group not found").for_resource(id.clone())
-            })?;
+    /// Delete an EC2 NAT Gateway and wait until it is gone.
+    /// A gateway that is already deleted is not an error.
+    async fn delete_ec2_nat_gateway(&self, id: ResourceId) -> ProviderResult<()> {
+        let nat = self.find_nat_gateway_by_name(&id.name).await?;
 
-        self.ec2_client
-            .delete_security_group()
-            .group_id(&sg_id)
+        let Some(nat_id) = nat.and_then(|n| n.nat_gateway_id().map(String::from)) else {
+            return Ok(());
+        };
+
+        if let Err(e) = self
+            .ec2_client
+            .delete_nat_gateway()
+            .nat_gateway_id(&nat_id)
             .send()
             .await
-            .map_err(|e| {
-                ProviderError::new(format!("Failed to delete security group: {:?}", e))
-                    .for_resource(id.clone())
-            })?;
+        {
+            if e.code() == Some("NatGatewayNotFound") {
+                return Ok(());
+            }
+            return Err(ProviderError::new(format!(
+                "Failed to delete NAT gateway: {}",
+                format_sdk_error(&e)
+            ))
+            .with_sdk_cause(e)
+            .for_resource(id.clone()));
+        }
 
-        Ok(())
+        // Wait so that dependent subnets and Elastic IPs can be released
+        self.wait_for_nat_gateway_state(&id, &nat_id, aws_sdk_ec2::types::NatGatewayState::Deleted)
+            .await
     }
 
-    // ========== EC2 Security Group Rule Operations ==========
+    // ========== EC2 Instance Operations ==========
 
-    /// Find all Security Group Rules by Name tag (returns all rules with the same name)
-    async fn find_security_group_rules_by_name(
+    /// Find the instance with the given Name tag, ignoring terminated instances
+    /// (AWS keeps them visible for a while after termination)
+    async fn find_instance_by_name(
         &self,
         name: &str,
-        is_ingress: bool,
-    ) -> ProviderResult<Vec<aws_sdk_ec2::types::SecurityGroupRule>> {
+    ) -> ProviderResult<Option<aws_sdk_ec2::types::Instance>> {
         use aws_sdk_ec2::types::Filter;
 
-        let filter = Filter::builder().name("tag:Name").values(name).build();
+        let name_filter = Filter::builder().name("tag:Name").values(name).build();
+        let state_filter = Filter::builder()
+            .name("instance-state-name")
+            .values("pending")
+            .values("running")
+            .values("stopping")
+            .values("stopped")
+            .build();
 
         let result = self
             .ec2_client
-            .describe_security_group_rules()
-            .filters(filter)
+            .describe_instances()
+            .filters(name_filter)
+            .filters(state_filter)
             .send()
             .await
             .map_err(|e| {
-                ProviderError::new(format!("Failed to describe security group rules: {:?}", e))
+                ProviderError::new(format!(
+                    "Failed to describe instances: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
             })?;
 
-        // Filter by ingress/egress and collect all matching rules
-        let rules: Vec<_> = result
-            .security_group_rules()
+        Ok(result
+            .reservations()
             .iter()
-            .filter(|rule| rule.is_egress() == Some(!is_ingress))
-            .cloned()
-            .collect();
-
-        Ok(rules)
+            .flat_map(|reservation| reservation.instances())
+            .next()
+            .cloned())
     }
 
-    /// Read an EC2 Security Group Rule
-    async fn read_ec2_security_group_rule(
+    /// Wait until an instance reaches the given state
+    async fn wait_for_instance_state(
         &self,
-        name: &str,
-        is_ingress: bool,
-    ) -> ProviderResult<State> {
-        let resource_type = if is_ingress {
-            "security_group.ingress_rule"
-        } else {
-            "security_group.egress_rule"
-        };
+        id: &ResourceId,
+        instance_id: &str,
+        target: aws_sdk_ec2::types::InstanceStateName,
+    ) -> ProviderResult<()> {
+        let max_attempts = 120;
+        let delay = std::time::Duration::from_secs(5);
+
+        for _ in 0..max_attempts {
+            let result = self
+                .ec2_client
+                .describe_instances()
+                .instance_ids(instance_id)
+                .send()
+                .await
+                .map_err(|e| {
+                    ProviderError::new(format!(
+                        "Failed to describe instance: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(id.clone())
+                })?;
+
+            let state = result
+                .reservations()
+                .iter()
+                .flat_map(|reservation| reservation.instances())
+                .next()
+                .and_then(|instance| instance.state())
+                .and_then(|state| state.name());
+            match state {
+                Some(state) if *state == target => return Ok(()),
+                _ => tokio::time::sleep(delay).await,
+            }
+        }
+
+        Err(ProviderError::new(format!(
+            "Timed out waiting for instance {} to become {}",
+            instance_id,
+            target.as_str()
+        ))
+        .for_resource(id.clone()))
+    }
+
+    /// Read an EC2 Instance
+    async fn read_ec2_instance(&self, name: &str) -> ProviderResult<State> {
+        let id = ResourceId::new("ec2.instance", name);
+
+        let Some(instance) = self.find_instance_by_name(name).await? else {
+            return Ok(State::not_found(id));
+        };
+
+        let mut attributes = HashMap::new();
+        attributes.insert("name".to_string(), Value::String(name.to_string()));
+        attributes.insert("tags".to_string(), tags_attribute(instance.tags()));
+
+        let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
+        attributes.insert("region".to_string(), Value::String(region_dsl));
+
+        let instance_id = instance.instance_id().map(String::from);
+        if let Some(ref instance_id) = instance_id {
+            attributes.insert("id".to_string(), Value::String(instance_id.clone()));
+            let arn = self.ec2_arn(&id, "instance", instance_id).await?;
+            attributes.insert("arn".to_string(), Value::String(arn));
+        }
+        if let Some(ami) = instance.image_id() {
+            attributes.insert("ami".to_string(), Value::String(ami.to_string()));
+        }
+        if let Some(instance_type) = instance.instance_type() {
+            attributes.insert(
+                "instance_type".to_string(),
+                Value::String(instance_type.as_str().to_string()),
+            );
+        }
+        if let Some(subnet_id) = instance.subnet_id() {
+            attributes.insert(
+                "subnet_id".to_string(),
+                Value::String(subnet_id.to_string()),
+            );
+        }
+
+        let security_group_ids: Vec<Value> = instance
+            .security_groups()
+            .iter()
+            .filter_map(|group| group.group_id())
+            .map(|group_id| Value::String(group_id.to_string()))
+            .collect();
+        attributes.insert(
+            "security_group_ids".to_string(),
+            Value::List(security_group_ids),
+        );
+
+        // An instance without a profile reads as "", matching the value that detaches one
+        let iam_instance_profile = instance
+            .iam_instance_profile()
+            .and_then(|profile| profile.arn())
+            .unwrap_or_default();
+        attributes.insert(
+            "iam_instance_profile".to_string(),
+            Value::String(iam_instance_profile.to_string()),
+        );
+
+        if let Some(private_ip) = instance.private_ip_address() {
+            attributes.insert(
+                "private_ip".to_string(),
+                Value::String(private_ip.to_string()),
+            );
+        }
+        if let Some(public_ip) = instance.public_ip_address() {
+            attributes.insert(
+                "public_ip".to_string(),
+                Value::String(public_ip.to_string()),
+            );
+        }
+
+        // Current lifecycle state; reads report it without waiting
+        let instance_state = instance
+            .state()
+            .and_then(|state| state.name())
+            .map(|name| name.as_str().to_string());
+        let state = lifecycle_state(id, attributes, instance_state, "running");
+        Ok(if let Some(instance_id) = instance_id {
+            state.with_identifier(instance_id)
+        } else {
+            state
+        })
+    }
+
+    /// Launch an EC2 Instance and wait until it is running
+    async fn create_ec2_instance(&self, resource: Resource) -> ProviderResult<State> {
+        let name = match resource.attributes.get("name") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(ProviderError::new("Instance name is required")
+                    .for_resource(resource.id.clone()));
+            }
+        };
+
+        let ami = match resource.attributes.get("ami") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(ProviderError::new("AMI is required").for_resource(resource.id.clone()));
+            }
+        };
+
+        let instance_type = match resource.attributes.get("instance_type") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(ProviderError::new("Instance type is required")
+                    .for_resource(resource.id.clone()));
+            }
+        };
+
+        let subnet_id = match resource.attributes.get("subnet_id") {
+            Some(Value::String(s)) => Some(s.clone()),
+            _ => None,
+        };
+
+        let iam_instance_profile = match resource.attributes.get("iam_instance_profile") {
+            Some(Value::String(s)) if !s.is_empty() => Some(iam_instance_profile_spec(s)),
+            _ => None,
+        };
+
+        // Tag at launch so the instance is findable by name while pending
+        let tag_spec = aws_sdk_ec2::types::TagSpecification::builder()
+            .resource_type(aws_sdk_ec2::types::ResourceType::Instance)
+            .set_tags(Some(ec2_tags(&name, &resource.attributes)))
+            .build();
+
+        let result = self
+            .ec2_client
+            .run_instances()
+            .image_id(&ami)
+            .instance_type(aws_sdk_ec2::types::InstanceType::from(
+                instance_type.as_str(),
+            ))
+            .min_count(1)
+            .max_count(1)
+            .set_subnet_id(subnet_id)
+            .set_security_group_ids(string_list(&resource.attributes, "security_group_ids"))
+            .set_iam_instance_profile(iam_instance_profile)
+            .tag_specifications(tag_spec)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to launch instance: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
+            })?;
+
+        let instance_id = result
+            .instances()
+            .first()
+            .and_then(|instance| instance.instance_id())
+            .ok_or_else(|| {
+                ProviderError::new("Instance launched but no ID returned")
+                    .for_resource(resource.id.clone())
+            })?;
+
+        self.wait_for_instance_state(
+            &resource.id,
+            instance_id,
+            aws_sdk_ec2::types::InstanceStateName::Running,
+        )
+        .await?;
+
+        self.read_ec2_instance(&name).await
+    }
+
+    /// Update an EC2 Instance. A new instance type requires the instance to be
+    /// stopped, modified and started again; security groups, the IAM instance profile
+    /// and tags change in place.
+    async fn update_ec2_instance(
+        &self,
+        id: ResourceId,
+        from: State,
+        to: Resource,
+    ) -> ProviderResult<State> {
+        use aws_sdk_ec2::types::{AttributeValue, InstanceStateName};
+
+        let instance_id = from
+            .identifier
+            .clone()
+            .ok_or_else(|| ProviderError::new("Instance not found").for_resource(id.clone()))?;
+
+        if let Some(Value::String(instance_type)) = to.attributes.get("instance_type")
+            && from.attributes.get("instance_type") != Some(&Value::String(instance_type.clone()))
+        {
+            self.ec2_client
+                .stop_instances()
+                .instance_ids(&instance_id)
+                .send()
+                .await
+                .map_err(|e| {
+                    ProviderError::new(format!("Failed to stop instance: {}", format_sdk_error(&e)))
+                        .with_sdk_cause(e)
+                        .for_resource(id.clone())
+                })?;
+            self.wait_for_instance_state(&id, &instance_id, InstanceStateName::Stopped)
+                .await?;
+
+            self.ec2_client
+                .modify_instance_attribute()
+                .instance_id(&instance_id)
+                .instance_type(AttributeValue::builder().value(instance_type).build())
+                .send()
+                .await
+                .map_err(|e| {
+                    ProviderError::new(format!(
+                        "Failed to change instance type: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(id.clone())
+                })?;
+
+            self.ec2_client
+                .start_instances()
+                .instance_ids(&instance_id)
+                .send()
+                .await
+                .map_err(|e| {
+                    ProviderError::new(format!(
+                        "Failed to start instance: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(id.clone())
+                })?;
+            self.wait_for_instance_state(&id, &instance_id, InstanceStateName::Running)
+                .await?;
+        }
+
+        if let Some(groups) = string_list(&to.attributes, "security_group_ids") {
+            let mut current =
+                string_list(&from.attributes, "security_group_ids").unwrap_or_default();
+            let mut desired = groups.clone();
+            current.sort();
+            desired.sort();
+            if current != desired {
+                self.ec2_client
+                    .modify_instance_attribute()
+                    .instance_id(&instance_id)
+                    .set_groups(Some(groups))
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        ProviderError::new(format!(
+                            "Failed to change security groups: {}",
+                            format_sdk_error(&e)
+                        ))
+                        .with_sdk_cause(e)
+                        .for_resource(id.clone())
+                    })?;
+            }
+        }
+
+        if let Some(Value::String(profile)) = to.attributes.get("iam_instance_profile")
+            && !matches!(
+                from.attributes.get("iam_instance_profile"),
+                Some(Value::String(current)) if same_instance_profile(profile, current)
+            )
+        {
+            self.sync_iam_instance_profile(&id, &instance_id, profile)
+                .await?;
+        }
+
+        self.sync_ec2_tags(&id, &instance_id, &from, &to).await?;
+
+        self.read_ec2_instance(&id.name).await
+    }
+
+    /// Attach, replace or detach (for an empty `profile`) the instance's IAM instance profile
+    async fn sync_iam_instance_profile(
+        &self,
+        id: &ResourceId,
+        instance_id: &str,
+        profile: &str,
+    ) -> ProviderResult<()> {
+        use aws_sdk_ec2::types::{Filter, IamInstanceProfileAssociationState};
+
+        let associations = self
+            .ec2_client
+            .describe_iam_instance_profile_associations()
+            .filters(
+                Filter::builder()
+                    .name("instance-id")
+                    .values(instance_id)
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to describe IAM instance profile associations: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
+            })?;
+        let association_id = associations
+            .iam_instance_profile_associations()
+            .iter()
+            .filter(|association| {
+                matches!(
+                    association.state(),
+                    Some(
+                        IamInstanceProfileAssociationState::Associated
+                            | IamInstanceProfileAssociationState::Associating
+                    )
+                )
+            })
+            .find_map(|association| association.association_id())
+            .map(String::from);
+
+        let change_error = |message: String| {
+            ProviderError::new(format!(
+                "Failed to change IAM instance profile: {}",
+                message
+            ))
+        };
+        match (association_id, profile.is_empty()) {
+            (None, true) => {}
+            (Some(association_id), true) => {
+                self.ec2_client
+                    .disassociate_iam_instance_profile()
+                    .association_id(association_id)
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        change_error(format_sdk_error(&e))
+                            .with_sdk_cause(e)
+                            .for_resource(id.clone())
+                    })?;
+            }
+            (Some(association_id), false) => {
+                self.ec2_client
+                    .replace_iam_instance_profile_association()
+                    .association_id(association_id)
+                    .iam_instance_profile(iam_instance_profile_spec(profile))
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        change_error(format_sdk_error(&e))
+                            .with_sdk_cause(e)
+                            .for_resource(id.clone())
+                    })?;
+            }
+            (None, false) => {
+                self.ec2_client
+                    .associate_iam_instance_profile()
+                    .instance_id(instance_id)
+                    .iam_instance_profile(iam_instance_profile_spec(profile))
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        change_error(format_sdk_error(&e))
+                            .with_sdk_cause(e)
+                            .for_resource(id.clone())
+                    })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Terminate an EC2 Instance and wait until it is gone.
+    /// An instance that is already terminated is not an error.
+    async fn delete_ec2_instance(&self, id: ResourceId) -> ProviderResult<()> {
+        let instance = self.find_instance_by_name(&id.name).await?;
+
+        let Some(instance_id) = instance.and_then(|i| i.instance_id().map(String::from)) else {
+            return Ok(());
+        };
+
+        if let Err(e) = self
+            .ec2_client
+            .terminate_instances()
+            .instance_ids(&instance_id)
+            .send()
+            .await
+        {
+            if e.code() == Some("InvalidInstanceID.NotFound") {
+                return Ok(());
+            }
+            return Err(ProviderError::new(format!(
+                "Failed to terminate instance: {}",
+                format_sdk_error(&e)
+            ))
+            .with_sdk_cause(e)
+            .for_resource(id.clone()));
+        }
+
+        // Wait so that the subnet and security groups can be deleted afterwards
+        self.wait_for_instance_state(
+            &id,
+            &instance_id,
+            aws_sdk_ec2::types::InstanceStateName::Terminated,
+        )
+        .await
+    }
+
+    // ========== EC2 Route Table Operations ==========
+
+    /// Find Route Table ID by Name tag
+    async fn find_route_table_id_by_name(&self, name: &str) -> ProviderResult<Option<String>> {
+        use aws_sdk_ec2::types::Filter;
+
+        let filter = Filter::builder().name("tag:Name").values(name).build();
+
+        let result = self
+            .ec2_client
+            .describe_route_tables()
+            .filters(filter)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to describe route tables: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+            })?;
+
+        Ok(result
+            .route_tables()
+            .first()
+            .and_then(|rt| rt.route_table_id().map(String::from)))
+    }
+
+    /// Read an EC2 Route Table
+    async fn read_ec2_route_table(&self, name: &str) -> ProviderResult<State> {
+        use aws_sdk_ec2::types::Filter;
+
+        let id = ResourceId::new("route_table", name);
+
+        let filter = Filter::builder().name("tag:Name").values(name).build();
+
+        let result = self
+            .ec2_client
+            .describe_route_tables()
+            .filters(filter)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to describe route tables: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
+            })?;
+
+        if let Some(rt) = result.route_tables().first() {
+            let mut attributes = HashMap::new();
+            attributes.insert("name".to_string(), Value::String(name.to_string()));
+            attributes.insert("tags".to_string(), tags_attribute(rt.tags()));
+
+            let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
+            attributes.insert("region".to_string(), Value::String(region_dsl));
+
+            // Store route table ID
+            let rt_id_str = rt.route_table_id().map(String::from);
+            if let Some(ref rt_id) = rt_id_str {
+                attributes.insert("id".to_string(), Value::String(rt_id.clone()));
+                let arn = self.ec2_arn(&id, "route-table", rt_id).await?;
+                attributes.insert("arn".to_string(), Value::String(arn));
+            }
+
+            // Store VPC ID
+            if let Some(vpc_id) = rt.vpc_id() {
+                attributes.insert("vpc_id".to_string(), Value::String(vpc_id.to_string()));
+            }
+
+            // Convert routes to list, leaving out the implicit local route
+            let mut routes_list = Vec::new();
+            for route in rt.routes() {
+                let Some(dest) = route.destination_cidr_block() else {
+                    continue;
+                };
+                if route.gateway_id() == Some("local") {
+                    continue;
+                }
+                let mut route_map = BTreeMap::new();
+                route_map.insert("destination".to_string(), Value::String(dest.to_string()));
+                if let Some(gw) = route.gateway_id() {
+                    route_map.insert("gateway_id".to_string(), Value::String(gw.to_string()));
+                }
+                if let Some(nat_gw) = route.nat_gateway_id() {
+                    route_map.insert(
+                        "nat_gateway_id".to_string(),
+                        Value::String(nat_gw.to_string()),
+                    );
+                }
+                routes_list.push(Value::Map(route_map));
+            }
+            if !routes_list.is_empty() {
+                attributes.insert("routes".to_string(), Value::List(routes_list));
+            }
+
+            let state = State::existing(id, attributes);
+            Ok(if let Some(rt_id) = rt_id_str {
+                state.with_identifier(rt_id)
+            } else {
+                state
+            })
+        } else {
+            Ok(State::not_found(id))
+        }
+    }
+
+    /// Create an EC2 Route Table
+    async fn create_ec2_route_table(&self, resource: Resource) -> ProviderResult<State> {
+        let name = match resource.attributes.get("name") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(ProviderError::new("Route Table name is required")
+                    .for_resource(resource.id.clone()));
+            }
+        };
+
+        let vpc_id = match resource.attributes.get("vpc_id") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(
+                    ProviderError::new("VPC ID is required").for_resource(resource.id.clone())
+                );
+            }
+        };
+
+        // Create Route Table
+        let result = self
+            .ec2_client
+            .create_route_table()
+            .vpc_id(&vpc_id)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to create route table: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
+            })?;
+
+        let rt_id = result
+            .route_table()
+            .and_then(|rt| rt.route_table_id())
+            .ok_or_else(|| {
+                ProviderError::new("Route Table created but no ID returned")
+                    .for_resource(resource.id.clone())
+            })?;
+
+        // Tag with Name and any additional tags
+        self.ec2_client
+            .create_tags()
+            .resources(rt_id)
+            .set_tags(Some(ec2_tags(&name, &resource.attributes)))
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to tag route table: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
+            })?;
+
+        // Add routes
+        let routes = route_targets(resource.attributes.get("routes"));
+        self.sync_routes(&resource.id, rt_id, &BTreeMap::new(), &routes)
+            .await?;
+
+        self.read_after_create(&resource, rt_id, || self.read_ec2_route_table(&name))
+            .await
+    }
+
+    /// Update an EC2 Route Table
+    async fn update_ec2_route_table(
+        &self,
+        id: ResourceId,
+        from: State,
+        to: Resource,
+    ) -> ProviderResult<State> {
+        let rt_id = self
+            .find_route_table_id_by_name(&id.name)
+            .await?
+            .ok_or_else(|| ProviderError::new("Route Table not found").for_resource(id.clone()))?;
+
+        self.sync_ec2_tags(&id, &rt_id, &from, &to).await?;
+
+        // Inline routes are only managed when declared, so that routes added
+        // through aws.route resources are left alone
+        if to.attributes.contains_key("routes") {
+            let live = self.read_ec2_route_table(&id.name).await?;
+            let current = route_targets(live.attributes.get("routes"));
+            let desired = route_targets(to.attributes.get("routes"));
+            self.sync_routes(&id, &rt_id, &current, &desired).await?;
+        }
+
+        self.read_ec2_route_table(&id.name).await
+    }
+
+    /// Create, replace and delete routes so that a route table's routes go
+    /// from `current` to `desired` (both keyed by destination CIDR)
+    async fn sync_routes(
+        &self,
+        id: &ResourceId,
+        rt_id: &str,
+        current: &BTreeMap<String, RouteTarget>,
+        desired: &BTreeMap<String, RouteTarget>,
+    ) -> ProviderResult<()> {
+        for (dest, target) in desired {
+            let result = match current.get(dest) {
+                Some(existing) if existing == target => continue,
+                Some(_) => {
+                    let req = self
+                        .ec2_client
+                        .replace_route()
+                        .route_table_id(rt_id)
+                        .destination_cidr_block(dest);
+                    match target {
+                        RouteTarget::Gateway(gw_id) => req.gateway_id(gw_id),
+                        RouteTarget::NatGateway(nat_gw_id) => req.nat_gateway_id(nat_gw_id),
+                    }
+                    .send()
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| {
+                        ProviderError::new(format!(
+                            "Failed to replace route to {}: {}",
+                            dest,
+                            format_sdk_error(&e)
+                        ))
+                        .with_sdk_cause(e)
+                    })
+                }
+                None => {
+                    let req = self
+                        .ec2_client
+                        .create_route()
+                        .route_table_id(rt_id)
+                        .destination_cidr_block(dest);
+                    match target {
+                        RouteTarget::Gateway(gw_id) => req.gateway_id(gw_id),
+                        RouteTarget::NatGateway(nat_gw_id) => req.nat_gateway_id(nat_gw_id),
+                    }
+                    .send()
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| {
+                        ProviderError::new(format!(
+                            "Failed to create route to {}: {}",
+                            dest,
+                            format_sdk_error(&e)
+                        ))
+                        .with_sdk_cause(e)
+                    })
+                }
+            };
+            result.map_err(|e| e.for_resource(id.clone()))?;
+        }
+
+        for dest in current.keys().filter(|dest| !desired.contains_key(*dest)) {
+            self.ec2_client
+                .delete_route()
+                .route_table_id(rt_id)
+                .destination_cidr_block(dest)
+                .send()
+                .await
+                .map_err(|e| {
+                    ProviderError::new(format!(
+                        "Failed to delete route to {}: {}",
+                        dest,
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(id.clone())
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Delete an EC2 Route Table
+    async fn delete_ec2_route_table(&self, id: ResourceId) -> ProviderResult<()> {
+        let rt_id = self
+            .find_route_table_id_by_name(&id.name)
+            .await?
+            .ok_or_else(|| ProviderError::new("Route Table not found").for_resource(id.clone()))?;
+
+        self.ec2_client
+            .delete_route_table()
+            .route_table_id(&rt_id)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to delete route table: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
+            })?;
+
+        Ok(())
+    }
+
+    // ========== EC2 Route Table Association Operations ==========
+
+    /// Read a Route Table Association. Associations have no Name tag, so they
+    /// are identified by route_table_id|subnet_id and located by describing
+    /// the route table's associations.
+    async fn read_ec2_route_table_association(
+        &self,
+        name: &str,
+        identifier: Option<&str>,
+    ) -> ProviderResult<State> {
+        let id = ResourceId::new("route_table_association", name);
+
+        let Some((route_table_id, subnet_id)) = identifier.and_then(|i| i.split_once('|')) else {
+            return Ok(State::not_found(id));
+        };
+
+        let result = self
+            .ec2_client
+            .describe_route_tables()
+            .route_table_ids(route_table_id)
+            .send()
+            .await;
+
+        let result = match result {
+            Ok(result) => result,
+            // The route table itself is gone, and the association with it
+            Err(e) if e.code() == Some("InvalidRouteTableID.NotFound") => {
+                return Ok(State::not_found(id));
+            }
+            Err(e) => {
+                return Err(ProviderError::new(format!(
+                    "Failed to describe route table: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone()));
+            }
+        };
+
+        let association = result
+            .route_tables()
+            .iter()
+            .flat_map(|rt| rt.associations())
+            .find(|assoc| assoc.subnet_id() == Some(subnet_id));
+
+        let Some(association_id) = association.and_then(|assoc| assoc.route_table_association_id())
+        else {
+            return Ok(State::not_found(id));
+        };
+
+        let mut attributes = HashMap::new();
+        attributes.insert("name".to_string(), Value::String(name.to_string()));
+
+        let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
+        attributes.insert("region".to_string(), Value::String(region_dsl));
+
+        attributes.insert(
+            "route_table_id".to_string(),
+            Value::String(route_table_id.to_string()),
+        );
+        attributes.insert(
+            "subnet_id".to_string(),
+            Value::String(subnet_id.to_string()),
+        );
+        attributes.insert(
+            "association_id".to_string(),
+            Value::String(association_id.to_string()),
+        );
+
+        Ok(State::existing(id, attributes)
+            .with_identifier(format!("{}|{}", route_table_id, subnet_id)))
+    }
+
+    /// Associate a Route Table with a Subnet
+    async fn create_ec2_route_table_association(
+        &self,
+        resource: Resource,
+    ) -> ProviderResult<State> {
+        let route_table_id = match resource.attributes.get("route_table_id") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(ProviderError::new("Route Table ID is required")
+                    .for_resource(resource.id.clone()));
+            }
+        };
+
+        let subnet_id = match resource.attributes.get("subnet_id") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(
+                    ProviderError::new("Subnet ID is required").for_resource(resource.id.clone())
+                );
+            }
+        };
+
+        self.ec2_client
+            .associate_route_table()
+            .route_table_id(&route_table_id)
+            .subnet_id(&subnet_id)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to associate route table: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
+            })?;
+
+        let identifier = format!("{}|{}", route_table_id, subnet_id);
+        self.read_ec2_route_table_association(&resource.id.name, Some(&identifier))
+            .await
+    }
+
+    /// Disassociate a Route Table from a Subnet. An association that is
+    /// already gone is not an error.
+    async fn delete_ec2_route_table_association(
+        &self,
+        id: ResourceId,
+        identifier: &str,
+    ) -> ProviderResult<()> {
+        let state = self
+            .read_ec2_route_table_association(&id.name, Some(identifier))
+            .await?;
+
+        let Some(Value::String(association_id)) = state.attributes.get("association_id") else {
+            return Ok(());
+        };
+
+        match self
+            .ec2_client
+            .disassociate_route_table()
+            .association_id(association_id)
+            .send()
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) if e.code() == Some("InvalidAssociationID.NotFound") => Ok(()),
+            Err(e) => Err(ProviderError::new(format!(
+                "Failed to disassociate route table: {}",
+                format_sdk_error(&e)
+            ))
+            .with_sdk_cause(e)
+            .for_resource(id.clone())),
+        }
+    }
+
+    // ========== EC2 Route Operations ==========
+
+    /// Read an EC2 Route (routes are identified by route_table_id + destination)
+    async fn read_ec2_route(&self, name: &str) -> ProviderResult<State> {
+        // Routes don't have a "name" in AWS - we use the name for identification
+        // The actual route is identified by route_table_id + destination_cidr_block
+        // For read, we return not_found since we can't look up by name alone
+        let id = ResourceId::new("route", name);
+        Ok(State::not_found(id))
+    }
+
+    /// Read an EC2 Route by route_table_id and destination_cidr_block
+    pub async fn read_ec2_route_by_key(
+        &self,
+        name: &str,
+        route_table_id: &str,
+        destination_cidr_block: &str,
+    ) -> ProviderResult<State> {
+        let id = ResourceId::new("route", name);
+
+        // Describe the route table to get its routes
+        let result = self
+            .ec2_client
+            .describe_route_tables()
+            .route_table_ids(route_table_id)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to describe route table: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
+            })?;
+
+        if let Some(rt) = result.route_tables().first() {
+            // Find the route matching destination_cidr_block
+            for route in rt.routes() {
+                if route.destination_cidr_block() == Some(destination_cidr_block) {
+                    let mut attributes = HashMap::new();
+                    attributes.insert("name".to_string(), Value::String(name.to_string()));
+                    attributes.insert(
+                        "route_table_id".to_string(),
+                        Value::String(route_table_id.to_string()),
+                    );
+                    attributes.insert(
+                        "destination_cidr_block".to_string(),
+                        Value::String(destination_cidr_block.to_string()),
+                    );
+
+                    let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
+                    attributes.insert("region".to_string(), Value::String(region_dsl));
+
+                    if let Some(gw_id) = route.gateway_id() {
+                        attributes
+                            .insert("gateway_id".to_string(), Value::String(gw_id.to_string()));
+                    }
+                    if let Some(nat_gw_id) = route.nat_gateway_id() {
+                        attributes.insert(
+                            "nat_gateway_id".to_string(),
+                            Value::String(nat_gw_id.to_string()),
+                        );
+                    }
+
+                    // Route identifier is route_table_id|destination_cidr_block
+                    let identifier = format!("{}|{}", route_table_id, destination_cidr_block);
+                    return Ok(State::existing(id, attributes).with_identifier(identifier));
+                }
+            }
+        }
+
+        Ok(State::not_found(id))
+    }
+
+    /// Create an EC2 Route
+    async fn create_ec2_route(&self, resource: Resource) -> ProviderResult<State> {
+        let name = match resource.attributes.get("name") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(
+                    ProviderError::new("Route name is required").for_resource(resource.id.clone())
+                );
+            }
+        };
+
+        let route_table_id = match resource.attributes.get("route_table_id") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(ProviderError::new("route_table_id is required")
+                    .for_resource(resource.id.clone()));
+            }
+        };
+
+        let destination_cidr = match resource.attributes.get("destination_cidr_block") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(ProviderError::new("destination_cidr_block is required")
+                    .for_resource(resource.id.clone()));
+            }
+        };
+
+        let mut req = self
+            .ec2_client
+            .create_route()
+            .route_table_id(&route_table_id)
+            .destination_cidr_block(&destination_cidr);
+
+        // Add gateway_id if specified
+        if let Some(Value::String(gw_id)) = resource.attributes.get("gateway_id") {
+            req = req.gateway_id(gw_id);
+        }
+
+        // Add nat_gateway_id if specified
+        if let Some(Value::String(nat_gw_id)) = resource.attributes.get("nat_gateway_id") {
+            req = req.nat_gateway_id(nat_gw_id);
+        }
+
+        req.send().await.map_err(|e| {
+            ProviderError::new(format!("Failed to create route: {}", format_sdk_error(&e)))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
+        })?;
+
+        // Return state with the route attributes
+        let mut attributes = resource.attributes.clone();
+        attributes.insert("name".to_string(), Value::String(name));
+
+        // Route identifier is route_table_id|destination_cidr_block
+        let identifier = format!("{}|{}", route_table_id, destination_cidr);
+        Ok(State::existing(resource.id, attributes).with_identifier(identifier))
+    }
+
+    /// Update an EC2 Route (replace the route)
+    async fn update_ec2_route(&self, id: ResourceId, to: Resource) -> ProviderResult<State> {
+        let route_table_id = match to.attributes.get("route_table_id") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(
+                    ProviderError::new("route_table_id is required").for_resource(id.clone())
+                );
+            }
+        };
+
+        let destination_cidr = match to.attributes.get("destination_cidr_block") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(ProviderError::new("destination_cidr_block is required")
+                    .for_resource(id.clone()));
+            }
+        };
+
+        let mut req = self
+            .ec2_client
+            .replace_route()
+            .route_table_id(&route_table_id)
+            .destination_cidr_block(&destination_cidr);
+
+        // Add gateway_id if specified
+        if let Some(Value::String(gw_id)) = to.attributes.get("gateway_id") {
+            req = req.gateway_id(gw_id);
+        }
+
+        // Add nat_gateway_id if specified
+        if let Some(Value::String(nat_gw_id)) = to.attributes.get("nat_gateway_id") {
+            req = req.nat_gateway_id(nat_gw_id);
+        }
+
+        req.send().await.map_err(|e| {
+            ProviderError::new(format!("Failed to update route: {}", format_sdk_error(&e)))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
+        })?;
+
+        // Route identifier is route_table_id|destination_cidr_block
+        let identifier = format!("{}|{}", route_table_id, destination_cidr);
+        Ok(State::existing(id, to.attributes.clone()).with_identifier(identifier))
+    }
+
+    // ========== EC2 Security Group Operations ==========
+
+    /// Find Security Group ID by Name tag (not group-name)
+    async fn find_security_group_id_by_name(&self, name: &str) -> ProviderResult<Option<String>> {
+        use aws_sdk_ec2::types::Filter;
+
+        let filter = Filter::builder().name("tag:Name").values(name).build();
+
+        let result = self
+            .ec2_client
+            .describe_security_groups()
+            .filters(filter)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to describe security groups: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+            })?;
+
+        Ok(result
+            .security_groups()
+            .first()
+            .and_then(|sg| sg.group_id().map(String::from)))
+    }
+
+    /// Read an EC2 Security Group
+    async fn read_ec2_security_group(&self, name: &str) -> ProviderResult<State> {
+        use aws_sdk_ec2::types::Filter;
+
+        let id = ResourceId::new("security_group", name);
+
+        let filter = Filter::builder().name("tag:Name").values(name).build();
+
+        let result = self
+            .ec2_client
+            .describe_security_groups()
+            .filters(filter)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to describe security groups: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
+            })?;
+
+        if let Some(sg) = result.security_groups().first() {
+            let mut attributes = HashMap::new();
+            attributes.insert("name".to_string(), Value::String(name.to_string()));
+            attributes.insert("tags".to_string(), tags_attribute(sg.tags()));
+
+            let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
+            attributes.insert("region".to_string(), Value::String(region_dsl));
+
+            if let Some(desc) = sg.description() {
+                attributes.insert("description".to_string(), Value::String(desc.to_string()));
+            }
+
+            // Store security group ID
+            let sg_id_str = sg.group_id().map(String::from);
+            if let Some(ref sg_id) = sg_id_str {
+                attributes.insert("id".to_string(), Value::String(sg_id.clone()));
+                let arn = self.ec2_arn(&id, "security-group", sg_id).await?;
+                attributes.insert("arn".to_string(), Value::String(arn));
+            }
+
+            // Store VPC ID
+            if let Some(vpc_id) = sg.vpc_id() {
+                attributes.insert("vpc_id".to_string(), Value::String(vpc_id.to_string()));
+            }
+
+            let state = State::existing(id, attributes);
+            Ok(if let Some(sg_id) = sg_id_str {
+                state.with_identifier(sg_id)
+            } else {
+                state
+            })
+        } else {
+            Ok(State::not_found(id))
+        }
+    }
+
+    /// Create an EC2 Security Group
+    async fn create_ec2_security_group(&self, resource: Resource) -> ProviderResult<State> {
+        let name = match resource.attributes.get("name") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(ProviderError::new("Security Group name is required")
+                    .for_resource(resource.id.clone()));
+            }
+        };
+
+        let vpc_id = match resource.attributes.get("vpc_id") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(
+                    ProviderError::new("VPC ID is required").for_resource(resource.id.clone())
+                );
+            }
+        };
+
+        let description = match resource.attributes.get("description") {
+            Some(Value::String(s)) => s.clone(),
+            _ => name.clone(), // Use name as description if not specified
+        };
+
+        // Create Security Group
+        let result = self
+            .ec2_client
+            .create_security_group()
+            .group_name(&name)
+            .description(&description)
+            .vpc_id(&vpc_id)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to create security group: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
+            })?;
+
+        let sg_id = result.group_id().ok_or_else(|| {
+            ProviderError::new("Security Group created but no ID returned")
+                .for_resource(resource.id.clone())
+        })?;
+
+        // Tag with Name and any additional tags
+        self.ec2_client
+            .create_tags()
+            .resources(sg_id)
+            .set_tags(Some(ec2_tags(&name, &resource.attributes)))
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to tag security group: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
+            })?;
+
+        self.read_after_create(&resource, sg_id, || self.read_ec2_security_group(&name))
+            .await
+    }
+
+    /// Update an EC2 Security Group
+    async fn update_ec2_security_group(
+        &self,
+        id: ResourceId,
+        from: State,
+        to: Resource,
+    ) -> ProviderResult<State> {
+        // Rules are managed as separate resources; only tags change in place
+        let sg_id = self
+            .find_security_group_id_by_name(&id.name)
+            .await?
+            .ok_or_else(|| {
+                ProviderError::new("Security Group not found").for_resource(id.clone())
+            })?;
+
+        self.sync_ec2_tags(&id, &sg_id, &from, &to).await?;
+
+        self.read_ec2_security_group(&id.name).await
+    }
+
+    /// Delete an EC2 Security Group
+    async fn delete_ec2_security_group(&self, id: ResourceId) -> ProviderResult<()> {
+        let sg_id = self
+            .find_security_group_id_by_name(&id.name)
+            .await?
+            .ok_or_else(|| {
+                ProviderError::new("Security Group not found").for_resource(id.clone())
+            })?;
+
+        self.ec2_client
+            .delete_security_group()
+            .group_id(&sg_id)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to delete security group: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
+            })?;
+
+        Ok(())
+    }
+
+    // ========== EC2 Security Group Rule Operations ==========
+
+    /// Find all Security Group Rules by Name tag (returns all rules with the same name)
+    async fn find_security_group_rules_by_name(
+        &self,
+        name: &str,
+        is_ingress: bool,
+    ) -> ProviderResult<Vec<aws_sdk_ec2::types::SecurityGroupRule>> {
+        use aws_sdk_ec2::types::Filter;
+
+        let filter = Filter::builder().name("tag:Name").values(name).build();
+
+        let result = self
+            .ec2_client
+            .describe_security_group_rules()
+            .filters(filter)
+            .send()
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to describe security group rules: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+            })?;
+
+        // Filter by ingress/egress and collect all matching rules
+        let rules: Vec<_> = result
+            .security_group_rules()
+            .iter()
+            .filter(|rule| rule.is_egress() == Some(!is_ingress))
+            .cloned()
+            .collect();
+
+        Ok(rules)
+    }
+
+    /// Read an EC2 Security Group Rule
+    async fn read_ec2_security_group_rule(
+        &self,
+        name: &str,
+        is_ingress: bool,
+    ) -> ProviderResult<State> {
+        let resource_type = if is_ingress {
+            "security_group.ingress_rule"
+        } else {
+            "security_group.egress_rule"
+        };
         let id = ResourceId::new(resource_type, name);
 
         let rules = self
-            .find_security_group_rules_by_name(name, is_ingress)
+            .find_security_group_rules_by_name(name, is_ingress)
+            .await?;
+
+        if rules.is_empty() {
+            return Ok(State::not_found(id));
+        }
+
+        // Use the first rule for common attributes
+        let first_rule = &rules[0];
+        let mut attributes = HashMap::new();
+        attributes.insert("name".to_string(), Value::String(name.to_string()));
+
+        let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
+        attributes.insert("region".to_string(), Value::String(region_dsl));
+
+        // Store rule IDs (comma-separated if multiple)
+        let rule_ids: Vec<String> = rules
+            .iter()
+            .filter_map(|r| r.security_group_rule_id().map(String::from))
+            .collect();
+        let identifier = if !rule_ids.is_empty() {
+            attributes.insert("id".to_string(), Value::String(rule_ids.join(",")));
+            Some(rule_ids.join(","))
+        } else {
+            None
+        };
+
+        // Store security group ID
+        if let Some(sg_id) = first_rule.group_id() {
+            attributes.insert(
+                "security_group_id".to_string(),
+                Value::String(sg_id.to_string()),
+            );
+        }
+
+        insert_rule_protocol(&mut attributes, first_rule);
+
+        if let Some(description) = first_rule.description() {
+            attributes.insert(
+                "description".to_string(),
+                Value::String(description.to_string()),
+            );
+        }
+
+        // Aggregate cidr_blocks from all rules with the same name
+        let cidr_blocks: Vec<Value> = rules
+            .iter()
+            .filter_map(|r| r.cidr_ipv4().map(|c| Value::String(c.to_string())))
+            .collect();
+        if !cidr_blocks.is_empty() {
+            attributes.insert("cidr_blocks".to_string(), Value::List(cidr_blocks));
+        }
+
+        let state = State::existing(id, attributes);
+        Ok(if let Some(id_str) = identifier {
+            state.with_identifier(id_str)
+        } else {
+            state
+        })
+    }
+
+    /// Create an EC2 Security Group Rule
+    async fn create_ec2_security_group_rule(
+        &self,
+        resource: Resource,
+        is_ingress: bool,
+    ) -> ProviderResult<State> {
+        let name = match resource.attributes.get("name") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(
+                    ProviderError::new("Rule name is required").for_resource(resource.id.clone())
+                );
+            }
+        };
+
+        let sg_id = match resource.attributes.get("security_group_id") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(ProviderError::new("Security Group ID is required")
+                    .for_resource(resource.id.clone()));
+            }
+        };
+
+        // Support both cidr_blocks (list) and cidr (single value) for backwards compatibility
+        let cidrs: Vec<String> = match resource.attributes.get("cidr_blocks") {
+            Some(Value::List(items)) => items
+                .iter()
+                .filter_map(|v| match v {
+                    Value::String(s) => Some(s.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => match resource.attributes.get("cidr") {
+                Some(Value::String(s)) => vec![s.clone()],
+                _ => vec!["0.0.0.0/0".to_string()],
+            },
+        };
+
+        let description = match resource.attributes.get("description") {
+            Some(Value::String(s)) => Some(s.as_str()),
+            _ => None,
+        };
+
+        let permission = ip_permission(&resource.attributes, &cidrs, description);
+
+        let rule_ids: Vec<String> = if is_ingress {
+            let result = retry_throttled(&self.retry, || {
+                self.ec2_client
+                    .authorize_security_group_ingress()
+                    .group_id(&sg_id)
+                    .ip_permissions(permission.clone())
+                    .send()
+            })
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to create ingress rule: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
+            })?;
+
+            result
+                .security_group_rules()
+                .iter()
+                .filter_map(|r| r.security_group_rule_id().map(String::from))
+                .collect()
+        } else {
+            let result = retry_throttled(&self.retry, || {
+                self.ec2_client
+                    .authorize_security_group_egress()
+                    .group_id(&sg_id)
+                    .ip_permissions(permission.clone())
+                    .send()
+            })
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to create egress rule: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
+            })?;
+
+            result
+                .security_group_rules()
+                .iter()
+                .filter_map(|r| r.security_group_rule_id().map(String::from))
+                .collect()
+        };
+
+        // Tag ALL rules with the same Name
+        if !rule_ids.is_empty() {
+            let mut tag_request = self.ec2_client.create_tags();
+            for rule_id in &rule_ids {
+                tag_request = tag_request.resources(rule_id);
+            }
+            tag_request
+                .tags(
+                    aws_sdk_ec2::types::Tag::builder()
+                        .key("Name")
+                        .value(&name)
+                        .build(),
+                )
+                .send()
+                .await
+                .map_err(|e| {
+                    ProviderError::new(format!(
+                        "Failed to tag security group rules: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(resource.id.clone())
+                })?;
+        }
+
+        self.read_ec2_security_group_rule(&name, is_ingress).await
+    }
+
+    /// Update an EC2 Security Group Rule. Every attribute but the description
+    /// is immutable, so an update rewrites the description of each rule in place.
+    async fn update_ec2_security_group_rule(
+        &self,
+        id: ResourceId,
+        to: Resource,
+        is_ingress: bool,
+    ) -> ProviderResult<State> {
+        let rules = self
+            .find_security_group_rules_by_name(&id.name, is_ingress)
+            .await?;
+
+        let Some(sg_id) = rules.first().and_then(|r| r.group_id()) else {
+            return Err(
+                ProviderError::new("Security Group Rule not found").for_resource(id.clone())
+            );
+        };
+
+        let description = match to.attributes.get("description") {
+            Some(Value::String(s)) => s.clone(),
+            _ => String::new(),
+        };
+
+        let mut request = self
+            .ec2_client
+            .modify_security_group_rules()
+            .group_id(sg_id);
+        for rule in &rules {
+            let rule_request = aws_sdk_ec2::types::SecurityGroupRuleRequest::builder()
+                .set_ip_protocol(rule.ip_protocol().map(String::from))
+                .set_from_port(rule.from_port())
+                .set_to_port(rule.to_port())
+                .set_cidr_ipv4(rule.cidr_ipv4().map(String::from))
+                .description(&description)
+                .build();
+            request = request.security_group_rules(
+                aws_sdk_ec2::types::SecurityGroupRuleUpdate::builder()
+                    .set_security_group_rule_id(rule.security_group_rule_id().map(String::from))
+                    .security_group_rule(rule_request)
+                    .build(),
+            );
+        }
+        request.send().await.map_err(|e| {
+            ProviderError::new(format!(
+                "Failed to update security group rules: {}",
+                format_sdk_error(&e)
+            ))
+            .with_sdk_cause(e)
+            .for_resource(id.clone())
+        })?;
+
+        self.read_ec2_security_group_rule(&id.name, is_ingress)
+            .await
+    }
+
+    /// Delete an EC2 Security Group Rule (deletes all rules with the same name tag)
+    async fn delete_ec2_security_group_rule(
+        &self,
+        id: ResourceId,
+        is_ingress: bool,
+    ) -> ProviderResult<()> {
+        let rules = self
+            .find_security_group_rules_by_name(&id.name, is_ingress)
+            .await?;
+
+        if rules.is_empty() {
+            return Err(
+                ProviderError::new("Security Group Rule not found").for_resource(id.clone())
+            );
+        }
+
+        // Collect all rule IDs and the security group ID
+        let rule_ids: Vec<String> = rules
+            .iter()
+            .filter_map(|r| r.security_group_rule_id().map(String::from))
+            .collect();
+
+        let sg_id = rules[0].group_id().ok_or_else(|| {
+            ProviderError::new("Rule has no security group ID").for_resource(id.clone())
+        })?;
+
+        // Delete all rules at once
+        if is_ingress {
+            let mut request = self
+                .ec2_client
+                .revoke_security_group_ingress()
+                .group_id(sg_id);
+            for rule_id in &rule_ids {
+                request = request.security_group_rule_ids(rule_id);
+            }
+            retry_throttled(&self.retry, || request.clone().send())
+                .await
+                .map_err(|e| {
+                    ProviderError::new(format!(
+                        "Failed to delete ingress rules: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(id.clone())
+                })?;
+        } else {
+            let mut request = self
+                .ec2_client
+                .revoke_security_group_egress()
+                .group_id(sg_id);
+            for rule_id in &rule_ids {
+                request = request.security_group_rule_ids(rule_id);
+            }
+            retry_throttled(&self.retry, || request.clone().send())
+                .await
+                .map_err(|e| {
+                    ProviderError::new(format!(
+                        "Failed to delete egress rules: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e)
+                    .for_resource(id.clone())
+                })?;
+        }
+
+        Ok(())
+    }
+
+    // ========== EC2 Unified Security Group Rule Operations ==========
+
+    /// Describe a single Security Group Rule by its rule ID
+    async fn describe_security_group_rule(
+        &self,
+        rule_id: &str,
+    ) -> ProviderResult<Option<aws_sdk_ec2::types::SecurityGroupRule>> {
+        let result = self
+            .ec2_client
+            .describe_security_group_rules()
+            .security_group_rule_ids(rule_id)
+            .send()
+            .await;
+
+        match result {
+            Ok(output) => Ok(output.security_group_rules().first().cloned()),
+            Err(e) if e.code() == Some("InvalidSecurityGroupRuleId.NotFound") => Ok(None),
+            Err(e) => Err(ProviderError::new(format!(
+                "Failed to describe security group rule: {}",
+                format_sdk_error(&e)
+            ))
+            .with_sdk_cause(e)),
+        }
+    }
+
+    /// Read a unified EC2 Security Group Rule by its rule ID
+    async fn read_ec2_security_group_rule_by_id(
+        &self,
+        name: &str,
+        identifier: Option<&str>,
+    ) -> ProviderResult<State> {
+        let id = ResourceId::new("security_group.rule", name);
+
+        let Some(rule_id) = identifier else {
+            return Ok(State::not_found(id));
+        };
+
+        let Some(rule) = self
+            .describe_security_group_rule(rule_id)
+            .await
+            .map_err(|e| e.for_resource(id.clone()))?
+        else {
+            return Ok(State::not_found(id));
+        };
+
+        let mut attributes = HashMap::new();
+        attributes.insert("name".to_string(), Value::String(name.to_string()));
+
+        let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
+        attributes.insert("region".to_string(), Value::String(region_dsl));
+
+        attributes.insert("id".to_string(), Value::String(rule_id.to_string()));
+
+        let rule_type = if rule.is_egress() == Some(true) {
+            "egress"
+        } else {
+            "ingress"
+        };
+        attributes.insert("type".to_string(), Value::String(rule_type.to_string()));
+
+        if let Some(sg_id) = rule.group_id() {
+            attributes.insert(
+                "security_group_id".to_string(),
+                Value::String(sg_id.to_string()),
+            );
+        }
+
+        insert_rule_protocol(&mut attributes, &rule);
+
+        if let Some(cidr) = rule.cidr_ipv4() {
+            attributes.insert("cidr_block".to_string(), Value::String(cidr.to_string()));
+        }
+
+        Ok(State::existing(id, attributes).with_identifier(rule_id))
+    }
+
+    /// Create a unified EC2 Security Group Rule (exactly one AWS rule)
+    async fn create_ec2_security_group_rule_unified(
+        &self,
+        resource: Resource,
+    ) -> ProviderResult<State> {
+        let name = match resource.attributes.get("name") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(
+                    ProviderError::new("Rule name is required").for_resource(resource.id.clone())
+                );
+            }
+        };
+
+        let is_ingress = match resource.attributes.get("type") {
+            Some(Value::String(s)) if s == "ingress" => true,
+            Some(Value::String(s)) if s == "egress" => false,
+            _ => {
+                return Err(
+                    ProviderError::new("Rule type must be \"ingress\" or \"egress\"")
+                        .for_resource(resource.id.clone()),
+                );
+            }
+        };
+
+        let sg_id = match resource.attributes.get("security_group_id") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(ProviderError::new("Security Group ID is required")
+                    .for_resource(resource.id.clone()));
+            }
+        };
+
+        let cidr = match resource.attributes.get("cidr_block") {
+            Some(Value::String(s)) => s.clone(),
+            _ => {
+                return Err(
+                    ProviderError::new("CIDR block is required").for_resource(resource.id.clone())
+                );
+            }
+        };
+
+        let permission = ip_permission(&resource.attributes, &[cidr], None);
+
+        let name_tag = aws_sdk_ec2::types::TagSpecification::builder()
+            .resource_type(aws_sdk_ec2::types::ResourceType::SecurityGroupRule)
+            .tags(
+                aws_sdk_ec2::types::Tag::builder()
+                    .key("Name")
+                    .value(&name)
+                    .build(),
+            )
+            .build();
+
+        let rule_id = if is_ingress {
+            retry_throttled(&self.retry, || {
+                self.ec2_client
+                    .authorize_security_group_ingress()
+                    .group_id(&sg_id)
+                    .ip_permissions(permission.clone())
+                    .tag_specifications(name_tag.clone())
+                    .send()
+            })
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to create ingress rule: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
+            })?
+            .security_group_rules()
+            .first()
+            .and_then(|r| r.security_group_rule_id().map(String::from))
+        } else {
+            retry_throttled(&self.retry, || {
+                self.ec2_client
+                    .authorize_security_group_egress()
+                    .group_id(&sg_id)
+                    .ip_permissions(permission.clone())
+                    .tag_specifications(name_tag.clone())
+                    .send()
+            })
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to create egress rule: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
+            })?
+            .security_group_rules()
+            .first()
+            .and_then(|r| r.security_group_rule_id().map(String::from))
+        };
+
+        let rule_id = rule_id.ok_or_else(|| {
+            ProviderError::new("AWS did not return a security group rule ID")
+                .for_resource(resource.id.clone())
+        })?;
+
+        self.read_ec2_security_group_rule_by_id(&name, Some(&rule_id))
+            .await
+    }
+
+    /// Update a unified EC2 Security Group Rule (rules are immutable, so recreate)
+    async fn update_ec2_security_group_rule_unified(
+        &self,
+        id: ResourceId,
+        identifier: &str,
+        to: Resource,
+    ) -> ProviderResult<State> {
+        self.delete_ec2_security_group_rule_by_id(id, identifier)
             .await?;
+        self.create_ec2_security_group_rule_unified(to).await
+    }
+
+    /// Delete a unified EC2 Security Group Rule by its rule ID
+    async fn delete_ec2_security_group_rule_by_id(
+        &self,
+        id: ResourceId,
+        identifier: &str,
+    ) -> ProviderResult<()> {
+        let rule = self
+            .describe_security_group_rule(identifier)
+            .await
+            .map_err(|e| e.for_resource(id.clone()))?
+            .ok_or_else(|| {
+                ProviderError::new("Security Group Rule not found").for_resource(id.clone())
+            })?;
+
+        let sg_id = rule.group_id().ok_or_else(|| {
+            ProviderError::new("Rule has no security group ID").for_resource(id.clone())
+        })?;
 
-        if rules.is_empty() {
-            return Ok(State::not_found(id));
+        if rule.is_egress() == Some(true) {
+            retry_throttled(&self.retry, || {
+                self.ec2_client
+                    .revoke_security_group_egress()
+                    .group_id(sg_id)
+                    .security_group_rule_ids(identifier)
+                    .send()
+            })
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to delete egress rule: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
+            })?;
+        } else {
+            retry_throttled(&self.retry, || {
+                self.ec2_client
+                    .revoke_security_group_ingress()
+                    .group_id(sg_id)
+                    .security_group_rule_ids(identifier)
+                    .send()
+            })
+            .await
+            .map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to delete ingress rule: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(id.clone())
+            })?;
         }
 
-        // Use the first rule for common attributes
-        let first_rule = &rules[0];
-        let mut attributes = HashMap::new();
-        attributes.insert("name".to_string(), Value::String(name.to_string()));
+        Ok(())
+    }
 
-        let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
-        attributes.insert("region".to_string(), Value::String(region_dsl));
+    // ========== Data Sources ==========
 
-        // Store rule IDs (comma-separated if multiple)
-        let rule_ids: Vec<String> = rules
-            .iter()
-            .filter_map(|r| r.security_group_rule_id().map(String::from))
-            .collect();
-        let identifier = if !rule_ids.is_empty() {
-            attributes.insert("id".to_string(), Value::String(rule_ids.join(",")));
-            Some(rule_ids.join(","))
-        } else {
-            None
+    /// Read the VPC Endpoint Services data source, optionally filtered by `service_type`
+    async fn read_vpc_endpoint_services(&self, resource: &Resource) -> ProviderResult<State> {
+        use aws_sdk_ec2::types::Filter;
+
+        let service_type = match resource.attributes.get("service_type") {
+            Some(Value::String(s)) => Some(s.rsplit('.').next().unwrap_or(s).to_string()),
+            _ => None,
         };
 
-        // Store security group ID
-        if let Some(sg_id) = first_rule.group_id() {
-            attributes.insert(
-                "security_group_id".to_string(),
-                Value::String(sg_id.to_string()),
+        let mut service_names = Vec::new();
+        let mut next_token: Option<String> = None;
+        loop {
+            let mut request = self
+                .ec2_client
+                .describe_vpc_endpoint_services()
+                .set_next_token(next_token.take());
+            if let Some(service_type) = &service_type {
+                request = request.filters(
+                    Filter::builder()
+                        .name("service-type")
+                        .values(service_type)
+                        .build(),
+                );
+            }
+
+            let result = request.send().await.map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to describe VPC endpoint services: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
+            })?;
+
+            service_names.extend(
+                result
+                    .service_details()
+                    .iter()
+                    .filter_map(|d| d.service_name().map(String::from)),
             );
-        }
 
-        if let Some(protocol) = first_rule.ip_protocol() {
-            // Keep protocol as raw string for comparison (tcp, udp, icmp, -1)
-            attributes.insert("protocol".to_string(), Value::String(protocol.to_string()));
+            match result.next_token() {
+                Some(token) if !token.is_empty() => next_token = Some(token.to_string()),
+                _ => break,
+            }
         }
+        service_names.sort();
+        service_names.dedup();
 
-        if let Some(from_port) = first_rule.from_port() {
-            attributes.insert("from_port".to_string(), Value::Int(from_port as i64));
-        }
+        let mut attributes = HashMap::new();
+        attributes.insert("name".to_string(), Value::String(resource.id.name.clone()));
 
-        if let Some(to_port) = first_rule.to_port() {
-            attributes.insert("to_port".to_string(), Value::Int(to_port as i64));
-        }
+        let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
+        attributes.insert("region".to_string(), Value::String(region_dsl));
 
-        // Aggregate cidr_blocks from all rules with the same name
-        let cidr_blocks: Vec<Value> = rules
-            .iter()
-            .filter_map(|r| r.cidr_ipv4().map(|c| Value::String(c.to_string())))
-            .collect();
-        if !cidr_blocks.is_empty() {
-            attributes.insert("cidr_blocks".to_string(), Value::List(cidr_blocks));
+        if let Some(service_type) = service_type {
+            attributes.insert("service_type".to_string(), Value::String(service_type));
         }
+        attributes.insert(
+            "service_names".to_string(),
+            Value::List(service_names.into_iter().map(Value::String).collect()),
+        );
 
-        let state = State::existing(id, attributes);
-        Ok(if let Some(id_str) = identifier {
-            state.with_identifier(id_str)
-        } else {
-            state
-        })
+        Ok(State::existing(resource.id.clone(), attributes))
     }
 
-    /// Create an EC2 Security Group Rule
-    async fn create_ec2_security_group_rule(
-        &self,
-        resource: Resource,
-        is_ingress: bool,
-    ) -> ProviderResult<State> {
-        let name = match resource.attributes.get("name") {
-            Some(Value::String(s)) => s.clone(),
-            _ => {
-                return Err(
-                    ProviderError::new("Rule name is required").for_resource(resource.id.clone())
-                );
-            }
-        };
+    /// Resolve the AMI data source to a single image via DescribeImages
+    async fn read_ami(&self, resource: &Resource) -> ProviderResult<State> {
+        use aws_sdk_ec2::types::Filter;
 
-        let sg_id = match resource.attributes.get("security_group_id") {
+        let name_pattern = match resource.attributes.get("name_pattern") {
             Some(Value::String(s)) => s.clone(),
             _ => {
-                return Err(ProviderError::new("Security Group ID is required")
+                return Err(ProviderError::new("name_pattern is required")
                     .for_resource(resource.id.clone()));
             }
         };
-
-        let protocol = match resource.attributes.get("protocol") {
-            Some(Value::String(s)) => convert_protocol_value(s),
-            _ => "-1".to_string(),
-        };
-
-        let from_port = match resource.attributes.get("from_port") {
-            Some(Value::Int(n)) => *n as i32,
-            _ => 0,
-        };
-
-        let to_port = match resource.attributes.get("to_port") {
-            Some(Value::Int(n)) => *n as i32,
-            _ => 0,
-        };
-
-        // Support both cidr_blocks (list) and cidr (single value) for backwards compatibility
-        let cidrs: Vec<String> = match resource.attributes.get("cidr_blocks") {
+        let owners: Vec<String> = match resource.attributes.get("owners") {
             Some(Value::List(items)) => items
                 .iter()
                 .filter_map(|v| match v {
@@ -1788,153 +4552,105 @@ impl AwsProvider {
                     _ => None,
                 })
                 .collect(),
-            _ => match resource.attributes.get("cidr") {
-                Some(Value::String(s)) => vec![s.clone()],
-                _ => vec!["0.0.0.0/0".to_string()],
-            },
+            _ => Vec::new(),
         };
+        let most_recent = matches!(
+            resource.attributes.get("most_recent"),
+            Some(Value::Bool(true))
+        );
 
-        let mut permission_builder = aws_sdk_ec2::types::IpPermission::builder()
-            .ip_protocol(&protocol)
-            .from_port(from_port)
-            .to_port(to_port);
-
-        for cidr in &cidrs {
-            permission_builder = permission_builder
-                .ip_ranges(aws_sdk_ec2::types::IpRange::builder().cidr_ip(cidr).build());
-        }
-
-        let permission = permission_builder.build();
-
-        let rule_ids: Vec<String> = if is_ingress {
-            let result = self
+        let mut images = Vec::new();
+        let mut next_token: Option<String> = None;
+        loop {
+            let mut request = self
                 .ec2_client
-                .authorize_security_group_ingress()
-                .group_id(&sg_id)
-                .ip_permissions(permission)
-                .send()
-                .await
-                .map_err(|e| {
-                    ProviderError::new(format!("Failed to create ingress rule: {:?}", e))
-                        .for_resource(resource.id.clone())
-                })?;
+                .describe_images()
+                .filters(Filter::builder().name("name").values(&name_pattern).build())
+                .filters(Filter::builder().name("state").values("available").build())
+                .set_next_token(next_token.take());
+            if !owners.is_empty() {
+                request = request.set_owners(Some(owners.clone()));
+            }
 
-            result
-                .security_group_rules()
-                .iter()
-                .filter_map(|r| r.security_group_rule_id().map(String::from))
-                .collect()
-        } else {
-            let result = self
-                .ec2_client
-                .authorize_security_group_egress()
-                .group_id(&sg_id)
-                .ip_permissions(permission)
-                .send()
-                .await
-                .map_err(|e| {
-                    ProviderError::new(format!("Failed to create egress rule: {:?}", e))
-                        .for_resource(resource.id.clone())
-                })?;
+            let result = request.send().await.map_err(|e| {
+                ProviderError::new(format!(
+                    "Failed to describe images: {}",
+                    format_sdk_error(&e)
+                ))
+                .with_sdk_cause(e)
+                .for_resource(resource.id.clone())
+            })?;
 
-            result
-                .security_group_rules()
-                .iter()
-                .filter_map(|r| r.security_group_rule_id().map(String::from))
-                .collect()
-        };
+            images.extend(result.images().iter().cloned());
 
-        // Tag ALL rules with the same Name
-        if !rule_ids.is_empty() {
-            let mut tag_request = self.ec2_client.create_tags();
-            for rule_id in &rule_ids {
-                tag_request = tag_request.resources(rule_id);
+            match result.next_token() {
+                Some(token) if !token.is_empty() => next_token = Some(token.to_string()),
+                _ => break,
             }
-            tag_request
-                .tags(
-                    aws_sdk_ec2::types::Tag::builder()
-                        .key("Name")
-                        .value(&name)
-                        .build(),
-                )
-                .send()
-                .await
-                .map_err(|e| {
-                    ProviderError::new(format!("Failed to tag security group rules: {:?}", e))
-                        .for_resource(resource.id.clone())
-                })?;
         }
 
-        self.read_ec2_security_group_rule(&name, is_ingress).await
-    }
+        let image = match images.len() {
+            0 => {
+                return Err(ProviderError::new(format!(
+                    "No AMI matches name_pattern '{}'{}",
+                    name_pattern,
+                    if owners.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" for owners [{}]", owners.join(", "))
+                    }
+                ))
+                .for_resource(resource.id.clone()));
+            }
+            1 => images.remove(0),
+            _ if most_recent => {
+                // CreationDate is ISO 8601, so string order is chronological
+                images
+                    .into_iter()
+                    .max_by(|a, b| a.creation_date().cmp(&b.creation_date()))
+                    .expect("images is not empty")
+            }
+            n => {
+                return Err(ProviderError::new(format!(
+                    "{} AMIs match name_pattern '{}'; narrow the pattern or set most_recent = true",
+                    n, name_pattern
+                ))
+                .for_resource(resource.id.clone()));
+            }
+        };
 
-    /// Update an EC2 Security Group Rule (rules are immutable, so recreate)
-    async fn update_ec2_security_group_rule(
-        &self,
-        id: ResourceId,
-        to: Resource,
-        is_ingress: bool,
-    ) -> ProviderResult<State> {
-        // Security group rules are immutable - delete and recreate
-        self.delete_ec2_security_group_rule(id.clone(), is_ingress)
-            .await?;
-        self.create_ec2_security_group_rule(to, is_ingress).await
-    }
+        let mut attributes = HashMap::new();
+        attributes.insert("name".to_string(), Value::String(resource.id.name.clone()));
 
-    /// Delete an EC2 Security Group Rule (deletes all rules with the same name tag)
-    async fn delete_ec2_security_group_rule(
-        &self,
-        id: ResourceId,
-        is_ingress: bool,
-    ) -> ProviderResult<()> {
-        let rules = self
-            .find_security_group_rules_by_name(&id.name, is_ingress)
-            .await?;
+        let region_dsl = format!("aws.Region.{}", self.region.replace('-', "_"));
+        attributes.insert("region".to_string(), Value::String(region_dsl));
 
-        if rules.is_empty() {
-            return Err(
-                ProviderError::new("Security Group Rule not found").for_resource(id.clone())
+        if !owners.is_empty() {
+            attributes.insert(
+                "owners".to_string(),
+                Value::List(owners.into_iter().map(Value::String).collect()),
             );
         }
+        attributes.insert("name_pattern".to_string(), Value::String(name_pattern));
+        attributes.insert("most_recent".to_string(), Value::Bool(most_recent));
 
-        // Collect all rule IDs and the security group ID
-        let rule_ids: Vec<String> = rules
-            .iter()
-            .filter_map(|r| r.security_group_rule_id().map(String::from))
-            .collect();
-
-        let sg_id = rules[0].group_id().ok_or_else(|| {
-            ProviderError::new("Rule has no security group ID").for_resource(id.clone())
-        })?;
-
-        // Delete all rules at once
-        if is_ingress {
-            let mut request = self
-                .ec2_client
-                .revoke_security_group_ingress()
-                .group_id(sg_id);
-            for rule_id in &rule_ids {
-                request = request.security_group_rule_ids(rule_id);
-            }
-            request.send().await.map_err(|e| {
-                ProviderError::new(format!("Failed to delete ingress rules: {:?}", e))
-                    .for_resource(id.clone())
-            })?;
-        } else {
-            let mut request = self
-                .ec2_client
-                .revoke_security_group_egress()
-                .group_id(sg_id);
-            for rule_id in &rule_ids {
-                request = request.security_group_rule_ids(rule_id);
-            }
-            request.send().await.map_err(|e| {
-                ProviderError::new(format!("Failed to delete egress rules: {:?}", e))
-                    .for_resource(id.clone())
-            })?;
+        if let Some(image_id) = image.image_id() {
+            attributes.insert("id".to_string(), Value::String(image_id.to_string()));
+        }
+        if let Some(image_name) = image.name() {
+            attributes.insert(
+                "image_name".to_string(),
+                Value::String(image_name.to_string()),
+            );
+        }
+        if let Some(creation_date) = image.creation_date() {
+            attributes.insert(
+                "creation_date".to_string(),
+                Value::String(creation_date.to_string()),
+            );
         }
 
-        Ok(())
+        Ok(State::existing(resource.id.clone(), attributes))
     }
 }
 
@@ -1946,32 +4662,52 @@ impl Provider for AwsProvider {
     fn resource_types(&self) -> Vec<Box<dyn ResourceType>> {
         vec![
             Box::new(S3BucketType),
+            Box::new(S3BucketPolicyType),
             Box::new(VpcType),
             Box::new(SubnetType),
             Box::new(InternetGatewayType),
+            Box::new(NatGatewayType),
+            Box::new(ElasticIpType),
+            Box::new(Ec2InstanceType),
             Box::new(RouteTableType),
+            Box::new(RouteTableAssociationType),
             Box::new(RouteType),
             Box::new(SecurityGroupType),
             Box::new(SecurityGroupIngressRuleType),
             Box::new(SecurityGroupEgressRuleType),
+            Box::new(SecurityGroupRuleType),
+            Box::new(VpcEndpointServicesType),
+            Box::new(AmiType),
         ]
     }
 
     fn read(
         &self,
         id: &ResourceId,
-        _identifier: Option<&str>,
+        identifier: Option<&str>,
     ) -> BoxFuture<'_, ProviderResult<State>> {
-        // Note: For AWS provider, we currently use name-based lookup.
-        // The identifier parameter is available for future optimization.
+        // Note: Most AWS resources use name-based lookup; security_group.rule,
+        // route_table_association and s3.bucket_policy are addressed by their identifier.
         let id = id.clone();
+        let identifier = identifier.map(String::from);
         Box::pin(async move {
             match id.resource_type.as_str() {
                 "s3.bucket" => self.read_s3_bucket(&id.name).await,
+                "s3.bucket_policy" => {
+                    self.read_s3_bucket_policy(&id.name, identifier.as_deref())
+                        .await
+                }
                 "vpc" => self.read_ec2_vpc(&id.name).await,
                 "subnet" => self.read_ec2_subnet(&id.name).await,
                 "internet_gateway" => self.read_ec2_internet_gateway(&id.name).await,
+                "nat_gateway" => self.read_ec2_nat_gateway(&id.name).await,
+                "ec2.eip" => self.read_ec2_eip(&id.name).await,
+                "ec2.instance" => self.read_ec2_instance(&id.name).await,
                 "route_table" => self.read_ec2_route_table(&id.name).await,
+                "route_table_association" => {
+                    self.read_ec2_route_table_association(&id.name, identifier.as_deref())
+                        .await
+                }
                 "route" => self.read_ec2_route(&id.name).await,
                 "security_group" => self.read_ec2_security_group(&id.name).await,
                 "security_group.ingress_rule" => {
@@ -1980,6 +4716,10 @@ impl Provider for AwsProvider {
                 "security_group.egress_rule" => {
                     self.read_ec2_security_group_rule(&id.name, false).await
                 }
+                "security_group.rule" => {
+                    self.read_ec2_security_group_rule_by_id(&id.name, identifier.as_deref())
+                        .await
+                }
                 _ => Err(ProviderError::new(format!(
                     "Unknown resource type: {}",
                     id.resource_type
@@ -1989,15 +4729,47 @@ impl Provider for AwsProvider {
         })
     }
 
-    fn create(&self, resource: &Resource) -> BoxFuture<'_, ProviderResult<State>> {
+    fn read_managed_resource(
+        &self,
+        resource: &Resource,
+        identifier: Option<&str>,
+    ) -> BoxFuture<'_, ProviderResult<State>> {
+        let resource = resource.clone();
+        let identifier = identifier.map(String::from);
+        Box::pin(async move {
+            let state = self.read(&resource.id, identifier.as_deref()).await?;
+            Ok(in_configured_form(&resource, state))
+        })
+    }
+
+    fn read_data_source(&self, resource: &Resource) -> BoxFuture<'_, ProviderResult<State>> {
         let resource = resource.clone();
         Box::pin(async move {
             match resource.id.resource_type.as_str() {
+                "vpc_endpoint_services" => self.read_vpc_endpoint_services(&resource).await,
+                "ec2.ami" => self.read_ami(&resource).await,
+                _ => self.read(&resource.id, None).await,
+            }
+        })
+    }
+
+    fn create(&self, resource: &Resource) -> BoxFuture<'_, ProviderResult<State>> {
+        let resource = resource.clone();
+        Box::pin(async move {
+            let configured = resource.clone();
+            let result = match resource.id.resource_type.as_str() {
                 "s3.bucket" => self.create_s3_bucket(resource).await,
+                "s3.bucket_policy" => self.create_s3_bucket_policy(resource).await,
                 "vpc" => self.create_ec2_vpc(resource).await,
                 "subnet" => self.create_ec2_subnet(resource).await,
                 "internet_gateway" => self.create_ec2_internet_gateway(resource).await,
+                "nat_gateway" => self.create_ec2_nat_gateway(resource).await,
+                "ec2.eip" => self.create_ec2_eip(resource).await,
+                "ec2.instance" => self.create_ec2_instance(resource).await,
                 "route_table" => self.create_ec2_route_table(resource).await,
+                "route_table_association" => {
+                    self.create_ec2_route_table_association(resource).await
+                }
                 "route" => self.create_ec2_route(resource).await,
                 "security_group" => self.create_ec2_security_group(resource).await,
                 "security_group.ingress_rule" => {
@@ -2006,61 +4778,86 @@ impl Provider for AwsProvider {
                 "security_group.egress_rule" => {
                     self.create_ec2_security_group_rule(resource, false).await
                 }
+                "security_group.rule" => {
+                    self.create_ec2_security_group_rule_unified(resource).await
+                }
                 _ => Err(ProviderError::new(format!(
                     "Unknown resource type: {}",
                     resource.id.resource_type
                 ))
                 .for_resource(resource.id.clone())),
-            }
+            };
+            result.map(|state| in_configured_form(&configured, state))
         })
     }
 
     fn update(
         &self,
         id: &ResourceId,
-        _identifier: &str,
-        _from: &State,
+        identifier: &str,
+        from: &State,
         to: &Resource,
     ) -> BoxFuture<'_, ProviderResult<State>> {
-        // Note: For AWS provider, we currently use name-based lookup.
-        // The identifier parameter is available for future optimization.
+        // Note: Most AWS resources use name-based lookup; security_group.rule
+        // is addressed by its rule ID, s3.bucket_policy by its bucket.
         let id = id.clone();
+        let identifier = identifier.to_string();
+        let from = from.clone();
         let to = to.clone();
         Box::pin(async move {
-            match id.resource_type.as_str() {
-                "s3.bucket" => self.update_s3_bucket(id, to).await,
-                "vpc" => self.update_ec2_vpc(id, to).await,
-                "subnet" => self.update_ec2_subnet(id, to).await,
-                "internet_gateway" => self.update_ec2_internet_gateway(id, to).await,
-                "route_table" => self.update_ec2_route_table(id, to).await,
+            let configured = to.clone();
+            let result = match id.resource_type.as_str() {
+                "s3.bucket" => self.update_s3_bucket(id, from, to).await,
+                "s3.bucket_policy" => self.update_s3_bucket_policy(id, &identifier, to).await,
+                "vpc" => self.update_ec2_vpc(id, from, to).await,
+                "subnet" => self.update_ec2_subnet(id, from, to).await,
+                "internet_gateway" => self.update_ec2_internet_gateway(id, from, to).await,
+                "nat_gateway" => self.update_ec2_nat_gateway(id, from, to).await,
+                "ec2.eip" => self.update_ec2_eip(id, from, to).await,
+                "ec2.instance" => self.update_ec2_instance(id, from, to).await,
+                "route_table" => self.update_ec2_route_table(id, from, to).await,
                 "route" => self.update_ec2_route(id, to).await,
-                "security_group" => self.update_ec2_security_group(id, to).await,
+                "security_group" => self.update_ec2_security_group(id, from, to).await,
                 "security_group.ingress_rule" => {
                     self.update_ec2_security_group_rule(id, to, true).await
                 }
                 "security_group.egress_rule" => {
                     self.update_ec2_security_group_rule(id, to, false).await
                 }
+                "security_group.rule" => {
+                    self.update_ec2_security_group_rule_unified(id, &identifier, to)
+                        .await
+                }
                 _ => Err(ProviderError::new(format!(
                     "Unknown resource type: {}",
                     id.resource_type
                 ))
                 .for_resource(id.clone())),
-            }
+            };
+            result.map(|state| in_configured_form(&configured, state))
         })
     }
 
-    fn delete(&self, id: &ResourceId, _identifier: &str) -> BoxFuture<'_, ProviderResult<()>> {
-        // Note: For AWS provider, we currently use name-based lookup.
-        // The identifier parameter is available for future optimization.
+    fn delete(&self, id: &ResourceId, identifier: &str) -> BoxFuture<'_, ProviderResult<()>> {
+        // Note: Most AWS resources use name-based lookup; security_group.rule,
+        // route_table_association and s3.bucket_policy are addressed by their identifier.
         let id = id.clone();
+        let identifier = identifier.to_string();
         Box::pin(async move {
             match id.resource_type.as_str() {
                 "s3.bucket" => self.delete_s3_bucket(id).await,
+                "s3.bucket_policy" => self.delete_s3_bucket_policy(id, &identifier).await,
                 "vpc" => self.delete_ec2_vpc(id).await,
                 "subnet" => self.delete_ec2_subnet(id).await,
                 "internet_gateway" => self.delete_ec2_internet_gateway(id).await,
+                "nat_gateway" => self.delete_ec2_nat_gateway(id).await,
+                "ec2.eip" => self.delete_ec2_eip(id).await,
+                "ec2.instance" => self.delete_ec2_instance(id).await,
                 "route_table" => self.delete_ec2_route_table(id).await,
+                "route_table_association" => {
+                    self.delete_ec2_route_table_association(id, &identifier)
+                        .await
+                }
                 "route" => {
                     // Route deletion requires route_table_id and destination_cidr_block
                     // which are not available from ResourceId alone.
@@ -2074,6 +4871,10 @@ impl Provider for AwsProvider {
                 "security_group.egress_rule" => {
                     self.delete_ec2_security_group_rule(id, false).await
                 }
+                "security_group.rule" => {
+                    self.delete_ec2_security_group_rule_by_id(id, &identifier)
+                        .await
+                }
                 _ => Err(ProviderError::new(format!(
                     "Unknown resource type: {}",
                     id.resource_type
@@ -2084,6 +4885,444 @@ impl Provider for AwsProvider {
     }
 }
 
+/// Re-serialize a JSON policy with sorted keys and no extra whitespace, so
+/// policies that differ only in formatting read back the same. Policies that
+/// are not valid JSON are returned as they are.
+fn normalize_policy(policy: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(policy)
+        .map(|json| json.to_string())
+        .unwrap_or_else(|_| policy.to_string())
+}
+
+/// Report read values that are equivalent to the configured ones in the
+/// configured form, so that they do not show as changes: AWS returns policies
+/// reformatted and instance profiles as ARNs even when configured by name
+fn in_configured_form(resource: &Resource, mut state: State) -> State {
+    for (key, equivalent) in [
+        ("policy", same_policy as fn(&str, &str) -> bool),
+        ("iam_instance_profile", same_instance_profile),
+        ("protocol", same_protocol),
+    ] {
+        if let (Some(Value::String(configured)), Some(Value::String(live))) =
+            (resource.attributes.get(key), state.attributes.get(key))
+            && configured != live
+            && equivalent(configured, live)
+        {
+            state
+                .attributes
+                .insert(key.to_string(), Value::String(configured.clone()));
+        }
+    }
+
+    // Rules for all protocols have no port range, so any configured one stands
+    let all_protocols = |attributes: &HashMap<String, Value>| match attributes.get("protocol") {
+        Some(Value::String(protocol)) => canonical_protocol(protocol) == "-1",
+        _ => false,
+    };
+    if all_protocols(&resource.attributes) && all_protocols(&state.attributes) {
+        for key in ["from_port", "to_port"] {
+            match resource.attributes.get(key) {
+                Some(port) => state.attributes.insert(key.to_string(), port.clone()),
+                None => state.attributes.remove(key),
+            };
+        }
+    }
+    state
+}
+
+/// State of a resource that reports a lifecycle state such as `pending`, recorded in
+/// its `state` attribute. Any lifecycle state but `ready` is reported as transitional.
+fn lifecycle_state(
+    id: ResourceId,
+    mut attributes: HashMap<String, Value>,
+    lifecycle: Option<String>,
+    ready: &str,
+) -> State {
+    let Some(lifecycle) = lifecycle else {
+        return State::existing(id, attributes);
+    };
+    attributes.insert("state".to_string(), Value::String(lifecycle.clone()));
+    let state = State::existing(id, attributes);
+    if lifecycle == ready {
+        state
+    } else {
+        state.with_transitional_status(lifecycle)
+    }
+}
+
+/// Whether two protocols are the same, e.g. `aws.Protocol.all` and `-1`
+fn same_protocol(a: &str, b: &str) -> bool {
+    canonical_protocol(a) == canonical_protocol(b)
+}
+
+/// Whether two policies are the same document, whatever their formatting
+fn same_policy(a: &str, b: &str) -> bool {
+    normalize_policy(a) == normalize_policy(b)
+}
+
+/// Whether two instance profile references, each a name or an ARN such as
+/// `arn:aws:iam::123456789012:instance-profile/web`, name the same profile
+fn same_instance_profile(a: &str, b: &str) -> bool {
+    fn name(profile: &str) -> &str {
+        if profile.starts_with("arn:") {
+            profile.rsplit('/').next().unwrap_or(profile)
+        } else {
+            profile
+        }
+    }
+    name(a) == name(b)
+}
+
+/// Id of the lifecycle rule the `expiration_days` shorthand stands for
+const AUTO_EXPIRATION_RULE_ID: &str = "auto-expiration";
+
+/// Build a bucket's lifecycle configuration from `lifecycle_rules` plus the
+/// `expiration_days` shorthand, or `None` when neither is set
+fn s3_lifecycle_configuration(
+    attributes: &HashMap<String, Value>,
+) -> Result<Option<aws_sdk_s3::types::BucketLifecycleConfiguration>, String> {
+    use aws_sdk_s3::types::{
+        BucketLifecycleConfiguration, ExpirationStatus, LifecycleExpiration, LifecycleRule,
+        LifecycleRuleFilter, Transition, TransitionStorageClass,
+    };
+
+    let build_rule = |id: &str, prefix: &str, days: Option<i64>, transitions: &[Value]| {
+        let mut rule = LifecycleRule::builder()
+            .id(id)
+            .status(ExpirationStatus::Enabled)
+            .filter(LifecycleRuleFilter::builder().prefix(prefix).build());
+        if let Some(days) = days {
+            rule = rule.expiration(LifecycleExpiration::builder().days(days as i32).build());
+        }
+        for transition in transitions {
+            let Value::Map(transition) = transition else {
+                continue;
+            };
+            let mut builder = Transition::builder();
+            if let Some(Value::Int(days)) = transition.get("days") {
+                builder = builder.days(*days as i32);
+            }
+            if let Some(Value::String(class)) = transition.get("storage_class") {
+                builder = builder.storage_class(TransitionStorageClass::from(class.as_str()));
+            }
+            rule = rule.transitions(builder.build());
+        }
+        rule.build()
+            .map_err(|e| format!("Failed to build lifecycle rule: {}", e))
+    };
+
+    let mut rules = Vec::new();
+    if let Some(Value::List(entries)) = attributes.get("lifecycle_rules") {
+        for entry in entries {
+            let Value::Map(entry) = entry else {
+                return Err("Invalid lifecycle rule".to_string());
+            };
+            let string = |key: &str| match entry.get(key) {
+                Some(Value::String(s)) => s.as_str(),
+                _ => "",
+            };
+            let days = match entry.get("expiration_days") {
+                Some(Value::Int(days)) => Some(*days),
+                _ => None,
+            };
+            let transitions = match entry.get("transitions") {
+                Some(Value::List(transitions)) => transitions.as_slice(),
+                _ => &[],
+            };
+            rules.push(build_rule(
+                string("id"),
+                string("prefix"),
+                days,
+                transitions,
+            )?);
+        }
+    }
+    if let Some(Value::Int(days)) = attributes.get("expiration_days") {
+        rules.push(build_rule(AUTO_EXPIRATION_RULE_ID, "", Some(*days), &[])?);
+    }
+
+    if rules.is_empty() {
+        return Ok(None);
+    }
+    BucketLifecycleConfiguration::builder()
+        .set_rules(Some(rules))
+        .build()
+        .map(Some)
+        .map_err(|e| format!("Failed to build lifecycle config: {}", e))
+}
+
+/// Build a bucket's CORS configuration from `cors_rules` entries
+fn s3_cors_configuration(rules: &[Value]) -> Result<aws_sdk_s3::types::CorsConfiguration, String> {
+    use aws_sdk_s3::types::{CorsConfiguration, CorsRule};
+
+    let mut cors_rules = Vec::new();
+    for rule in rules {
+        let Value::Map(rule) = rule else {
+            return Err("Invalid CORS rule".to_string());
+        };
+        let strings = |key: &str| match rule.get(key) {
+            Some(Value::List(items)) => Some(
+                items
+                    .iter()
+                    .filter_map(|item| match item {
+                        Value::String(s) => Some(s.clone()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            _ => None,
+        };
+        let max_age_seconds = match rule.get("max_age_seconds") {
+            Some(Value::Int(seconds)) => Some(*seconds as i32),
+            _ => None,
+        };
+        cors_rules.push(
+            CorsRule::builder()
+                .set_allowed_methods(strings("allowed_methods"))
+                .set_allowed_origins(strings("allowed_origins"))
+                .set_allowed_headers(strings("allowed_headers"))
+                .set_max_age_seconds(max_age_seconds)
+                .build()
+                .map_err(|e| format!("Failed to build CORS rule: {}", e))?,
+        );
+    }
+
+    CorsConfiguration::builder()
+        .set_cors_rules(Some(cors_rules))
+        .build()
+        .map_err(|e| format!("Failed to build CORS configuration: {}", e))
+}
+
+/// Convert a CORS rule read from S3 into its `cors_rules` entry
+fn cors_rule_value(rule: &aws_sdk_s3::types::CorsRule) -> Value {
+    let strings =
+        |items: &[String]| Value::List(items.iter().map(|s| Value::String(s.clone())).collect());
+    let mut entry = BTreeMap::new();
+    entry.insert(
+        "allowed_methods".to_string(),
+        strings(rule.allowed_methods()),
+    );
+    entry.insert(
+        "allowed_origins".to_string(),
+        strings(rule.allowed_origins()),
+    );
+    if !rule.allowed_headers().is_empty() {
+        entry.insert(
+            "allowed_headers".to_string(),
+            strings(rule.allowed_headers()),
+        );
+    }
+    if let Some(seconds) = rule.max_age_seconds() {
+        entry.insert("max_age_seconds".to_string(), Value::Int(seconds as i64));
+    }
+    Value::Map(entry)
+}
+
+/// Prefix a lifecycle rule applies to, from its filter or the legacy field
+/// older rules still carry
+#[allow(deprecated)]
+fn lifecycle_rule_prefix(rule: &aws_sdk_s3::types::LifecycleRule) -> &str {
+    rule.filter()
+        .and_then(|f| f.prefix())
+        .or(rule.prefix())
+        .unwrap_or("")
+}
+
+/// Convert a lifecycle rule read from S3 into its `lifecycle_rules` entry
+fn lifecycle_rule_value(rule: &aws_sdk_s3::types::LifecycleRule) -> Value {
+    let mut entry = BTreeMap::new();
+    entry.insert(
+        "id".to_string(),
+        Value::String(rule.id().unwrap_or("").to_string()),
+    );
+    let prefix = lifecycle_rule_prefix(rule);
+    if !prefix.is_empty() {
+        entry.insert("prefix".to_string(), Value::String(prefix.to_string()));
+    }
+    if let Some(days) = rule.expiration().and_then(|e| e.days) {
+        entry.insert("expiration_days".to_string(), Value::Int(days as i64));
+    }
+    let transitions: Vec<Value> = rule
+        .transitions()
+        .iter()
+        .map(|t| {
+            let mut transition = BTreeMap::new();
+            if let Some(days) = t.days() {
+                transition.insert("days".to_string(), Value::Int(days as i64));
+            }
+            if let Some(class) = t.storage_class() {
+                transition.insert(
+                    "storage_class".to_string(),
+                    Value::String(class.as_str().to_string()),
+                );
+            }
+            Value::Map(transition)
+        })
+        .collect();
+    if !transitions.is_empty() {
+        entry.insert("transitions".to_string(), Value::List(transitions));
+    }
+    Value::Map(entry)
+}
+
+/// ARN of an S3 bucket. Bucket ARNs carry neither region nor account.
+fn s3_bucket_arn(name: &str) -> String {
+    format!("arn:aws:s3:::{}", name)
+}
+
+/// ARN of an EC2 resource, e.g. `arn:aws:ec2:us-east-1:123456789012:vpc/vpc-123`.
+fn ec2_arn(region: &str, account_id: &str, kind: &str, resource_id: &str) -> String {
+    format!(
+        "arn:aws:ec2:{}:{}:{}/{}",
+        region, account_id, kind, resource_id
+    )
+}
+
+/// State of a just-created resource that reads don't return yet: its configured
+/// attributes and the identifier from the create response
+fn created_state(resource: &Resource, identifier: &str) -> State {
+    let mut attributes: HashMap<String, Value> = resource
+        .attributes
+        .iter()
+        .filter(|(key, _)| !key.starts_with('_'))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    attributes.insert("id".to_string(), Value::String(identifier.to_string()));
+    State::existing(resource.id.clone(), attributes).with_identifier(identifier)
+}
+
+/// Build the EC2 tags for a resource: the implicit `Name` tag plus the entries
+/// of its `tags` map. `Name` always comes from the resource name.
+fn ec2_tags(name: &str, attributes: &HashMap<String, Value>) -> Vec<aws_sdk_ec2::types::Tag> {
+    let mut tags = vec![
+        aws_sdk_ec2::types::Tag::builder()
+            .key("Name")
+            .value(name)
+            .build(),
+    ];
+    tags.extend(tag_entries(attributes).into_iter().map(|(key, value)| {
+        aws_sdk_ec2::types::Tag::builder()
+            .key(key)
+            .value(value)
+            .build()
+    }));
+    tags
+}
+
+/// Secondary IPv4 CIDR blocks of a VPC with their association ids, in
+/// association order. The primary block and blocks being (or already)
+/// disassociated are left out.
+fn vpc_secondary_cidr_blocks(vpc: &aws_sdk_ec2::types::Vpc) -> Vec<(String, String)> {
+    use aws_sdk_ec2::types::VpcCidrBlockStateCode;
+
+    vpc.cidr_block_association_set()
+        .iter()
+        .filter(|assoc| {
+            matches!(
+                assoc.cidr_block_state().and_then(|s| s.state()),
+                Some(VpcCidrBlockStateCode::Associated | VpcCidrBlockStateCode::Associating)
+            )
+        })
+        .filter_map(|assoc| {
+            let cidr = assoc.cidr_block()?;
+            if Some(cidr) == vpc.cidr_block() {
+                return None;
+            }
+            Some((cidr.to_string(), assoc.association_id()?.to_string()))
+        })
+        .collect()
+}
+
+/// The string elements of a list attribute, if the attribute is set
+fn string_list(attributes: &HashMap<String, Value>, key: &str) -> Option<Vec<String>> {
+    match attributes.get(key) {
+        Some(Value::List(items)) => Some(
+            items
+                .iter()
+                .filter_map(|v| match v {
+                    Value::String(s) => Some(s.clone()),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Instance profile specification for a profile given by name or ARN
+fn iam_instance_profile_spec(profile: &str) -> aws_sdk_ec2::types::IamInstanceProfileSpecification {
+    let builder = aws_sdk_ec2::types::IamInstanceProfileSpecification::builder();
+    if profile.starts_with("arn:") {
+        builder.arn(profile).build()
+    } else {
+        builder.name(profile).build()
+    }
+}
+
+/// String entries of a `tags` map attribute, excluding the reserved `Name` key
+fn tag_entries(attributes: &HashMap<String, Value>) -> BTreeMap<String, String> {
+    match attributes.get("tags") {
+        Some(Value::Map(map)) => map
+            .iter()
+            .filter(|(key, _)| key.as_str() != "Name")
+            .filter_map(|(key, value)| match value {
+                Value::String(s) => Some((key.clone(), s.clone())),
+                _ => None,
+            })
+            .collect(),
+        _ => BTreeMap::new(),
+    }
+}
+
+/// Convert described EC2 tags into a `tags` map attribute, excluding `Name`
+fn tags_attribute(tags: &[aws_sdk_ec2::types::Tag]) -> Value {
+    Value::Map(
+        tags.iter()
+            .filter_map(|tag| match (tag.key(), tag.value()) {
+                (Some(key), Some(value)) if key != "Name" => {
+                    Some((key.to_string(), Value::String(value.to_string())))
+                }
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+/// Target of a route table route
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RouteTarget {
+    Gateway(String),
+    NatGateway(String),
+}
+
+/// Routes of a `routes` list attribute keyed by destination CIDR. The
+/// implicit `local` route is skipped since it can be neither created nor
+/// deleted.
+fn route_targets(routes: Option<&Value>) -> BTreeMap<String, RouteTarget> {
+    let Some(Value::List(routes)) = routes else {
+        return BTreeMap::new();
+    };
+
+    routes
+        .iter()
+        .filter_map(|route| {
+            let Value::Map(route_map) = route else {
+                return None;
+            };
+            let Some(Value::String(dest)) = route_map.get("destination") else {
+                return None;
+            };
+            let target = match (route_map.get("gateway_id"), route_map.get("nat_gateway_id")) {
+                (Some(Value::String(gw_id)), _) if gw_id == "local" => return None,
+                (Some(Value::String(gw_id)), _) => RouteTarget::Gateway(gw_id.clone()),
+                (_, Some(Value::String(nat_gw_id))) => RouteTarget::NatGateway(nat_gw_id.clone()),
+                _ => return None,
+            };
+            Some((dest.clone(), target))
+        })
+        .collect()
+}
+
 /// Convert DSL enum value (provider.TypeName.value_name) to AWS SDK format (value-name)
 /// Handles patterns like:
 /// - aws.Region.ap_northeast_1 -> ap-northeast-1
@@ -2093,25 +5332,13 @@ fn convert_enum_value(value: &str) -> String {
     let parts: Vec<&str> = value.split('.').collect();
 
     let raw_value = match parts.len() {
-        2 => {
-            // TypeName.value pattern
-            if parts[0].chars().next().is_some_and(|c| c.is_uppercase()) {
-                parts[1]
-            } else {
-                return value.to_string();
-            }
-        }
-        3 => {
-            // provider.TypeName.value pattern
-            let provider = parts[0];
-            let type_name = parts[1];
-            if provider.chars().all(|c| c.is_lowercase())
-                && type_name.chars().next().is_some_and(|c| c.is_uppercase())
-            {
-                parts[2]
-            } else {
-                return value.to_string();
-            }
+        // TypeName.value pattern
+        2 if parts[0].chars().next().is_some_and(|c| c.is_uppercase()) => parts[1],
+        // provider.TypeName.value pattern
+        3 if parts[0].chars().all(|c| c.is_lowercase())
+            && parts[1].chars().next().is_some_and(|c| c.is_uppercase()) =>
+        {
+            parts[2]
         }
         _ => return value.to_string(),
     };
@@ -2119,6 +5346,73 @@ fn convert_enum_value(value: &str) -> String {
     raw_value.replace('_', "-")
 }
 
+/// Build the IP permission for a security group rule. Rules for all protocols
+/// (-1) carry no port range, since AWS rejects one for them.
+fn ip_permission(
+    attributes: &HashMap<String, Value>,
+    cidrs: &[String],
+    description: Option<&str>,
+) -> aws_sdk_ec2::types::IpPermission {
+    let protocol = match attributes.get("protocol") {
+        Some(Value::String(s)) => convert_protocol_value(s),
+        _ => "-1".to_string(),
+    };
+
+    let mut builder = aws_sdk_ec2::types::IpPermission::builder().ip_protocol(&protocol);
+    if protocol != "-1" {
+        let port = |key: &str| match attributes.get(key) {
+            Some(Value::Int(n)) => *n as i32,
+            _ => 0,
+        };
+        builder = builder
+            .from_port(port("from_port"))
+            .to_port(port("to_port"));
+    }
+
+    for cidr in cidrs {
+        builder = builder.ip_ranges(
+            aws_sdk_ec2::types::IpRange::builder()
+                .cidr_ip(cidr)
+                .set_description(description.map(String::from))
+                .build(),
+        );
+    }
+
+    builder.build()
+}
+
+/// Record a security group rule's protocol and port range as read. The protocol is
+/// given by name where AWS reports a number, and rules for all protocols (-1) have
+/// no port range, since AWS reports -1 for both ports of them.
+fn insert_rule_protocol(
+    attributes: &mut HashMap<String, Value>,
+    rule: &aws_sdk_ec2::types::SecurityGroupRule,
+) {
+    let Some(protocol) = rule.ip_protocol().map(canonical_protocol) else {
+        return;
+    };
+    if protocol != "-1" {
+        if let Some(from_port) = rule.from_port() {
+            attributes.insert("from_port".to_string(), Value::Int(from_port as i64));
+        }
+        if let Some(to_port) = rule.to_port() {
+            attributes.insert("to_port".to_string(), Value::Int(to_port as i64));
+        }
+    }
+    attributes.insert("protocol".to_string(), Value::String(protocol));
+}
+
+/// A protocol in the form AWS reports it: tcp, udp and icmp by name, -1 for all,
+/// and any other protocol by number
+fn canonical_protocol(value: &str) -> String {
+    match convert_protocol_value(value).as_str() {
+        "6" => "tcp".to_string(),
+        "17" => "udp".to_string(),
+        "1" => "icmp".to_string(),
+        other => other.to_string(),
+    }
+}
+
 /// Convert protocol value from DSL format to AWS format
 /// - aws.Protocol.tcp / Protocol.tcp / tcp -> tcp
 /// - aws.Protocol.all / Protocol.all / all / -1 -> -1
@@ -2161,6 +5455,380 @@ mod tests {
         assert_eq!(convert_enum_value("ap-northeast-1a"), "ap-northeast-1a");
     }
 
+    #[test]
+    fn test_ec2_tags_merge_name_with_tags_map() {
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            "tags".to_string(),
+            Value::Map(BTreeMap::from([
+                ("Environment".to_string(), Value::String("dev".to_string())),
+                ("Name".to_string(), Value::String("ignored".to_string())),
+            ])),
+        );
+
+        let tags = ec2_tags("main-vpc", &attributes);
+        let pairs: Vec<_> = tags.iter().map(|t| (t.key(), t.value())).collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (Some("Name"), Some("main-vpc")),
+                (Some("Environment"), Some("dev")),
+            ]
+        );
+
+        // Reading back excludes the implicit Name tag
+        assert_eq!(
+            tags_attribute(&tags),
+            Value::Map(BTreeMap::from([(
+                "Environment".to_string(),
+                Value::String("dev".to_string())
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_route_targets_skip_local_route() {
+        let route = |dest: &str, key: &str, target: &str| {
+            Value::Map(BTreeMap::from([
+                ("destination".to_string(), Value::String(dest.to_string())),
+                (key.to_string(), Value::String(target.to_string())),
+            ]))
+        };
+        let routes = Value::List(vec![
+            route("10.0.0.0/16", "gateway_id", "local"),
+            route("0.0.0.0/0", "nat_gateway_id", "nat-123"),
+            route("192.168.0.0/16", "gateway_id", "igw-123"),
+        ]);
+
+        assert_eq!(
+            route_targets(Some(&routes)),
+            BTreeMap::from([
+                (
+                    "0.0.0.0/0".to_string(),
+                    RouteTarget::NatGateway("nat-123".to_string())
+                ),
+                (
+                    "192.168.0.0/16".to_string(),
+                    RouteTarget::Gateway("igw-123".to_string())
+                ),
+            ])
+        );
+        assert!(route_targets(None).is_empty());
+    }
+
+    #[test]
+    fn test_ip_permission_all_protocols_has_no_ports() {
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            "protocol".to_string(),
+            Value::String("aws.Protocol.all".to_string()),
+        );
+        attributes.insert("from_port".to_string(), Value::Int(0));
+        attributes.insert("to_port".to_string(), Value::Int(0));
+
+        let permission = ip_permission(
+            &attributes,
+            &["10.0.0.0/8".to_string(), "192.168.0.0/16".to_string()],
+            Some("internal"),
+        );
+        assert_eq!(permission.ip_protocol(), Some("-1"));
+        assert_eq!(permission.from_port(), None);
+        assert_eq!(permission.to_port(), None);
+        assert_eq!(permission.ip_ranges().len(), 2);
+        assert_eq!(permission.ip_ranges()[0].description(), Some("internal"));
+
+        attributes.insert(
+            "protocol".to_string(),
+            Value::String("aws.Protocol.tcp".to_string()),
+        );
+        attributes.insert("from_port".to_string(), Value::Int(443));
+        attributes.insert("to_port".to_string(), Value::Int(443));
+        let permission = ip_permission(&attributes, &[], None);
+        assert_eq!(permission.from_port(), Some(443));
+        assert_eq!(permission.to_port(), Some(443));
+    }
+
+    #[test]
+    fn test_lifecycle_rules_round_trip() {
+        let rule = Value::Map(BTreeMap::from([
+            ("id".to_string(), Value::String("logs".to_string())),
+            ("prefix".to_string(), Value::String("logs/".to_string())),
+            ("expiration_days".to_string(), Value::Int(365)),
+            (
+                "transitions".to_string(),
+                Value::List(vec![Value::Map(BTreeMap::from([
+                    ("days".to_string(), Value::Int(30)),
+                    (
+                        "storage_class".to_string(),
+                        Value::String("GLACIER".to_string()),
+                    ),
+                ]))]),
+            ),
+        ]));
+        let attributes = HashMap::from([
+            (
+                "lifecycle_rules".to_string(),
+                Value::List(vec![rule.clone()]),
+            ),
+            ("expiration_days".to_string(), Value::Int(7)),
+        ]);
+
+        let config = s3_lifecycle_configuration(&attributes).unwrap().unwrap();
+        let rules = config.rules();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(lifecycle_rule_value(&rules[0]), rule);
+        assert_eq!(rules[1].id(), Some(AUTO_EXPIRATION_RULE_ID));
+        assert_eq!(rules[1].expiration().and_then(|e| e.days), Some(7));
+
+        assert!(
+            s3_lifecycle_configuration(&HashMap::new())
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_cors_rules_round_trip() {
+        let strings = |items: &[&str]| {
+            Value::List(items.iter().map(|s| Value::String(s.to_string())).collect())
+        };
+        let rule = Value::Map(BTreeMap::from([
+            ("allowed_methods".to_string(), strings(&["GET", "HEAD"])),
+            (
+                "allowed_origins".to_string(),
+                strings(&["https://example.com"]),
+            ),
+            ("allowed_headers".to_string(), strings(&["*"])),
+            ("max_age_seconds".to_string(), Value::Int(3000)),
+        ]));
+
+        let config = s3_cors_configuration(std::slice::from_ref(&rule)).unwrap();
+        assert_eq!(config.cors_rules().len(), 1);
+        assert_eq!(cors_rule_value(&config.cors_rules()[0]), rule);
+    }
+
+    #[test]
+    fn test_normalize_policy_sorts_keys() {
+        assert_eq!(
+            normalize_policy("{\n  \"Version\": \"2012-10-17\",\n  \"Statement\": []\n}"),
+            r#"{"Statement":[],"Version":"2012-10-17"}"#
+        );
+        assert_eq!(normalize_policy("not json"), "not json");
+    }
+
+    #[test]
+    fn test_instance_profiles_read_back_in_configured_form() {
+        let arn = "arn:aws:iam::123456789012:instance-profile/app/web";
+        let read = |configured: &str| {
+            let resource = Resource::new("ec2.instance", "web").with_attribute(
+                "iam_instance_profile",
+                Value::String(configured.to_string()),
+            );
+            let attributes = HashMap::from([(
+                "iam_instance_profile".to_string(),
+                Value::String(arn.to_string()),
+            )]);
+            in_configured_form(&resource, State::existing(resource.id.clone(), attributes))
+                .attributes["iam_instance_profile"]
+                .clone()
+        };
+
+        assert_eq!(read("web"), Value::String("web".to_string()));
+        assert_eq!(read(arn), Value::String(arn.to_string()));
+        assert_eq!(read("db"), Value::String(arn.to_string()));
+        assert_eq!(read(""), Value::String(arn.to_string()));
+    }
+
+    #[test]
+    fn test_lifecycle_state_reports_transitions_without_waiting() {
+        let id = ResourceId::new("vpc", "main");
+        let read = |lifecycle: Option<&str>| {
+            lifecycle_state(
+                id.clone(),
+                HashMap::new(),
+                lifecycle.map(String::from),
+                "available",
+            )
+        };
+
+        let pending = read(Some("pending"));
+        assert!(pending.exists);
+        assert_eq!(pending.transitional_status.as_deref(), Some("pending"));
+        assert_eq!(
+            pending.attributes.get("state"),
+            Some(&Value::String("pending".to_string()))
+        );
+
+        let available = read(Some("available"));
+        assert!(available.is_ready());
+        assert_eq!(
+            available.attributes.get("state"),
+            Some(&Value::String("available".to_string()))
+        );
+
+        let unknown = read(None);
+        assert!(unknown.exists && unknown.is_ready());
+        assert!(!unknown.attributes.contains_key("state"));
+    }
+
+    #[test]
+    fn test_rule_protocols_read_back_in_configured_form() {
+        let read = |protocol: &str, from_port: i32, to_port: i32| {
+            let rule = aws_sdk_ec2::types::SecurityGroupRule::builder()
+                .ip_protocol(protocol)
+                .from_port(from_port)
+                .to_port(to_port)
+                .build();
+            let mut attributes = HashMap::new();
+            insert_rule_protocol(&mut attributes, &rule);
+            attributes
+        };
+
+        let all = read("-1", -1, -1);
+        assert_eq!(all.get("protocol"), Some(&Value::String("-1".to_string())));
+        assert!(!all.contains_key("from_port") && !all.contains_key("to_port"));
+        let https = read("6", 443, 443);
+        assert_eq!(https["protocol"], Value::String("tcp".to_string()));
+        assert_eq!(https["from_port"], Value::Int(443));
+        assert_eq!(
+            read("58", -1, -1)["protocol"],
+            Value::String("58".to_string())
+        );
+
+        let rule = |protocol: &str| {
+            Resource::new("security_group.rule", "all_out")
+                .with_attribute("protocol", Value::String(protocol.to_string()))
+                .with_attribute("from_port", Value::Int(0))
+                .with_attribute("to_port", Value::Int(0))
+        };
+        let configured = rule("aws.Protocol.all");
+        let state = in_configured_form(
+            &configured,
+            State::existing(configured.id.clone(), all.clone()),
+        );
+        assert_eq!(state.attributes, configured.attributes);
+
+        // A different protocol is drift, and keeps the live values
+        let configured = rule("aws.Protocol.tcp");
+        let state = in_configured_form(&configured, State::existing(configured.id.clone(), all));
+        assert_eq!(
+            state.attributes["protocol"],
+            Value::String("-1".to_string())
+        );
+        assert!(!state.attributes.contains_key("from_port"));
+        let state = in_configured_form(&configured, State::existing(configured.id.clone(), https));
+        assert_eq!(
+            state.attributes["protocol"],
+            Value::String("aws.Protocol.tcp".to_string())
+        );
+        assert_eq!(state.attributes["from_port"], Value::Int(443));
+    }
+
+    #[test]
+    fn test_policies_read_back_in_configured_form() {
+        let configured = "{ \"Version\": \"2012-10-17\", \"Statement\": [] }";
+        let resource = Resource::new("s3.bucket_policy", "logs")
+            .with_attribute("policy", Value::String(configured.to_string()));
+        let read = |policy: &str| {
+            let attributes = HashMap::from([(
+                "policy".to_string(),
+                Value::String(normalize_policy(policy)),
+            )]);
+            in_configured_form(&resource, State::existing(resource.id.clone(), attributes))
+                .attributes["policy"]
+                .clone()
+        };
+
+        assert_eq!(
+            read("{\"Statement\":[],\"Version\":\"2012-10-17\"}"),
+            Value::String(configured.to_string())
+        );
+        assert_eq!(
+            read("{\"Statement\":[],\"Version\":\"2008-10-17\"}"),
+            Value::String("{\"Statement\":[],\"Version\":\"2008-10-17\"}".to_string())
+        );
+    }
+
+    #[test]
+    fn test_created_state_keeps_configured_attributes() {
+        let resource = Resource::new("vpc", "main")
+            .with_attribute("cidr_block", Value::String("10.0.0.0/16".to_string()))
+            .with_attribute("_binding", Value::String("main".to_string()));
+
+        let state = created_state(&resource, "vpc-123");
+        assert!(state.exists);
+        assert_eq!(state.identifier.as_deref(), Some("vpc-123"));
+        assert_eq!(
+            state.attributes.get("cidr_block"),
+            Some(&Value::String("10.0.0.0/16".to_string()))
+        );
+        assert_eq!(
+            state.attributes.get("id"),
+            Some(&Value::String("vpc-123".to_string()))
+        );
+        assert!(!state.attributes.contains_key("_binding"));
+    }
+
+    #[test]
+    fn test_arn_formats() {
+        assert_eq!(s3_bucket_arn("my-bucket"), "arn:aws:s3:::my-bucket");
+        assert_eq!(
+            ec2_arn(
+                "ap-northeast-1",
+                "123456789012",
+                "security-group",
+                "sg-0abc"
+            ),
+            "arn:aws:ec2:ap-northeast-1:123456789012:security-group/sg-0abc"
+        );
+    }
+
+    #[test]
+    fn test_vpc_secondary_cidr_blocks_skip_primary_and_disassociated() {
+        use aws_sdk_ec2::types::{
+            VpcCidrBlockAssociation, VpcCidrBlockState, VpcCidrBlockStateCode,
+        };
+
+        let assoc = |id: &str, cidr: &str, state: VpcCidrBlockStateCode| {
+            VpcCidrBlockAssociation::builder()
+                .association_id(id)
+                .cidr_block(cidr)
+                .cidr_block_state(VpcCidrBlockState::builder().state(state).build())
+                .build()
+        };
+        let vpc = aws_sdk_ec2::types::Vpc::builder()
+            .cidr_block("10.0.0.0/16")
+            .cidr_block_association_set(assoc(
+                "a-0",
+                "10.0.0.0/16",
+                VpcCidrBlockStateCode::Associated,
+            ))
+            .cidr_block_association_set(assoc(
+                "a-1",
+                "10.1.0.0/16",
+                VpcCidrBlockStateCode::Associated,
+            ))
+            .cidr_block_association_set(assoc(
+                "a-2",
+                "10.2.0.0/16",
+                VpcCidrBlockStateCode::Disassociated,
+            ))
+            .cidr_block_association_set(assoc(
+                "a-3",
+                "10.3.0.0/16",
+                VpcCidrBlockStateCode::Associating,
+            ))
+            .build();
+
+        assert_eq!(
+            vpc_secondary_cidr_blocks(&vpc),
+            vec![
+                ("10.1.0.0/16".to_string(), "a-1".to_string()),
+                ("10.3.0.0/16".to_string(), "a-3".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn test_s3_bucket_type_name() {
         let bucket_type = S3BucketType;
@@ -0,0 +1,209 @@
+//! Retry of AWS calls that fail with throttling
+//!
+//! The calls retried here change resources, so they are retried only when AWS
+//! throttled them: a throttled request was rejected before it took effect, while
+//! a timeout or server error may come after the change was made. The SDK's own
+//! retries are disabled for these clients, so the two layers don't multiply.
+
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use aws_sdk_ec2::error::{ProvideErrorMetadata, SdkError};
+use carina_core::provider::ProviderErrorKind;
+
+/// Environment variable overriding the number of retries
+pub const MAX_RETRIES_ENV: &str = "CARINA_AWS_MAX_RETRIES";
+/// Environment variable overriding the base backoff delay in milliseconds
+pub const BASE_DELAY_MS_ENV: &str = "CARINA_AWS_RETRY_BASE_DELAY_MS";
+
+const DEFAULT_MAX_RETRIES: u32 = 5;
+const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+const MAX_DELAY: Duration = Duration::from_secs(20);
+
+/// How often and how patiently throttled calls are retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: DEFAULT_BASE_DELAY,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Defaults, overridden by `CARINA_AWS_MAX_RETRIES` and
+    /// `CARINA_AWS_RETRY_BASE_DELAY_MS` when they hold valid numbers
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+        Self {
+            max_retries: lookup(MAX_RETRIES_ENV)
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(defaults.max_retries),
+            base_delay: lookup(BASE_DELAY_MS_ENV)
+                .and_then(|v| v.trim().parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.base_delay),
+        }
+    }
+
+    /// Backoff before retry number `attempt` (0-based): exponential growth
+    /// capped at MAX_DELAY, with the upper half randomized ("equal jitter")
+    fn delay(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_DELAY);
+        let half = exponential / 2;
+        let jitter_range = half.as_millis() as u64;
+        let jitter = if jitter_range == 0 {
+            0
+        } else {
+            RandomState::new().build_hasher().finish() % (jitter_range + 1)
+        };
+        half + Duration::from_millis(jitter)
+    }
+}
+
+/// Whether AWS rejected a call by throttling it
+pub fn is_throttled<E, R>(err: &SdkError<E, R>) -> bool
+where
+    E: ProvideErrorMetadata,
+{
+    err.code().is_some_and(|code| {
+        ProviderErrorKind::from_error_code(code) == ProviderErrorKind::Throttled
+    })
+}
+
+/// Run an AWS call, retrying it with exponential backoff and jitter while it
+/// is throttled. The last error is returned once retries run
+/// out, for the caller to turn into a ProviderError.
+pub async fn retry_throttled<F, Fut, T, E, R>(
+    config: &RetryConfig,
+    mut call: F,
+) -> Result<T, SdkError<E, R>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, SdkError<E, R>>>,
+    E: ProvideErrorMetadata,
+{
+    let mut attempt = 0;
+    loop {
+        match call().await {
+            Err(err) if attempt < config.max_retries && is_throttled(&err) => {
+                tokio::time::sleep(config.delay(attempt)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Repeat a read with the same backoff as throttled calls until `visible` accepts its
+/// result, for `describe_*` calls that may not see a resource right after it was
+/// created. Returns None when the result never became visible, and the first error
+/// as is.
+pub async fn retry_until_visible<F, Fut, T, E>(
+    config: &RetryConfig,
+    mut read: F,
+    visible: impl Fn(&T) -> bool,
+) -> Result<Option<T>, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut attempt = 0;
+    loop {
+        let result = read().await?;
+        if visible(&result) {
+            return Ok(Some(result));
+        }
+        if attempt >= config.max_retries {
+            return Ok(None);
+        }
+        tokio::time::sleep(config.delay(attempt)).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_reads_overrides_and_ignores_garbage() {
+        let config = RetryConfig::from_lookup(|key| match key {
+            MAX_RETRIES_ENV => Some("8".to_string()),
+            BASE_DELAY_MS_ENV => Some("not-a-number".to_string()),
+            _ => None,
+        });
+        assert_eq!(config.max_retries, 8);
+        assert_eq!(config.base_delay, DEFAULT_BASE_DELAY);
+    }
+
+    #[test]
+    fn delay_grows_exponentially_within_jitter_bounds() {
+        let config = RetryConfig {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+        };
+        for attempt in 0..4 {
+            let full = Duration::from_millis(100 * 2u64.pow(attempt));
+            let delay = config.delay(attempt);
+            assert!(delay >= full / 2 && delay <= full, "{:?}", delay);
+        }
+        assert!(config.delay(30) <= MAX_DELAY);
+    }
+
+    #[test]
+    fn only_throttling_is_retried() {
+        use aws_sdk_ec2::error::ErrorMetadata;
+        use aws_sdk_ec2::operation::create_vpc::CreateVpcError;
+
+        let service_error = |code: &str| {
+            let err = CreateVpcError::generic(ErrorMetadata::builder().code(code).build());
+            SdkError::<CreateVpcError, ()>::service_error(err, ())
+        };
+        assert!(is_throttled(&service_error("RequestLimitExceeded")));
+        assert!(is_throttled(&service_error("Throttling")));
+        // The change may have been made before these failures
+        assert!(!is_throttled(&service_error("InternalError")));
+        assert!(!is_throttled(
+            &SdkError::<CreateVpcError, ()>::timeout_error("timed out")
+        ));
+    }
+
+    #[tokio::test]
+    async fn reads_are_retried_until_visible() {
+        let config = RetryConfig {
+            max_retries: 3,
+            base_delay: Duration::from_millis(1),
+        };
+        let mut reads = 0;
+        let result: Result<_, ()> = retry_until_visible(
+            &config,
+            || {
+                reads += 1;
+                std::future::ready(Ok(reads))
+            },
+            |reads| *reads == 2,
+        )
+        .await;
+        assert_eq!(result, Ok(Some(2)));
+
+        let never: Result<Option<()>, ()> =
+            retry_until_visible(&config, || std::future::ready(Ok(())), |_| false).await;
+        assert_eq!(never, Ok(None));
+    }
+}
@@ -0,0 +1,261 @@
+//! EC2 schema definitions
+
+use carina_core::resource::Value;
+use carina_core::schema::{AttributeSchema, AttributeType, ResourceSchema};
+
+use super::types as aws_types;
+
+/// Returns the schema for the AMI lookup data source
+pub fn ami_schema() -> ResourceSchema {
+    ResourceSchema::new("ec2.ami")
+        .with_description("Looks up an Amazon Machine Image by owner and name (data source only)")
+        .attribute(
+            AttributeSchema::new("name", AttributeType::String)
+                .required()
+                .with_description("Data source name (for identification)"),
+        )
+        .attribute(
+            AttributeSchema::new("region", aws_types::aws_region())
+                .with_description("The AWS region (inherited from provider if not specified)"),
+        )
+        .attribute(
+            AttributeSchema::new(
+                "owners",
+                AttributeType::List(Box::new(AttributeType::String)),
+            )
+            .with_description(
+                "Image owners to search: account IDs, self, amazon or aws-marketplace",
+            ),
+        )
+        .attribute(
+            AttributeSchema::new("name_pattern", AttributeType::String)
+                .required()
+                .with_description("Image name filter; * and ? wildcards are allowed"),
+        )
+        .attribute(
+            AttributeSchema::new("most_recent", AttributeType::Bool)
+                .with_default(Value::Bool(false))
+                .with_description(
+                    "Pick the newest image when several match (otherwise exactly one must match)",
+                ),
+        )
+        .attribute(
+            AttributeSchema::new("id", AttributeType::String)
+                .read_only()
+                .with_description("The resolved image ID, e.g. ami-0123456789abcdef0 (read-only)"),
+        )
+        .attribute(
+            AttributeSchema::new("image_name", AttributeType::String)
+                .read_only()
+                .with_description("The name of the resolved image (read-only)"),
+        )
+        .attribute(
+            AttributeSchema::new("creation_date", AttributeType::String)
+                .read_only()
+                .with_description("When the resolved image was created (read-only)"),
+        )
+}
+
+/// Returns the schema for Elastic IP
+pub fn eip_schema() -> ResourceSchema {
+    ResourceSchema::new("ec2.eip")
+        .with_description("An AWS Elastic IP address allocated for use in a VPC")
+        .attribute(
+            AttributeSchema::new("name", AttributeType::String)
+                .required()
+                .with_description("Elastic IP name (Name tag)"),
+        )
+        .attribute(
+            AttributeSchema::new("region", aws_types::aws_region()).with_description(
+                "The AWS region for the Elastic IP (inherited from provider if not specified)",
+            ),
+        )
+        .attribute(
+            AttributeSchema::new("arn", AttributeType::String)
+                .read_only()
+                .with_description("Elastic IP ARN (read-only)"),
+        )
+        .attribute(
+            AttributeSchema::new("allocation_id", AttributeType::String)
+                .read_only()
+                .with_description("The allocation ID, e.g. eipalloc-0123456789abcdef0 (read-only)"),
+        )
+        .attribute(
+            AttributeSchema::new("public_ip", AttributeType::String)
+                .read_only()
+                .with_description("The allocated public IPv4 address (read-only)"),
+        )
+        .attribute(
+            AttributeSchema::new("tags", super::vpc::tags_type())
+                .with_description("Additional tags for the Elastic IP"),
+        )
+}
+
+/// Returns the schema for EC2 Instance
+pub fn instance_schema() -> ResourceSchema {
+    ResourceSchema::new("ec2.instance")
+        .with_description("An AWS EC2 instance")
+        .attribute(
+            AttributeSchema::new("id", AttributeType::String)
+                .read_only()
+                .with_description("Instance ID (read-only, set after creation)"),
+        )
+        .attribute(
+            AttributeSchema::new("arn", AttributeType::String)
+                .read_only()
+                .with_description("Instance ARN (read-only, set after creation)"),
+        )
+        .attribute(
+            AttributeSchema::new("name", AttributeType::String)
+                .required()
+                .with_description("Instance name (Name tag)"),
+        )
+        .attribute(
+            AttributeSchema::new("region", aws_types::aws_region()).with_description(
+                "The AWS region for the instance (inherited from provider if not specified)",
+            ),
+        )
+        .attribute(
+            AttributeSchema::new("ami", AttributeType::String)
+                .required()
+                .immutable()
+                .with_description("ID of the AMI to launch, e.g. ami-0123456789abcdef0"),
+        )
+        .attribute(
+            AttributeSchema::new("instance_type", AttributeType::String)
+                .required()
+                .with_description(
+                    "Instance type, e.g. t3.micro (changing it stops and restarts the instance)",
+                ),
+        )
+        .attribute(
+            AttributeSchema::new("subnet_id", AttributeType::String)
+                .immutable()
+                .with_description("Subnet to launch the instance in (default subnet if omitted)"),
+        )
+        .attribute(
+            AttributeSchema::new(
+                "security_group_ids",
+                AttributeType::List(Box::new(AttributeType::String)),
+            )
+            .unordered()
+            .with_description("Security groups for the instance's primary network interface"),
+        )
+        .attribute(
+            AttributeSchema::new("iam_instance_profile", AttributeType::String).with_description(
+                "IAM instance profile to attach, by name or ARN (an empty string detaches it)",
+            ),
+        )
+        .attribute(
+            AttributeSchema::new("private_ip", AttributeType::String)
+                .read_only()
+                .with_description("The private IPv4 address (read-only)"),
+        )
+        .attribute(
+            AttributeSchema::new("public_ip", AttributeType::String)
+                .read_only()
+                .with_description("The public IPv4 address, if any (read-only)"),
+        )
+        .attribute(
+            AttributeSchema::new("state", AttributeType::String)
+                .read_only()
+                .with_description(
+                    "The current state of the instance, e.g. pending or running (read-only)",
+                ),
+        )
+        .attribute(
+            AttributeSchema::new("tags", super::vpc::tags_type())
+                .with_description("Additional tags for the instance"),
+        )
+}
+
+/// Returns all EC2-related schemas
+pub fn schemas() -> Vec<ResourceSchema> {
+    vec![ami_schema(), eip_schema(), instance_schema()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn valid_ami_lookup() {
+        let schema = ami_schema();
+        let mut attrs = HashMap::new();
+        attrs.insert("name".to_string(), Value::String("al2023".to_string()));
+        attrs.insert(
+            "owners".to_string(),
+            Value::List(vec![Value::String("amazon".to_string())]),
+        );
+        attrs.insert(
+            "name_pattern".to_string(),
+            Value::String("al2023-ami-*-x86_64".to_string()),
+        );
+        attrs.insert("most_recent".to_string(), Value::Bool(true));
+
+        assert!(schema.validate(&attrs).is_ok());
+    }
+
+    #[test]
+    fn ami_lookup_requires_name_pattern() {
+        let schema = ami_schema();
+        let mut attrs = HashMap::new();
+        attrs.insert("name".to_string(), Value::String("al2023".to_string()));
+
+        assert!(schema.validate(&attrs).is_err());
+    }
+
+    #[test]
+    fn valid_eip() {
+        let schema = eip_schema();
+        let mut attrs = HashMap::new();
+        attrs.insert("name".to_string(), Value::String("nat-eip".to_string()));
+        attrs.insert(
+            "tags".to_string(),
+            Value::Map(
+                [("Environment".to_string(), Value::String("dev".to_string()))]
+                    .into_iter()
+                    .collect(),
+            ),
+        );
+
+        assert!(schema.validate(&attrs).is_ok());
+    }
+
+    #[test]
+    fn valid_instance() {
+        let schema = instance_schema();
+        let mut attrs = HashMap::new();
+        attrs.insert("name".to_string(), Value::String("web".to_string()));
+        attrs.insert(
+            "ami".to_string(),
+            Value::String("ami-0123456789abcdef0".to_string()),
+        );
+        attrs.insert(
+            "instance_type".to_string(),
+            Value::String("t3.micro".to_string()),
+        );
+        attrs.insert(
+            "security_group_ids".to_string(),
+            Value::List(vec![Value::String("sg-0123456789abcdef0".to_string())]),
+        );
+
+        assert!(schema.validate(&attrs).is_ok());
+    }
+
+    #[test]
+    fn instance_type_updates_in_place() {
+        let schema = instance_schema();
+        assert_eq!(schema.immutable_attributes(), vec!["ami", "subnet_id"]);
+    }
+
+    #[test]
+    fn instance_ids_and_addresses_are_read_only() {
+        let schema = instance_schema();
+        for name in ["id", "arn", "private_ip", "public_ip", "state"] {
+            assert!(schema.attributes[name].read_only, "{}", name);
+        }
+        assert!(!schema.attributes["instance_type"].read_only);
+    }
+}
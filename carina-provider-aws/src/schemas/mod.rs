@@ -1,5 +1,6 @@
 //! AWS resource schema definitions
 
+pub mod ec2;
 pub mod s3;
 pub mod types;
 pub mod vpc;
@@ -11,5 +12,6 @@ pub fn all_schemas() -> Vec<ResourceSchema> {
     let mut schemas = Vec::new();
     schemas.extend(s3::schemas());
     schemas.extend(vpc::schemas());
+    schemas.extend(ec2::schemas());
     schemas
 }
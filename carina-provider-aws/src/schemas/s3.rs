@@ -1,6 +1,6 @@
 //! S3 bucket schema definition
 
-use carina_core::schema::{AttributeSchema, CompletionValue, ResourceSchema, types};
+use carina_core::schema::{AttributeSchema, AttributeType, CompletionValue, ResourceSchema};
 
 use super::types as aws_types;
 
@@ -10,19 +10,26 @@ pub fn bucket_schema() -> ResourceSchema {
         .with_description("An S3 bucket for object storage")
         .attribute(
             AttributeSchema::new("name", aws_types::s3_bucket_name())
-                .with_description("Override bucket name (defaults to resource name)"),
+                .with_description("Override bucket name (defaults to resource name)")
+                .with_pattern(r"^[a-z0-9][a-z0-9.-]*[a-z0-9]$"),
         )
         .attribute(
             AttributeSchema::new("region", aws_types::aws_region()).with_description(
                 "The AWS region for the bucket (inherited from provider if not specified)",
             ),
         )
+        .attribute(
+            AttributeSchema::new("arn", AttributeType::String)
+                .read_only()
+                .with_description("Bucket ARN, e.g. arn:aws:s3:::my-bucket (read-only)"),
+        )
         .attribute(
             AttributeSchema::new("acl", aws_types::s3_acl())
                 .with_description("The canned ACL for the bucket"),
         )
         .attribute(
             AttributeSchema::new("versioning", aws_types::versioning_status())
+                .with_enum_values(aws_types::VALID_VERSIONING_STATUS)
                 .with_description("Versioning status for the bucket (Enabled or Suspended)")
                 .with_completions(vec![
                     CompletionValue::new("Enabled", "Enable versioning for the bucket"),
@@ -30,14 +37,66 @@ pub fn bucket_schema() -> ResourceSchema {
                 ]),
         )
         .attribute(
-            AttributeSchema::new("expiration_days", types::positive_int())
-                .with_description("Number of days before objects expire"),
+            AttributeSchema::new("expiration_days", AttributeType::Int)
+                .with_description(
+                    "Number of days before objects expire (shorthand for a single lifecycle rule)",
+                )
+                .with_min(1),
+        )
+        .attribute(
+            AttributeSchema::new("lifecycle_rules", aws_types::s3_lifecycle_rules())
+                .with_description(
+                    "Lifecycle rules, each with id, prefix, expiration_days and transitions ({ days, storage_class })",
+                ),
+        )
+        .attribute(
+            AttributeSchema::new("cors_rules", aws_types::s3_cors_rules()).with_description(
+                "CORS rules, each with allowed_methods, allowed_origins, allowed_headers and max_age_seconds",
+            ),
+        )
+        .attribute(
+            AttributeSchema::new("website", aws_types::s3_website()).with_description(
+                "Static website hosting (index_document, error_document, redirect_all_requests_to)",
+            ),
+        )
+        .attribute(
+            AttributeSchema::new("website_endpoint", AttributeType::String)
+                .read_only()
+                .with_description(
+                    "Website endpoint host name (read-only, set when website is configured)",
+                ),
+        )
+}
+
+/// Returns the schema for S3 bucket policies
+pub fn bucket_policy_schema() -> ResourceSchema {
+    ResourceSchema::new("s3.bucket_policy")
+        .with_description("The policy attached to an S3 bucket")
+        .attribute(
+            AttributeSchema::new("name", AttributeType::String)
+                .with_description("Policy name (defaults to resource name)"),
+        )
+        .attribute(
+            AttributeSchema::new("region", aws_types::aws_region()).with_description(
+                "The AWS region of the bucket (inherited from provider if not specified)",
+            ),
+        )
+        .attribute(
+            AttributeSchema::new("bucket", AttributeType::String)
+                .required()
+                .immutable()
+                .with_description("Name of the bucket the policy is attached to"),
+        )
+        .attribute(
+            AttributeSchema::new("policy", aws_types::policy_document())
+                .required()
+                .with_description("The bucket policy as a JSON document"),
         )
 }
 
 /// Returns all S3-related schemas
 pub fn schemas() -> Vec<ResourceSchema> {
-    vec![bucket_schema()]
+    vec![bucket_schema(), bucket_policy_schema()]
 }
 
 #[cfg(test)]
@@ -122,4 +181,30 @@ mod tests {
 
         assert!(schema.validate(&attrs).is_err());
     }
+
+    #[test]
+    fn constraint_errors_are_reported_together() {
+        let schema = bucket_schema();
+        let mut attrs = HashMap::new();
+        attrs.insert("name".to_string(), Value::String("my_bucket".to_string()));
+        attrs.insert("expiration_days".to_string(), Value::Int(0));
+
+        let errors: Vec<String> = schema
+            .validate(&attrs)
+            .unwrap_err()
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(errors.len(), 2);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e == "'expiration_days' must be at least 1, got 0")
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.starts_with("'name' value \"my_bucket\" does not match pattern"))
+        );
+    }
 }
@@ -70,10 +70,10 @@ fn normalize_region(s: &str) -> String {
 }
 
 /// Valid versioning status values
-const VALID_VERSIONING_STATUS: &[&str] = &["Enabled", "Suspended"];
+pub const VALID_VERSIONING_STATUS: &[&str] = &["Enabled", "Suspended"];
 
 /// S3 bucket versioning status
-/// Accepts:
+/// Accepts (case-insensitive):
 /// - DSL format: aws.s3.VersioningStatus.Enabled
 /// - Short DSL format: VersioningStatus.Enabled
 /// - Value only: Enabled, Suspended
@@ -83,46 +83,26 @@ pub fn versioning_status() -> AttributeType {
         base: Box::new(AttributeType::String),
         validate: |value| {
             if let Value::String(s) = value {
-                // Check namespace format if it contains dots
-                if s.contains('.') {
-                    let parts: Vec<&str> = s.split('.').collect();
-                    match parts.len() {
-                        // 2-part: VersioningStatus.value
-                        2 => {
-                            if parts[0] != "VersioningStatus" {
-                                return Err(format!(
-                                    "Invalid versioning status '{}', expected format: VersioningStatus.Enabled or VersioningStatus.Suspended",
-                                    s
-                                ));
-                            }
-                        }
-                        // 4-part: aws.s3.VersioningStatus.value
-                        4 => {
-                            if parts[0] != "aws"
-                                || parts[1] != "s3"
-                                || parts[2] != "VersioningStatus"
-                            {
-                                return Err(format!(
-                                    "Invalid versioning status '{}', expected format: aws.s3.VersioningStatus.Enabled or aws.s3.VersioningStatus.Suspended",
-                                    s
-                                ));
-                            }
-                        }
-                        _ => {
-                            return Err(format!(
-                                "Invalid versioning status '{}', expected one of: Enabled, Suspended, VersioningStatus.Enabled, or aws.s3.VersioningStatus.Enabled",
-                                s
-                            ));
-                        }
-                    }
+                // Everything before the value must be nothing, the type name, or the full namespace
+                let prefix: Vec<&str> = s.split('.').collect();
+                let prefix = &prefix[..prefix.len() - 1];
+                if !matches!(
+                    prefix,
+                    [] | ["VersioningStatus"] | ["aws", "s3", "VersioningStatus"]
+                ) {
+                    return Err(format!(
+                        "Invalid versioning status '{}', expected one of: Enabled, Suspended, VersioningStatus.Enabled, or aws.s3.VersioningStatus.Enabled",
+                        s
+                    ));
                 }
                 let normalized = normalize_versioning_status(s);
                 if VALID_VERSIONING_STATUS.contains(&normalized.as_str()) {
                     Ok(())
                 } else {
                     Err(format!(
-                        "Invalid versioning status '{}', expected one of: Enabled, Suspended",
-                        s
+                        "Invalid versioning status '{}', expected one of: {}",
+                        s,
+                        VALID_VERSIONING_STATUS.join(", ")
                     ))
                 }
             } else {
@@ -135,13 +115,14 @@ pub fn versioning_status() -> AttributeType {
 
 /// Normalize versioning status to API format
 /// - "aws.s3.VersioningStatus.Enabled" -> "Enabled"
-/// - "Enabled" -> "Enabled"
+/// - "enabled" -> "Enabled"
 pub fn normalize_versioning_status(s: &str) -> String {
-    if s.contains('.') {
-        s.split('.').next_back().unwrap_or(s).to_string()
-    } else {
-        s.to_string()
-    }
+    let raw = s.split('.').next_back().unwrap_or(s);
+    VALID_VERSIONING_STATUS
+        .iter()
+        .find(|valid| valid.eq_ignore_ascii_case(raw))
+        .map_or(raw, |valid| valid)
+        .to_string()
 }
 
 /// S3 ACL enum type
@@ -183,6 +164,274 @@ pub fn s3_bucket_name() -> AttributeType {
     }
 }
 
+/// S3 static website configuration block type
+/// A single `website { ... }` block with `index_document` and optional `error_document`,
+/// or only `redirect_all_requests_to` (a host name) to redirect every request.
+pub fn s3_website() -> AttributeType {
+    AttributeType::Custom {
+        name: "Website".to_string(),
+        base: Box::new(AttributeType::List(Box::new(AttributeType::Map(Box::new(
+            AttributeType::String,
+        ))))),
+        validate: |value| {
+            let block = match value {
+                Value::List(blocks) if blocks.len() == 1 => &blocks[0],
+                Value::List(_) => return Err("Only one website block is allowed".to_string()),
+                _ => return Err("Expected a website block".to_string()),
+            };
+            let Value::Map(attrs) = block else {
+                return Err("Expected a website block".to_string());
+            };
+
+            for (key, value) in attrs {
+                if !matches!(
+                    key.as_str(),
+                    "index_document" | "error_document" | "redirect_all_requests_to"
+                ) {
+                    return Err(format!("Unknown website attribute '{}'", key));
+                }
+                if !matches!(value, Value::String(_) | Value::ResourceRef(_, _)) {
+                    return Err(format!("Website attribute '{}' must be a string", key));
+                }
+            }
+
+            let redirects = attrs.contains_key("redirect_all_requests_to");
+            if redirects && attrs.len() > 1 {
+                return Err(
+                    "redirect_all_requests_to cannot be combined with other website attributes"
+                        .to_string(),
+                );
+            }
+            if !redirects && !attrs.contains_key("index_document") {
+                return Err(
+                    "Website requires index_document (or redirect_all_requests_to)".to_string(),
+                );
+            }
+            Ok(())
+        },
+        namespace: None,
+    }
+}
+
+/// Storage classes S3 lifecycle transitions can move objects to
+pub const S3_TRANSITION_STORAGE_CLASSES: &[&str] = &[
+    "STANDARD_IA",
+    "ONEZONE_IA",
+    "INTELLIGENT_TIERING",
+    "GLACIER_IR",
+    "GLACIER",
+    "DEEP_ARCHIVE",
+];
+
+/// S3 lifecycle rules: a list of `{ id, prefix, expiration_days, transitions }`
+/// maps, where `transitions` is a list of `{ days, storage_class }` maps
+pub fn s3_lifecycle_rules() -> AttributeType {
+    AttributeType::Custom {
+        name: "LifecycleRules".to_string(),
+        base: Box::new(AttributeType::List(Box::new(AttributeType::Map(Box::new(
+            AttributeType::String,
+        ))))),
+        validate: |value| {
+            let Value::List(rules) = value else {
+                return Err("Expected a list of lifecycle rules".to_string());
+            };
+
+            let mut ids = std::collections::HashSet::new();
+            for rule in rules {
+                let Value::Map(attrs) = rule else {
+                    return Err("Expected a lifecycle rule map".to_string());
+                };
+                for key in attrs.keys() {
+                    if !matches!(
+                        key.as_str(),
+                        "id" | "prefix" | "expiration_days" | "transitions"
+                    ) {
+                        return Err(format!("Unknown lifecycle rule attribute '{}'", key));
+                    }
+                }
+
+                let id = match attrs.get("id") {
+                    Some(Value::String(id)) if !id.is_empty() => id,
+                    _ => return Err("Lifecycle rule requires a string id".to_string()),
+                };
+                if !ids.insert(id.as_str()) {
+                    return Err(format!("Duplicate lifecycle rule id '{}'", id));
+                }
+                if !matches!(attrs.get("prefix"), None | Some(Value::String(_))) {
+                    return Err(format!("Lifecycle rule '{}': prefix must be a string", id));
+                }
+                match attrs.get("expiration_days") {
+                    None | Some(Value::Int(1..)) => {}
+                    Some(_) => {
+                        return Err(format!(
+                            "Lifecycle rule '{}': expiration_days must be a positive integer",
+                            id
+                        ));
+                    }
+                }
+
+                let transitions = match attrs.get("transitions") {
+                    None => &Vec::new(),
+                    Some(Value::List(transitions)) => transitions,
+                    Some(_) => {
+                        return Err(format!(
+                            "Lifecycle rule '{}': transitions must be a list",
+                            id
+                        ));
+                    }
+                };
+                for transition in transitions {
+                    let Value::Map(transition) = transition else {
+                        return Err(format!(
+                            "Lifecycle rule '{}': expected a transition map",
+                            id
+                        ));
+                    };
+                    if !matches!(transition.get("days"), Some(Value::Int(0..))) {
+                        return Err(format!(
+                            "Lifecycle rule '{}': transition days must be a non-negative integer",
+                            id
+                        ));
+                    }
+                    match transition.get("storage_class") {
+                        Some(Value::String(class))
+                            if S3_TRANSITION_STORAGE_CLASSES.contains(&class.as_str()) => {}
+                        _ => {
+                            return Err(format!(
+                                "Lifecycle rule '{}': transition storage_class must be one of {}",
+                                id,
+                                S3_TRANSITION_STORAGE_CLASSES.join(", ")
+                            ));
+                        }
+                    }
+                    if transition.len() > 2 {
+                        return Err(format!(
+                            "Lifecycle rule '{}': transitions only take days and storage_class",
+                            id
+                        ));
+                    }
+                }
+
+                if !attrs.contains_key("expiration_days") && transitions.is_empty() {
+                    return Err(format!(
+                        "Lifecycle rule '{}' needs expiration_days or transitions",
+                        id
+                    ));
+                }
+            }
+            Ok(())
+        },
+        namespace: None,
+    }
+}
+
+/// A JSON policy document, such as an S3 bucket policy
+pub fn policy_document() -> AttributeType {
+    AttributeType::Custom {
+        name: "PolicyDocument".to_string(),
+        base: Box::new(AttributeType::String),
+        validate: |value| {
+            let Value::String(s) = value else {
+                return Err("Expected a JSON policy string".to_string());
+            };
+            match serde_json::from_str::<serde_json::Value>(s) {
+                Ok(serde_json::Value::Object(_)) => Ok(()),
+                Ok(_) => Err("Policy must be a JSON object".to_string()),
+                Err(e) => Err(format!("Policy is not valid JSON: {}", e)),
+            }
+        },
+        namespace: None,
+    }
+}
+
+/// HTTP methods S3 CORS rules can allow
+pub const S3_CORS_METHODS: &[&str] = &["GET", "PUT", "POST", "DELETE", "HEAD"];
+
+/// S3 CORS rules: a list of `{ allowed_methods, allowed_origins,
+/// allowed_headers, max_age_seconds }` maps
+pub fn s3_cors_rules() -> AttributeType {
+    AttributeType::Custom {
+        name: "CorsRules".to_string(),
+        base: Box::new(AttributeType::List(Box::new(AttributeType::Map(Box::new(
+            AttributeType::String,
+        ))))),
+        validate: |value| {
+            let Value::List(rules) = value else {
+                return Err("Expected a list of CORS rules".to_string());
+            };
+
+            for rule in rules {
+                let Value::Map(attrs) = rule else {
+                    return Err("Expected a CORS rule map".to_string());
+                };
+                for key in attrs.keys() {
+                    if !matches!(
+                        key.as_str(),
+                        "allowed_methods"
+                            | "allowed_origins"
+                            | "allowed_headers"
+                            | "max_age_seconds"
+                    ) {
+                        return Err(format!("Unknown CORS rule attribute '{}'", key));
+                    }
+                }
+
+                let strings = |key: &str, required: bool| -> Result<Vec<String>, String> {
+                    match attrs.get(key) {
+                        None if !required => Ok(Vec::new()),
+                        Some(Value::List(items)) if !items.is_empty() || !required => items
+                            .iter()
+                            .map(|item| match item {
+                                Value::String(s) => Ok(s.clone()),
+                                _ => Err(format!("CORS rule {} must be strings", key)),
+                            })
+                            .collect(),
+                        _ => Err(format!("CORS rule requires a non-empty {} list", key)),
+                    }
+                };
+                for method in strings("allowed_methods", true)? {
+                    if !S3_CORS_METHODS.contains(&method.as_str()) {
+                        return Err(format!(
+                            "Invalid CORS method '{}', expected one of {}",
+                            method,
+                            S3_CORS_METHODS.join(", ")
+                        ));
+                    }
+                }
+                strings("allowed_origins", true)?;
+                strings("allowed_headers", false)?;
+                if !matches!(attrs.get("max_age_seconds"), None | Some(Value::Int(0..))) {
+                    return Err(
+                        "CORS rule max_age_seconds must be a non-negative integer".to_string()
+                    );
+                }
+            }
+            Ok(())
+        },
+        namespace: None,
+    }
+}
+
+/// S3 website endpoint host name for a bucket in a region.
+/// Older regions use the "s3-website-<region>" form, newer ones "s3-website.<region>".
+pub fn s3_website_endpoint(bucket: &str, region: &str) -> String {
+    const DASH_REGIONS: &[&str] = &[
+        "us-east-1",
+        "us-west-1",
+        "us-west-2",
+        "ap-southeast-1",
+        "ap-southeast-2",
+        "ap-northeast-1",
+        "eu-west-1",
+        "sa-east-1",
+    ];
+    if DASH_REGIONS.contains(&region) {
+        format!("{}.s3-website-{}.amazonaws.com", bucket, region)
+    } else {
+        format!("{}.s3-website.{}.amazonaws.com", bucket, region)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -301,12 +550,39 @@ mod tests {
     }
 
     #[test]
-    fn versioning_rejects_lowercase() {
+    fn versioning_accepts_mixed_case_at_every_depth() {
         let versioning = versioning_status();
+        for s in [
+            "enabled",
+            "SUSPENDED",
+            "VersioningStatus.enabled",
+            "aws.s3.VersioningStatus.enabled",
+        ] {
+            assert!(versioning.validate(&Value::String(s.to_string())).is_ok());
+        }
+    }
+
+    #[test]
+    fn versioning_normalizes_to_canonical_value() {
+        assert_eq!(normalize_versioning_status("enabled"), "Enabled");
+        assert_eq!(
+            normalize_versioning_status("VersioningStatus.suspended"),
+            "Suspended"
+        );
+        assert_eq!(
+            normalize_versioning_status("aws.s3.VersioningStatus.ENABLED"),
+            "Enabled"
+        );
+    }
+
+    #[test]
+    fn versioning_error_lists_canonical_values() {
+        let err = versioning_status()
+            .validate(&Value::String("aws.s3.VersioningStatus.on".to_string()))
+            .unwrap_err();
         assert!(
-            versioning
-                .validate(&Value::String("enabled".to_string()))
-                .is_err()
+            err.to_string()
+                .contains("expected one of: Enabled, Suspended")
         );
     }
 
@@ -369,4 +645,174 @@ mod tests {
         assert_eq!(normalize_versioning_status("Enabled"), "Enabled");
         assert_eq!(normalize_versioning_status("Suspended"), "Suspended");
     }
+
+    // S3 website tests
+
+    fn website(entries: &[(&str, &str)]) -> Value {
+        Value::List(vec![Value::Map(
+            entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), Value::String(v.to_string())))
+                .collect(),
+        )])
+    }
+
+    #[test]
+    fn website_accepts_index_and_error_documents() {
+        let t = s3_website();
+        assert!(
+            t.validate(&website(&[
+                ("index_document", "index.html"),
+                ("error_document", "error.html")
+            ]))
+            .is_ok()
+        );
+        assert!(
+            t.validate(&website(&[("redirect_all_requests_to", "example.com")]))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn website_rejects_invalid_blocks() {
+        let t = s3_website();
+        assert!(
+            t.validate(&website(&[("error_document", "error.html")]))
+                .is_err()
+        );
+        assert!(
+            t.validate(&website(&[
+                ("index_document", "index.html"),
+                ("redirect_all_requests_to", "example.com")
+            ]))
+            .is_err()
+        );
+        assert!(t.validate(&website(&[("index", "index.html")])).is_err());
+    }
+
+    fn map_value(entries: Vec<(&str, Value)>) -> Value {
+        Value::Map(
+            entries
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        )
+    }
+
+    fn glacier_after(days: i64) -> Value {
+        Value::List(vec![map_value(vec![
+            ("days", Value::Int(days)),
+            ("storage_class", Value::String("GLACIER".to_string())),
+        ])])
+    }
+
+    #[test]
+    fn lifecycle_rules_accept_expirations_and_transitions() {
+        let t = s3_lifecycle_rules();
+        let rules = Value::List(vec![
+            map_value(vec![
+                ("id", Value::String("logs".to_string())),
+                ("prefix", Value::String("logs/".to_string())),
+                ("expiration_days", Value::Int(365)),
+                ("transitions", glacier_after(30)),
+            ]),
+            map_value(vec![
+                ("id", Value::String("tmp".to_string())),
+                ("expiration_days", Value::Int(1)),
+            ]),
+        ]);
+        assert!(t.validate(&rules).is_ok());
+    }
+
+    #[test]
+    fn lifecycle_rules_reject_invalid_rules() {
+        let t = s3_lifecycle_rules();
+        let id = || ("id", Value::String("logs".to_string()));
+
+        // No action
+        assert!(
+            t.validate(&Value::List(vec![map_value(vec![id()])]))
+                .is_err()
+        );
+        // Duplicate ids
+        let rule = map_value(vec![id(), ("expiration_days", Value::Int(7))]);
+        assert!(t.validate(&Value::List(vec![rule.clone(), rule])).is_err());
+        // Unknown storage class
+        let rule = map_value(vec![
+            id(),
+            (
+                "transitions",
+                Value::List(vec![map_value(vec![
+                    ("days", Value::Int(30)),
+                    ("storage_class", Value::String("TAPE".to_string())),
+                ])]),
+            ),
+        ]);
+        let err = t.validate(&Value::List(vec![rule])).unwrap_err();
+        assert!(err.to_string().contains("GLACIER"));
+        // Non-positive expiration
+        let rule = map_value(vec![id(), ("expiration_days", Value::Int(0))]);
+        assert!(t.validate(&Value::List(vec![rule])).is_err());
+    }
+
+    fn strings(items: &[&str]) -> Value {
+        Value::List(items.iter().map(|s| Value::String(s.to_string())).collect())
+    }
+
+    #[test]
+    fn cors_rules_accept_valid_rules() {
+        let t = s3_cors_rules();
+        let rule = map_value(vec![
+            ("allowed_methods", strings(&["GET", "HEAD"])),
+            ("allowed_origins", strings(&["https://example.com"])),
+            ("allowed_headers", strings(&["*"])),
+            ("max_age_seconds", Value::Int(3000)),
+        ]);
+        assert!(t.validate(&Value::List(vec![rule])).is_ok());
+        assert!(t.validate(&Value::List(vec![])).is_ok());
+    }
+
+    #[test]
+    fn cors_rules_reject_invalid_rules() {
+        let t = s3_cors_rules();
+        let origins = || ("allowed_origins", strings(&["*"]));
+
+        let rule = map_value(vec![("allowed_methods", strings(&["PATCH"])), origins()]);
+        let err = t.validate(&Value::List(vec![rule])).unwrap_err();
+        assert!(err.to_string().contains("PATCH"));
+
+        let rule = map_value(vec![origins()]);
+        assert!(t.validate(&Value::List(vec![rule])).is_err());
+
+        let rule = map_value(vec![
+            ("allowed_methods", strings(&["GET"])),
+            origins(),
+            ("max_age_seconds", Value::Int(-1)),
+        ]);
+        assert!(t.validate(&Value::List(vec![rule])).is_err());
+    }
+
+    #[test]
+    fn policy_document_requires_json_object() {
+        let t = policy_document();
+        let policy = |s: &str| Value::String(s.to_string());
+        assert!(
+            t.validate(&policy(r#"{"Version": "2012-10-17", "Statement": []}"#))
+                .is_ok()
+        );
+        assert!(t.validate(&policy("[]")).is_err());
+        assert!(t.validate(&policy("{ Version: 1 }")).is_err());
+    }
+
+    #[test]
+    fn website_endpoint_depends_on_region() {
+        assert_eq!(
+            s3_website_endpoint("site", "ap-northeast-1"),
+            "site.s3-website-ap-northeast-1.amazonaws.com"
+        );
+        assert_eq!(
+            s3_website_endpoint("site", "eu-central-1"),
+            "site.s3-website.eu-central-1.amazonaws.com"
+        );
+    }
 }
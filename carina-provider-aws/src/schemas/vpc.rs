@@ -44,6 +44,20 @@ pub fn protocol() -> AttributeType {
     ])
 }
 
+/// Direction of a unified security group rule
+pub fn rule_type() -> AttributeType {
+    AttributeType::Enum(vec!["ingress".to_string(), "egress".to_string()])
+}
+
+/// VPC endpoint service type (filter for the vpc_endpoint_services data source)
+pub fn endpoint_service_type() -> AttributeType {
+    AttributeType::Enum(vec![
+        "Gateway".to_string(),
+        "Interface".to_string(),
+        "GatewayLoadBalancer".to_string(),
+    ])
+}
+
 /// Availability zone enum type
 pub fn availability_zone() -> AttributeType {
     AttributeType::Enum(vec![
@@ -205,11 +219,22 @@ pub fn vpc_schema() -> ResourceSchema {
                 "The AWS region for the VPC (inherited from provider if not specified)",
             ),
         )
+        .attribute(
+            AttributeSchema::new("arn", AttributeType::String)
+                .read_only()
+                .with_description("VPC ARN (read-only, set after creation)"),
+        )
         // ========== CloudFormation input properties ==========
         .attribute(
             AttributeSchema::new("cidr_block", types::cidr())
+                .immutable()
                 .with_description("The IPv4 network range for the VPC, in CIDR notation. Required if not using Ipv4IpamPoolId."),
         )
+        .attribute(
+            AttributeSchema::new("secondary_cidr_blocks", AttributeType::List(Box::new(types::cidr())))
+                .unordered()
+                .with_description("Additional IPv4 CIDR blocks to associate with the VPC. Blocks can be added and removed without replacing the VPC"),
+        )
         .attribute(
             AttributeSchema::new("enable_dns_hostnames", AttributeType::Bool)
                 .with_description("Indicates whether instances launched in the VPC get DNS hostnames. Default: false"),
@@ -220,6 +245,7 @@ pub fn vpc_schema() -> ResourceSchema {
         )
         .attribute(
             AttributeSchema::new("instance_tenancy", instance_tenancy())
+                .immutable()
                 .with_description("The allowed tenancy of instances launched into the VPC. Values: default, dedicated, host")
                 .with_completions(vec![
                     CompletionValue::new("default", "Instances can have any tenancy"),
@@ -227,12 +253,18 @@ pub fn vpc_schema() -> ResourceSchema {
                     CompletionValue::new("host", "Instances run on dedicated host"),
                 ]),
         )
+        .attribute(
+            AttributeSchema::new("assign_generated_ipv6_cidr_block", AttributeType::Bool)
+                .with_description("Requests an Amazon-provided IPv6 CIDR block with a /56 prefix length for the VPC"),
+        )
         .attribute(
             AttributeSchema::new("ipv4_ipam_pool_id", AttributeType::String)
+                .immutable()
                 .with_description("The ID of an IPv4 IPAM pool to allocate the VPC CIDR from"),
         )
         .attribute(
             AttributeSchema::new("ipv4_netmask_length", AttributeType::Int)
+                .immutable()
                 .with_description("The netmask length of the IPv4 CIDR to allocate from an IPAM pool"),
         )
         .attribute(
@@ -242,24 +274,39 @@ pub fn vpc_schema() -> ResourceSchema {
         // ========== CloudFormation return values (read-only) ==========
         .attribute(
             AttributeSchema::new("vpc_id", AttributeType::String)
+                .read_only()
                 .with_description("The ID of the VPC (read-only)"),
         )
         .attribute(
             AttributeSchema::new("cidr_block_associations", AttributeType::List(Box::new(AttributeType::String)))
+                .read_only()
                 .with_description("The association IDs of the IPv4 CIDR blocks for the VPC (read-only)"),
         )
         .attribute(
             AttributeSchema::new("default_network_acl", AttributeType::String)
+                .read_only()
                 .with_description("The ID of the default network ACL for the VPC (read-only)"),
         )
         .attribute(
             AttributeSchema::new("default_security_group", AttributeType::String)
+                .read_only()
                 .with_description("The ID of the default security group for the VPC (read-only)"),
         )
+        .attribute(
+            AttributeSchema::new("ipv6_cidr_block", types::ipv6_cidr())
+                .read_only()
+                .with_description("The Amazon-provided IPv6 CIDR block (/56) assigned to the VPC (read-only)"),
+        )
         .attribute(
             AttributeSchema::new("ipv6_cidr_blocks", AttributeType::List(Box::new(AttributeType::String)))
+                .read_only()
                 .with_description("The IPv6 CIDR blocks associated with the VPC (read-only)"),
         )
+        .attribute(
+            AttributeSchema::new("state", AttributeType::String)
+                .read_only()
+                .with_description("The current state of the VPC, e.g. pending or available (read-only)"),
+        )
 }
 
 /// Returns the schema for Subnet
@@ -268,8 +315,14 @@ pub fn subnet_schema() -> ResourceSchema {
         .with_description("An AWS VPC Subnet")
         .attribute(
             AttributeSchema::new("id", AttributeType::String)
+                .read_only()
                 .with_description("Subnet ID (read-only, set after creation)"),
         )
+        .attribute(
+            AttributeSchema::new("arn", AttributeType::String)
+                .read_only()
+                .with_description("Subnet ARN (read-only, set after creation)"),
+        )
         .attribute(
             AttributeSchema::new("name", AttributeType::String)
                 .required()
@@ -283,17 +336,38 @@ pub fn subnet_schema() -> ResourceSchema {
         .attribute(
             AttributeSchema::new("vpc_id", AttributeType::String)
                 .required()
+                .immutable()
                 .with_description("VPC ID to create the subnet in"),
         )
         .attribute(
             AttributeSchema::new("cidr_block", types::cidr())
                 .required()
+                .immutable()
                 .with_description("The IPv4 CIDR block for the subnet"),
         )
+        .attribute(
+            AttributeSchema::new("ipv6_cidr_block", types::ipv6_cidr())
+                .with_description("The IPv6 CIDR block (/64) for the subnet, carved from the VPC's IPv6 block"),
+        )
         .attribute(
             AttributeSchema::new("availability_zone", availability_zone())
+                .immutable()
                 .with_description("The availability zone for the subnet"),
         )
+        .attribute(
+            AttributeSchema::new("map_public_ip_on_launch", AttributeType::Bool).with_description(
+                "Whether instances launched in the subnet get a public IPv4 address. Default: false",
+            ),
+        )
+        .attribute(
+            AttributeSchema::new("tags", tags_type())
+                .with_description("Additional tags for the subnet"),
+        )
+        .attribute(
+            AttributeSchema::new("state", AttributeType::String)
+                .read_only()
+                .with_description("The current state of the subnet, e.g. pending or available (read-only)"),
+        )
 }
 
 /// Returns the schema for Internet Gateway
@@ -302,8 +376,14 @@ pub fn internet_gateway_schema() -> ResourceSchema {
         .with_description("An AWS Internet Gateway")
         .attribute(
             AttributeSchema::new("id", AttributeType::String)
+                .read_only()
                 .with_description("Internet Gateway ID (read-only, set after creation)"),
         )
+        .attribute(
+            AttributeSchema::new("arn", AttributeType::String)
+                .read_only()
+                .with_description("Internet Gateway ARN (read-only, set after creation)"),
+        )
         .attribute(
             AttributeSchema::new("name", AttributeType::String)
                 .required()
@@ -315,8 +395,67 @@ pub fn internet_gateway_schema() -> ResourceSchema {
         )
         .attribute(
             AttributeSchema::new("vpc_id", AttributeType::String)
+                .immutable()
                 .with_description("VPC ID to attach the Internet Gateway to"),
         )
+        .attribute(
+            AttributeSchema::new("tags", tags_type())
+                .with_description("Additional tags for the Internet Gateway"),
+        )
+}
+
+/// Returns the schema for NAT Gateway
+pub fn nat_gateway_schema() -> ResourceSchema {
+    ResourceSchema::new("nat_gateway")
+        .with_description("An AWS NAT Gateway for outbound internet access from private subnets")
+        .attribute(
+            AttributeSchema::new("id", AttributeType::String)
+                .read_only()
+                .with_description("NAT Gateway ID (read-only, set after creation)"),
+        )
+        .attribute(
+            AttributeSchema::new("arn", AttributeType::String)
+                .read_only()
+                .with_description("NAT Gateway ARN (read-only, set after creation)"),
+        )
+        .attribute(
+            AttributeSchema::new("name", AttributeType::String)
+                .required()
+                .with_description("NAT Gateway name (Name tag)"),
+        )
+        .attribute(
+            AttributeSchema::new("region", aws_types::aws_region()).with_description(
+                "The AWS region for the NAT Gateway (inherited from provider if not specified)",
+            ),
+        )
+        .attribute(
+            AttributeSchema::new("subnet_id", AttributeType::String)
+                .required()
+                .immutable()
+                .with_description("Public subnet ID to create the NAT Gateway in"),
+        )
+        .attribute(
+            AttributeSchema::new("allocation_id", AttributeType::String)
+                .required()
+                .immutable()
+                .with_description("Allocation ID of the Elastic IP address for the NAT Gateway"),
+        )
+        .attribute(
+            AttributeSchema::new("public_ip", AttributeType::String)
+                .read_only()
+                .with_description("The Elastic IP address of the NAT Gateway (read-only)"),
+        )
+        .attribute(
+            AttributeSchema::new("state", AttributeType::String)
+                .read_only()
+                .with_description(
+                    "The current state of the NAT Gateway, e.g. pending or available (read-only)",
+                ),
+        )
+        .attribute(
+            AttributeSchema::new("tags", tags_type())
+                .with_description("Additional tags for the NAT Gateway"),
+        )
 }
 
 /// Returns the schema for Route Table
@@ -325,8 +464,14 @@ pub fn route_table_schema() -> ResourceSchema {
         .with_description("An AWS VPC Route Table")
         .attribute(
             AttributeSchema::new("id", AttributeType::String)
+                .read_only()
                 .with_description("Route Table ID (read-only, set after creation)"),
         )
+        .attribute(
+            AttributeSchema::new("arn", AttributeType::String)
+                .read_only()
+                .with_description("Route Table ARN (read-only, set after creation)"),
+        )
         .attribute(
             AttributeSchema::new("name", AttributeType::String)
                 .required()
@@ -340,8 +485,53 @@ pub fn route_table_schema() -> ResourceSchema {
         .attribute(
             AttributeSchema::new("vpc_id", AttributeType::String)
                 .required()
+                .immutable()
                 .with_description("VPC ID for the Route Table"),
         )
+        .attribute(
+            AttributeSchema::new(
+                "routes",
+                AttributeType::List(Box::new(AttributeType::Map(Box::new(AttributeType::String)))),
+            )
+            .unordered()
+            .with_description(
+                "Inline routes: { destination, gateway_id | nat_gateway_id } (the local route is implicit)",
+            ),
+        )
+        .attribute(
+            AttributeSchema::new("tags", tags_type())
+                .with_description("Additional tags for the Route Table"),
+        )
+}
+
+/// Returns the schema for Route Table Association
+pub fn route_table_association_schema() -> ResourceSchema {
+    ResourceSchema::new("route_table_association")
+        .with_description("Associates an AWS VPC Route Table with a Subnet")
+        .attribute(
+            AttributeSchema::new("name", AttributeType::String)
+                .required()
+                .with_description("Association name (for identification)"),
+        )
+        .attribute(
+            AttributeSchema::new("region", aws_types::aws_region())
+                .with_description("The AWS region (inherited from provider if not specified)"),
+        )
+        .attribute(
+            AttributeSchema::new("route_table_id", AttributeType::String)
+                .required()
+                .with_description("Route Table ID"),
+        )
+        .attribute(
+            AttributeSchema::new("subnet_id", AttributeType::String)
+                .required()
+                .with_description("Subnet ID to associate with the Route Table"),
+        )
+        .attribute(
+            AttributeSchema::new("association_id", AttributeType::String)
+                .read_only()
+                .with_description("The association ID (read-only, set after creation)"),
+        )
 }
 
 /// Returns the schema for Route
@@ -383,8 +573,14 @@ pub fn security_group_schema() -> ResourceSchema {
         .with_description("An AWS VPC Security Group")
         .attribute(
             AttributeSchema::new("id", AttributeType::String)
+                .read_only()
                 .with_description("Security Group ID (read-only, set after creation)"),
         )
+        .attribute(
+            AttributeSchema::new("arn", AttributeType::String)
+                .read_only()
+                .with_description("Security Group ARN (read-only, set after creation)"),
+        )
         .attribute(
             AttributeSchema::new("name", AttributeType::String)
                 .required()
@@ -398,12 +594,18 @@ pub fn security_group_schema() -> ResourceSchema {
         .attribute(
             AttributeSchema::new("vpc_id", AttributeType::String)
                 .required()
+                .immutable()
                 .with_description("VPC ID for the Security Group"),
         )
         .attribute(
             AttributeSchema::new("description", AttributeType::String)
+                .immutable()
                 .with_description("Description of the Security Group"),
         )
+        .attribute(
+            AttributeSchema::new("tags", tags_type())
+                .with_description("Additional tags for the Security Group"),
+        )
 }
 
 /// Returns the schema for Security Group Ingress Rule
@@ -412,6 +614,7 @@ pub fn security_group_ingress_rule_schema() -> ResourceSchema {
         .with_description("An inbound rule for an AWS VPC Security Group")
         .attribute(
             AttributeSchema::new("id", AttributeType::String)
+                .read_only()
                 .with_description("Security Group Rule ID (read-only, set after creation)"),
         )
         .attribute(
@@ -426,27 +629,35 @@ pub fn security_group_ingress_rule_schema() -> ResourceSchema {
         .attribute(
             AttributeSchema::new("security_group_id", AttributeType::String)
                 .required()
+                .immutable()
                 .with_description("Security Group ID"),
         )
         .attribute(
             AttributeSchema::new("protocol", protocol())
                 .required()
+                .immutable()
                 .with_description("Protocol (tcp, udp, icmp, or -1 for all)"),
         )
         .attribute(
             AttributeSchema::new("from_port", port_number())
-                .required()
-                .with_description("Start of port range"),
+                .immutable()
+                .with_description("Start of port range (omit when protocol is all)"),
         )
         .attribute(
             AttributeSchema::new("to_port", port_number())
-                .required()
-                .with_description("End of port range"),
+                .immutable()
+                .with_description("End of port range (omit when protocol is all)"),
         )
         .attribute(
             AttributeSchema::new("cidr_blocks", AttributeType::List(Box::new(types::cidr())))
+                .unordered()
+                .immutable()
                 .with_description("List of CIDR blocks to allow"),
         )
+        .attribute(
+            AttributeSchema::new("description", AttributeType::String)
+                .with_description("Description of the rule (can be changed in place)"),
+        )
 }
 
 /// Returns the schema for Security Group Egress Rule
@@ -455,6 +666,7 @@ pub fn security_group_egress_rule_schema() -> ResourceSchema {
         .with_description("An outbound rule for an AWS VPC Security Group")
         .attribute(
             AttributeSchema::new("id", AttributeType::String)
+                .read_only()
                 .with_description("Security Group Rule ID (read-only, set after creation)"),
         )
         .attribute(
@@ -466,6 +678,63 @@ pub fn security_group_egress_rule_schema() -> ResourceSchema {
             AttributeSchema::new("region", aws_types::aws_region())
                 .with_description("The AWS region (inherited from provider if not specified)"),
         )
+        .attribute(
+            AttributeSchema::new("security_group_id", AttributeType::String)
+                .required()
+                .immutable()
+                .with_description("Security Group ID"),
+        )
+        .attribute(
+            AttributeSchema::new("protocol", protocol())
+                .required()
+                .immutable()
+                .with_description("Protocol (tcp, udp, icmp, or -1 for all)"),
+        )
+        .attribute(
+            AttributeSchema::new("from_port", port_number())
+                .immutable()
+                .with_description("Start of port range (omit when protocol is all)"),
+        )
+        .attribute(
+            AttributeSchema::new("to_port", port_number())
+                .immutable()
+                .with_description("End of port range (omit when protocol is all)"),
+        )
+        .attribute(
+            AttributeSchema::new("cidr_blocks", AttributeType::List(Box::new(types::cidr())))
+                .unordered()
+                .immutable()
+                .with_description("List of CIDR blocks to allow"),
+        )
+        .attribute(
+            AttributeSchema::new("description", AttributeType::String)
+                .with_description("Description of the rule (can be changed in place)"),
+        )
+}
+
+/// Returns the schema for a single Security Group Rule (ingress or egress)
+pub fn security_group_rule_schema() -> ResourceSchema {
+    ResourceSchema::new("security_group.rule")
+        .with_description("A single inbound or outbound rule for an AWS VPC Security Group")
+        .attribute(
+            AttributeSchema::new("id", AttributeType::String)
+                .read_only()
+                .with_description("Security Group Rule ID (read-only, set after creation)"),
+        )
+        .attribute(
+            AttributeSchema::new("name", AttributeType::String)
+                .required()
+                .with_description("Rule name (for identification)"),
+        )
+        .attribute(
+            AttributeSchema::new("region", aws_types::aws_region())
+                .with_description("The AWS region (inherited from provider if not specified)"),
+        )
+        .attribute(
+            AttributeSchema::new("type", rule_type())
+                .required()
+                .with_description("Rule direction (ingress or egress)"),
+        )
         .attribute(
             AttributeSchema::new("security_group_id", AttributeType::String)
                 .required()
@@ -487,8 +756,41 @@ pub fn security_group_egress_rule_schema() -> ResourceSchema {
                 .with_description("End of port range"),
         )
         .attribute(
-            AttributeSchema::new("cidr_blocks", AttributeType::List(Box::new(types::cidr())))
-                .with_description("List of CIDR blocks to allow"),
+            AttributeSchema::new("cidr_block", types::cidr())
+                .required()
+                .with_description("CIDR block to allow"),
+        )
+}
+
+/// Returns the schema for the VPC Endpoint Services data source
+pub fn vpc_endpoint_services_schema() -> ResourceSchema {
+    ResourceSchema::new("vpc_endpoint_services")
+        .with_description(
+            "Lists the VPC endpoint service names available in a region (data source only)",
+        )
+        .attribute(
+            AttributeSchema::new("name", AttributeType::String)
+                .required()
+                .with_description("Data source name (for identification)"),
+        )
+        .attribute(
+            AttributeSchema::new("region", aws_types::aws_region())
+                .with_description("The AWS region (inherited from provider if not specified)"),
+        )
+        .attribute(
+            AttributeSchema::new("service_type", endpoint_service_type()).with_description(
+                "Only list services of this type (Gateway, Interface, GatewayLoadBalancer)",
+            ),
+        )
+        .attribute(
+            AttributeSchema::new(
+                "service_names",
+                AttributeType::List(Box::new(AttributeType::String)),
+            )
+            .read_only()
+            .with_description(
+                "Available service names, e.g. com.amazonaws.ap-northeast-1.s3 (read-only)",
+            ),
         )
 }
 
@@ -498,11 +800,15 @@ pub fn schemas() -> Vec<ResourceSchema> {
         vpc_schema(),
         subnet_schema(),
         internet_gateway_schema(),
+        nat_gateway_schema(),
         route_table_schema(),
+        route_table_association_schema(),
         route_schema(),
         security_group_schema(),
         security_group_ingress_rule_schema(),
         security_group_egress_rule_schema(),
+        security_group_rule_schema(),
+        vpc_endpoint_services_schema(),
     ]
 }
 
@@ -572,6 +878,28 @@ mod tests {
         assert!(schema.validate(&attrs).is_ok());
     }
 
+    #[test]
+    fn valid_vpc_with_secondary_cidr_blocks() {
+        let schema = vpc_schema();
+        let mut attrs = HashMap::new();
+        attrs.insert("name".to_string(), Value::String("my-vpc".to_string()));
+        attrs.insert(
+            "cidr_block".to_string(),
+            Value::String("10.0.0.0/16".to_string()),
+        );
+        attrs.insert(
+            "secondary_cidr_blocks".to_string(),
+            Value::List(vec![Value::String("10.1.0.0/16".to_string())]),
+        );
+        assert!(schema.validate(&attrs).is_ok());
+
+        attrs.insert(
+            "secondary_cidr_blocks".to_string(),
+            Value::List(vec![Value::String("10.1.0.0/33".to_string())]),
+        );
+        assert!(schema.validate(&attrs).is_err());
+    }
+
     #[test]
     fn valid_vpc_with_ipam() {
         let schema = vpc_schema();
@@ -750,6 +1078,7 @@ mod tests {
             "availability_zone".to_string(),
             Value::String("aws.AvailabilityZone.ap_northeast_1a".to_string()),
         );
+        attrs.insert("map_public_ip_on_launch".to_string(), Value::Bool(true));
 
         assert!(schema.validate(&attrs).is_ok());
     }
@@ -771,6 +1100,25 @@ mod tests {
         assert!(schema.validate(&attrs).is_ok());
     }
 
+    #[test]
+    fn valid_nat_gateway() {
+        let schema = nat_gateway_schema();
+        let mut attrs = HashMap::new();
+        attrs.insert("name".to_string(), Value::String("my-nat".to_string()));
+        attrs.insert(
+            "subnet_id".to_string(),
+            Value::String("subnet-12345678".to_string()),
+        );
+        attrs.insert(
+            "allocation_id".to_string(),
+            Value::String("eipalloc-12345678".to_string()),
+        );
+        assert!(schema.validate(&attrs).is_ok());
+
+        attrs.remove("allocation_id");
+        assert!(schema.validate(&attrs).is_err());
+    }
+
     #[test]
     fn valid_route_table() {
         let schema = route_table_schema();
@@ -788,6 +1136,25 @@ mod tests {
         assert!(schema.validate(&attrs).is_ok());
     }
 
+    #[test]
+    fn valid_route_table_association() {
+        let schema = route_table_association_schema();
+        let mut attrs = HashMap::new();
+        attrs.insert("name".to_string(), Value::String("public-1a".to_string()));
+        attrs.insert(
+            "route_table_id".to_string(),
+            Value::String("rtb-12345678".to_string()),
+        );
+        attrs.insert(
+            "subnet_id".to_string(),
+            Value::String("subnet-12345678".to_string()),
+        );
+        assert!(schema.validate(&attrs).is_ok());
+
+        attrs.remove("subnet_id");
+        assert!(schema.validate(&attrs).is_err());
+    }
+
     #[test]
     fn valid_security_group() {
         let schema = security_group_schema();
@@ -808,4 +1175,66 @@ mod tests {
 
         assert!(schema.validate(&attrs).is_ok());
     }
+
+    #[test]
+    fn valid_dual_stack_vpc_and_subnet() {
+        let schema = vpc_schema();
+        let mut attrs = HashMap::new();
+        attrs.insert("name".to_string(), Value::String("my-vpc".to_string()));
+        attrs.insert(
+            "cidr_block".to_string(),
+            Value::String("10.0.0.0/16".to_string()),
+        );
+        attrs.insert(
+            "assign_generated_ipv6_cidr_block".to_string(),
+            Value::Bool(true),
+        );
+        assert!(schema.validate(&attrs).is_ok());
+
+        let schema = subnet_schema();
+        let mut attrs = HashMap::new();
+        attrs.insert("name".to_string(), Value::String("my-subnet".to_string()));
+        attrs.insert(
+            "vpc_id".to_string(),
+            Value::String("vpc-12345678".to_string()),
+        );
+        attrs.insert(
+            "cidr_block".to_string(),
+            Value::String("10.0.1.0/24".to_string()),
+        );
+        attrs.insert(
+            "ipv6_cidr_block".to_string(),
+            Value::String("2001:db8:1234:1a00::/64".to_string()),
+        );
+        assert!(schema.validate(&attrs).is_ok());
+
+        attrs.insert(
+            "ipv6_cidr_block".to_string(),
+            Value::String("10.0.2.0/24".to_string()),
+        );
+        assert!(schema.validate(&attrs).is_err());
+    }
+
+    #[test]
+    fn security_group_rule_requires_valid_type() {
+        let schema = security_group_rule_schema();
+        let mut attrs = HashMap::new();
+        attrs.insert("name".to_string(), Value::String("allow-http".to_string()));
+        attrs.insert("type".to_string(), Value::String("ingress".to_string()));
+        attrs.insert(
+            "security_group_id".to_string(),
+            Value::String("sg-12345678".to_string()),
+        );
+        attrs.insert("protocol".to_string(), Value::String("tcp".to_string()));
+        attrs.insert("from_port".to_string(), Value::Int(80));
+        attrs.insert("to_port".to_string(), Value::Int(80));
+        attrs.insert(
+            "cidr_block".to_string(),
+            Value::String("0.0.0.0/0".to_string()),
+        );
+        assert!(schema.validate(&attrs).is_ok());
+
+        attrs.insert("type".to_string(), Value::String("inbound".to_string()));
+        assert!(schema.validate(&attrs).is_err());
+    }
 }
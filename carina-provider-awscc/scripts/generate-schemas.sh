@@ -5,28 +5,14 @@
 #   aws-vault exec <profile> -- ./carina-provider-awscc/scripts/generate-schemas.sh
 #
 # This script generates Rust schema code from CloudFormation resource type schemas.
+# The resource types to generate are listed in resource-types.txt.
 
 set -e
 
 OUTPUT_DIR="carina-provider-awscc/src/schemas/generated"
-mkdir -p "$OUTPUT_DIR"
-
-# List of resource types to generate
-RESOURCE_TYPES=(
-    "AWS::EC2::VPC"
-    "AWS::EC2::Subnet"
-    "AWS::EC2::InternetGateway"
-    "AWS::EC2::RouteTable"
-    "AWS::EC2::Route"
-    "AWS::EC2::SubnetRouteTableAssociation"
-    "AWS::EC2::EIP"
-    "AWS::EC2::NatGateway"
-    "AWS::EC2::SecurityGroup"
-    "AWS::EC2::SecurityGroupIngress"
-    "AWS::EC2::SecurityGroupEgress"
-    "AWS::EC2::VPCEndpoint"
-    "AWS::EC2::VPCGatewayAttachment"
-)
+TYPE_NAMES_FILE="carina-provider-awscc/scripts/resource-types.txt"
+SCHEMA_DIR=$(mktemp -d)
+trap 'rm -rf "$SCHEMA_DIR"' EXIT
 
 echo "Generating awscc provider schemas..."
 echo "Output directory: $OUTPUT_DIR"
@@ -48,171 +34,36 @@ if [ ! -f "$CODEGEN_BIN" ]; then
     fi
 fi
 
-for TYPE_NAME in "${RESOURCE_TYPES[@]}"; do
-    # Use codegen to compute the module name (e.g., security_group_egress)
-    MODNAME=$("$CODEGEN_BIN" --type-name "$TYPE_NAME" --print-module-name)
-    OUTPUT_FILE="$OUTPUT_DIR/${MODNAME}.rs"
-
-    echo "Generating $TYPE_NAME -> $OUTPUT_FILE"
+# Fetch each schema as <service>_<resource>.json (e.g., ec2_vpc.json)
+grep -v -e '^#' -e '^[[:space:]]*$' "$TYPE_NAMES_FILE" | while read -r TYPE_NAME; do
+    FULL_RESOURCE=$("$CODEGEN_BIN" --type-name "$TYPE_NAME" --print-full-resource-name)
+    echo "Fetching $TYPE_NAME"
 
-    aws cloudformation describe-type \
+    if ! aws cloudformation describe-type \
         --type RESOURCE \
         --type-name "$TYPE_NAME" \
         --query 'Schema' \
-        --output text 2>/dev/null | \
-    "$CODEGEN_BIN" --type-name "$TYPE_NAME" > "$OUTPUT_FILE"
-
-    if [ $? -ne 0 ]; then
-        echo "  ERROR: Failed to generate $TYPE_NAME"
-        rm -f "$OUTPUT_FILE"
+        --output text > "$SCHEMA_DIR/${FULL_RESOURCE}.json" 2>/dev/null; then
+        echo "  ERROR: Failed to fetch $TYPE_NAME"
+        rm -f "$SCHEMA_DIR/${FULL_RESOURCE}.json"
     fi
 done
 
-# Generate mod.rs
+# Generate one module per type and mod.rs; types that fail are reported at the end
 echo ""
-echo "Generating $OUTPUT_DIR/mod.rs"
-
-cat > "$OUTPUT_DIR/mod.rs" << 'EOF'
-//! Auto-generated AWS Cloud Control resource schemas
-//!
-//! DO NOT EDIT MANUALLY - regenerate with:
-//!   aws-vault exec <profile> -- ./carina-provider-awscc/scripts/generate-schemas.sh
-
-use carina_core::resource::Value;
-use carina_core::schema::{AttributeType, ResourceSchema};
-
-/// AWS Cloud Control schema configuration
-///
-/// Combines the generated ResourceSchema with AWS-specific metadata
-/// that was previously in ResourceConfig.
-pub struct AwsccSchemaConfig {
-    /// AWS CloudFormation type name (e.g., "AWS::EC2::VPC")
-    pub aws_type_name: &'static str,
-    /// Resource type name used in DSL (e.g., "ec2_vpc")
-    pub resource_type_name: &'static str,
-    /// Whether this resource type uses tags
-    pub has_tags: bool,
-    /// The resource schema with attribute definitions
-    pub schema: ResourceSchema,
-}
-
-/// Tags type for AWS resources (Terraform-style map)
-pub fn tags_type() -> AttributeType {
-    AttributeType::Map(Box::new(AttributeType::String))
-}
-
-/// Normalize a namespaced enum value to its base value.
-/// Handles formats like:
-/// - "value" -> "value"
-/// - "TypeName.value" -> "value"
-/// - "awscc.resource.TypeName.value" -> "value"
-pub fn normalize_namespaced_enum(s: &str) -> String {
-    if s.contains('.') {
-        let parts: Vec<&str> = s.split('.').collect();
-        parts.last().map(|s| s.to_string()).unwrap_or_default()
-    } else {
-        s.to_string()
-    }
-}
-
-/// Validate a namespaced enum value.
-/// Returns Ok(()) if valid, Err with message if invalid.
-pub fn validate_namespaced_enum(
-    value: &Value,
-    type_name: &str,
-    namespace: &str,
-    valid_values: &[&str],
-) -> Result<(), String> {
-    if let Value::String(s) = value {
-        // Validate namespace format if it contains dots
-        if s.contains('.') {
-            let parts: Vec<&str> = s.split('.').collect();
-            match parts.len() {
-                // 2-part: TypeName.value
-                2 => {
-                    if parts[0] != type_name {
-                        return Err(format!(
-                            "Invalid format '{}', expected {}.value",
-                            s, type_name
-                        ));
-                    }
-                }
-                // 4-part: awscc.resource.TypeName.value
-                4 => {
-                    let expected_namespace: Vec<&str> = namespace.split('.').collect();
-                    if expected_namespace.len() != 2
-                        || parts[0] != expected_namespace[0]
-                        || parts[1] != expected_namespace[1]
-                        || parts[2] != type_name
-                    {
-                        return Err(format!(
-                            "Invalid format '{}', expected {}.{}.value",
-                            s, namespace, type_name
-                        ));
-                    }
-                }
-                _ => {
-                    return Err(format!(
-                        "Invalid format '{}', expected one of: value, {}.value, or {}.{}.value",
-                        s, type_name, namespace, type_name
-                    ));
-                }
-            }
-        }
-
-        let normalized = normalize_namespaced_enum(s);
-        if valid_values.contains(&normalized.as_str()) {
-            Ok(())
-        } else {
-            Err(format!(
-                "Invalid value '{}', expected one of: {}",
-                s,
-                valid_values.join(", ")
-            ))
-        }
-    } else {
-        Err("Expected string".to_string())
-    }
-}
-
-EOF
-
-# Add module declarations
-for TYPE_NAME in "${RESOURCE_TYPES[@]}"; do
-    MODNAME=$("$CODEGEN_BIN" --type-name "$TYPE_NAME" --print-module-name)
-    echo "pub mod ${MODNAME};" >> "$OUTPUT_DIR/mod.rs"
-done
-
-# Add configs() function
-cat >> "$OUTPUT_DIR/mod.rs" << 'EOF'
-
-/// Returns all generated schema configs
-pub fn configs() -> Vec<AwsccSchemaConfig> {
-    vec![
-EOF
-
-# Add config function calls dynamically
-for TYPE_NAME in "${RESOURCE_TYPES[@]}"; do
-    MODNAME=$("$CODEGEN_BIN" --type-name "$TYPE_NAME" --print-module-name)
-    FULL_RESOURCE=$("$CODEGEN_BIN" --type-name "$TYPE_NAME" --print-full-resource-name)
-    FUNC_NAME="${FULL_RESOURCE}_config"
-
-    echo "        ${MODNAME}::${FUNC_NAME}()," >> "$OUTPUT_DIR/mod.rs"
-done
-
-cat >> "$OUTPUT_DIR/mod.rs" << 'EOF'
-    ]
-}
-
-/// Returns all generated schemas (for backward compatibility)
-pub fn schemas() -> Vec<ResourceSchema> {
-    configs().into_iter().map(|c| c.schema).collect()
-}
-EOF
+STATUS=0
+"$CODEGEN_BIN" \
+    --type-names-file "$TYPE_NAMES_FILE" \
+    --schema-dir "$SCHEMA_DIR" \
+    --output-dir "$OUTPUT_DIR" || STATUS=$?
 
 echo ""
 echo "Running cargo fmt..."
 cargo fmt -p carina-provider-awscc
 
 echo ""
+if [ "$STATUS" -ne 0 ]; then
+    echo "Done with errors; see above. Generated schemas in $OUTPUT_DIR"
+    exit "$STATUS"
+fi
 echo "Done! Generated schemas in $OUTPUT_DIR"
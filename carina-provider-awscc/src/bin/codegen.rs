@@ -11,6 +11,11 @@
 //!
 //!   # Generate from file
 //!   carina-codegen --file schema.json --type-name AWS::EC2::VPC
+//!
+//!   # Generate every type listed in a file (one per line) from schemas saved as
+//!   # <service>_<resource>.json (e.g., ec2_vpc.json), writing the modules and mod.rs
+//!   carina-codegen --type-names-file resource-types.txt --schema-dir schemas/ \
+//!     --output-dir src/schemas/generated
 
 use anyhow::{Context, Result};
 use clap::Parser;
@@ -19,6 +24,7 @@ use regex::Regex;
 use serde::Deserialize;
 use std::collections::{BTreeMap, HashSet};
 use std::io::{self, Read};
+use std::path::Path;
 
 /// Information about a detected enum type
 #[derive(Debug, Clone)]
@@ -34,8 +40,26 @@ struct EnumInfo {
 #[command(about = "Generate Carina schema code from CloudFormation schemas")]
 struct Args {
     /// CloudFormation type name (e.g., AWS::EC2::VPC)
+    #[arg(long, required_unless_present = "type_names_file")]
+    type_name: Option<String>,
+
+    /// File listing CloudFormation type names to generate in one run, one per line
+    /// (blank lines and lines starting with # are ignored)
+    #[arg(
+        long,
+        conflicts_with = "type_name",
+        requires_all = ["schema_dir", "output_dir"]
+    )]
+    type_names_file: Option<String>,
+
+    /// Directory holding the schema of each listed type as <service>_<resource>.json
+    /// (e.g., ec2_vpc.json), for --type-names-file
     #[arg(long)]
-    type_name: String,
+    schema_dir: Option<String>,
+
+    /// Directory to write one module per type and mod.rs to, for --type-names-file
+    #[arg(long)]
+    output_dir: Option<String>,
 
     /// Input file (reads from stdin if not specified)
     #[arg(long)]
@@ -125,6 +149,8 @@ struct CfnProperty {
 struct CfnDefinition {
     #[serde(rename = "type")]
     def_type: Option<String>,
+    #[serde(rename = "enum")]
+    enum_values: Option<Vec<String>>,
     properties: Option<BTreeMap<String, CfnProperty>>,
     #[serde(default)]
     required: Vec<String>,
@@ -155,13 +181,22 @@ fn full_resource_name_from_type(type_name: &str) -> Result<String> {
 fn main() -> Result<()> {
     let args = Args::parse();
 
+    let Some(type_name) = &args.type_name else {
+        // clap requires --schema-dir and --output-dir along with --type-names-file
+        return run_batch(
+            args.type_names_file.as_deref().unwrap_or_default(),
+            Path::new(args.schema_dir.as_deref().unwrap_or_default()),
+            Path::new(args.output_dir.as_deref().unwrap_or_default()),
+        );
+    };
+
     if args.print_module_name {
-        println!("{}", module_name_from_type(&args.type_name)?);
+        println!("{}", module_name_from_type(type_name)?);
         return Ok(());
     }
 
     if args.print_full_resource_name {
-        println!("{}", full_resource_name_from_type(&args.type_name)?);
+        println!("{}", full_resource_name_from_type(type_name)?);
         return Ok(());
     }
 
@@ -182,7 +217,7 @@ fn main() -> Result<()> {
         serde_json::from_str(&schema_json).context("Failed to parse CloudFormation schema")?;
 
     // Generate code
-    let code = generate_schema_code(&schema, &args.type_name)?;
+    let code = generate_schema_code(&schema, type_name)?;
 
     // Output
     if let Some(output_path) = &args.output {
@@ -196,6 +231,214 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// Generate a module for each type listed in `type_names_file` and a mod.rs
+/// registering them. A type that fails is reported and skipped; it stays in
+/// mod.rs if a module generated by an earlier run is still in place.
+fn run_batch(type_names_file: &str, schema_dir: &Path, output_dir: &Path) -> Result<()> {
+    let list = std::fs::read_to_string(type_names_file)
+        .with_context(|| format!("Failed to read file: {}", type_names_file))?;
+    std::fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create: {}", output_dir.display()))?;
+
+    let mut modules = Vec::new();
+    let mut failed = Vec::new();
+    for type_name in read_type_names(&list) {
+        let module = module_name_from_type(&type_name)?;
+        let full_resource = full_resource_name_from_type(&type_name)?;
+        let output_path = output_dir.join(format!("{}.rs", module));
+
+        let generated = (|| {
+            let schema_path = schema_dir.join(format!("{}.json", full_resource));
+            let schema_json = std::fs::read_to_string(&schema_path)
+                .with_context(|| format!("Failed to read file: {}", schema_path.display()))?;
+            let schema: CfnSchema = serde_json::from_str(&schema_json)
+                .context("Failed to parse CloudFormation schema")?;
+            let code = generate_schema_code(&schema, &type_name)?;
+            std::fs::write(&output_path, code)
+                .with_context(|| format!("Failed to write to: {}", output_path.display()))
+        })();
+
+        match generated {
+            Ok(()) => eprintln!("Generated: {}", output_path.display()),
+            Err(e) => {
+                eprintln!("ERROR: Failed to generate {}: {:#}", type_name, e);
+                failed.push(type_name);
+            }
+        }
+        if output_path.is_file() {
+            modules.push((module, full_resource));
+        }
+    }
+
+    let mod_path = output_dir.join("mod.rs");
+    std::fs::write(&mod_path, generate_mod_rs(&modules))
+        .with_context(|| format!("Failed to write to: {}", mod_path.display()))?;
+    eprintln!("Generated: {}", mod_path.display());
+
+    if !failed.is_empty() {
+        anyhow::bail!("Failed to generate: {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// Type names listed in a --type-names-file
+fn read_type_names(list: &str) -> Vec<String> {
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect()
+}
+
+/// Shared definitions at the top of the generated mod.rs
+const MOD_RS_HEADER: &str = r#"//! Auto-generated AWS Cloud Control resource schemas
+//!
+//! DO NOT EDIT MANUALLY - regenerate with:
+//!   aws-vault exec <profile> -- ./carina-provider-awscc/scripts/generate-schemas.sh
+
+use carina_core::resource::Value;
+use carina_core::schema::{AttributeType, ResourceSchema};
+
+/// AWS Cloud Control schema configuration
+///
+/// Combines the generated ResourceSchema with AWS-specific metadata
+/// that was previously in ResourceConfig.
+pub struct AwsccSchemaConfig {
+    /// AWS CloudFormation type name (e.g., "AWS::EC2::VPC")
+    pub aws_type_name: &'static str,
+    /// Resource type name used in DSL (e.g., "ec2_vpc")
+    pub resource_type_name: &'static str,
+    /// Whether this resource type uses tags
+    pub has_tags: bool,
+    /// Properties forming the Cloud Control identifier (e.g., ["VpcId"])
+    pub primary_identifier: &'static [&'static str],
+    /// The resource schema with attribute definitions
+    pub schema: ResourceSchema,
+}
+
+/// Tags type for AWS resources (Terraform-style map)
+pub fn tags_type() -> AttributeType {
+    AttributeType::Map(Box::new(AttributeType::String))
+}
+
+/// Normalize a namespaced enum value to its spelling in `valid_values`.
+/// Handles formats like (in any case):
+/// - "value" -> "value"
+/// - "TypeName.VALUE" -> "value"
+/// - "awscc.resource.TypeName.value" -> "value"
+///
+/// A value matching none of `valid_values` is returned without its prefix.
+pub fn normalize_namespaced_enum(s: &str, valid_values: &[&str]) -> String {
+    let raw = s.rsplit('.').next().unwrap_or(s);
+    valid_values
+        .iter()
+        .find(|valid| valid.eq_ignore_ascii_case(raw))
+        .map_or(raw, |valid| valid)
+        .to_string()
+}
+
+/// Validate a namespaced enum value.
+/// Accepts "value", "TypeName.value" and "awscc.resource.TypeName.value" alike;
+/// the value itself is compared case-insensitively against `valid_values`.
+/// Returns Ok(()) if valid, Err with message if invalid.
+pub fn validate_namespaced_enum(
+    value: &Value,
+    type_name: &str,
+    namespace: &str,
+    valid_values: &[&str],
+) -> Result<(), String> {
+    if let Value::String(s) = value {
+        // Everything before the value must be nothing, the type name, or the full namespace
+        let prefix: Vec<&str> = s.split('.').collect();
+        let prefix = &prefix[..prefix.len() - 1];
+        let full_prefix: Vec<&str> = namespace.split('.').chain([type_name]).collect();
+        if !(prefix.is_empty() || prefix == [type_name] || prefix == full_prefix.as_slice()) {
+            return Err(format!(
+                "Invalid format '{}', expected one of: value, {}.value, or {}.{}.value",
+                s, type_name, namespace, type_name
+            ));
+        }
+
+        let normalized = normalize_namespaced_enum(s, valid_values);
+        if valid_values.contains(&normalized.as_str()) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Invalid value '{}', expected one of: {}",
+                s,
+                valid_values.join(", ")
+            ))
+        }
+    } else {
+        Err("Expected string".to_string())
+    }
+}
+
+/// Validate a map value against the fields of an object definition.
+/// Every key must be one of `fields` and its value must match the field's type.
+/// Returns Ok(()) if valid, Err with message if invalid.
+pub fn validate_struct(
+    value: &Value,
+    type_name: &str,
+    fields: &[(&str, AttributeType)],
+) -> Result<(), String> {
+    let Value::Map(map) = value else {
+        return Err(format!("Expected a map for {}", type_name));
+    };
+    for (key, field_value) in map {
+        let Some((_, field_type)) = fields.iter().find(|(name, _)| name == key) else {
+            let names: Vec<&str> = fields.iter().map(|(name, _)| *name).collect();
+            return Err(format!(
+                "Unknown field '{}' in {}, expected one of: {}",
+                key,
+                type_name,
+                names.join(", ")
+            ));
+        };
+        field_type
+            .validate(field_value)
+            .map_err(|e| format!("{}.{}: {}", type_name, key, e))?;
+    }
+    Ok(())
+}
+
+"#;
+
+/// Generate mod.rs for the given (module, full resource name) pairs
+fn generate_mod_rs(modules: &[(String, String)]) -> String {
+    let mut code = String::from(MOD_RS_HEADER);
+
+    for (module, _) in modules {
+        code.push_str(&format!("pub mod {};\n", module));
+    }
+
+    code.push_str(
+        r#"
+/// Returns all generated schema configs
+pub fn configs() -> Vec<AwsccSchemaConfig> {
+    vec![
+"#,
+    );
+    for (module, full_resource) in modules {
+        code.push_str(&format!(
+            "        {}::{}_config(),\n",
+            module, full_resource
+        ));
+    }
+    code.push_str(
+        r#"    ]
+}
+
+/// Returns all generated schemas (for backward compatibility)
+pub fn schemas() -> Vec<ResourceSchema> {
+    configs().into_iter().map(|c| c.schema).collect()
+}
+"#,
+    );
+
+    code
+}
+
 fn generate_schema_code(schema: &CfnSchema, type_name: &str) -> Result<String> {
     let mut code = String::new();
 
@@ -216,6 +459,20 @@ fn generate_schema_code(schema: &CfnSchema, type_name: &str) -> Result<String> {
         .map(|p| p.trim_start_matches("/properties/").to_string())
         .collect();
 
+    // Create-only properties replace the resource when changed
+    let create_only: HashSet<String> = schema
+        .create_only_properties
+        .iter()
+        .map(|p| p.trim_start_matches("/properties/").to_string())
+        .collect();
+
+    // Write-only properties are never returned by reads
+    let write_only: HashSet<String> = schema
+        .write_only_properties
+        .iter()
+        .map(|p| p.trim_start_matches("/properties/").to_string())
+        .collect();
+
     let required: HashSet<String> = schema.required.iter().cloned().collect();
 
     // Pre-scan properties to determine which imports are needed and collect enum info
@@ -236,11 +493,31 @@ fn generate_schema_code(schema: &CfnSchema, type_name: &str) -> Result<String> {
         }
     }
 
+    let (structs, struct_enums) = struct_definitions(schema, &namespace);
+    enums.extend(struct_enums);
+    if structs
+        .values()
+        .flatten()
+        .any(|(_, field_type)| field_type.contains("types::"))
+    {
+        needs_types = true;
+    }
+
     let has_enums = !enums.is_empty();
+    let has_structs = !structs.is_empty();
 
     // Determine has_tags from tagging metadata
     let has_tags = schema.tagging.as_ref().map(|t| t.taggable).unwrap_or(false);
 
+    // Properties forming the Cloud Control identifier: ["/properties/VpcId"] -> "VpcId"
+    let primary_identifier = schema
+        .primary_identifier
+        .iter()
+        .flatten()
+        .map(|p| format!("\"{}\"", p.trim_start_matches("/properties/")))
+        .collect::<Vec<_>>()
+        .join(", ");
+
     // Generate header with conditional imports
     let types_import = if needs_types { ", types" } else { "" };
     code.push_str(&format!(
@@ -256,7 +533,7 @@ use super::AwsccSchemaConfig;
         resource, type_name, types_import
     ));
 
-    if has_enums {
+    if has_enums || has_structs {
         code.push_str("use carina_core::resource::Value;\n");
     }
     if needs_tags_type {
@@ -265,6 +542,9 @@ use super::AwsccSchemaConfig;
     if has_enums {
         code.push_str("use super::validate_namespaced_enum;\n");
     }
+    if has_structs {
+        code.push_str("use super::validate_struct;\n");
+    }
     code.push('\n');
 
     // Generate enum constants and validation functions
@@ -295,6 +575,29 @@ use super::AwsccSchemaConfig;
         ));
     }
 
+    // Generate validation functions for object definitions referenced via $ref
+    for (def_name, fields) in &structs {
+        let fields_str = fields
+            .iter()
+            .map(|(field, field_type)| format!("            (\"{}\", {}),\n", field, field_type))
+            .collect::<String>();
+        code.push_str(&format!(
+            r#"fn {}(value: &Value) -> Result<(), String> {{
+    validate_struct(
+        value,
+        "{}",
+        &[
+{}        ],
+    )
+}}
+
+"#,
+            struct_validator_name(def_name),
+            def_name,
+            fields_str
+        ));
+    }
+
     // Generate config function
     let config_fn_name = format!("{}_config", full_resource);
     // Use awscc.service_resource format (e.g., awscc.ec2_vpc)
@@ -307,9 +610,17 @@ pub fn {}() -> AwsccSchemaConfig {{
         aws_type_name: "{}",
         resource_type_name: "{}",
         has_tags: {},
+        primary_identifier: &[{}],
         schema: ResourceSchema::new("{}")
 "#,
-        full_resource, type_name, config_fn_name, type_name, full_resource, has_tags, schema_name
+        full_resource,
+        type_name,
+        config_fn_name,
+        type_name,
+        full_resource,
+        has_tags,
+        primary_identifier,
+        schema_name
     ));
 
     // Add description
@@ -329,20 +640,14 @@ pub fn {}() -> AwsccSchemaConfig {{
         let is_required = required.contains(prop_name) && !read_only.contains(prop_name);
         let is_read_only = read_only.contains(prop_name);
 
+        let (attr_type, _) = cfn_type_to_carina_type_with_enum(prop, prop_name, schema);
         let attr_type = if let Some(enum_info) = enums.get(prop_name) {
-            // Use AttributeType::Custom for enums
-            let validate_fn = format!("validate_{}", prop_name.to_snake_case());
-            format!(
-                r#"AttributeType::Custom {{
-                name: "{}".to_string(),
-                base: Box::new(AttributeType::String),
-                validate: {},
-                namespace: Some("{}".to_string()),
-            }}"#,
-                enum_info.type_name, validate_fn, namespace
+            // Use AttributeType::Custom for enums (or the items of a list of enums)
+            attr_type.replace(
+                ENUM_PLACEHOLDER,
+                &enum_type_code(prop_name, enum_info, &namespace),
             )
         } else {
-            let (attr_type, _) = cfn_type_to_carina_type_with_enum(prop, prop_name, schema);
             attr_type
         };
 
@@ -355,6 +660,25 @@ pub fn {}() -> AwsccSchemaConfig {{
             attr_code.push_str("\n                .required()");
         }
 
+        if create_only.contains(prop_name) {
+            attr_code.push_str("\n                .immutable()");
+        }
+
+        if write_only.contains(prop_name) {
+            attr_code.push_str("\n                .write_only()");
+        }
+
+        if is_read_only {
+            attr_code.push_str("\n                .read_only()");
+        }
+
+        if enums.contains_key(prop_name) {
+            attr_code.push_str(&format!(
+                "\n                .with_enum_values(VALID_{})",
+                prop_name.to_snake_case().to_uppercase()
+            ));
+        }
+
         if let Some(desc) = &prop.description {
             let escaped = desc
                 .replace('"', "\\\"")
@@ -434,12 +758,32 @@ fn extract_enum_from_description(description: &str) -> Option<Vec<String>> {
     None
 }
 
+/// Stands in for the enum type in the type string until its validator is generated
+const ENUM_PLACEHOLDER: &str = "/* enum */";
+
+/// Attribute type of an enum, validated by the generated function for `enum_key`
+/// (the property name, or definition and field name for fields of an object)
+fn enum_type_code(enum_key: &str, enum_info: &EnumInfo, namespace: &str) -> String {
+    format!(
+        r#"AttributeType::Custom {{
+                name: "{}".to_string(),
+                base: Box::new(AttributeType::String),
+                validate: validate_{},
+                namespace: Some("{}".to_string()),
+            }}"#,
+        enum_info.type_name,
+        enum_key.to_snake_case(),
+        namespace
+    )
+}
+
 /// Returns (type_string, Option<EnumInfo>)
-/// EnumInfo is Some if this property is an enum that should use AttributeType::Custom
+/// EnumInfo is Some if this property is an enum, or a list of enums, that should use
+/// AttributeType::Custom; the type string then contains ENUM_PLACEHOLDER
 fn cfn_type_to_carina_type_with_enum(
     prop: &CfnProperty,
     prop_name: &str,
-    _schema: &CfnSchema,
+    schema: &CfnSchema,
 ) -> (String, Option<EnumInfo>) {
     // Tags property is special - it's a Map in Carina (Terraform-style)
     if prop_name == "Tags" {
@@ -451,6 +795,22 @@ fn cfn_type_to_carina_type_with_enum(
         if ref_path.contains("/Tag") {
             return ("tags_type()".to_string(), None);
         }
+        // Definitions that are string enums are validated like inline enums
+        if let Some(enum_values) = ref_path
+            .strip_prefix("#/definitions/")
+            .and_then(|name| schema.definitions.as_ref()?.get(name))
+            .and_then(|def| def.enum_values.as_ref())
+        {
+            let enum_info = EnumInfo {
+                type_name: prop_name.to_pascal_case(),
+                values: enum_values.clone(),
+            };
+            return (ENUM_PLACEHOLDER.to_string(), Some(enum_info));
+        }
+        // Object definitions become maps checked field by field
+        if let Some((def_name, _)) = object_definition(ref_path, schema) {
+            return (struct_type_code(def_name), None);
+        }
         // Default to String for unknown refs
         return ("AttributeType::String".to_string(), None);
     }
@@ -463,7 +823,7 @@ fn cfn_type_to_carina_type_with_enum(
             values: enum_values.clone(),
         };
         // Return placeholder - actual type will be generated using enum_info
-        return ("/* enum */".to_string(), Some(enum_info));
+        return (ENUM_PLACEHOLDER.to_string(), Some(enum_info));
     }
 
     // Handle type
@@ -502,7 +862,7 @@ fn cfn_type_to_carina_type_with_enum(
                     values: enum_values,
                 };
                 // Return placeholder - actual type will be generated using enum_info
-                return ("/* enum */".to_string(), Some(enum_info));
+                return (ENUM_PLACEHOLDER.to_string(), Some(enum_info));
             }
 
             ("AttributeType::String".to_string(), None)
@@ -512,10 +872,11 @@ fn cfn_type_to_carina_type_with_enum(
         Some("number") => ("AttributeType::Int".to_string(), None),
         Some("array") => {
             if let Some(items) = &prop.items {
-                let (item_type, _) = cfn_type_to_carina_type_with_enum(items, prop_name, _schema);
+                let (item_type, enum_info) =
+                    cfn_type_to_carina_type_with_enum(items, prop_name, schema);
                 (
                     format!("AttributeType::List(Box::new({}))", item_type),
-                    None,
+                    enum_info,
                 )
             } else {
                 (
@@ -532,6 +893,100 @@ fn cfn_type_to_carina_type_with_enum(
     }
 }
 
+/// Look up the object definition (one with properties) a `$ref` points to
+fn object_definition<'a>(
+    ref_path: &'a str,
+    schema: &'a CfnSchema,
+) -> Option<(&'a str, &'a BTreeMap<String, CfnProperty>)> {
+    let name = ref_path.strip_prefix("#/definitions/")?;
+    let properties = schema
+        .definitions
+        .as_ref()?
+        .get(name)?
+        .properties
+        .as_ref()?;
+    Some((name, properties))
+}
+
+fn struct_validator_name(def_name: &str) -> String {
+    format!("validate_{}_struct", def_name.to_snake_case())
+}
+
+/// Attribute type of a value matching an object definition: a map whose fields
+/// are checked by the definition's generated validator
+fn struct_type_code(def_name: &str) -> String {
+    format!(
+        r#"AttributeType::Custom {{
+                name: "{}".to_string(),
+                base: Box::new(AttributeType::Map(Box::new(AttributeType::String))),
+                validate: {},
+                namespace: None,
+            }}"#,
+        def_name,
+        struct_validator_name(def_name)
+    )
+}
+
+/// Fields of an object definition as (snake_case name, attribute type code) pairs
+type StructFields = Vec<(String, String)>;
+
+/// Object definitions reachable from the schema's properties through `$ref`,
+/// with their fields as (snake_case name, attribute type) pairs. Each definition
+/// is visited once, so self-referential definitions don't recurse forever; they
+/// simply refer to their own validator. Enum fields are returned along with them,
+/// keyed by definition and field name (e.g. "DnsOptionsSpecificationDnsRecordIpType").
+fn struct_definitions(
+    schema: &CfnSchema,
+    namespace: &str,
+) -> (BTreeMap<String, StructFields>, BTreeMap<String, EnumInfo>) {
+    fn refs(prop: &CfnProperty) -> Vec<&str> {
+        match (&prop.ref_path, &prop.items) {
+            (Some(ref_path), _) => vec![ref_path.as_str()],
+            (None, Some(items)) => refs(items),
+            (None, None) => vec![],
+        }
+    }
+
+    let mut structs = BTreeMap::new();
+    let mut enums = BTreeMap::new();
+    let mut pending: Vec<&str> = schema
+        .properties
+        .iter()
+        .filter(|(name, _)| name.as_str() != "Tags")
+        .flat_map(|(_, prop)| refs(prop))
+        .collect();
+    while let Some(ref_path) = pending.pop() {
+        let Some((def_name, properties)) = object_definition(ref_path, schema) else {
+            continue;
+        };
+        if ref_path.contains("/Tag") || structs.contains_key(def_name) {
+            continue;
+        }
+        let fields = properties
+            .iter()
+            .map(|(name, prop)| {
+                let (field_type, enum_info) = cfn_type_to_carina_type_with_enum(prop, name, schema);
+                let field_type = match enum_info {
+                    Some(enum_info) => {
+                        let enum_key = format!("{}{}", def_name, name);
+                        let field_type = field_type.replace(
+                            ENUM_PLACEHOLDER,
+                            &enum_type_code(&enum_key, &enum_info, namespace),
+                        );
+                        enums.insert(enum_key, enum_info);
+                        field_type
+                    }
+                    None => field_type,
+                };
+                (name.to_snake_case(), field_type)
+            })
+            .collect();
+        structs.insert(def_name.to_string(), fields);
+        pending.extend(properties.values().flat_map(refs));
+    }
+    (structs, enums)
+}
+
 /// Tags type helper (to be included in generated module)
 #[allow(dead_code)]
 fn tags_type_helper() -> &'static str {
@@ -603,4 +1058,207 @@ mod tests {
         let values = result.unwrap();
         assert_eq!(values, vec!["enabled", "disabled"]);
     }
+
+    #[test]
+    fn test_generate_validators_for_list_and_ref_enums() {
+        let schema: CfnSchema = serde_json::from_str(
+            r##"{
+                "typeName": "AWS::EC2::FlowLog",
+                "properties": {
+                    "LogFormats": { "type": "array", "items": { "type": "string", "enum": ["json", "text"] } },
+                    "TrafficType": { "$ref": "#/definitions/TrafficType" }
+                },
+                "definitions": {
+                    "TrafficType": { "type": "string", "enum": ["ACCEPT", "REJECT", "ALL"] }
+                }
+            }"##,
+        )
+        .unwrap();
+
+        let code = generate_schema_code(&schema, "AWS::EC2::FlowLog").unwrap();
+
+        assert!(!code.contains(ENUM_PLACEHOLDER), "{}", code);
+        assert!(code.contains(
+            r#"validate_namespaced_enum(value, "LogFormats", "awscc.ec2_flow_log", VALID_LOG_FORMATS)"#
+        ));
+        assert!(
+            code.contains(r#"const VALID_TRAFFIC_TYPE: &[&str] = &["ACCEPT", "REJECT", "ALL"];"#)
+        );
+        assert!(code.contains("AttributeType::List(Box::new(AttributeType::Custom {"));
+        assert!(code.contains("validate: validate_traffic_type,"));
+        assert!(code.contains(".with_enum_values(VALID_LOG_FORMATS)"));
+        assert!(code.contains(".with_enum_values(VALID_TRAFFIC_TYPE)"));
+    }
+
+    #[test]
+    fn test_generate_create_only_and_write_only_flags() {
+        let schema: CfnSchema = serde_json::from_str(
+            r##"{
+                "typeName": "AWS::EC2::VPC",
+                "properties": {
+                    "CidrBlock": { "type": "string" },
+                    "Ipv4IpamPoolId": { "type": "string" },
+                    "EnableDnsSupport": { "type": "boolean" }
+                },
+                "createOnlyProperties": ["/properties/CidrBlock", "/properties/Ipv4IpamPoolId"],
+                "writeOnlyProperties": ["/properties/Ipv4IpamPoolId"]
+            }"##,
+        )
+        .unwrap();
+
+        let code = generate_schema_code(&schema, "AWS::EC2::VPC").unwrap();
+
+        assert!(code.contains(
+            "AttributeSchema::new(\"cidr_block\", types::cidr())\n                .immutable()\n"
+        ));
+        assert!(code.contains(
+            "AttributeSchema::new(\"ipv4_ipam_pool_id\", AttributeType::String)\n                .immutable()\n                .write_only()\n"
+        ));
+        assert_eq!(code.matches(".immutable()").count(), 2);
+        assert_eq!(code.matches(".write_only()").count(), 1);
+    }
+
+    #[test]
+    fn test_generate_primary_identifier() {
+        let schema: CfnSchema = serde_json::from_str(
+            r##"{
+                "typeName": "AWS::EC2::Route",
+                "properties": {
+                    "RouteTableId": { "type": "string" },
+                    "CidrBlock": { "type": "string" }
+                },
+                "primaryIdentifier": ["/properties/RouteTableId", "/properties/CidrBlock"]
+            }"##,
+        )
+        .unwrap();
+
+        let code = generate_schema_code(&schema, "AWS::EC2::Route").unwrap();
+        assert!(code.contains(r#"primary_identifier: &["RouteTableId", "CidrBlock"],"#));
+
+        let schema: CfnSchema =
+            serde_json::from_str(r#"{ "typeName": "AWS::EC2::Route", "properties": {} }"#).unwrap();
+        let code = generate_schema_code(&schema, "AWS::EC2::Route").unwrap();
+        assert!(code.contains("primary_identifier: &[],"));
+    }
+
+    #[test]
+    fn test_generate_struct_validators_for_object_definitions() {
+        let schema: CfnSchema = serde_json::from_str(
+            r##"{
+                "typeName": "AWS::EC2::Instance",
+                "properties": {
+                    "BlockDeviceMappings": { "type": "array", "items": { "$ref": "#/definitions/BlockDeviceMapping" } }
+                },
+                "definitions": {
+                    "BlockDeviceMapping": {
+                        "type": "object",
+                        "properties": {
+                            "DeviceName": { "type": "string" },
+                            "Ebs": { "$ref": "#/definitions/Ebs" }
+                        }
+                    },
+                    "Ebs": {
+                        "type": "object",
+                        "properties": {
+                            "Encrypted": { "type": "boolean" },
+                            "VolumeSize": { "type": "integer" },
+                            "Snapshot": { "$ref": "#/definitions/Ebs" }
+                        }
+                    }
+                }
+            }"##,
+        )
+        .unwrap();
+
+        let code = generate_schema_code(&schema, "AWS::EC2::Instance").unwrap();
+
+        assert!(code.contains("use super::validate_struct;"));
+        assert!(code.contains("fn validate_block_device_mapping_struct(value: &Value)"));
+        assert!(code.contains("fn validate_ebs_struct(value: &Value)"));
+        assert_eq!(code.matches("fn validate_ebs_struct(").count(), 1);
+        assert!(code.contains(r#"("volume_size", AttributeType::Int),"#));
+        assert!(code.contains("AttributeType::List(Box::new(AttributeType::Custom {"));
+        assert!(code.contains("validate: validate_block_device_mapping_struct,"));
+    }
+
+    #[test]
+    fn test_generate_enum_validators_for_object_fields() {
+        let schema: CfnSchema = serde_json::from_str(
+            r##"{
+                "typeName": "AWS::EC2::VPCEndpoint",
+                "properties": {
+                    "DnsOptions": { "$ref": "#/definitions/DnsOptionsSpecification" },
+                    "IpAddressType": { "type": "string", "enum": ["ipv4", "ipv6"] }
+                },
+                "definitions": {
+                    "DnsOptionsSpecification": {
+                        "type": "object",
+                        "properties": {
+                            "DnsRecordIpType": { "type": "string", "enum": ["ipv4", "dualstack"] },
+                            "Protocols": { "type": "array", "items": { "type": "string", "enum": ["tcp", "udp"] } }
+                        }
+                    }
+                }
+            }"##,
+        )
+        .unwrap();
+
+        let code = generate_schema_code(&schema, "AWS::EC2::VPCEndpoint").unwrap();
+
+        assert!(!code.contains(ENUM_PLACEHOLDER), "{}", code);
+        assert!(code.contains(
+            r#"const VALID_DNS_OPTIONS_SPECIFICATION_DNS_RECORD_IP_TYPE: &[&str] = &["ipv4", "dualstack"];"#
+        ));
+        assert!(code.contains(
+            r#"validate_namespaced_enum(value, "DnsRecordIpType", "awscc.ec2_vpc_endpoint", VALID_DNS_OPTIONS_SPECIFICATION_DNS_RECORD_IP_TYPE)"#
+        ));
+        assert!(code.contains("validate: validate_dns_options_specification_dns_record_ip_type,"));
+        assert!(code.contains("validate: validate_dns_options_specification_protocols,"));
+        assert!(
+            code.contains("(\"protocols\", AttributeType::List(Box::new(AttributeType::Custom {")
+        );
+        // Top-level enums keep their own validators
+        assert!(code.contains("validate: validate_ip_address_type,"));
+        assert!(!code.contains("with_enum_values(VALID_DNS_OPTIONS"));
+    }
+
+    #[test]
+    fn test_read_type_names_skips_comments_and_blank_lines() {
+        let list = "# EC2\nAWS::EC2::VPC\n\n  AWS::EC2::Subnet  \n";
+        assert_eq!(
+            read_type_names(list),
+            vec!["AWS::EC2::VPC", "AWS::EC2::Subnet"]
+        );
+    }
+
+    #[test]
+    fn test_run_batch_reports_failures_and_keeps_going() {
+        let root = std::env::temp_dir().join(format!("codegen-batch-{}", std::process::id()));
+        let schema_dir = root.join("schemas");
+        let output_dir = root.join("generated");
+        std::fs::create_dir_all(&schema_dir).unwrap();
+        std::fs::write(
+            schema_dir.join("ec2_vpc.json"),
+            r#"{"typeName": "AWS::EC2::VPC", "properties": {"CidrBlock": {"type": "string"}}}"#,
+        )
+        .unwrap();
+        std::fs::write(schema_dir.join("ec2_subnet.json"), "not json").unwrap();
+        let list = root.join("types.txt");
+        std::fs::write(&list, "AWS::EC2::VPC\nAWS::EC2::Subnet\nAWS::EC2::EIP\n").unwrap();
+
+        let err = run_batch(list.to_str().unwrap(), &schema_dir, &output_dir).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to generate: AWS::EC2::Subnet, AWS::EC2::EIP"
+        );
+
+        assert!(output_dir.join("vpc.rs").is_file());
+        let mod_rs = std::fs::read_to_string(output_dir.join("mod.rs")).unwrap();
+        assert!(mod_rs.starts_with(MOD_RS_HEADER));
+        assert!(mod_rs.contains("pub mod vpc;\n"));
+        assert!(mod_rs.contains("        vpc::ec2_vpc_config(),\n"));
+        assert!(!mod_rs.contains("subnet"));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
 }
@@ -51,6 +51,19 @@ impl Provider for AwsccProvider {
         })
     }
 
+    fn read_managed_resource(
+        &self,
+        resource: &Resource,
+        identifier: Option<&str>,
+    ) -> BoxFuture<'_, ProviderResult<State>> {
+        let resource = resource.clone();
+        let identifier = identifier.map(|s| s.to_string());
+        Box::pin(async move {
+            self.read_resource_from_attributes(&resource, identifier.as_deref())
+                .await
+        })
+    }
+
     fn create(&self, resource: &Resource) -> BoxFuture<'_, ProviderResult<State>> {
         let resource = resource.clone();
         Box::pin(async move { self.create_resource(resource).await })
@@ -60,13 +73,14 @@ impl Provider for AwsccProvider {
         &self,
         id: &ResourceId,
         identifier: &str,
-        _from: &State,
+        from: &State,
         to: &Resource,
     ) -> BoxFuture<'_, ProviderResult<State>> {
         let id = id.clone();
         let identifier = identifier.to_string();
+        let from = from.clone();
         let to = to.clone();
-        Box::pin(async move { self.update_resource(id, &identifier, to).await })
+        Box::pin(async move { self.update_resource(id, &identifier, &from, to).await })
     }
 
     fn delete(&self, id: &ResourceId, identifier: &str) -> BoxFuture<'_, ProviderResult<()>> {
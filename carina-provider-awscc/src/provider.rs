@@ -3,18 +3,22 @@
 //! This module contains the main provider implementation that communicates
 //! with AWS Cloud Control API to manage resources.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
-use aws_config::Region;
 use aws_sdk_cloudcontrol::Client as CloudControlClient;
-use aws_sdk_cloudcontrol::types::OperationStatus;
-use carina_core::provider::{ProviderError, ProviderResult};
+use aws_sdk_cloudcontrol::error::{ProvideErrorMetadata, SdkError};
+use aws_sdk_cloudcontrol::types::{
+    Operation, OperationStatus, ProgressEvent, ResourceRequestStatusFilter,
+};
+use carina_aws_config::{WithSdkCause, format_sdk_error, load_sdk_config};
+use carina_core::provider::{ProviderError, ProviderErrorKind, ProviderResult};
 use carina_core::resource::{Resource, ResourceId, State, Value};
+use carina_core::schema::{AttributeSchema, AttributeType, canonicalize_enum};
 use serde_json::json;
 
 use crate::schemas::generated::AwsccSchemaConfig;
-use crate::utils::{normalize_availability_zone, normalize_instance_tenancy};
+use crate::utils::normalize_availability_zone;
 
 /// Get the AwsccSchemaConfig for a resource type
 fn get_schema_config(resource_type: &str) -> Option<AwsccSchemaConfig> {
@@ -28,23 +32,212 @@ fn get_schema_config(resource_type: &str) -> Option<AwsccSchemaConfig> {
     })
 }
 
+/// Attributes that can be updated in place for a resource type.
+/// `Some(None)` means every attribute; `None` means the resource must be recreated.
+pub(crate) fn updatable_attributes(resource_type: &str) -> Option<Option<&'static [&'static str]>> {
+    match resource_type {
+        "ec2_vpc" => Some(None),
+        // Cloud Control reconciles RouteTableIds by associating added and
+        // disassociating removed route tables (ModifyVpcEndpoint)
+        "ec2_vpc_endpoint" => Some(Some(&["route_table_ids"])),
+        _ => None,
+    }
+}
+
+/// Whether a VPC endpoint is a gateway endpoint, the only kind with route tables.
+/// Endpoints of unknown type are gateway endpoints, the default. Resources whose
+/// schema has no `vpc_endpoint_type` are not endpoints and always pass.
+fn is_gateway_endpoint(config: &AwsccSchemaConfig, state: &State) -> bool {
+    let Some(endpoint_type) = config.schema.attributes.get("vpc_endpoint_type") else {
+        return true;
+    };
+    match (
+        state.attributes.get("vpc_endpoint_type"),
+        &endpoint_type.enum_values,
+    ) {
+        (Some(value), Some(valid_values)) => {
+            canonicalize_enum(valid_values, value) == Value::String("Gateway".to_string())
+        }
+        _ => true,
+    }
+}
+
+/// Append a hint to create errors that are usually caused by a missing dependency
+fn with_create_hint(resource_type: &str, mut err: ProviderError) -> ProviderError {
+    if resource_type == "ec2_nat_gateway" && err.message.contains("InvalidAllocationID.NotFound") {
+        err.message.push_str(
+            " (hint: reference the EIP resource, e.g. `allocation_id = my_eip.allocation_id`, \
+             so that Carina creates it before the NAT gateway)",
+        );
+    }
+    err
+}
+
+/// Whether a Cloud Control request was rejected because another operation is
+/// still running on the resource
+fn is_conflict<E, R>(err: &SdkError<E, R>) -> bool
+where
+    E: ProvideErrorMetadata,
+{
+    matches!(
+        err.code(),
+        Some("ResourceConflictException" | "ConcurrentOperationException")
+    )
+}
+
+/// Build the error for a failed Cloud Control request, e.g. "Failed to create
+/// resource: Code: message"
+fn operation_error<E, R>(action: &str, err: SdkError<E, R>) -> ProviderError
+where
+    E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+    R: std::fmt::Debug + Send + Sync + 'static,
+{
+    ProviderError::new(format!("{}: {}", action, format_sdk_error(&err))).with_sdk_cause(err)
+}
+
+/// Build the Cloud Control identifier of a resource from the attributes mapped
+/// to its primary identifier properties. Composite identifiers are joined with
+/// `|`. Returns None unless every part is a known string.
+fn primary_identifier(
+    config: &AwsccSchemaConfig,
+    attributes: &HashMap<String, Value>,
+) -> Option<String> {
+    if config.primary_identifier.is_empty() {
+        return None;
+    }
+    let parts = config
+        .primary_identifier
+        .iter()
+        .map(|property| {
+            let (dsl_name, _) = config
+                .schema
+                .attributes
+                .iter()
+                .find(|(_, attr)| attr.provider_name.as_deref() == Some(*property))?;
+            match attributes.get(dsl_name) {
+                Some(Value::String(s)) => Some(s.as_str()),
+                _ => None,
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(parts.join("|"))
+}
+
+/// The resource an in-flight request is looked up for: an existing resource by
+/// identifier, or a resource being created (not yet assigned one) by its
+/// desired state
+enum InFlightTarget<'a> {
+    Identifier(&'a str),
+    Create(&'a serde_json::Value),
+}
+
+/// Pick the in-flight request on a resource from listed requests. A create
+/// matches only an unambiguous create of the type whose resource model carries
+/// the desired state, so another stack's create is never adopted.
+fn in_flight_request<'a>(
+    requests: &'a [ProgressEvent],
+    type_name: &str,
+    target: &InFlightTarget,
+) -> Option<&'a ProgressEvent> {
+    let mut candidates = requests
+        .iter()
+        .filter(|r| r.type_name() == Some(type_name))
+        .filter(|r| match target {
+            InFlightTarget::Identifier(identifier) => r.identifier() == Some(*identifier),
+            InFlightTarget::Create(desired_state) => {
+                r.operation() == Some(&Operation::Create)
+                    && r.resource_model()
+                        .and_then(|model| serde_json::from_str(model).ok())
+                        .is_some_and(|model| model_contains(&model, desired_state))
+            }
+        });
+    let request = candidates.next()?;
+    if matches!(target, InFlightTarget::Create(_)) && candidates.next().is_some() {
+        return None;
+    }
+    Some(request)
+}
+
+/// Whether a resource model has every property of the desired state. The model
+/// may carry more, such as defaults and read-only properties.
+fn model_contains(model: &serde_json::Value, desired: &serde_json::Value) -> bool {
+    match (model, desired) {
+        (serde_json::Value::Object(model), serde_json::Value::Object(desired)) => {
+            desired.iter().all(|(key, value)| {
+                model
+                    .get(key)
+                    .is_some_and(|actual| model_contains(actual, value))
+            })
+        }
+        _ => model == desired,
+    }
+}
+
+/// Environment variable overriding how long to wait for a Cloud Control
+/// operation, in seconds
+pub const OPERATION_TIMEOUT_ENV: &str = "CARINA_AWSCC_OPERATION_TIMEOUT_SECS";
+
+const DEFAULT_OPERATION_TIMEOUT: Duration = Duration::from_secs(600);
+const MIN_POLL_DELAY: Duration = Duration::from_secs(1);
+const MAX_POLL_DELAY: Duration = Duration::from_secs(15);
+
+/// Operation timeout from `CARINA_AWSCC_OPERATION_TIMEOUT_SECS`, or the default
+/// when unset or invalid
+fn operation_timeout(lookup: impl Fn(&str) -> Option<String>) -> Duration {
+    lookup(OPERATION_TIMEOUT_ENV)
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_OPERATION_TIMEOUT)
+}
+
+/// Delay before the next status poll: until `retry_after` when the operation
+/// gives one, otherwise doubling from one second. Capped either way.
+fn poll_delay(
+    attempt: u32,
+    retry_after: Option<std::time::SystemTime>,
+    now: std::time::SystemTime,
+) -> Duration {
+    let delay = match retry_after {
+        Some(at) => at.duration_since(now).unwrap_or(Duration::ZERO),
+        None => MIN_POLL_DELAY.saturating_mul(2u32.saturating_pow(attempt)),
+    };
+    delay.clamp(MIN_POLL_DELAY, MAX_POLL_DELAY)
+}
+
 /// AWS Cloud Control Provider
 pub struct AwsccProvider {
     cloudcontrol_client: CloudControlClient,
     region: String,
+    operation_timeout: Duration,
 }
 
 impl AwsccProvider {
     /// Create a new AwsccProvider for the specified region
     pub async fn new(region: &str) -> Self {
-        let config = aws_config::defaults(aws_config::BehaviorVersion::latest())
-            .region(Region::new(region.to_string()))
-            .load()
-            .await;
+        Self::with_options(region, None, None, None).await
+    }
+
+    /// Create a new AWS Cloud Control provider with credentials from a named
+    /// profile and/or an assumed role. Either can be omitted to use the default
+    /// credential chain. `endpoint_url` points the Cloud Control client at
+    /// another endpoint (e.g., LocalStack).
+    pub async fn with_options(
+        region: &str,
+        profile: Option<&str>,
+        assume_role_arn: Option<&str>,
+        endpoint_url: Option<&str>,
+    ) -> Self {
+        let config = load_sdk_config(region, profile, assume_role_arn).await;
+        let mut client_config = aws_sdk_cloudcontrol::config::Builder::from(&config);
+        if let Some(endpoint_url) = endpoint_url {
+            client_config = client_config.endpoint_url(endpoint_url);
+        }
 
         Self {
-            cloudcontrol_client: CloudControlClient::new(&config),
+            cloudcontrol_client: CloudControlClient::from_conf(client_config.build()),
             region: region.to_string(),
+            operation_timeout: operation_timeout(|key| std::env::var(key).ok()),
         }
     }
 
@@ -84,28 +277,46 @@ impl AwsccProvider {
                     Ok(None)
                 } else {
                     Err(ProviderError::new(format!(
-                        "Failed to get resource: {:?}",
-                        e
-                    )))
+                        "Failed to get resource: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e))
                 }
             }
         }
     }
 
-    /// Create a resource using Cloud Control API
+    /// Create a resource using Cloud Control API. When a create of this
+    /// resource is still running (e.g. from an interrupted apply), waits for
+    /// that request instead.
     pub async fn cc_create_resource(
         &self,
         type_name: &str,
         desired_state: serde_json::Value,
     ) -> ProviderResult<String> {
-        let result = self
+        let result = match self
             .cloudcontrol_client
             .create_resource()
             .type_name(type_name)
             .desired_state(desired_state.to_string())
             .send()
             .await
-            .map_err(|e| ProviderError::new(format!("Failed to create resource: {:?}", e)))?;
+        {
+            Ok(result) => result,
+            Err(e) if is_conflict(&e) => {
+                return match self
+                    .find_in_flight_request(type_name, &InFlightTarget::Create(&desired_state))
+                    .await
+                {
+                    Some((request_token, _)) => self.wait_for_operation(&request_token).await,
+                    None => Err(operation_error(
+                        "Failed to create resource: another operation is in progress",
+                        e,
+                    )),
+                };
+            }
+            Err(e) => return Err(operation_error("Failed to create resource", e)),
+        };
 
         let request_token = result
             .progress_event()
@@ -115,7 +326,9 @@ impl AwsccProvider {
         self.wait_for_operation(request_token).await
     }
 
-    /// Update a resource using Cloud Control API
+    /// Update a resource using Cloud Control API. When another operation is
+    /// still running on the resource, waits for it first and then sends this
+    /// update, since an earlier update may not carry the same patch.
     pub async fn cc_update_resource(
         &self,
         type_name: &str,
@@ -129,15 +342,30 @@ impl AwsccProvider {
         let patch_document = serde_json::to_string(&patch_ops)
             .map_err(|e| ProviderError::new(format!("Failed to build patch: {}", e)))?;
 
-        let result = self
-            .cloudcontrol_client
-            .update_resource()
-            .type_name(type_name)
-            .identifier(identifier)
-            .patch_document(patch_document)
-            .send()
-            .await
-            .map_err(|e| ProviderError::new(format!("Failed to update resource: {:?}", e)))?;
+        let request = || {
+            self.cloudcontrol_client
+                .update_resource()
+                .type_name(type_name)
+                .identifier(identifier)
+                .patch_document(patch_document.clone())
+                .send()
+        };
+        let result = match request().await {
+            Ok(result) => result,
+            Err(e) if is_conflict(&e) => {
+                let Some((request_token, _)) = self
+                    .find_in_flight_request(type_name, &InFlightTarget::Identifier(identifier))
+                    .await
+                else {
+                    return Err(operation_error("Failed to update resource", e));
+                };
+                self.wait_for_operation(&request_token).await?;
+                request()
+                    .await
+                    .map_err(|e| operation_error("Failed to update resource", e))?
+            }
+            Err(e) => return Err(operation_error("Failed to update resource", e)),
+        };
 
         if let Some(request_token) = result.progress_event().and_then(|p| p.request_token()) {
             self.wait_for_operation(request_token).await?;
@@ -146,20 +374,40 @@ impl AwsccProvider {
         Ok(())
     }
 
-    /// Delete a resource using Cloud Control API
+    /// Delete a resource using Cloud Control API. When another operation is
+    /// still running on the resource, waits for it first: a delete is taken
+    /// as this one resumed, anything else is followed by sending this delete.
     pub async fn cc_delete_resource(
         &self,
         type_name: &str,
         identifier: &str,
     ) -> ProviderResult<()> {
-        let result = self
-            .cloudcontrol_client
-            .delete_resource()
-            .type_name(type_name)
-            .identifier(identifier)
-            .send()
-            .await
-            .map_err(|e| ProviderError::new(format!("Failed to delete resource: {:?}", e)))?;
+        let request = || {
+            self.cloudcontrol_client
+                .delete_resource()
+                .type_name(type_name)
+                .identifier(identifier)
+                .send()
+        };
+        let result = match request().await {
+            Ok(result) => result,
+            Err(e) if is_conflict(&e) => {
+                let Some((request_token, operation)) = self
+                    .find_in_flight_request(type_name, &InFlightTarget::Identifier(identifier))
+                    .await
+                else {
+                    return Err(operation_error("Failed to delete resource", e));
+                };
+                self.wait_for_operation(&request_token).await?;
+                if operation == Some(Operation::Delete) {
+                    return Ok(());
+                }
+                request()
+                    .await
+                    .map_err(|e| operation_error("Failed to delete resource", e))?
+            }
+            Err(e) => return Err(operation_error("Failed to delete resource", e)),
+        };
 
         if let Some(request_token) = result.progress_event().and_then(|p| p.request_token()) {
             self.wait_for_operation(request_token).await?;
@@ -168,42 +416,123 @@ impl AwsccProvider {
         Ok(())
     }
 
-    /// Wait for a Cloud Control operation to complete
+    /// Find a pending or in-progress request for a resource, returning its
+    /// request token and operation. Errors listing requests count as none found,
+    /// so the caller reports its original error.
+    async fn find_in_flight_request(
+        &self,
+        type_name: &str,
+        target: &InFlightTarget<'_>,
+    ) -> Option<(String, Option<Operation>)> {
+        let filter = ResourceRequestStatusFilter::builder()
+            .operation_statuses(OperationStatus::Pending)
+            .operation_statuses(OperationStatus::InProgress)
+            .build();
+        let mut requests = Vec::new();
+        let mut next_token = None;
+        loop {
+            let page = self
+                .cloudcontrol_client
+                .list_resource_requests()
+                .resource_request_status_filter(filter.clone())
+                .set_next_token(next_token)
+                .send()
+                .await
+                .ok()?;
+            requests.extend(page.resource_request_status_summaries().iter().cloned());
+            next_token = page.next_token().map(String::from);
+            if next_token.is_none() {
+                break;
+            }
+        }
+
+        let request = in_flight_request(&requests, type_name, target)?;
+        Some((
+            request.request_token()?.to_string(),
+            request.operation().cloned(),
+        ))
+    }
+
+    /// Wait for a Cloud Control operation to reach a terminal status, polling
+    /// with the request token. Polls back off exponentially, or until the
+    /// operation's `RetryAfter` when given; throttled status requests are
+    /// retried. Gives up after the provider's operation timeout.
     async fn wait_for_operation(&self, request_token: &str) -> ProviderResult<String> {
-        let max_attempts = 120;
-        let delay = Duration::from_secs(5);
+        let deadline = tokio::time::Instant::now() + self.operation_timeout;
+        let mut attempt = 0;
+        let mut last_status = String::from("PENDING");
 
-        for _ in 0..max_attempts {
-            let status = self
+        loop {
+            let retry_after = match self
                 .cloudcontrol_client
                 .get_resource_request_status()
                 .request_token(request_token)
                 .send()
                 .await
-                .map_err(|e| {
-                    ProviderError::new(format!("Failed to get operation status: {:?}", e))
-                })?;
-
-            if let Some(progress) = status.progress_event() {
-                match progress.operation_status() {
-                    Some(OperationStatus::Success) => {
-                        return Ok(progress.identifier().unwrap_or("").to_string());
-                    }
-                    Some(OperationStatus::Failed) => {
-                        let msg = progress.status_message().unwrap_or("Unknown error");
-                        return Err(ProviderError::new(format!("Operation failed: {}", msg)));
-                    }
-                    Some(OperationStatus::CancelComplete) => {
-                        return Err(ProviderError::new("Operation was cancelled"));
-                    }
-                    _ => {
-                        tokio::time::sleep(delay).await;
+            {
+                Ok(status) => {
+                    let Some(progress) = status.progress_event() else {
+                        return Err(ProviderError::new(format!(
+                            "No progress event returned for request {}",
+                            request_token
+                        )));
+                    };
+                    match progress.operation_status() {
+                        Some(OperationStatus::Success) => {
+                            return Ok(progress.identifier().unwrap_or("").to_string());
+                        }
+                        Some(OperationStatus::Failed) => {
+                            let msg = progress.status_message().unwrap_or("Unknown error");
+                            return Err(match progress.error_code() {
+                                Some(code) => ProviderError::new(format!(
+                                    "Operation failed ({}): {}",
+                                    code.as_str(),
+                                    msg
+                                ))
+                                .with_kind(ProviderErrorKind::from_error_code(code.as_str())),
+                                None => ProviderError::new(format!("Operation failed: {}", msg)),
+                            });
+                        }
+                        Some(OperationStatus::CancelComplete) => {
+                            return Err(ProviderError::new("Operation was cancelled"));
+                        }
+                        status => {
+                            if let Some(status) = status {
+                                last_status = status.as_str().to_string();
+                            }
+                            if let Some(msg) = progress.status_message() {
+                                last_status = format!("{}: {}", last_status, msg);
+                            }
+                            progress
+                                .retry_after()
+                                .and_then(|at| std::time::SystemTime::try_from(*at).ok())
+                        }
                     }
                 }
+                Err(e) if e.code() == Some("ThrottlingException") => None,
+                Err(e) => {
+                    return Err(ProviderError::new(format!(
+                        "Failed to get operation status: {}",
+                        format_sdk_error(&e)
+                    ))
+                    .with_sdk_cause(e));
+                }
+            };
+
+            let delay = poll_delay(attempt, retry_after, std::time::SystemTime::now());
+            if tokio::time::Instant::now() + delay > deadline {
+                return Err(ProviderError::new(format!(
+                    "Operation timed out after {}s waiting for request {} (last status: {}); \
+                     raise {} to wait longer",
+                    self.operation_timeout.as_secs(),
+                    request_token,
+                    last_status,
+                    OPERATION_TIMEOUT_ENV
+                )));
             }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
         }
-
-        Err(ProviderError::new("Operation timed out"))
     }
 
     // =========================================================================
@@ -277,6 +606,26 @@ impl AwsccProvider {
         Ok(State::existing(id, attributes).with_identifier(identifier))
     }
 
+    /// Read a resource, deriving its identifier from the primary identifier
+    /// attributes of the resource when none is given
+    pub async fn read_resource_from_attributes(
+        &self,
+        resource: &Resource,
+        identifier: Option<&str>,
+    ) -> ProviderResult<State> {
+        let derived = match identifier {
+            Some(_) => None,
+            None => get_schema_config(&resource.id.resource_type)
+                .and_then(|config| primary_identifier(&config, &resource.attributes)),
+        };
+        self.read_resource(
+            &resource.id.resource_type,
+            &resource.id.name,
+            identifier.or(derived.as_deref()),
+        )
+        .await
+    }
+
     /// Create a resource using its configuration
     pub async fn create_resource(&self, resource: Resource) -> ProviderResult<State> {
         let config = get_schema_config(&resource.id.resource_type).ok_or_else(|| {
@@ -298,7 +647,7 @@ impl AwsccProvider {
             if let Some(aws_name) = &attr_schema.provider_name
                 && let Some(value) = resource.attributes.get(dsl_name.as_str())
             {
-                let aws_value = self.dsl_value_to_aws(dsl_name, value);
+                let aws_value = self.dsl_value_to_aws(attr_schema, value);
                 if let Some(v) = aws_value {
                     desired_state.insert(aws_name.to_string(), v);
                 }
@@ -325,7 +674,9 @@ impl AwsccProvider {
                 serde_json::Value::Object(desired_state),
             )
             .await
-            .map_err(|e| e.for_resource(resource.id.clone()))?;
+            .map_err(|e| {
+                with_create_hint(&resource.id.resource_type, e).for_resource(resource.id.clone())
+            })?;
 
         self.read_resource(
             &resource.id.resource_type,
@@ -335,11 +686,12 @@ impl AwsccProvider {
         .await
     }
 
-    /// Update a resource
+    /// Update a resource from its current state `from` to `to`
     pub async fn update_resource(
         &self,
         id: ResourceId,
         identifier: &str,
+        from: &State,
         to: Resource,
     ) -> ProviderResult<State> {
         let config = get_schema_config(&id.resource_type).ok_or_else(|| {
@@ -347,15 +699,33 @@ impl AwsccProvider {
                 .for_resource(id.clone())
         })?;
 
-        // Only VPC supports in-place updates currently
-        if id.resource_type != "ec2_vpc" {
-            return Err(ProviderError::new(format!(
+        let updatable = updatable_attributes(&id.resource_type).ok_or_else(|| {
+            ProviderError::new(format!(
                 "Update not supported for {}, delete and recreate",
                 id.resource_type
             ))
-            .for_resource(id));
-        }
+            .for_resource(id.clone())
+        })?;
+
+        let patch_ops = self.update_patch(&config, updatable, from, &to);
 
+        self.cc_update_resource(config.aws_type_name, identifier, patch_ops)
+            .await
+            .map_err(|e| e.for_resource(id.clone()))?;
+
+        self.read_resource(&id.resource_type, &id.name, Some(identifier))
+            .await
+    }
+
+    /// JSON Patch operations that update a resource from `from` to `to`, limited to
+    /// the `updatable` attributes (`None` for all)
+    fn update_patch(
+        &self,
+        config: &AwsccSchemaConfig,
+        updatable: Option<&[&str]>,
+        from: &State,
+        to: &Resource,
+    ) -> Vec<serde_json::Value> {
         let mut patch_ops = Vec::new();
 
         // Build patch operations for changed attributes using provider_name
@@ -364,9 +734,22 @@ impl AwsccProvider {
             if dsl_name == "tags" {
                 continue;
             }
+            if let Some(allowed) = updatable
+                && !allowed.contains(&dsl_name.as_str())
+            {
+                continue;
+            }
             if let Some(aws_name) = &attr_schema.provider_name
+                && let Some(Value::Null) = to.attributes.get(dsl_name.as_str())
+            {
+                // An explicitly unset attribute is removed from the resource. JSON
+                // Patch fails to remove a path that does not exist.
+                if from.attributes.contains_key(dsl_name.as_str()) {
+                    patch_ops.push(json!({"op": "remove", "path": format!("/{}", aws_name)}));
+                }
+            } else if let Some(aws_name) = &attr_schema.provider_name
                 && let Some(value) = to.attributes.get(dsl_name.as_str())
-                && let Some(aws_value) = self.dsl_value_to_aws(dsl_name, value)
+                && let Some(aws_value) = self.dsl_value_to_aws(attr_schema, value)
             {
                 patch_ops.push(json!({
                     "op": "replace",
@@ -376,6 +759,25 @@ impl AwsccProvider {
             }
         }
 
+        // Removing an updatable list from the config empties it, e.g. disassociates
+        // all route tables of a gateway endpoint. Lists the resource does not have
+        // are left alone: interface endpoints have no route tables to clear.
+        for (dsl_name, attr_schema) in &config.schema.attributes {
+            if let AttributeType::List(_) = attr_schema.attr_type
+                && updatable.is_some_and(|allowed| allowed.contains(&dsl_name.as_str()))
+                && let Some(aws_name) = &attr_schema.provider_name
+                && !to.attributes.contains_key(dsl_name.as_str())
+                && matches!(
+                    from.attributes.get(dsl_name.as_str()),
+                    Some(Value::List(items)) if !items.is_empty()
+                )
+                && is_gateway_endpoint(config, from)
+            {
+                patch_ops
+                    .push(json!({"op": "replace", "path": format!("/{}", aws_name), "value": []}));
+            }
+        }
+
         // Handle tags update
         if config.has_tags
             && let Some(Value::Map(user_tags)) = to.attributes.get("tags")
@@ -391,12 +793,7 @@ impl AwsccProvider {
             }
         }
 
-        self.cc_update_resource(config.aws_type_name, identifier, patch_ops)
-            .await
-            .map_err(|e| e.for_resource(id.clone()))?;
-
-        self.read_resource(&id.resource_type, &id.name, Some(identifier))
-            .await
+        patch_ops
     }
 
     /// Delete a resource
@@ -441,7 +838,7 @@ impl AwsccProvider {
                 if let Some(i) = n.as_i64() {
                     Some(Value::Int(i))
                 } else {
-                    n.as_f64().map(|f| Value::Int(f as i64))
+                    n.as_f64().map(Value::Float)
                 }
             }
             serde_json::Value::Array(arr) => {
@@ -452,9 +849,17 @@ impl AwsccProvider {
         }
     }
 
-    /// Convert DSL value to AWS JSON value
-    fn dsl_value_to_aws(&self, dsl_name: &str, value: &Value) -> Option<serde_json::Value> {
-        match dsl_name {
+    /// Convert DSL value to AWS JSON value. Enum values are sent in the
+    /// spelling of the schema's valid values.
+    fn dsl_value_to_aws(
+        &self,
+        attr_schema: &AttributeSchema,
+        value: &Value,
+    ) -> Option<serde_json::Value> {
+        if let Some(valid_values) = &attr_schema.enum_values {
+            return self.value_to_json(&canonicalize_enum(valid_values, value));
+        }
+        match attr_schema.name.as_str() {
             "availability_zone" => {
                 if let Value::String(s) = value {
                     Some(json!(normalize_availability_zone(s)))
@@ -462,13 +867,6 @@ impl AwsccProvider {
                     None
                 }
             }
-            "instance_tenancy" => {
-                if let Value::String(s) = value {
-                    Some(json!(normalize_instance_tenancy(s)))
-                } else {
-                    None
-                }
-            }
             _ => self.value_to_json(value),
         }
     }
@@ -479,6 +877,7 @@ impl AwsccProvider {
             Value::String(s) => Some(json!(s)),
             Value::Bool(b) => Some(json!(b)),
             Value::Int(i) => Some(json!(i)),
+            Value::Float(f) => Some(json!(f)),
             Value::List(items) => {
                 let arr: Vec<serde_json::Value> =
                     items.iter().filter_map(|v| self.value_to_json(v)).collect();
@@ -586,8 +985,8 @@ impl AwsccProvider {
     }
 
     /// Parse tags from CloudFormation format to map
-    fn parse_tags(&self, tags_array: &[serde_json::Value]) -> HashMap<String, Value> {
-        let mut tags_map = HashMap::new();
+    fn parse_tags(&self, tags_array: &[serde_json::Value]) -> BTreeMap<String, Value> {
+        let mut tags_map = BTreeMap::new();
         for tag in tags_array {
             if let (Some(key), Some(value)) = (
                 tag.get("Key").and_then(|v| v.as_str()),
@@ -599,3 +998,299 @@ impl AwsccProvider {
         tags_map
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nat_gateway_missing_allocation_gets_hint() {
+        let err = ProviderError::new(
+            "Operation failed: The allocation ID 'eipalloc-123' does not exist (Error Code: InvalidAllocationID.NotFound)",
+        );
+        let err = with_create_hint("ec2_nat_gateway", err);
+        assert!(err.message.contains("allocation_id = my_eip.allocation_id"));
+
+        let err = with_create_hint(
+            "ec2_vpc",
+            ProviderError::new("InvalidAllocationID.NotFound"),
+        );
+        assert!(!err.message.contains("hint"));
+    }
+
+    #[test]
+    fn primary_identifier_joins_parts_from_attributes() {
+        let config = get_schema_config("ec2_route").unwrap();
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            "route_table_id".to_string(),
+            Value::String("rtb-123".to_string()),
+        );
+        assert_eq!(primary_identifier(&config, &attributes), None);
+
+        attributes.insert(
+            "cidr_block".to_string(),
+            Value::String("0.0.0.0/0".to_string()),
+        );
+        assert_eq!(
+            primary_identifier(&config, &attributes).as_deref(),
+            Some("rtb-123|0.0.0.0/0")
+        );
+
+        let config = get_schema_config("ec2_vpc").unwrap();
+        let mut attributes = HashMap::new();
+        attributes.insert("vpc_id".to_string(), Value::String("vpc-123".to_string()));
+        assert_eq!(
+            primary_identifier(&config, &attributes).as_deref(),
+            Some("vpc-123")
+        );
+    }
+
+    #[test]
+    fn poll_delay_backs_off_and_honors_retry_after() {
+        let now = std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        assert_eq!(poll_delay(0, None, now), Duration::from_secs(1));
+        assert_eq!(poll_delay(2, None, now), Duration::from_secs(4));
+        assert_eq!(poll_delay(10, None, now), MAX_POLL_DELAY);
+
+        let later = now + Duration::from_secs(7);
+        assert_eq!(poll_delay(0, Some(later), now), Duration::from_secs(7));
+        let past = now - Duration::from_secs(3);
+        assert_eq!(poll_delay(5, Some(past), now), MIN_POLL_DELAY);
+    }
+
+    fn request(operation: Operation, type_name: &str, identifier: Option<&str>) -> ProgressEvent {
+        let mut builder = ProgressEvent::builder()
+            .operation(operation)
+            .type_name(type_name)
+            .request_token(format!("token-{}", identifier.unwrap_or("new")));
+        if let Some(identifier) = identifier {
+            builder = builder.identifier(identifier);
+        }
+        builder.build()
+    }
+
+    fn create_request(token: &str, model: serde_json::Value) -> ProgressEvent {
+        ProgressEvent::builder()
+            .operation(Operation::Create)
+            .type_name("AWS::EC2::VPC")
+            .request_token(token)
+            .resource_model(model.to_string())
+            .build()
+    }
+
+    #[test]
+    fn in_flight_request_matches_resource() {
+        let requests = vec![
+            request(Operation::Update, "AWS::EC2::VPC", Some("vpc-1")),
+            request(Operation::Delete, "AWS::EC2::Subnet", Some("subnet-1")),
+            create_request(
+                "token-new",
+                serde_json::json!({"CidrBlock": "10.0.0.0/16", "InstanceTenancy": "default"}),
+            ),
+        ];
+
+        let found = in_flight_request(
+            &requests,
+            "AWS::EC2::Subnet",
+            &InFlightTarget::Identifier("subnet-1"),
+        )
+        .unwrap();
+        assert_eq!(found.request_token(), Some("token-subnet-1"));
+        assert!(
+            in_flight_request(
+                &requests,
+                "AWS::EC2::Subnet",
+                &InFlightTarget::Identifier("subnet-2")
+            )
+            .is_none()
+        );
+
+        let desired = serde_json::json!({"CidrBlock": "10.0.0.0/16"});
+        let found = in_flight_request(
+            &requests,
+            "AWS::EC2::VPC",
+            &InFlightTarget::Create(&desired),
+        )
+        .unwrap();
+        assert_eq!(found.request_token(), Some("token-new"));
+        assert!(
+            in_flight_request(
+                &requests,
+                "AWS::EC2::Subnet",
+                &InFlightTarget::Create(&desired)
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn in_flight_create_must_match_desired_state() {
+        let other = serde_json::json!({"CidrBlock": "10.1.0.0/16"});
+        let requests = vec![
+            create_request("token-other", other.clone()),
+            request(Operation::Create, "AWS::EC2::VPC", None),
+        ];
+        let desired = serde_json::json!({"CidrBlock": "10.0.0.0/16"});
+        assert!(
+            in_flight_request(
+                &requests,
+                "AWS::EC2::VPC",
+                &InFlightTarget::Create(&desired)
+            )
+            .is_none()
+        );
+
+        let requests = vec![
+            create_request("token-a", other.clone()),
+            create_request("token-b", other.clone()),
+        ];
+        assert!(
+            in_flight_request(&requests, "AWS::EC2::VPC", &InFlightTarget::Create(&other))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn operation_timeout_reads_env_override() {
+        assert_eq!(operation_timeout(|_| None), DEFAULT_OPERATION_TIMEOUT);
+        assert_eq!(
+            operation_timeout(|_| Some("90".to_string())),
+            Duration::from_secs(90)
+        );
+        assert_eq!(
+            operation_timeout(|_| Some("soon".to_string())),
+            DEFAULT_OPERATION_TIMEOUT
+        );
+    }
+
+    /// A provider whose client is never called
+    fn offline_provider() -> AwsccProvider {
+        use aws_sdk_cloudcontrol::config::{BehaviorVersion, Region};
+
+        let config = aws_sdk_cloudcontrol::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .build();
+        AwsccProvider {
+            cloudcontrol_client: CloudControlClient::from_conf(config),
+            region: "us-east-1".to_string(),
+            operation_timeout: DEFAULT_OPERATION_TIMEOUT,
+        }
+    }
+
+    /// Patch paths in a stable order
+    fn patch_paths(patch: &[serde_json::Value]) -> Vec<(String, String)> {
+        let mut paths: Vec<(String, String)> = patch
+            .iter()
+            .map(|op| {
+                (
+                    op["op"].as_str().unwrap().to_string(),
+                    op["path"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn updatable_attributes_by_resource_type() {
+        assert_eq!(updatable_attributes("ec2_vpc"), Some(None));
+        assert_eq!(
+            updatable_attributes("ec2_vpc_endpoint"),
+            Some(Some(&["route_table_ids"][..]))
+        );
+        assert_eq!(updatable_attributes("ec2_subnet"), None);
+    }
+
+    #[test]
+    fn vpc_endpoint_patch_sends_only_route_table_ids() {
+        let provider = offline_provider();
+        let config = get_schema_config("ec2_vpc_endpoint").unwrap();
+        let updatable = updatable_attributes("ec2_vpc_endpoint").unwrap();
+        let from = State::existing(ResourceId::new("ec2_vpc_endpoint", "s3"), HashMap::new());
+
+        let to = Resource::new("ec2_vpc_endpoint", "s3")
+            .with_attribute(
+                "service_name",
+                Value::String("com.amazonaws.us-east-1.s3".to_string()),
+            )
+            .with_attribute("private_dns_enabled", Value::Bool(true))
+            .with_attribute(
+                "route_table_ids",
+                Value::List(vec![Value::String("rtb-1".to_string())]),
+            );
+        let patch = provider.update_patch(&config, updatable, &from, &to);
+        assert_eq!(
+            patch,
+            vec![json!({"op": "replace", "path": "/RouteTableIds", "value": ["rtb-1"]})]
+        );
+
+        // Without route_table_ids, every route table of a gateway endpoint is
+        // disassociated
+        let to = Resource::new("ec2_vpc_endpoint", "s3").with_attribute(
+            "service_name",
+            Value::String("com.amazonaws.us-east-1.s3".to_string()),
+        );
+        let associated = |endpoint_type: &str| {
+            let attributes = HashMap::from([
+                (
+                    "route_table_ids".to_string(),
+                    Value::List(vec![Value::String("rtb-1".to_string())]),
+                ),
+                (
+                    "vpc_endpoint_type".to_string(),
+                    Value::String(endpoint_type.to_string()),
+                ),
+            ]);
+            State::existing(ResourceId::new("ec2_vpc_endpoint", "s3"), attributes)
+        };
+        assert_eq!(
+            provider.update_patch(&config, updatable, &associated("Gateway"), &to),
+            vec![json!({"op": "replace", "path": "/RouteTableIds", "value": []})]
+        );
+
+        // Nothing to clear without route tables, or on other endpoint types
+        assert!(
+            provider
+                .update_patch(&config, updatable, &from, &to)
+                .is_empty()
+        );
+        assert!(
+            provider
+                .update_patch(&config, updatable, &associated("Interface"), &to)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn vpc_patch_removes_only_attributes_the_resource_has() {
+        let provider = offline_provider();
+        let config = get_schema_config("ec2_vpc").unwrap();
+        let updatable = updatable_attributes("ec2_vpc").unwrap();
+        let to = Resource::new("ec2_vpc", "main")
+            .with_attribute("cidr_block", Value::String("10.0.0.0/16".to_string()))
+            .with_attribute("instance_tenancy", Value::Null);
+
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            "instance_tenancy".to_string(),
+            Value::String("dedicated".to_string()),
+        );
+        let from = State::existing(ResourceId::new("ec2_vpc", "main"), attributes);
+        assert_eq!(
+            patch_paths(&provider.update_patch(&config, updatable, &from, &to)),
+            vec![
+                ("remove".to_string(), "/InstanceTenancy".to_string()),
+                ("replace".to_string(), "/CidrBlock".to_string()),
+            ]
+        );
+
+        let from = State::existing(ResourceId::new("ec2_vpc", "main"), HashMap::new());
+        assert_eq!(
+            patch_paths(&provider.update_patch(&config, updatable, &from, &to)),
+            vec![("replace".to_string(), "/CidrBlock".to_string())]
+        );
+    }
+}
@@ -2,13 +2,16 @@
 //!
 //! Resource types are automatically derived from generated schema configs.
 
-use carina_core::provider::{ResourceSchema, ResourceType};
+use carina_core::provider::{Capabilities, ResourceSchema, ResourceType};
 
-use crate::schemas::generated::configs;
+use crate::provider::updatable_attributes;
+use crate::schemas::generated::{AwsccSchemaConfig, configs};
 
 /// A resource type backed by an AwsccSchemaConfig
 struct AwsccResourceType {
     name: &'static str,
+    description: Option<String>,
+    capabilities: Capabilities,
 }
 
 impl ResourceType for AwsccResourceType {
@@ -17,7 +20,44 @@ impl ResourceType for AwsccResourceType {
     }
 
     fn schema(&self) -> ResourceSchema {
-        ResourceSchema::default()
+        ResourceSchema {
+            description: self.description.clone(),
+        }
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.capabilities.clone()
+    }
+}
+
+/// Derive capabilities from the attributes the provider can update in place
+/// and from the create-only, write-only and enum attributes of the generated schema
+fn capabilities_for(config: &AwsccSchemaConfig) -> Capabilities {
+    let capabilities = match updatable_attributes(config.resource_type_name) {
+        Some(None) => Capabilities::default(),
+        Some(Some(updatable)) => {
+            let immutable: Vec<&str> = config
+                .schema
+                .attributes
+                .keys()
+                .map(String::as_str)
+                .filter(|name| *name != "tags" && !updatable.contains(name))
+                .collect();
+            Capabilities::default().with_replace_on_change(&immutable)
+        }
+        None => Capabilities::replace_only(),
+    };
+    let create_only: Vec<&str> = config
+        .schema
+        .immutable_attributes()
+        .into_iter()
+        .filter(|name| !capabilities.replace_on_change.iter().any(|a| a == name))
+        .collect();
+    Capabilities {
+        enum_values: config.schema.enum_attributes(),
+        ..capabilities
+            .with_replace_on_change(&create_only)
+            .with_write_only(&config.schema.write_only_attributes())
     }
 }
 
@@ -29,6 +69,8 @@ pub fn resource_types() -> Vec<Box<dyn ResourceType>> {
         .map(|c| {
             Box::new(AwsccResourceType {
                 name: c.resource_type_name,
+                capabilities: capabilities_for(&c),
+                description: c.schema.description,
             }) as Box<dyn ResourceType>
         })
         .collect()
@@ -49,6 +91,47 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_validate_struct() {
+        use crate::schemas::generated::validate_struct;
+        use carina_core::resource::Value;
+        use carina_core::schema::AttributeType;
+        use std::collections::BTreeMap;
+
+        let fields = [
+            ("device_name", AttributeType::String),
+            ("volume_size", AttributeType::Int),
+        ];
+        let map = |entries: &[(&str, Value)]| {
+            Value::Map(
+                entries
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.clone()))
+                    .collect::<BTreeMap<_, _>>(),
+            )
+        };
+
+        let valid = map(&[
+            ("device_name", Value::String("/dev/xvda".to_string())),
+            ("volume_size", Value::Int(8)),
+        ]);
+        assert!(validate_struct(&valid, "BlockDeviceMapping", &fields).is_ok());
+
+        let wrong_type = map(&[("volume_size", Value::Bool(true))]);
+        assert!(
+            validate_struct(&wrong_type, "BlockDeviceMapping", &fields)
+                .unwrap_err()
+                .starts_with("BlockDeviceMapping.volume_size: ")
+        );
+
+        let unknown = map(&[("size", Value::Int(8))]);
+        assert_eq!(
+            validate_struct(&unknown, "BlockDeviceMapping", &fields).unwrap_err(),
+            "Unknown field 'size' in BlockDeviceMapping, expected one of: device_name, volume_size"
+        );
+        assert!(validate_struct(&Value::Int(1), "BlockDeviceMapping", &fields).is_err());
+    }
+
     #[test]
     fn test_get_schema_config() {
         assert!(get_config("ec2_vpc").is_some());
@@ -90,4 +173,120 @@ mod tests {
         let vpc_id_attr = vpc_config.schema.attributes.get("vpc_id").unwrap();
         assert_eq!(vpc_id_attr.provider_name.as_deref(), Some("VpcId"));
     }
+
+    #[test]
+    fn test_capabilities_follow_updatable_attributes() {
+        let caps = |name: &str| {
+            super::resource_types()
+                .into_iter()
+                .find(|t| t.name() == name)
+                .unwrap()
+                .capabilities()
+        };
+
+        assert!(!caps("ec2_vpc").requires_replacement(&["enable_dns_support".to_string()]));
+        assert!(caps("ec2_vpc").requires_replacement(&["cidr_block".to_string()]));
+        assert_eq!(
+            caps("ec2_vpc").write_only,
+            vec![
+                "ipv4_ipam_pool_id".to_string(),
+                "ipv4_netmask_length".to_string()
+            ]
+        );
+        assert!(caps("ec2_subnet").requires_replacement(&["tags".to_string()]));
+        assert_eq!(
+            caps("ec2_nat_gateway").write_only,
+            vec!["max_drain_duration_seconds".to_string()]
+        );
+        let subnet = get_config("ec2_subnet").unwrap().schema;
+        assert!(subnet.immutable_attributes().contains(&"cidr_block"));
+        assert!(
+            subnet
+                .write_only_attributes()
+                .contains(&"ipv4_ipam_pool_id")
+        );
+
+        let endpoint = caps("ec2_vpc_endpoint");
+        assert!(!endpoint.requires_replacement(&["route_table_ids".to_string()]));
+        assert!(endpoint.requires_replacement(&["service_name".to_string()]));
+    }
+
+    #[test]
+    fn test_enum_validation_ignores_case_at_every_depth() {
+        use carina_core::resource::Value;
+
+        let tenancy = get_config("ec2_vpc")
+            .unwrap()
+            .schema
+            .attributes
+            .get("instance_tenancy")
+            .unwrap()
+            .attr_type
+            .clone();
+        let check = |s: &str| tenancy.validate(&Value::String(s.to_string()));
+
+        assert!(check("DEDICATED").is_ok());
+        assert!(check("InstanceTenancy.Dedicated").is_ok());
+        assert!(check("awscc.ec2_vpc.InstanceTenancy.dedicated").is_ok());
+
+        assert!(check("Tenancy.dedicated").is_err());
+        assert!(check("awscc.ec2_subnet.InstanceTenancy.dedicated").is_err());
+        assert!(check("ec2_vpc.InstanceTenancy.dedicated").is_err());
+
+        let err = check("InstanceTenancy.shared").unwrap_err().to_string();
+        assert!(err.contains("expected one of: default, dedicated, host"));
+    }
+
+    #[test]
+    fn test_generated_object_attributes_validate_fields_and_enums() {
+        use carina_core::resource::Value;
+        use std::collections::BTreeMap;
+
+        let dns_options = get_config("ec2_vpc_endpoint")
+            .unwrap()
+            .schema
+            .attributes
+            .get("dns_options")
+            .unwrap()
+            .attr_type
+            .clone();
+        let check = |key: &str, value: &str| {
+            dns_options.validate(&Value::Map(BTreeMap::from([(
+                key.to_string(),
+                Value::String(value.to_string()),
+            )])))
+        };
+
+        assert!(check("dns_record_ip_type", "dualstack").is_ok());
+        assert!(check("dns_record_ip_type", "DnsRecordIpType.ipv4").is_ok());
+        assert!(check("dns_record_ip_type", "ipv5").is_err());
+        assert!(check("record_type", "ipv4").is_err());
+    }
+
+    #[test]
+    fn test_enum_values_are_canonicalized() {
+        use crate::schemas::generated::normalize_namespaced_enum;
+
+        assert_eq!(
+            normalize_namespaced_enum("InstanceTenancy.DEDICATED", &["default", "dedicated"]),
+            "dedicated"
+        );
+        assert_eq!(
+            normalize_namespaced_enum(
+                "awscc.ec2_vpc_endpoint.VpcEndpointType.interface",
+                &["Interface", "Gateway"]
+            ),
+            "Interface"
+        );
+
+        let capabilities = super::capabilities_for(&get_config("ec2_vpc").unwrap());
+        assert_eq!(
+            capabilities.enum_values.get("instance_tenancy"),
+            Some(&vec![
+                "default".to_string(),
+                "dedicated".to_string(),
+                "host".to_string()
+            ])
+        );
+    }
 }
@@ -14,15 +14,19 @@ pub fn ec2_eip_config() -> AwsccSchemaConfig {
         aws_type_name: "AWS::EC2::EIP",
         resource_type_name: "ec2_eip",
         has_tags: true,
+        primary_identifier: &["PublicIp", "AllocationId"],
         schema: ResourceSchema::new("awscc.ec2_eip")
         .with_description("Specifies an Elastic IP (EIP) address and can, optionally, associate it with an Amazon EC2 instance.  You can allocate an Elastic IP address from an address pool owned by AWS or from an address pool c...")
         .attribute(
             AttributeSchema::new("address", AttributeType::String)
+                .immutable()
+                .write_only()
                 .with_description("")
                 .with_provider_name("Address"),
         )
         .attribute(
             AttributeSchema::new("allocation_id", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("AllocationId"),
         )
@@ -38,16 +42,20 @@ pub fn ec2_eip_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("ipam_pool_id", AttributeType::String)
+                .immutable()
+                .write_only()
                 .with_description("")
                 .with_provider_name("IpamPoolId"),
         )
         .attribute(
             AttributeSchema::new("network_border_group", AttributeType::String)
+                .immutable()
                 .with_description("A unique set of Availability Zones, Local Zones, or Wavelength Zones from which AWS advertises IP addresses. Use this parameter to limit the IP addres...")
                 .with_provider_name("NetworkBorderGroup"),
         )
         .attribute(
             AttributeSchema::new("public_ip", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("PublicIp"),
         )
@@ -63,6 +71,8 @@ pub fn ec2_eip_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("transfer_address", AttributeType::String)
+                .immutable()
+                .write_only()
                 .with_description("The Elastic IP address you are accepting for transfer. You can only accept one transferred address. For more information on Elastic IP address transfe...")
                 .with_provider_name("TransferAddress"),
         )
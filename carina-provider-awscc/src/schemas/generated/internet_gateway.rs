@@ -14,10 +14,12 @@ pub fn ec2_internet_gateway_config() -> AwsccSchemaConfig {
         aws_type_name: "AWS::EC2::InternetGateway",
         resource_type_name: "ec2_internet_gateway",
         has_tags: true,
+        primary_identifier: &["InternetGatewayId"],
         schema: ResourceSchema::new("awscc.ec2_internet_gateway")
         .with_description("Allocates an internet gateway for use with a VPC. After creating the Internet gateway, you then attach it to a VPC.")
         .attribute(
             AttributeSchema::new("internet_gateway_id", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("InternetGatewayId"),
         )
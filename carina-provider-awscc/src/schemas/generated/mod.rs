@@ -17,6 +17,8 @@ pub struct AwsccSchemaConfig {
     pub resource_type_name: &'static str,
     /// Whether this resource type uses tags
     pub has_tags: bool,
+    /// Properties forming the Cloud Control identifier (e.g., ["VpcId"])
+    pub primary_identifier: &'static [&'static str],
     /// The resource schema with attribute definitions
     pub schema: ResourceSchema,
 }
@@ -26,21 +28,25 @@ pub fn tags_type() -> AttributeType {
     AttributeType::Map(Box::new(AttributeType::String))
 }
 
-/// Normalize a namespaced enum value to its base value.
-/// Handles formats like:
+/// Normalize a namespaced enum value to its spelling in `valid_values`.
+/// Handles formats like (in any case):
 /// - "value" -> "value"
-/// - "TypeName.value" -> "value"
+/// - "TypeName.VALUE" -> "value"
 /// - "awscc.resource.TypeName.value" -> "value"
-pub fn normalize_namespaced_enum(s: &str) -> String {
-    if s.contains('.') {
-        let parts: Vec<&str> = s.split('.').collect();
-        parts.last().map(|s| s.to_string()).unwrap_or_default()
-    } else {
-        s.to_string()
-    }
+///
+/// A value matching none of `valid_values` is returned without its prefix.
+pub fn normalize_namespaced_enum(s: &str, valid_values: &[&str]) -> String {
+    let raw = s.rsplit('.').next().unwrap_or(s);
+    valid_values
+        .iter()
+        .find(|valid| valid.eq_ignore_ascii_case(raw))
+        .map_or(raw, |valid| valid)
+        .to_string()
 }
 
 /// Validate a namespaced enum value.
+/// Accepts "value", "TypeName.value" and "awscc.resource.TypeName.value" alike;
+/// the value itself is compared case-insensitively against `valid_values`.
 /// Returns Ok(()) if valid, Err with message if invalid.
 pub fn validate_namespaced_enum(
     value: &Value,
@@ -49,43 +55,18 @@ pub fn validate_namespaced_enum(
     valid_values: &[&str],
 ) -> Result<(), String> {
     if let Value::String(s) = value {
-        // Validate namespace format if it contains dots
-        if s.contains('.') {
-            let parts: Vec<&str> = s.split('.').collect();
-            match parts.len() {
-                // 2-part: TypeName.value
-                2 => {
-                    if parts[0] != type_name {
-                        return Err(format!(
-                            "Invalid format '{}', expected {}.value",
-                            s, type_name
-                        ));
-                    }
-                }
-                // 4-part: awscc.resource.TypeName.value
-                4 => {
-                    let expected_namespace: Vec<&str> = namespace.split('.').collect();
-                    if expected_namespace.len() != 2
-                        || parts[0] != expected_namespace[0]
-                        || parts[1] != expected_namespace[1]
-                        || parts[2] != type_name
-                    {
-                        return Err(format!(
-                            "Invalid format '{}', expected {}.{}.value",
-                            s, namespace, type_name
-                        ));
-                    }
-                }
-                _ => {
-                    return Err(format!(
-                        "Invalid format '{}', expected one of: value, {}.value, or {}.{}.value",
-                        s, type_name, namespace, type_name
-                    ));
-                }
-            }
+        // Everything before the value must be nothing, the type name, or the full namespace
+        let prefix: Vec<&str> = s.split('.').collect();
+        let prefix = &prefix[..prefix.len() - 1];
+        let full_prefix: Vec<&str> = namespace.split('.').chain([type_name]).collect();
+        if !(prefix.is_empty() || prefix == [type_name] || prefix == full_prefix.as_slice()) {
+            return Err(format!(
+                "Invalid format '{}', expected one of: value, {}.value, or {}.{}.value",
+                s, type_name, namespace, type_name
+            ));
         }
 
-        let normalized = normalize_namespaced_enum(s);
+        let normalized = normalize_namespaced_enum(s, valid_values);
         if valid_values.contains(&normalized.as_str()) {
             Ok(())
         } else {
@@ -100,6 +81,34 @@ pub fn validate_namespaced_enum(
     }
 }
 
+/// Validate a map value against the fields of an object definition.
+/// Every key must be one of `fields` and its value must match the field's type.
+/// Returns Ok(()) if valid, Err with message if invalid.
+pub fn validate_struct(
+    value: &Value,
+    type_name: &str,
+    fields: &[(&str, AttributeType)],
+) -> Result<(), String> {
+    let Value::Map(map) = value else {
+        return Err(format!("Expected a map for {}", type_name));
+    };
+    for (key, field_value) in map {
+        let Some((_, field_type)) = fields.iter().find(|(name, _)| name == key) else {
+            let names: Vec<&str> = fields.iter().map(|(name, _)| *name).collect();
+            return Err(format!(
+                "Unknown field '{}' in {}, expected one of: {}",
+                key,
+                type_name,
+                names.join(", ")
+            ));
+        };
+        field_type
+            .validate(field_value)
+            .map_err(|e| format!("{}.{}: {}", type_name, key, e))?;
+    }
+    Ok(())
+}
+
 pub mod eip;
 pub mod internet_gateway;
 pub mod nat_gateway;
@@ -6,68 +6,101 @@
 
 use super::AwsccSchemaConfig;
 use super::tags_type;
+use super::validate_struct;
+use carina_core::resource::Value;
 use carina_core::schema::{AttributeSchema, AttributeType, ResourceSchema};
 
+fn validate_availability_zone_address_struct(value: &Value) -> Result<(), String> {
+    validate_struct(
+        value,
+        "AvailabilityZoneAddress",
+        &[
+            (
+                "allocation_ids",
+                AttributeType::List(Box::new(AttributeType::String)),
+            ),
+            ("availability_zone", AttributeType::String),
+            ("availability_zone_id", AttributeType::String),
+        ],
+    )
+}
+
 /// Returns the schema config for ec2_nat_gateway (AWS::EC2::NatGateway)
 pub fn ec2_nat_gateway_config() -> AwsccSchemaConfig {
     AwsccSchemaConfig {
         aws_type_name: "AWS::EC2::NatGateway",
         resource_type_name: "ec2_nat_gateway",
         has_tags: true,
+        primary_identifier: &["NatGatewayId"],
         schema: ResourceSchema::new("awscc.ec2_nat_gateway")
         .with_description("Specifies a network address translation (NAT) gateway in the specified subnet. You can create either a public NAT gateway or a private NAT gateway. The default is a public NAT gateway. If you create a...")
         .attribute(
             AttributeSchema::new("allocation_id", AttributeType::String)
+                .immutable()
                 .with_description("[Public NAT gateway only] The allocation ID of the Elastic IP address that's associated with the NAT gateway. This property is required for a public N...")
                 .with_provider_name("AllocationId"),
         )
         .attribute(
             AttributeSchema::new("auto_provision_zones", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("AutoProvisionZones"),
         )
         .attribute(
             AttributeSchema::new("auto_scaling_ips", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("AutoScalingIps"),
         )
         .attribute(
             AttributeSchema::new("availability_mode", AttributeType::String)
+                .immutable()
                 .with_description("Indicates whether this is a zonal (single-AZ) or regional (multi-AZ) NAT gateway. A zonal NAT gateway is a NAT Gateway that provides redundancy and sc...")
                 .with_provider_name("AvailabilityMode"),
         )
         .attribute(
-            AttributeSchema::new("availability_zone_addresses", AttributeType::List(Box::new(AttributeType::String)))
+            AttributeSchema::new("availability_zone_addresses", AttributeType::List(Box::new(AttributeType::Custom {
+                name: "AvailabilityZoneAddress".to_string(),
+                base: Box::new(AttributeType::Map(Box::new(AttributeType::String))),
+                validate: validate_availability_zone_address_struct,
+                namespace: None,
+            })))
                 .with_description("For regional NAT gateways only: Specifies which Availability Zones you want the NAT gateway to support and the Elastic IP addresses (EIPs) to use in e...")
                 .with_provider_name("AvailabilityZoneAddresses"),
         )
         .attribute(
             AttributeSchema::new("connectivity_type", AttributeType::String)
+                .immutable()
                 .with_description("Indicates whether the NAT gateway supports public or private connectivity. The default is public connectivity.")
                 .with_provider_name("ConnectivityType"),
         )
         .attribute(
             AttributeSchema::new("eni_id", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("EniId"),
         )
         .attribute(
             AttributeSchema::new("max_drain_duration_seconds", AttributeType::Int)
+                .write_only()
                 .with_description("The maximum amount of time to wait (in seconds) before forcibly releasing the IP addresses if connections are still in progress. Default value is 350 ...")
                 .with_provider_name("MaxDrainDurationSeconds"),
         )
         .attribute(
             AttributeSchema::new("nat_gateway_id", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("NatGatewayId"),
         )
         .attribute(
             AttributeSchema::new("private_ip_address", AttributeType::String)
+                .immutable()
                 .with_description("The private IPv4 address to assign to the NAT gateway. If you don't provide an address, a private IPv4 address will be automatically assigned.")
                 .with_provider_name("PrivateIpAddress"),
         )
         .attribute(
             AttributeSchema::new("route_table_id", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("RouteTableId"),
         )
@@ -88,6 +121,7 @@ pub fn ec2_nat_gateway_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("subnet_id", AttributeType::String)
+                .immutable()
                 .with_description("The ID of the subnet in which the NAT gateway is located.")
                 .with_provider_name("SubnetId"),
         )
@@ -98,6 +132,7 @@ pub fn ec2_nat_gateway_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("vpc_id", AttributeType::String)
+                .immutable()
                 .with_description("The ID of the VPC in which the NAT gateway is located.")
                 .with_provider_name("VpcId"),
         )
@@ -13,6 +13,7 @@ pub fn ec2_route_config() -> AwsccSchemaConfig {
         aws_type_name: "AWS::EC2::Route",
         resource_type_name: "ec2_route",
         has_tags: false,
+        primary_identifier: &["RouteTableId", "CidrBlock"],
         schema: ResourceSchema::new("awscc.ec2_route")
         .with_description("Specifies a route in a route table. For more information, see [Routes](https://docs.aws.amazon.com/vpc/latest/userguide/VPC_Route_Tables.html#route-table-routes) in the *Amazon VPC User Guide*.  You m...")
         .attribute(
@@ -22,6 +23,7 @@ pub fn ec2_route_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("cidr_block", types::cidr())
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("CidrBlock"),
         )
@@ -32,16 +34,19 @@ pub fn ec2_route_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("destination_cidr_block", types::cidr())
+                .immutable()
                 .with_description("The IPv4 CIDR address block used for the destination match. Routing decisions are based on the most specific match. We modify the specified CIDR block...")
                 .with_provider_name("DestinationCidrBlock"),
         )
         .attribute(
             AttributeSchema::new("destination_ipv6_cidr_block", types::cidr())
+                .immutable()
                 .with_description("The IPv6 CIDR block used for the destination match. Routing decisions are based on the most specific match.")
                 .with_provider_name("DestinationIpv6CidrBlock"),
         )
         .attribute(
             AttributeSchema::new("destination_prefix_list_id", AttributeType::String)
+                .immutable()
                 .with_description("The ID of a prefix list used for the destination match.")
                 .with_provider_name("DestinationPrefixListId"),
         )
@@ -78,6 +83,7 @@ pub fn ec2_route_config() -> AwsccSchemaConfig {
         .attribute(
             AttributeSchema::new("route_table_id", AttributeType::String)
                 .required()
+                .immutable()
                 .with_description("The ID of the route table for the route.")
                 .with_provider_name("RouteTableId"),
         )
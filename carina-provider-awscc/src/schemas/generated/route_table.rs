@@ -14,10 +14,12 @@ pub fn ec2_route_table_config() -> AwsccSchemaConfig {
         aws_type_name: "AWS::EC2::RouteTable",
         resource_type_name: "ec2_route_table",
         has_tags: true,
+        primary_identifier: &["RouteTableId"],
         schema: ResourceSchema::new("awscc.ec2_route_table")
         .with_description("Specifies a route table for the specified VPC. After you create a route table, you can add routes and associate the table with a subnet.  For more information, see [Route tables](https://docs.aws.amaz...")
         .attribute(
             AttributeSchema::new("route_table_id", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("RouteTableId"),
         )
@@ -29,6 +31,7 @@ pub fn ec2_route_table_config() -> AwsccSchemaConfig {
         .attribute(
             AttributeSchema::new("vpc_id", AttributeType::String)
                 .required()
+                .immutable()
                 .with_description("The ID of the VPC.")
                 .with_provider_name("VpcId"),
         )
@@ -6,44 +6,97 @@
 
 use super::AwsccSchemaConfig;
 use super::tags_type;
+use super::validate_struct;
+use carina_core::resource::Value;
 use carina_core::schema::{AttributeSchema, AttributeType, ResourceSchema};
 
+fn validate_egress_struct(value: &Value) -> Result<(), String> {
+    validate_struct(
+        value,
+        "Egress",
+        &[
+            ("cidr_ip", AttributeType::String),
+            ("cidr_ipv6", AttributeType::String),
+            ("description", AttributeType::String),
+            ("destination_prefix_list_id", AttributeType::String),
+            ("destination_security_group_id", AttributeType::String),
+            ("from_port", AttributeType::Int),
+            ("ip_protocol", AttributeType::String),
+            ("to_port", AttributeType::Int),
+        ],
+    )
+}
+
+fn validate_ingress_struct(value: &Value) -> Result<(), String> {
+    validate_struct(
+        value,
+        "Ingress",
+        &[
+            ("cidr_ip", AttributeType::String),
+            ("cidr_ipv6", AttributeType::String),
+            ("description", AttributeType::String),
+            ("from_port", AttributeType::Int),
+            ("ip_protocol", AttributeType::String),
+            ("source_prefix_list_id", AttributeType::String),
+            ("source_security_group_id", AttributeType::String),
+            ("source_security_group_name", AttributeType::String),
+            ("source_security_group_owner_id", AttributeType::String),
+            ("to_port", AttributeType::Int),
+        ],
+    )
+}
+
 /// Returns the schema config for ec2_security_group (AWS::EC2::SecurityGroup)
 pub fn ec2_security_group_config() -> AwsccSchemaConfig {
     AwsccSchemaConfig {
         aws_type_name: "AWS::EC2::SecurityGroup",
         resource_type_name: "ec2_security_group",
         has_tags: true,
+        primary_identifier: &["Id"],
         schema: ResourceSchema::new("awscc.ec2_security_group")
         .with_description("Resource Type definition for AWS::EC2::SecurityGroup")
         .attribute(
             AttributeSchema::new("group_description", AttributeType::String)
                 .required()
+                .immutable()
                 .with_description("A description for the security group.")
                 .with_provider_name("GroupDescription"),
         )
         .attribute(
             AttributeSchema::new("group_id", AttributeType::String)
+                .read_only()
                 .with_description("The group ID of the specified security group. (read-only)")
                 .with_provider_name("GroupId"),
         )
         .attribute(
             AttributeSchema::new("group_name", AttributeType::String)
+                .immutable()
                 .with_description("The name of the security group.")
                 .with_provider_name("GroupName"),
         )
         .attribute(
             AttributeSchema::new("id", AttributeType::String)
+                .read_only()
                 .with_description("The group name or group ID depending on whether the SG is created in default or specific VPC (read-only)")
                 .with_provider_name("Id"),
         )
         .attribute(
-            AttributeSchema::new("security_group_egress", AttributeType::List(Box::new(AttributeType::String)))
+            AttributeSchema::new("security_group_egress", AttributeType::List(Box::new(AttributeType::Custom {
+                name: "Egress".to_string(),
+                base: Box::new(AttributeType::Map(Box::new(AttributeType::String))),
+                validate: validate_egress_struct,
+                namespace: None,
+            })))
                 .with_description("[VPC only] The outbound rules associated with the security group. There is a short interruption during which you cannot connect to the security group.")
                 .with_provider_name("SecurityGroupEgress"),
         )
         .attribute(
-            AttributeSchema::new("security_group_ingress", AttributeType::List(Box::new(AttributeType::String)))
+            AttributeSchema::new("security_group_ingress", AttributeType::List(Box::new(AttributeType::Custom {
+                name: "Ingress".to_string(),
+                base: Box::new(AttributeType::Map(Box::new(AttributeType::String))),
+                validate: validate_ingress_struct,
+                namespace: None,
+            })))
                 .with_description("The inbound rules associated with the security group. There is a short interruption during which you cannot connect to the security group.")
                 .with_provider_name("SecurityGroupIngress"),
         )
@@ -54,6 +107,7 @@ pub fn ec2_security_group_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("vpc_id", AttributeType::String)
+                .immutable()
                 .with_description("The ID of the VPC for the security group.")
                 .with_provider_name("VpcId"),
         )
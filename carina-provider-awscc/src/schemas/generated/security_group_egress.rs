@@ -26,15 +26,18 @@ pub fn ec2_security_group_egress_config() -> AwsccSchemaConfig {
         aws_type_name: "AWS::EC2::SecurityGroupEgress",
         resource_type_name: "ec2_security_group_egress",
         has_tags: false,
+        primary_identifier: &["Id"],
         schema: ResourceSchema::new("awscc.ec2_security_group_egress")
         .with_description("Adds the specified outbound (egress) rule to a security group.  An outbound rule permits instances to send traffic to the specified IPv4 or IPv6 address range, the IP addresses that are specified by a...")
         .attribute(
             AttributeSchema::new("cidr_ip", AttributeType::String)
+                .immutable()
                 .with_description("The IPv4 address range, in CIDR format. You must specify exactly one of the following: ``CidrIp``, ``CidrIpv6``, ``DestinationPrefixListId``, or ``Des...")
                 .with_provider_name("CidrIp"),
         )
         .attribute(
             AttributeSchema::new("cidr_ipv6", AttributeType::String)
+                .immutable()
                 .with_description("The IPv6 address range, in CIDR format. You must specify exactly one of the following: ``CidrIp``, ``CidrIpv6``, ``DestinationPrefixListId``, or ``Des...")
                 .with_provider_name("CidrIpv6"),
         )
@@ -45,27 +48,32 @@ pub fn ec2_security_group_egress_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("destination_prefix_list_id", AttributeType::String)
+                .immutable()
                 .with_description("The prefix list IDs for an AWS service. This is the AWS service to access through a VPC endpoint from instances associated with the security group. Yo...")
                 .with_provider_name("DestinationPrefixListId"),
         )
         .attribute(
             AttributeSchema::new("destination_security_group_id", AttributeType::String)
+                .immutable()
                 .with_description("The ID of the security group. You must specify exactly one of the following: ``CidrIp``, ``CidrIpv6``, ``DestinationPrefixListId``, or ``DestinationSe...")
                 .with_provider_name("DestinationSecurityGroupId"),
         )
         .attribute(
             AttributeSchema::new("from_port", AttributeType::Int)
+                .immutable()
                 .with_description("If the protocol is TCP or UDP, this is the start of the port range. If the protocol is ICMP or ICMPv6, this is the ICMP type or -1 (all ICMP types).")
                 .with_provider_name("FromPort"),
         )
         .attribute(
             AttributeSchema::new("group_id", AttributeType::String)
                 .required()
+                .immutable()
                 .with_description("The ID of the security group. You must specify either the security group ID or the security group name in the request. For security groups in a nondef...")
                 .with_provider_name("GroupId"),
         )
         .attribute(
             AttributeSchema::new("id", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("Id"),
         )
@@ -77,11 +85,14 @@ pub fn ec2_security_group_egress_config() -> AwsccSchemaConfig {
                 namespace: Some("awscc.ec2_security_group_egress".to_string()),
             })
                 .required()
+                .immutable()
+                .with_enum_values(VALID_IP_PROTOCOL)
                 .with_description("The IP protocol name (``tcp``, ``udp``, ``icmp``, ``icmpv6``) or number (see [Protocol Numbers](https://docs.aws.amazon.com/http://www.iana.org/assign...")
                 .with_provider_name("IpProtocol"),
         )
         .attribute(
             AttributeSchema::new("to_port", AttributeType::Int)
+                .immutable()
                 .with_description("If the protocol is TCP or UDP, this is the end of the port range. If the protocol is ICMP or ICMPv6, this is the ICMP code or -1 (all ICMP codes). If ...")
                 .with_provider_name("ToPort"),
         )
@@ -14,6 +14,7 @@ pub fn ec2_subnet_config() -> AwsccSchemaConfig {
         aws_type_name: "AWS::EC2::Subnet",
         resource_type_name: "ec2_subnet",
         has_tags: true,
+        primary_identifier: &["SubnetId"],
         schema: ResourceSchema::new("awscc.ec2_subnet")
         .with_description("Specifies a subnet for the specified VPC.  For an IPv4 only subnet, specify an IPv4 CIDR block. If the VPC has an IPv6 CIDR block, you can create an IPv6 only subnet or a dual stack subnet instead. Fo...")
         .attribute(
@@ -23,21 +24,25 @@ pub fn ec2_subnet_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("availability_zone", AttributeType::String)
+                .immutable()
                 .with_description("The Availability Zone of the subnet. If you update this property, you must also update the ``CidrBlock`` property.")
                 .with_provider_name("AvailabilityZone"),
         )
         .attribute(
             AttributeSchema::new("availability_zone_id", AttributeType::String)
+                .immutable()
                 .with_description("The AZ ID of the subnet.")
                 .with_provider_name("AvailabilityZoneId"),
         )
         .attribute(
             AttributeSchema::new("block_public_access_states", AttributeType::Map(Box::new(AttributeType::String)))
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("BlockPublicAccessStates"),
         )
         .attribute(
             AttributeSchema::new("cidr_block", types::cidr())
+                .immutable()
                 .with_description("The IPv4 CIDR block assigned to the subnet. If you update this property, we create a new subnet, and then delete the existing one.")
                 .with_provider_name("CidrBlock"),
         )
@@ -48,16 +53,21 @@ pub fn ec2_subnet_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("enable_lni_at_device_index", AttributeType::Int)
+                .write_only()
                 .with_description("Indicates the device position for local network interfaces in this subnet. For example, ``1`` indicates local network interfaces in this subnet are th...")
                 .with_provider_name("EnableLniAtDeviceIndex"),
         )
         .attribute(
             AttributeSchema::new("ipv4_ipam_pool_id", AttributeType::String)
+                .immutable()
+                .write_only()
                 .with_description("An IPv4 IPAM pool ID for the subnet.")
                 .with_provider_name("Ipv4IpamPoolId"),
         )
         .attribute(
             AttributeSchema::new("ipv4_netmask_length", AttributeType::Int)
+                .immutable()
+                .write_only()
                 .with_description("An IPv4 netmask length for the subnet.")
                 .with_provider_name("Ipv4NetmaskLength"),
         )
@@ -68,21 +78,27 @@ pub fn ec2_subnet_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("ipv6_cidr_blocks", AttributeType::List(Box::new(types::cidr())))
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("Ipv6CidrBlocks"),
         )
         .attribute(
             AttributeSchema::new("ipv6_ipam_pool_id", AttributeType::String)
+                .immutable()
+                .write_only()
                 .with_description("An IPv6 IPAM pool ID for the subnet.")
                 .with_provider_name("Ipv6IpamPoolId"),
         )
         .attribute(
             AttributeSchema::new("ipv6_native", AttributeType::Bool)
+                .immutable()
                 .with_description("Indicates whether this is an IPv6 only subnet. For more information, see [Subnet basics](https://docs.aws.amazon.com/vpc/latest/userguide/VPC_Subnets....")
                 .with_provider_name("Ipv6Native"),
         )
         .attribute(
             AttributeSchema::new("ipv6_netmask_length", AttributeType::Int)
+                .immutable()
+                .write_only()
                 .with_description("An IPv6 netmask length for the subnet.")
                 .with_provider_name("Ipv6NetmaskLength"),
         )
@@ -93,11 +109,13 @@ pub fn ec2_subnet_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("network_acl_association_id", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("NetworkAclAssociationId"),
         )
         .attribute(
             AttributeSchema::new("outpost_arn", AttributeType::String)
+                .immutable()
                 .with_description("The Amazon Resource Name (ARN) of the Outpost.")
                 .with_provider_name("OutpostArn"),
         )
@@ -108,6 +126,7 @@ pub fn ec2_subnet_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("subnet_id", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("SubnetId"),
         )
@@ -119,6 +138,7 @@ pub fn ec2_subnet_config() -> AwsccSchemaConfig {
         .attribute(
             AttributeSchema::new("vpc_id", AttributeType::String)
                 .required()
+                .immutable()
                 .with_description("The ID of the VPC the subnet is in. If you update this property, you must also update the ``CidrBlock`` property.")
                 .with_provider_name("VpcId"),
         )
@@ -27,25 +27,30 @@ pub fn ec2_vpc_config() -> AwsccSchemaConfig {
         aws_type_name: "AWS::EC2::VPC",
         resource_type_name: "ec2_vpc",
         has_tags: true,
+        primary_identifier: &["VpcId"],
         schema: ResourceSchema::new("awscc.ec2_vpc")
         .with_description("Specifies a virtual private cloud (VPC).  To add an IPv6 CIDR block to the VPC, see [AWS::EC2::VPCCidrBlock](https://docs.aws.amazon.com/AWSCloudFormation/latest/UserGuide/aws-resource-ec2-vpccidrbloc...")
         .attribute(
             AttributeSchema::new("cidr_block", types::cidr())
+                .immutable()
                 .with_description("The IPv4 network range for the VPC, in CIDR notation. For example, ``10.0.0.0/16``. We modify the specified CIDR block to its canonical form; for exam...")
                 .with_provider_name("CidrBlock"),
         )
         .attribute(
             AttributeSchema::new("cidr_block_associations", AttributeType::List(Box::new(types::cidr())))
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("CidrBlockAssociations"),
         )
         .attribute(
             AttributeSchema::new("default_network_acl", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("DefaultNetworkAcl"),
         )
         .attribute(
             AttributeSchema::new("default_security_group", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("DefaultSecurityGroup"),
         )
@@ -66,21 +71,27 @@ pub fn ec2_vpc_config() -> AwsccSchemaConfig {
                 validate: validate_instance_tenancy,
                 namespace: Some("awscc.ec2_vpc".to_string()),
             })
+                .with_enum_values(VALID_INSTANCE_TENANCY)
                 .with_description("The allowed tenancy of instances launched into the VPC.  + ``default``: An instance launched into the VPC runs on shared hardware by default, unless y...")
                 .with_provider_name("InstanceTenancy"),
         )
         .attribute(
             AttributeSchema::new("ipv4_ipam_pool_id", AttributeType::String)
+                .immutable()
+                .write_only()
                 .with_description("The ID of an IPv4 IPAM pool you want to use for allocating this VPC's CIDR. For more information, see [What is IPAM?](https://docs.aws.amazon.com//vpc...")
                 .with_provider_name("Ipv4IpamPoolId"),
         )
         .attribute(
             AttributeSchema::new("ipv4_netmask_length", AttributeType::Int)
+                .immutable()
+                .write_only()
                 .with_description("The netmask length of the IPv4 CIDR you want to allocate to this VPC from an Amazon VPC IP Address Manager (IPAM) pool. For more information about IPA...")
                 .with_provider_name("Ipv4NetmaskLength"),
         )
         .attribute(
             AttributeSchema::new("ipv6_cidr_blocks", AttributeType::List(Box::new(types::cidr())))
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("Ipv6CidrBlocks"),
         )
@@ -91,6 +102,7 @@ pub fn ec2_vpc_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("vpc_id", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("VpcId"),
         )
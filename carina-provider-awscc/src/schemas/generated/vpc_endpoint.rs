@@ -7,9 +7,57 @@
 use super::AwsccSchemaConfig;
 use super::tags_type;
 use super::validate_namespaced_enum;
+use super::validate_struct;
 use carina_core::resource::Value;
 use carina_core::schema::{AttributeSchema, AttributeType, ResourceSchema};
 
+const VALID_DNS_OPTIONS_SPECIFICATION_DNS_RECORD_IP_TYPE: &[&str] = &[
+    "ipv4",
+    "ipv6",
+    "dualstack",
+    "service-defined",
+    "not-specified",
+];
+
+fn validate_dns_options_specification_dns_record_ip_type(value: &Value) -> Result<(), String> {
+    validate_namespaced_enum(
+        value,
+        "DnsRecordIpType",
+        "awscc.ec2_vpc_endpoint",
+        VALID_DNS_OPTIONS_SPECIFICATION_DNS_RECORD_IP_TYPE,
+    )
+}
+
+const VALID_DNS_OPTIONS_SPECIFICATION_PRIVATE_DNS_ONLY_FOR_INBOUND_RESOLVER_ENDPOINT: &[&str] =
+    &["OnlyInboundResolver", "AllResolvers", "NotSpecified"];
+
+fn validate_dns_options_specification_private_dns_only_for_inbound_resolver_endpoint(
+    value: &Value,
+) -> Result<(), String> {
+    validate_namespaced_enum(
+        value,
+        "PrivateDnsOnlyForInboundResolverEndpoint",
+        "awscc.ec2_vpc_endpoint",
+        VALID_DNS_OPTIONS_SPECIFICATION_PRIVATE_DNS_ONLY_FOR_INBOUND_RESOLVER_ENDPOINT,
+    )
+}
+
+const VALID_DNS_OPTIONS_SPECIFICATION_PRIVATE_DNS_PREFERENCE: &[&str] = &[
+    "VERIFIED_DOMAINS_ONLY",
+    "ALL_DOMAINS",
+    "VERIFIED_DOMAINS_AND_SPECIFIED_DOMAINS",
+    "SPECIFIED_DOMAINS_ONLY",
+];
+
+fn validate_dns_options_specification_private_dns_preference(value: &Value) -> Result<(), String> {
+    validate_namespaced_enum(
+        value,
+        "PrivateDnsPreference",
+        "awscc.ec2_vpc_endpoint",
+        VALID_DNS_OPTIONS_SPECIFICATION_PRIVATE_DNS_PREFERENCE,
+    )
+}
+
 const VALID_IP_ADDRESS_TYPE: &[&str] = &["ipv4", "ipv6", "dualstack", "not-specified"];
 
 fn validate_ip_address_type(value: &Value) -> Result<(), String> {
@@ -38,31 +86,68 @@ fn validate_vpc_endpoint_type(value: &Value) -> Result<(), String> {
     )
 }
 
+fn validate_dns_options_specification_struct(value: &Value) -> Result<(), String> {
+    validate_struct(
+        value,
+        "DnsOptionsSpecification",
+        &[
+            ("dns_record_ip_type", AttributeType::Custom {
+                name: "DnsRecordIpType".to_string(),
+                base: Box::new(AttributeType::String),
+                validate: validate_dns_options_specification_dns_record_ip_type,
+                namespace: Some("awscc.ec2_vpc_endpoint".to_string()),
+            }),
+            ("private_dns_only_for_inbound_resolver_endpoint", AttributeType::Custom {
+                name: "PrivateDnsOnlyForInboundResolverEndpoint".to_string(),
+                base: Box::new(AttributeType::String),
+                validate: validate_dns_options_specification_private_dns_only_for_inbound_resolver_endpoint,
+                namespace: Some("awscc.ec2_vpc_endpoint".to_string()),
+            }),
+            ("private_dns_preference", AttributeType::Custom {
+                name: "PrivateDnsPreference".to_string(),
+                base: Box::new(AttributeType::String),
+                validate: validate_dns_options_specification_private_dns_preference,
+                namespace: Some("awscc.ec2_vpc_endpoint".to_string()),
+            }),
+            ("private_dns_specified_domains", AttributeType::List(Box::new(AttributeType::String))),
+        ],
+    )
+}
+
 /// Returns the schema config for ec2_vpc_endpoint (AWS::EC2::VPCEndpoint)
 pub fn ec2_vpc_endpoint_config() -> AwsccSchemaConfig {
     AwsccSchemaConfig {
         aws_type_name: "AWS::EC2::VPCEndpoint",
         resource_type_name: "ec2_vpc_endpoint",
         has_tags: true,
+        primary_identifier: &["Id"],
         schema: ResourceSchema::new("awscc.ec2_vpc_endpoint")
         .with_description("Specifies a VPC endpoint. A VPC endpoint provides a private connection between your VPC and an endpoint service. You can use an endpoint service provided by AWS, an MKT Partner, or another AWS account...")
         .attribute(
             AttributeSchema::new("creation_timestamp", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("CreationTimestamp"),
         )
         .attribute(
             AttributeSchema::new("dns_entries", AttributeType::List(Box::new(AttributeType::String)))
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("DnsEntries"),
         )
         .attribute(
-            AttributeSchema::new("dns_options", AttributeType::String)
+            AttributeSchema::new("dns_options", AttributeType::Custom {
+                name: "DnsOptionsSpecification".to_string(),
+                base: Box::new(AttributeType::Map(Box::new(AttributeType::String))),
+                validate: validate_dns_options_specification_struct,
+                namespace: None,
+            })
                 .with_description("Describes the DNS options for an endpoint.")
                 .with_provider_name("DnsOptions"),
         )
         .attribute(
             AttributeSchema::new("id", AttributeType::String)
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("Id"),
         )
@@ -73,11 +158,13 @@ pub fn ec2_vpc_endpoint_config() -> AwsccSchemaConfig {
                 validate: validate_ip_address_type,
                 namespace: Some("awscc.ec2_vpc_endpoint".to_string()),
             })
+                .with_enum_values(VALID_IP_ADDRESS_TYPE)
                 .with_description("The supported IP address types.")
                 .with_provider_name("IpAddressType"),
         )
         .attribute(
             AttributeSchema::new("network_interface_ids", AttributeType::List(Box::new(AttributeType::String)))
+                .read_only()
                 .with_description(" (read-only)")
                 .with_provider_name("NetworkInterfaceIds"),
         )
@@ -93,6 +180,7 @@ pub fn ec2_vpc_endpoint_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("resource_configuration_arn", AttributeType::String)
+                .immutable()
                 .with_description("The Amazon Resource Name (ARN) of the resource configuration.")
                 .with_provider_name("ResourceConfigurationArn"),
         )
@@ -108,16 +196,19 @@ pub fn ec2_vpc_endpoint_config() -> AwsccSchemaConfig {
         )
         .attribute(
             AttributeSchema::new("service_name", AttributeType::String)
+                .immutable()
                 .with_description("The name of the endpoint service.")
                 .with_provider_name("ServiceName"),
         )
         .attribute(
             AttributeSchema::new("service_network_arn", AttributeType::String)
+                .immutable()
                 .with_description("The Amazon Resource Name (ARN) of the service network.")
                 .with_provider_name("ServiceNetworkArn"),
         )
         .attribute(
             AttributeSchema::new("service_region", AttributeType::String)
+                .immutable()
                 .with_description("Describes a Region.")
                 .with_provider_name("ServiceRegion"),
         )
@@ -138,12 +229,15 @@ pub fn ec2_vpc_endpoint_config() -> AwsccSchemaConfig {
                 validate: validate_vpc_endpoint_type,
                 namespace: Some("awscc.ec2_vpc_endpoint".to_string()),
             })
+                .immutable()
+                .with_enum_values(VALID_VPC_ENDPOINT_TYPE)
                 .with_description("The type of endpoint. Default: Gateway")
                 .with_provider_name("VpcEndpointType"),
         )
         .attribute(
             AttributeSchema::new("vpc_id", AttributeType::String)
                 .required()
+                .immutable()
                 .with_description("The ID of the VPC.")
                 .with_provider_name("VpcId"),
         )
@@ -13,10 +13,12 @@ pub fn ec2_vpc_gateway_attachment_config() -> AwsccSchemaConfig {
         aws_type_name: "AWS::EC2::VPCGatewayAttachment",
         resource_type_name: "ec2_vpc_gateway_attachment",
         has_tags: false,
+        primary_identifier: &["AttachmentType", "VpcId"],
         schema: ResourceSchema::new("awscc.ec2_vpc_gateway_attachment")
         .with_description("Resource Type definition for AWS::EC2::VPCGatewayAttachment")
         .attribute(
             AttributeSchema::new("attachment_type", AttributeType::String)
+                .read_only()
                 .with_description("Used to identify if this resource is an Internet Gateway or Vpn Gateway Attachment  (read-only)")
                 .with_provider_name("AttachmentType"),
         )
@@ -28,6 +30,7 @@ pub fn ec2_vpc_gateway_attachment_config() -> AwsccSchemaConfig {
         .attribute(
             AttributeSchema::new("vpc_id", AttributeType::String)
                 .required()
+                .immutable()
                 .with_description("The ID of the VPC.")
                 .with_provider_name("VpcId"),
         )
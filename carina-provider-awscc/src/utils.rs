@@ -10,13 +10,9 @@ pub fn normalize_region(s: &str) -> String {
     region_part.replace('_', "-")
 }
 
-/// Normalize instance tenancy value (e.g., "awscc.ec2_vpc.InstanceTenancy.default" -> "default")
+/// Normalize instance tenancy value (e.g., "awscc.ec2_vpc.InstanceTenancy.Default" -> "default")
 pub fn normalize_instance_tenancy(s: &str) -> String {
-    if s.contains('.') {
-        s.split('.').next_back().unwrap_or(s).to_string()
-    } else {
-        s.to_string()
-    }
+    s.split('.').next_back().unwrap_or(s).to_lowercase()
 }
 
 /// Normalize availability zone value (e.g., "ap_northeast_1a" -> "ap-northeast-1a")
@@ -34,23 +30,11 @@ pub fn normalize_availability_zone(s: &str) -> String {
 pub fn convert_enum_value(value: &str) -> String {
     let parts: Vec<&str> = value.split('.').collect();
     let raw_value = match parts.len() {
-        2 => {
-            if parts[0].chars().next().is_some_and(|c| c.is_uppercase()) {
-                parts[1]
-            } else {
-                return value.to_string();
-            }
-        }
-        3 => {
-            let provider = parts[0];
-            let type_name = parts[1];
-            if provider.chars().all(|c| c.is_lowercase())
-                && type_name.chars().next().is_some_and(|c| c.is_uppercase())
-            {
-                parts[2]
-            } else {
-                return value.to_string();
-            }
+        2 if parts[0].chars().next().is_some_and(|c| c.is_uppercase()) => parts[1],
+        3 if parts[0].chars().all(|c| c.is_lowercase())
+            && parts[1].chars().next().is_some_and(|c| c.is_uppercase()) =>
+        {
+            parts[2]
         }
         _ => return value.to_string(),
     };
@@ -74,6 +58,10 @@ mod tests {
             normalize_instance_tenancy("awscc.ec2_vpc.InstanceTenancy.dedicated"),
             "dedicated"
         );
+        assert_eq!(
+            normalize_instance_tenancy("InstanceTenancy.Dedicated"),
+            "dedicated"
+        );
     }
 
     #[test]
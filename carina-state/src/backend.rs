@@ -1,7 +1,11 @@
 //! State backend trait and error types
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
+use sha2::{Digest, Sha256};
 use thiserror::Error;
+use tokio::runtime::{Handle, RuntimeFlavor};
 
 use crate::lock::LockInfo;
 use crate::state::StateFile;
@@ -10,11 +14,13 @@ use crate::state::StateFile;
 #[derive(Debug, Error)]
 pub enum BackendError {
     /// The state is locked by another process
-    #[error("State is locked by {who} (lock ID: {lock_id}, operation: {operation})")]
+    #[error("State is locked by {who} (lock ID: {lock_id}, operation: {operation}, age: {age})")]
     Locked {
         lock_id: String,
         who: String,
         operation: String,
+        /// How long ago the lock was acquired (e.g., "2h 5m")
+        age: String,
     },
 
     /// The lock was not found (for release/force-unlock operations)
@@ -45,6 +51,11 @@ pub enum BackendError {
     #[error("Invalid state file: {0}")]
     InvalidState(String),
 
+    /// State content does not match the checksum stored with it (e.g., a
+    /// truncated or partially written state file)
+    #[error("State file is corrupt: checksum mismatch (expected {expected}, got {actual})")]
+    Corrupt { expected: String, actual: String },
+
     /// State lineage mismatch (prevents accidental state overwrites)
     #[error("State lineage mismatch: expected {expected}, got {actual}")]
     LineageMismatch { expected: String, actual: String },
@@ -69,6 +80,7 @@ impl BackendError {
             lock_id: lock.id.clone(),
             who: lock.who.clone(),
             operation: lock.operation.clone(),
+            age: lock.age_description(),
         }
     }
 
@@ -86,6 +98,26 @@ impl BackendError {
 /// Result type for backend operations
 pub type BackendResult<T> = Result<T, BackendError>;
 
+/// Hex-encoded SHA-256 of serialized state, stored alongside it by backends
+pub(crate) fn state_checksum(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Check serialized state against the checksum stored with it
+pub(crate) fn verify_state_checksum(bytes: &[u8], expected: &str) -> BackendResult<()> {
+    let actual = state_checksum(bytes);
+    if actual != expected.trim() {
+        return Err(BackendError::Corrupt {
+            expected: expected.trim().to_string(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
 /// Trait for state storage backends
 ///
 /// This trait defines the interface for storing and retrieving state files,
@@ -104,8 +136,9 @@ pub trait StateBackend: Send + Sync {
 
     /// Acquire a lock for the given operation
     ///
-    /// This should fail if a lock is already held by another process
-    /// (unless the existing lock has expired)
+    /// This should fail if a lock is already held by another process, unless
+    /// the existing lock is stale (older than its TTL), in which case it is
+    /// taken over with a warning
     async fn acquire_lock(&self, operation: &str) -> BackendResult<LockInfo>;
 
     /// Release a previously acquired lock
@@ -113,10 +146,10 @@ pub trait StateBackend: Send + Sync {
     /// This should verify that the lock being released matches the provided lock info
     async fn release_lock(&self, lock: &LockInfo) -> BackendResult<()>;
 
-    /// Force release a lock by its ID
+    /// Force release a lock by its ID, returning the removed lock
     ///
     /// This is an administrative operation that should be used with caution
-    async fn force_unlock(&self, lock_id: &str) -> BackendResult<()>;
+    async fn force_unlock(&self, lock_id: &str) -> BackendResult<LockInfo>;
 
     /// Initialize the backend (create bucket if needed, etc.)
     ///
@@ -130,11 +163,91 @@ pub trait StateBackend: Send + Sync {
     ///
     /// This creates the bucket with:
     /// - Versioning enabled (for state history)
-    /// - Server-side encryption (AES256)
+    /// - Server-side encryption (AES256, or SSE-KMS when `kms_key_id` is set)
     /// - Public access blocked
     async fn create_bucket(&self) -> BackendResult<()>;
 }
 
+/// Warn that a stale lock held by someone else is being taken over
+pub(crate) fn warn_stale_lock(lock: &LockInfo) {
+    eprintln!(
+        "Warning: taking over stale state lock held by {} (lock ID: {}, operation: {}, age: {}, TTL: {}s)",
+        lock.who,
+        lock.id,
+        lock.operation,
+        lock.age_description(),
+        lock.ttl_seconds.unwrap_or_default()
+    );
+}
+
+/// A held state lock that is released when dropped
+///
+/// Release it with `release` to handle errors; dropping it (on an early return,
+/// an error or a panic) releases the lock as well, reporting failures on stderr.
+pub struct LockGuard {
+    backend: Arc<dyn StateBackend>,
+    lock: Option<LockInfo>,
+}
+
+impl LockGuard {
+    /// Guard a lock acquired from `backend`
+    pub fn new(backend: Arc<dyn StateBackend>, lock: LockInfo) -> Self {
+        Self {
+            backend,
+            lock: Some(lock),
+        }
+    }
+
+    /// The guarded lock
+    pub fn info(&self) -> &LockInfo {
+        self.lock.as_ref().expect("lock is held until released")
+    }
+
+    /// Release the lock
+    pub async fn release(mut self) -> BackendResult<()> {
+        match self.lock.take() {
+            Some(lock) => self.backend.release_lock(&lock).await,
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        let Some(lock) = self.lock.take() else {
+            return;
+        };
+        let backend = self.backend.as_ref();
+
+        // Drop cannot await: block on the release, on this runtime when it has
+        // other workers to keep running, otherwise on a runtime of its own
+        let result = match Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(|| handle.block_on(backend.release_lock(&lock)))
+            }
+            _ => std::thread::scope(|scope| {
+                scope
+                    .spawn(|| {
+                        tokio::runtime::Builder::new_current_thread()
+                            .enable_all()
+                            .build()
+                            .map_err(|e| BackendError::Io(e.to_string()))?
+                            .block_on(backend.release_lock(&lock))
+                    })
+                    .join()
+                    .unwrap_or_else(|_| Err(BackendError::Io("lock release panicked".to_string())))
+            }),
+        };
+
+        if let Err(e) = result {
+            eprintln!(
+                "Failed to release state lock: {}\nTo unlock it, run: carina force-unlock {}",
+                e, lock.id
+            );
+        }
+    }
+}
+
 /// Configuration for a state backend
 #[derive(Debug, Clone)]
 pub struct BackendConfig {
@@ -161,6 +274,25 @@ impl BackendConfig {
         }
     }
 
+    /// Get an integer attribute value
+    pub fn get_int(&self, key: &str) -> Option<i64> {
+        match self.attributes.get(key) {
+            Some(carina_core::resource::Value::Int(i)) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// Lock TTL in seconds from the `lock_ttl` attribute; locks never go stale
+    /// without one
+    pub fn lock_ttl(&self) -> BackendResult<Option<i64>> {
+        match self.get_int("lock_ttl") {
+            Some(ttl) if ttl <= 0 => Err(BackendError::configuration(
+                "lock_ttl must be a positive number of seconds",
+            )),
+            ttl => Ok(ttl),
+        }
+    }
+
     /// Get a boolean attribute with a default value
     pub fn get_bool_or(&self, key: &str, default: bool) -> bool {
         self.get_bool(key).unwrap_or(default)
@@ -182,10 +314,12 @@ mod tests {
                 lock_id,
                 who,
                 operation,
+                age,
             } => {
                 assert_eq!(lock_id, lock.id);
                 assert_eq!(who, lock.who);
                 assert_eq!(operation, "apply");
+                assert_eq!(age, "0s");
             }
             _ => panic!("Expected Locked error"),
         }
@@ -1,12 +1,24 @@
 //! Local file backend for state storage
 //!
 //! This backend stores state in a local JSON file (default: carina.state.json).
-//! It uses a .lock file for simple locking mechanism.
+//! A sibling .sha256 file holds the state's checksum, verified on read. Both
+//! are replaced atomically; if they still disagree (e.g., after a crash between
+//! the two writes, or a manual edit), check the state file and delete the
+//! .sha256 file to accept it.
+//! Locking takes an OS advisory lock on a .lock file next to it, which also
+//! records who holds the lock. The OS releases the lock if the process dies;
+//! a lock held by a hung process is taken over once it is older than its TTL.
 
 use async_trait::async_trait;
-use std::path::PathBuf;
-
-use crate::backend::{BackendConfig, BackendError, BackendResult, StateBackend};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::backend::{
+    BackendConfig, BackendError, BackendResult, StateBackend, state_checksum,
+    verify_state_checksum, warn_stale_lock,
+};
 use crate::lock::LockInfo;
 use crate::state::StateFile;
 
@@ -16,6 +28,14 @@ pub struct LocalBackend {
     state_path: PathBuf,
     /// Path to the lock file
     lock_path: PathBuf,
+    /// Open lock file while this backend holds the lock; the OS lock on it is
+    /// released when it is dropped (or when the process exits)
+    held_lock: Mutex<Option<File>>,
+    /// Stale lock this backend took over without its OS lock, restored on release
+    /// so that later runs can take it over again while its holder still runs
+    taken_over: Mutex<Option<LockInfo>>,
+    /// TTL in seconds recorded in the locks this backend acquires
+    lock_ttl: Option<i64>,
 }
 
 impl LocalBackend {
@@ -33,9 +53,18 @@ impl LocalBackend {
         Self {
             state_path,
             lock_path,
+            held_lock: Mutex::new(None),
+            taken_over: Mutex::new(None),
+            lock_ttl: None,
         }
     }
 
+    /// Record a TTL in acquired locks, after which others may take them over
+    pub fn with_lock_ttl(mut self, ttl_seconds: Option<i64>) -> Self {
+        self.lock_ttl = ttl_seconds;
+        self
+    }
+
     /// Create a LocalBackend from configuration
     pub fn from_config(config: &BackendConfig) -> BackendResult<Self> {
         let path = config
@@ -43,13 +72,39 @@ impl LocalBackend {
             .map(PathBuf::from)
             .unwrap_or_else(|| PathBuf::from(Self::DEFAULT_STATE_FILE));
 
-        Ok(Self::with_path(path))
+        Ok(Self::with_path(path).with_lock_ttl(config.lock_ttl()?))
     }
 
     /// Get the state file path
     pub fn state_path(&self) -> &PathBuf {
         &self.state_path
     }
+
+    /// Path of the file holding the state file's SHA-256 checksum
+    fn checksum_path(&self) -> PathBuf {
+        let mut path = self.state_path.clone().into_os_string();
+        path.push(".sha256");
+        PathBuf::from(path)
+    }
+
+    /// Read the lock info from the lock file; `None` when there is no lock file
+    /// or it holds no lock
+    fn read_lock_file(&self) -> BackendResult<Option<LockInfo>> {
+        let content = match std::fs::read_to_string(&self.lock_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(BackendError::Io(format!("Failed to read lock file: {}", e)));
+            }
+        };
+        if content.trim().is_empty() {
+            return Ok(None);
+        }
+
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| BackendError::InvalidState(format!("Failed to parse lock file: {}", e)))
+    }
 }
 
 impl Default for LocalBackend {
@@ -68,6 +123,11 @@ impl StateBackend for LocalBackend {
         let content = std::fs::read_to_string(&self.state_path)
             .map_err(|e| BackendError::Io(format!("Failed to read state file: {}", e)))?;
 
+        // State written before checksums were recorded has none to verify
+        if let Ok(expected) = std::fs::read_to_string(self.checksum_path()) {
+            verify_state_checksum(content.as_bytes(), &expected)?;
+        }
+
         let state: StateFile = serde_json::from_str(&content).map_err(|e| {
             BackendError::InvalidState(format!("Failed to parse state file: {}", e))
         })?;
@@ -80,47 +140,70 @@ impl StateBackend for LocalBackend {
             BackendError::Serialization(format!("Failed to serialize state: {}", e))
         })?;
 
-        std::fs::write(&self.state_path, content)
+        // Replace each file by renaming a fully written copy over it, so a crash
+        // never leaves either one half written. The checksum goes last: a crash
+        // between the two renames leaves the new state with the old checksum,
+        // which is reported as corrupt rather than loaded silently.
+        write_atomically(&self.state_path, content.as_bytes())
             .map_err(|e| BackendError::Io(format!("Failed to write state file: {}", e)))?;
+        write_atomically(
+            &self.checksum_path(),
+            state_checksum(content.as_bytes()).as_bytes(),
+        )
+        .map_err(|e| BackendError::Io(format!("Failed to write state checksum: {}", e)))?;
 
         Ok(())
     }
 
     async fn acquire_lock(&self, operation: &str) -> BackendResult<LockInfo> {
-        // Check if lock file exists and read it
-        if self.lock_path.exists() {
-            let content = std::fs::read_to_string(&self.lock_path)
-                .map_err(|e| BackendError::Io(format!("Failed to read lock file: {}", e)))?;
-
-            if let Ok(existing_lock) = serde_json::from_str::<LockInfo>(&content) {
-                // Check if lock is expired
-                if !existing_lock.is_expired() {
-                    return Err(BackendError::locked(&existing_lock));
-                }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.lock_path)
+            .map_err(|e| BackendError::Io(format!("Failed to open lock file: {}", e)))?;
+
+        let lock = LockInfo::with_ttl(operation, self.lock_ttl);
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                // The holder may not have written its lock info yet
+                return match self.read_lock_file()? {
+                    // The holder is alive but past its TTL (e.g., hung): take the lock
+                    // over by recording ourselves, without its OS lock
+                    Some(existing_lock) if existing_lock.is_stale() => {
+                        warn_stale_lock(&existing_lock);
+                        write_lock_info(&mut file, &lock)?;
+                        *self.taken_over.lock().unwrap() = Some(existing_lock);
+                        Ok(lock)
+                    }
+                    Some(existing_lock) => Err(BackendError::locked(&existing_lock)),
+                    None => Err(BackendError::Locked {
+                        lock_id: "unknown".to_string(),
+                        who: "unknown".to_string(),
+                        operation: "unknown".to_string(),
+                        age: "unknown".to_string(),
+                    }),
+                };
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(BackendError::Io(format!("Failed to lock lock file: {}", e)));
             }
         }
 
-        // Create new lock
-        let lock = LockInfo::new(operation);
-        let content = serde_json::to_string_pretty(&lock)
-            .map_err(|e| BackendError::Serialization(format!("Failed to serialize lock: {}", e)))?;
-
-        std::fs::write(&self.lock_path, content)
-            .map_err(|e| BackendError::Io(format!("Failed to write lock file: {}", e)))?;
+        // We hold the OS lock, so any lock info left in the file is from a process
+        // that exited without releasing it
+        write_lock_info(&mut file, &lock)?;
 
+        *self.held_lock.lock().unwrap() = Some(file);
         Ok(lock)
     }
 
     async fn release_lock(&self, lock: &LockInfo) -> BackendResult<()> {
-        if !self.lock_path.exists() {
-            return Err(BackendError::LockNotFound(lock.id.clone()));
-        }
-
-        let content = std::fs::read_to_string(&self.lock_path)
-            .map_err(|e| BackendError::Io(format!("Failed to read lock file: {}", e)))?;
-
-        let existing_lock: LockInfo = serde_json::from_str(&content)
-            .map_err(|e| BackendError::InvalidState(format!("Failed to parse lock file: {}", e)))?;
+        let existing_lock = self
+            .read_lock_file()?
+            .ok_or_else(|| BackendError::LockNotFound(lock.id.clone()))?;
 
         if existing_lock.id != lock.id {
             return Err(BackendError::LockMismatch {
@@ -129,24 +212,32 @@ impl StateBackend for LocalBackend {
             });
         }
 
-        std::fs::remove_file(&self.lock_path)
-            .map_err(|e| BackendError::Io(format!("Failed to remove lock file: {}", e)))?;
+        // The hung holder of a lock we took over still has its OS lock; give its
+        // info back, or the next run would find the file locked with no info
+        if let Some(taken_over) = self.taken_over.lock().unwrap().take() {
+            let mut file = OpenOptions::new()
+                .write(true)
+                .open(&self.lock_path)
+                .map_err(|e| BackendError::Io(format!("Failed to open lock file: {}", e)))?;
+            return write_lock_info(&mut file, &taken_over);
+        }
+
+        // Clear the lock info before giving up the OS lock. The file itself stays, so
+        // a process waiting on it never ends up locking a file that was removed.
+        std::fs::write(&self.lock_path, "")
+            .map_err(|e| BackendError::Io(format!("Failed to clear lock file: {}", e)))?;
+        self.held_lock.lock().unwrap().take();
 
         Ok(())
     }
 
-    async fn force_unlock(&self, lock_id: &str) -> BackendResult<()> {
-        if !self.lock_path.exists() {
-            return Err(BackendError::LockNotFound(lock_id.to_string()));
-        }
-
+    async fn force_unlock(&self, lock_id: &str) -> BackendResult<LockInfo> {
         // Verify lock ID matches
-        let content = std::fs::read_to_string(&self.lock_path)
-            .map_err(|e| BackendError::Io(format!("Failed to read lock file: {}", e)))?;
+        let existing_lock = self
+            .read_lock_file()?
+            .ok_or_else(|| BackendError::LockNotFound(lock_id.to_string()))?;
 
-        if let Ok(existing_lock) = serde_json::from_str::<LockInfo>(&content)
-            && existing_lock.id != lock_id
-        {
+        if existing_lock.id != lock_id {
             return Err(BackendError::LockMismatch {
                 expected: lock_id.to_string(),
                 actual: existing_lock.id,
@@ -155,8 +246,9 @@ impl StateBackend for LocalBackend {
 
         std::fs::remove_file(&self.lock_path)
             .map_err(|e| BackendError::Io(format!("Failed to remove lock file: {}", e)))?;
+        self.held_lock.lock().unwrap().take();
 
-        Ok(())
+        Ok(existing_lock)
     }
 
     async fn init(&self) -> BackendResult<()> {
@@ -176,9 +268,34 @@ impl StateBackend for LocalBackend {
     }
 }
 
+/// Write `content` to a temporary file next to `path` and rename it over `path`
+fn write_atomically(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut temp_path = path.as_os_str().to_os_string();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let mut file = File::create(&temp_path)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    std::fs::rename(&temp_path, path)
+}
+
+/// Replace the contents of the lock file with the lock info
+fn write_lock_info(file: &mut File, lock: &LockInfo) -> BackendResult<()> {
+    let content = serde_json::to_string_pretty(lock)
+        .map_err(|e| BackendError::Serialization(format!("Failed to serialize lock: {}", e)))?;
+
+    file.set_len(0)
+        .and_then(|()| file.write_all(content.as_bytes()))
+        .and_then(|()| file.sync_all())
+        .map_err(|e| BackendError::Io(format!("Failed to write lock file: {}", e)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::LockGuard;
+    use std::sync::Arc;
     use tempfile::tempdir;
 
     #[tokio::test]
@@ -201,6 +318,30 @@ mod tests {
         assert!(read_state.is_some());
         let read_state = read_state.unwrap();
         assert_eq!(read_state.serial, 1);
+
+        // Writes go through temporary files that are renamed into place
+        let mut entries: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        entries.sort();
+        assert_eq!(entries, ["test.state.json", "test.state.json.sha256"]);
+    }
+
+    #[tokio::test]
+    async fn test_local_backend_detects_corrupt_state() {
+        let dir = tempdir().unwrap();
+        let state_path = dir.path().join("test.state.json");
+        let backend = LocalBackend::with_path(state_path.clone());
+
+        backend.write_state(&StateFile::new()).await.unwrap();
+        let content = std::fs::read_to_string(&state_path).unwrap();
+        std::fs::write(&state_path, &content[..content.len() / 2]).unwrap();
+
+        assert!(matches!(
+            backend.read_state().await,
+            Err(BackendError::Corrupt { .. })
+        ));
     }
 
     #[tokio::test]
@@ -226,6 +367,132 @@ mod tests {
         backend.release_lock(&lock2).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_local_backend_lock_is_shared_across_backends() {
+        let dir = tempdir().unwrap();
+        let state_path = dir.path().join("test.state.json");
+        let holder = LocalBackend::with_path(state_path.clone());
+        let other = LocalBackend::with_path(state_path.clone());
+
+        let lock = holder.acquire_lock("apply").await.unwrap();
+        match other.acquire_lock("plan").await {
+            Err(BackendError::Locked {
+                lock_id, operation, ..
+            }) => {
+                assert_eq!(lock_id, lock.id);
+                assert_eq!(operation, "apply");
+            }
+            _ => panic!("Expected Locked error"),
+        }
+
+        // A holder that goes away without releasing (e.g., a crashed process)
+        // leaves lock info behind, but not the OS lock
+        drop(holder);
+        let lock2 = other.acquire_lock("plan").await.unwrap();
+        other.release_lock(&lock2).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_local_backend_takes_over_stale_lock() {
+        let dir = tempdir().unwrap();
+        let state_path = dir.path().join("test.state.json");
+        let holder = LocalBackend::with_path(state_path.clone()).with_lock_ttl(Some(60));
+        let other = LocalBackend::with_path(state_path.clone());
+
+        // A live holder within its TTL keeps the lock
+        let lock = holder.acquire_lock("apply").await.unwrap();
+        assert!(matches!(
+            other.acquire_lock("plan").await,
+            Err(BackendError::Locked { .. })
+        ));
+
+        // Once past its TTL, the lock is taken over
+        let mut stale = lock.clone();
+        stale.created -= chrono::Duration::seconds(61);
+        write_lock_info(
+            &mut OpenOptions::new()
+                .write(true)
+                .open(&holder.lock_path)
+                .unwrap(),
+            &stale,
+        )
+        .unwrap();
+        let taken = other.acquire_lock("plan").await.unwrap();
+        assert_eq!(other.read_lock_file().unwrap().unwrap().id, taken.id);
+
+        // The old holder can no longer release it
+        assert!(matches!(
+            holder.release_lock(&lock).await,
+            Err(BackendError::LockMismatch { .. })
+        ));
+        other.release_lock(&taken).await.unwrap();
+
+        // While the old holder still runs, its stale lock is taken over again
+        let retaken = other.acquire_lock("plan").await.unwrap();
+        other.release_lock(&retaken).await.unwrap();
+        assert_eq!(other.read_lock_file().unwrap().unwrap().id, lock.id);
+
+        // Once it finally releases, the lock is free
+        holder.release_lock(&lock).await.unwrap();
+        let lock2 = other.acquire_lock("plan").await.unwrap();
+        assert!(other.taken_over.lock().unwrap().is_none());
+        other.release_lock(&lock2).await.unwrap();
+    }
+
+    /// Acquire the lock and bail out before releasing it, like a failing apply
+    async fn fail_while_locked(backend: Arc<dyn StateBackend>) -> Result<(), String> {
+        let lock = backend.acquire_lock("apply").await.unwrap();
+        let _guard = LockGuard::new(backend.clone(), lock);
+        Err("apply failed".to_string())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_lock_guard_releases_on_early_return() {
+        let dir = tempdir().unwrap();
+        let backend: Arc<dyn StateBackend> =
+            Arc::new(LocalBackend::with_path(dir.path().join("test.state.json")));
+
+        assert!(fail_while_locked(backend.clone()).await.is_err());
+
+        // The lock is gone, so it can be taken again
+        let lock = backend.acquire_lock("plan").await.unwrap();
+        LockGuard::new(backend.clone(), lock)
+            .release()
+            .await
+            .unwrap();
+        assert!(backend.acquire_lock("plan").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_lock_guard_releases_on_current_thread_runtime() {
+        let dir = tempdir().unwrap();
+        let backend: Arc<dyn StateBackend> =
+            Arc::new(LocalBackend::with_path(dir.path().join("test.state.json")));
+
+        assert!(fail_while_locked(backend.clone()).await.is_err());
+        assert!(backend.acquire_lock("plan").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_local_backend_force_unlock() {
+        let dir = tempdir().unwrap();
+        let state_path = dir.path().join("test.state.json");
+        let backend = LocalBackend::with_path(state_path);
+
+        let lock = backend.acquire_lock("apply").await.unwrap();
+        assert!(matches!(
+            backend.force_unlock("wrong-id").await,
+            Err(BackendError::LockMismatch { .. })
+        ));
+
+        let removed = backend.force_unlock(&lock.id).await.unwrap();
+        assert_eq!(removed.operation, "apply");
+        assert!(!backend.lock_path.exists());
+
+        let lock2 = backend.acquire_lock("apply").await.unwrap();
+        backend.release_lock(&lock2).await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_local_backend_from_config() {
         use std::collections::HashMap;
@@ -1,17 +1,34 @@
 //! S3 backend for state storage
 
+use std::io::{Read, Write};
+
 use async_trait::async_trait;
 use aws_sdk_s3::Client;
+use aws_sdk_s3::error::{ProvideErrorMetadata, SdkError};
+use aws_sdk_s3::operation::put_object::builders::PutObjectFluentBuilder;
 use aws_sdk_s3::primitives::ByteStream;
 use aws_sdk_s3::types::{
     BucketVersioningStatus, PublicAccessBlockConfiguration, ServerSideEncryption,
+    ServerSideEncryptionByDefault, ServerSideEncryptionConfiguration, ServerSideEncryptionRule,
     VersioningConfiguration,
 };
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
 
-use crate::backend::{BackendConfig, BackendError, BackendResult, StateBackend};
+use crate::backend::{
+    BackendConfig, BackendError, BackendResult, StateBackend, state_checksum,
+    verify_state_checksum, warn_stale_lock,
+};
 use crate::lock::LockInfo;
 use crate::state::StateFile;
 
+/// First bytes of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// User metadata key holding the SHA-256 checksum of the state object
+const CHECKSUM_METADATA_KEY: &str = "sha256";
+
 /// S3-based state backend
 pub struct S3Backend {
     /// S3 client
@@ -24,8 +41,15 @@ pub struct S3Backend {
     region: String,
     /// Whether to encrypt the state file (default: true)
     encrypt: bool,
+    /// KMS key used for SSE-KMS encryption (SSE-S3 is used when not set)
+    kms_key_id: Option<String>,
     /// Whether to auto-create the bucket if it doesn't exist (default: true)
     auto_create: bool,
+    /// Whether to gzip the state object (default: false)
+    compress: bool,
+    /// TTL in seconds recorded in acquired locks, after which others may take
+    /// them over (default: none)
+    lock_ttl: Option<i64>,
 }
 
 impl S3Backend {
@@ -49,7 +73,10 @@ impl S3Backend {
         let region = convert_region_value(region_value);
 
         let encrypt = config.get_bool_or("encrypt", true);
+        let kms_key_id = config.get_string("kms_key_id").map(String::from);
         let auto_create = config.get_bool_or("auto_create", true);
+        let compress = config.get_bool_or("compress", false);
+        let lock_ttl = config.lock_ttl()?;
 
         // Load AWS config with the specified region
         let aws_config = aws_config::defaults(aws_config::BehaviorVersion::latest())
@@ -65,10 +92,43 @@ impl S3Backend {
             key,
             region,
             encrypt,
+            kms_key_id,
             auto_create,
+            compress,
+            lock_ttl,
         })
     }
 
+    /// Apply server-side encryption settings to a put_object request
+    fn with_encryption(&self, request: PutObjectFluentBuilder) -> PutObjectFluentBuilder {
+        if let Some(kms_key_id) = &self.kms_key_id {
+            request
+                .server_side_encryption(ServerSideEncryption::AwsKms)
+                .ssekms_key_id(kms_key_id)
+        } else if self.encrypt {
+            request.server_side_encryption(ServerSideEncryption::Aes256)
+        } else {
+            request
+        }
+    }
+
+    /// Convert an S3 error, explaining KMS access failures for the configured key
+    fn aws_error<E>(&self, err: SdkError<E>) -> BackendError
+    where
+        E: ProvideErrorMetadata + std::error::Error + 'static,
+    {
+        match &self.kms_key_id {
+            Some(kms_key_id) if is_kms_access_denied(err.code(), err.message()) => {
+                BackendError::Aws(format!(
+                    "Access denied to KMS key '{}'. Ensure the caller is allowed kms:Encrypt, \
+                     kms:Decrypt and kms:GenerateDataKey on this key",
+                    kms_key_id
+                ))
+            }
+            _ => BackendError::Aws(err.to_string()),
+        }
+    }
+
     /// Get the lock file key (state key + ".lock")
     fn lock_key(&self) -> String {
         format!("{}.lock", self.key)
@@ -101,7 +161,7 @@ impl S3Backend {
                 if is_not_found_error(&err) {
                     Ok(None)
                 } else {
-                    Err(BackendError::Aws(err.to_string()))
+                    Err(self.aws_error(err))
                 }
             }
         }
@@ -112,7 +172,7 @@ impl S3Backend {
         let body = serde_json::to_vec_pretty(lock)
             .map_err(|e| BackendError::Serialization(e.to_string()))?;
 
-        let mut request = self
+        let request = self
             .client
             .put_object()
             .bucket(&self.bucket)
@@ -120,14 +180,10 @@ impl S3Backend {
             .body(ByteStream::from(body))
             .content_type("application/json");
 
-        if self.encrypt {
-            request = request.server_side_encryption(ServerSideEncryption::Aes256);
-        }
-
-        request
+        self.with_encryption(request)
             .send()
             .await
-            .map_err(|e| BackendError::Aws(e.to_string()))?;
+            .map_err(|e| self.aws_error(e))?;
 
         Ok(())
     }
@@ -169,46 +225,51 @@ impl StateBackend for S3Backend {
 
         match result {
             Ok(output) => {
+                let expected_checksum = output
+                    .metadata()
+                    .and_then(|metadata| metadata.get(CHECKSUM_METADATA_KEY))
+                    .cloned();
                 let body = output
                     .body
                     .collect()
                     .await
                     .map_err(|e| BackendError::Io(e.to_string()))?;
                 let bytes = body.into_bytes();
-                let state: StateFile = serde_json::from_slice(&bytes)
-                    .map_err(|e| BackendError::InvalidState(e.to_string()))?;
-                Ok(Some(state))
+                // Objects written before checksums were recorded have none to verify
+                if let Some(expected) = &expected_checksum {
+                    verify_state_checksum(&bytes, expected)?;
+                }
+                Ok(Some(decode_state(&bytes)?))
             }
             Err(err) => {
                 if is_not_found_error(&err) {
                     Ok(None)
                 } else {
-                    Err(BackendError::Aws(err.to_string()))
+                    Err(self.aws_error(err))
                 }
             }
         }
     }
 
     async fn write_state(&self, state: &StateFile) -> BackendResult<()> {
-        let body = serde_json::to_vec_pretty(state)
-            .map_err(|e| BackendError::Serialization(e.to_string()))?;
+        let body = encode_state(state, self.compress)?;
 
         let mut request = self
             .client
             .put_object()
             .bucket(&self.bucket)
             .key(&self.key)
+            .metadata(CHECKSUM_METADATA_KEY, state_checksum(&body))
             .body(ByteStream::from(body))
             .content_type("application/json");
-
-        if self.encrypt {
-            request = request.server_side_encryption(ServerSideEncryption::Aes256);
+        if self.compress {
+            request = request.content_encoding("gzip");
         }
 
-        request
+        self.with_encryption(request)
             .send()
             .await
-            .map_err(|e| BackendError::Aws(e.to_string()))?;
+            .map_err(|e| self.aws_error(e))?;
 
         Ok(())
     }
@@ -216,9 +277,9 @@ impl StateBackend for S3Backend {
     async fn acquire_lock(&self, operation: &str) -> BackendResult<LockInfo> {
         // Check for existing lock
         if let Some(existing_lock) = self.read_lock().await? {
-            // If the lock has expired, we can take it
-            if existing_lock.is_expired() {
-                // Expired lock - delete it and proceed
+            // A lock older than its TTL is stale and can be taken over
+            if existing_lock.is_stale() {
+                warn_stale_lock(&existing_lock);
                 self.delete_lock().await?;
             } else {
                 // Lock is still valid
@@ -227,7 +288,7 @@ impl StateBackend for S3Backend {
         }
 
         // Create and write new lock
-        let lock = LockInfo::new(operation);
+        let lock = LockInfo::with_ttl(operation, self.lock_ttl);
         self.write_lock(&lock).await?;
 
         // Verify we got the lock (in case of race condition)
@@ -261,20 +322,21 @@ impl StateBackend for S3Backend {
         self.delete_lock().await
     }
 
-    async fn force_unlock(&self, lock_id: &str) -> BackendResult<()> {
+    async fn force_unlock(&self, lock_id: &str) -> BackendResult<LockInfo> {
         // Verify a lock exists
-        if let Some(existing_lock) = self.read_lock().await? {
-            if existing_lock.id != lock_id {
-                return Err(BackendError::LockMismatch {
-                    expected: lock_id.to_string(),
-                    actual: existing_lock.id,
-                });
-            }
-        } else {
-            return Err(BackendError::LockNotFound(lock_id.to_string()));
+        let existing_lock = self
+            .read_lock()
+            .await?
+            .ok_or_else(|| BackendError::LockNotFound(lock_id.to_string()))?;
+        if existing_lock.id != lock_id {
+            return Err(BackendError::LockMismatch {
+                expected: lock_id.to_string(),
+                actual: existing_lock.id,
+            });
         }
 
-        self.delete_lock().await
+        self.delete_lock().await?;
+        Ok(existing_lock)
     }
 
     async fn init(&self) -> BackendResult<()> {
@@ -368,6 +430,17 @@ impl StateBackend for S3Backend {
             .await
             .map_err(|e| BackendError::Aws(format!("Failed to block public access: {}", e)))?;
 
+        // Encrypt objects by default the same way the state is written
+        if let Some(encryption) = bucket_encryption(self.encrypt, self.kms_key_id.as_deref()) {
+            self.client
+                .put_bucket_encryption()
+                .bucket(&self.bucket)
+                .server_side_encryption_configuration(encryption)
+                .send()
+                .await
+                .map_err(|e| self.aws_error(e))?;
+        }
+
         Ok(())
     }
 }
@@ -385,6 +458,70 @@ fn convert_region_value(value: &str) -> String {
     }
 }
 
+/// Default encryption of a created bucket: SSE-KMS with the key when one is set,
+/// otherwise SSE-S3 unless `encrypt` is off
+fn bucket_encryption(
+    encrypt: bool,
+    kms_key_id: Option<&str>,
+) -> Option<ServerSideEncryptionConfiguration> {
+    let by_default = match kms_key_id {
+        Some(kms_key_id) => ServerSideEncryptionByDefault::builder()
+            .sse_algorithm(ServerSideEncryption::AwsKms)
+            .kms_master_key_id(kms_key_id),
+        None if encrypt => {
+            ServerSideEncryptionByDefault::builder().sse_algorithm(ServerSideEncryption::Aes256)
+        }
+        None => return None,
+    };
+    let rule = ServerSideEncryptionRule::builder()
+        .apply_server_side_encryption_by_default(by_default.build().ok()?)
+        .build();
+    ServerSideEncryptionConfiguration::builder()
+        .rules(rule)
+        .build()
+        .ok()
+}
+
+/// Check whether an S3 error was caused by missing access to a KMS key, from its
+/// error code: a `KMS.*` code, or `AccessDenied` with a message about KMS
+fn is_kms_access_denied(code: Option<&str>, message: Option<&str>) -> bool {
+    match code {
+        Some(code) if code.starts_with("KMS.") => true,
+        Some("AccessDenied") => message.is_some_and(|m| m.to_ascii_lowercase().contains("kms")),
+        _ => false,
+    }
+}
+
+/// Serialize state for storage, gzipped when `compress` is set
+fn encode_state(state: &StateFile, compress: bool) -> BackendResult<Vec<u8>> {
+    let json =
+        serde_json::to_vec_pretty(state).map_err(|e| BackendError::Serialization(e.to_string()))?;
+    if !compress {
+        return Ok(json);
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&json)
+        .and_then(|()| encoder.finish())
+        .map_err(|e| BackendError::Serialization(format!("Failed to compress state: {}", e)))
+}
+
+/// Deserialize stored state, decompressing it first if it starts with the gzip
+/// magic bytes, so both compressed and plain objects load
+fn decode_state(bytes: &[u8]) -> BackendResult<StateFile> {
+    if !bytes.starts_with(&GZIP_MAGIC) {
+        return serde_json::from_slice(bytes)
+            .map_err(|e| BackendError::InvalidState(e.to_string()));
+    }
+
+    let mut json = Vec::new();
+    GzDecoder::new(bytes)
+        .read_to_end(&mut json)
+        .map_err(|e| BackendError::InvalidState(format!("Failed to decompress state: {}", e)))?;
+    serde_json::from_slice(&json).map_err(|e| BackendError::InvalidState(e.to_string()))
+}
+
 /// Check if an S3 error is a "not found" error
 fn is_not_found_error<E: std::fmt::Debug>(err: &aws_sdk_s3::error::SdkError<E>) -> bool {
     // Check the raw HTTP response status
@@ -409,6 +546,85 @@ mod tests {
         assert_eq!(convert_region_value("eu-west-1"), "eu-west-1");
     }
 
+    #[test]
+    fn test_is_kms_access_denied() {
+        assert!(is_kms_access_denied(
+            Some("AccessDenied"),
+            Some("User is not authorized to perform: kms:GenerateDataKey")
+        ));
+        assert!(is_kms_access_denied(Some("KMS.DisabledException"), None));
+        assert!(!is_kms_access_denied(
+            Some("AccessDenied"),
+            Some("Access Denied")
+        ));
+        assert!(!is_kms_access_denied(Some("AccessDenied"), None));
+        assert!(!is_kms_access_denied(
+            Some("NoSuchBucket"),
+            Some("The bucket KMS-logs does not exist")
+        ));
+        assert!(!is_kms_access_denied(None, None));
+    }
+
+    #[test]
+    fn test_bucket_encryption_matches_state_encryption() {
+        let default_encryption = |encrypt: bool, kms_key_id: Option<&str>| {
+            bucket_encryption(encrypt, kms_key_id).map(|config| {
+                let by_default = config.rules()[0]
+                    .apply_server_side_encryption_by_default()
+                    .unwrap()
+                    .clone();
+                (
+                    by_default.sse_algorithm().clone(),
+                    by_default.kms_master_key_id().map(String::from),
+                )
+            })
+        };
+
+        assert_eq!(
+            default_encryption(true, Some("alias/state")),
+            Some((
+                ServerSideEncryption::AwsKms,
+                Some("alias/state".to_string())
+            ))
+        );
+        // The key is used even with encrypt = false, as for the state object
+        assert_eq!(
+            default_encryption(false, Some("alias/state")),
+            Some((
+                ServerSideEncryption::AwsKms,
+                Some("alias/state".to_string())
+            ))
+        );
+        assert_eq!(
+            default_encryption(true, None),
+            Some((ServerSideEncryption::Aes256, None))
+        );
+        assert_eq!(default_encryption(false, None), None);
+    }
+
+    #[test]
+    fn test_compressed_state_round_trip() {
+        let mut state = StateFile::new();
+        state.increment_serial();
+        state.upsert_resource(
+            crate::state::ResourceState::new("vpc", "main", "aws")
+                .with_identifier("vpc-123")
+                .with_attribute("cidr_block", serde_json::json!("10.0.0.0/16")),
+        );
+
+        let compressed = encode_state(&state, true).unwrap();
+        assert!(compressed.starts_with(&GZIP_MAGIC));
+        let decoded = decode_state(&compressed).unwrap();
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&state).unwrap()
+        );
+
+        // Plain state written without compression still loads
+        let plain = encode_state(&state, false).unwrap();
+        assert_eq!(decode_state(&plain).unwrap().serial, state.serial);
+    }
+
     #[test]
     fn test_lock_key() {
         // We can't easily test this without mocking AWS, so just verify the format
@@ -49,7 +49,7 @@ pub mod lock;
 pub mod state;
 
 // Re-export main types for convenience
-pub use backend::{BackendConfig, BackendError, BackendResult, StateBackend};
+pub use backend::{BackendConfig, BackendError, BackendResult, LockGuard, StateBackend};
 pub use backends::{LocalBackend, create_backend, create_local_backend};
 pub use lock::LockInfo;
 pub use state::{ResourceState, StateFile};
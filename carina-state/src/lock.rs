@@ -3,9 +3,6 @@
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
-/// Default lock timeout in seconds (15 minutes)
-pub const DEFAULT_LOCK_TIMEOUT_SECS: i64 = 900;
-
 /// Information about a state lock
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LockInfo {
@@ -17,47 +14,66 @@ pub struct LockInfo {
     pub who: String,
     /// When the lock was created
     pub created: DateTime<Utc>,
-    /// When the lock expires
-    pub expires: DateTime<Utc>,
+    /// Age in seconds after which the lock is considered stale and may be taken
+    /// over; `None` keeps it until released or force-unlocked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_seconds: Option<i64>,
 }
 
 impl LockInfo {
     /// Create a new lock for an operation
     pub fn new(operation: impl Into<String>) -> Self {
-        let now = Utc::now();
-        let who = get_lock_owner();
-
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             operation: operation.into(),
-            who,
-            created: now,
-            expires: now + Duration::seconds(DEFAULT_LOCK_TIMEOUT_SECS),
+            who: get_lock_owner(),
+            created: Utc::now(),
+            ttl_seconds: None,
         }
     }
 
-    /// Create a new lock with a custom timeout
-    pub fn with_timeout(operation: impl Into<String>, timeout_secs: i64) -> Self {
-        let now = Utc::now();
-        let who = get_lock_owner();
-
+    /// Create a new lock that becomes stale after `ttl_seconds`
+    pub fn with_ttl(operation: impl Into<String>, ttl_seconds: Option<i64>) -> Self {
         Self {
-            id: uuid::Uuid::new_v4().to_string(),
-            operation: operation.into(),
-            who,
-            created: now,
-            expires: now + Duration::seconds(timeout_secs),
+            ttl_seconds,
+            ..Self::new(operation)
         }
     }
 
-    /// Check if the lock has expired
-    pub fn is_expired(&self) -> bool {
-        Utc::now() > self.expires
+    /// How long ago the lock was created
+    pub fn age(&self) -> Duration {
+        Utc::now() - self.created
+    }
+
+    /// Check if the lock is older than its TTL
+    pub fn is_stale(&self) -> bool {
+        self.ttl_seconds
+            .is_some_and(|ttl| self.age() > Duration::seconds(ttl))
+    }
+
+    /// The lock's age for messages, e.g. "2h 5m"
+    pub fn age_description(&self) -> String {
+        format_age(self.age())
     }
+}
 
-    /// Get the remaining time until expiration
-    pub fn time_remaining(&self) -> Duration {
-        self.expires - Utc::now()
+/// Format an age as its two largest units, e.g. "3d 4h", "2h 5m", "45s"
+fn format_age(age: Duration) -> String {
+    let secs = age.num_seconds().max(0);
+    let (days, hours, minutes, seconds) = (
+        secs / 86_400,
+        secs % 86_400 / 3_600,
+        secs % 3_600 / 60,
+        secs % 60,
+    );
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
     }
 }
 
@@ -85,22 +101,44 @@ mod tests {
         assert_eq!(lock.operation, "apply");
         assert!(!lock.id.is_empty());
         assert!(!lock.who.is_empty());
-        assert!(lock.expires > lock.created);
+        assert_eq!(lock.ttl_seconds, None);
     }
 
     #[test]
-    fn test_lock_info_not_expired() {
-        let lock = LockInfo::new("apply");
-        assert!(!lock.is_expired());
+    fn test_lock_info_without_ttl_never_goes_stale() {
+        let mut lock = LockInfo::new("apply");
+        lock.created = Utc::now() - Duration::days(30);
+        assert!(!lock.is_stale());
+    }
+
+    #[test]
+    fn test_lock_info_with_ttl() {
+        let mut lock = LockInfo::with_ttl("apply", Some(60));
+        assert!(!lock.is_stale());
+
+        lock.created = Utc::now() - Duration::seconds(61);
+        assert!(lock.is_stale());
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::seconds(45)), "45s");
+        assert_eq!(format_age(Duration::seconds(125)), "2m 5s");
+        assert_eq!(format_age(Duration::seconds(7_500)), "2h 5m");
+        assert_eq!(format_age(Duration::seconds(273_600)), "3d 4h");
     }
 
     #[test]
-    fn test_lock_info_with_timeout() {
-        let lock = LockInfo::with_timeout("apply", 60);
-        let remaining = lock.time_remaining();
-        // Should be close to 60 seconds (allowing for test execution time)
-        assert!(remaining.num_seconds() > 55);
-        assert!(remaining.num_seconds() <= 60);
+    fn test_lock_info_reads_locks_without_ttl() {
+        let json = r#"{
+            "id": "abc",
+            "operation": "apply",
+            "who": "ci@runner",
+            "created": "2026-01-01T00:00:00Z",
+            "expires": "2026-01-01T00:15:00Z"
+        }"#;
+        let lock: LockInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(lock.ttl_seconds, None);
     }
 
     #[test]
@@ -1,7 +1,7 @@
 //! State file structures for persisting infrastructure state
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 /// The main state file structure that persists to the backend
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,12 +16,17 @@ pub struct StateFile {
     pub carina_version: String,
     /// All managed resources and their current state
     pub resources: Vec<ResourceState>,
+    /// Resolved values of the configuration's outputs as of the last apply
+    /// (sorted by name for stable output)
+    #[serde(default)]
+    pub outputs: BTreeMap<String, serde_json::Value>,
 }
 
 impl StateFile {
     /// Current state file format version
     /// v2: Added identifier field to ResourceState
-    pub const CURRENT_VERSION: u32 = 2;
+    /// v3: Added outputs
+    pub const CURRENT_VERSION: u32 = 3;
 
     /// Create a new empty state file
     pub fn new() -> Self {
@@ -31,6 +36,7 @@ impl StateFile {
             lineage: uuid::Uuid::new_v4().to_string(),
             carina_version: env!("CARGO_PKG_VERSION").to_string(),
             resources: Vec::new(),
+            outputs: BTreeMap::new(),
         }
     }
 
@@ -42,6 +48,7 @@ impl StateFile {
             lineage,
             carina_version: env!("CARGO_PKG_VERSION").to_string(),
             resources: Vec::new(),
+            outputs: BTreeMap::new(),
         }
     }
 
@@ -111,8 +118,8 @@ pub struct ResourceState {
     /// If None, the resource is considered not to exist
     #[serde(default)]
     pub identifier: Option<String>,
-    /// All attributes of the resource as JSON values
-    pub attributes: HashMap<String, serde_json::Value>,
+    /// All attributes of the resource as JSON values (sorted by key for stable output)
+    pub attributes: BTreeMap<String, serde_json::Value>,
     /// Whether this resource is protected from deletion (e.g., state bucket)
     #[serde(default)]
     pub protected: bool,
@@ -130,7 +137,7 @@ impl ResourceState {
             name: name.into(),
             provider: provider.into(),
             identifier: None,
-            attributes: HashMap::new(),
+            attributes: BTreeMap::new(),
             protected: false,
         }
     }
@@ -239,4 +246,45 @@ mod tests {
         assert_eq!(deserialized.lineage, state.lineage);
         assert_eq!(deserialized.resources.len(), 1);
     }
+
+    #[test]
+    fn test_state_file_outputs_default_when_missing() {
+        let json = r#"{
+            "version": 2,
+            "serial": 4,
+            "lineage": "abc",
+            "carina_version": "0.1.0",
+            "resources": []
+        }"#;
+        let state: StateFile = serde_json::from_str(json).unwrap();
+        assert!(state.outputs.is_empty());
+
+        let mut state = StateFile::new();
+        state
+            .outputs
+            .insert("vpc_id".to_string(), serde_json::json!("vpc-123"));
+        let reloaded: StateFile =
+            serde_json::from_str(&serde_json::to_string(&state).unwrap()).unwrap();
+        assert_eq!(reloaded.outputs, state.outputs);
+    }
+
+    #[test]
+    fn test_resource_state_attributes_serialize_in_key_order() {
+        let mut state = StateFile::new();
+        let resource = ResourceState::new("vpc", "main", "aws")
+            .with_attribute("tags".to_string(), serde_json::json!({"b": "2", "a": "1"}))
+            .with_attribute("cidr_block".to_string(), serde_json::json!("10.0.0.0/16"))
+            .with_attribute("region".to_string(), serde_json::json!("ap-northeast-1"));
+        state.upsert_resource(resource);
+
+        let first = serde_json::to_string_pretty(&state).unwrap();
+        let reloaded: StateFile = serde_json::from_str(&first).unwrap();
+        let second = serde_json::to_string_pretty(&reloaded).unwrap();
+        assert_eq!(first, second);
+
+        let cidr = first.find("cidr_block").unwrap();
+        let region = first.find("region").unwrap();
+        let tags = first.find("tags").unwrap();
+        assert!(cidr < region && region < tags);
+    }
 }